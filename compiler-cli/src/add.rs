@@ -39,6 +39,7 @@ pub fn command(paths: &ProjectPaths, packages_to_add: Vec<String>, dev: bool) ->
         dependencies::DependencyManagerConfig {
             use_manifest: dependencies::UseManifest::Yes,
             check_major_versions: dependencies::CheckMajorVersions::No,
+            offline: dependencies::Offline::No,
         },
     )?;
 
@@ -0,0 +1,399 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::collections::HashSet;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use flate2::read::GzDecoder;
+use gleam_core::{
+    Error, Result,
+    analyse::TargetSupport,
+    build::{
+        Codegen, Compile, Mode, NullTelemetry, Options, Package, PackageCompiler, StaleTracker,
+        Target, TargetCodegenConfiguration,
+    },
+    error::{FileIoAction, FileKind},
+    hex,
+    io::HttpClient as _,
+    manifest::{Base16Checksum, ManifestPackage, ManifestPackageSource},
+    package_interface::{
+        PackageInterface,
+        diff::{ApiDiff, Bump},
+    },
+    paths::{self, ProjectPaths},
+    type_::ModuleInterface,
+    uid::UniqueIdGenerator,
+    warning::WarningEmitter,
+};
+use hexpm::version::Version;
+
+use crate::{
+    dependencies::{Offline, Untar},
+    fs::{self, ProjectIO},
+    http::{CachingHttpClient, HttpClient},
+};
+
+/// The release of the package to compare its public API against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Against {
+    /// The latest release on Hex before the version in `gleam.toml`.
+    LatestRelease,
+    /// A release on Hex.
+    Release(Version),
+    /// A package tarball, such as one made by `gleam export hex-tarball`.
+    Tarball(Utf8PathBuf),
+    /// A `package-interface.json` file, such as one made by
+    /// `gleam export package-interface`.
+    Interface(Utf8PathBuf),
+}
+
+impl Against {
+    fn parse(against: Option<String>) -> Self {
+        let Some(against) = against else {
+            return Self::LatestRelease;
+        };
+        if let Ok(version) = Version::parse(&against) {
+            return Self::Release(version);
+        }
+        let path = Utf8PathBuf::from(against);
+        if path.extension() == Some("json") {
+            Self::Interface(path)
+        } else {
+            Self::Tarball(path)
+        }
+    }
+}
+
+pub fn command(paths: &ProjectPaths, against: Option<String>, offline: Offline) -> Result<()> {
+    let mut built = crate::build::main(
+        paths,
+        Options {
+            mode: Mode::Prod,
+            target: None,
+            codegen: Codegen::None,
+            compile: Compile::All,
+            warnings_as_errors: false,
+            root_target_support: TargetSupport::Enforced,
+            no_print_progress: false,
+        },
+        crate::build::download_dependencies(paths, crate::cli::Reporter::new(), offline)?,
+    )?;
+    built.root_package.attach_doc_and_module_comments();
+    let current = PackageInterface::from_package(&built.root_package, &built.module_interfaces);
+
+    let Some(previous) = previous_interface(
+        paths,
+        &built.root_package.config.version,
+        Against::parse(against),
+        &built.module_interfaces,
+        offline,
+    )?
+    else {
+        return Err(Error::PreviousReleaseNotFound {
+            package: built.root_package.config.name.clone(),
+            version: None,
+        });
+    };
+
+    let previous_version = parse_version(previous.version())?;
+    let version = &built.root_package.config.version;
+    let diff = ApiDiff::new(&previous, &current);
+    print_report(&current, &previous_version, &diff);
+
+    if Bump::between(&previous_version, version).is_none() {
+        let next = diff.required_bump().next_version(&previous_version);
+        println!("The next release should have the version v{next} or later.");
+        return Ok(());
+    }
+    check_version_bump(&current, &previous_version, version, &diff)
+}
+
+/// Refuses to publish the package if the version in `gleam.toml` is too small
+/// a bump from the latest release on Hex for the changes to its public API.
+/// Packages that have not been published before are not checked.
+///
+pub fn check_for_publish(
+    paths: &ProjectPaths,
+    package: &Package,
+    module_interfaces: &im::HashMap<EcoString, ModuleInterface>,
+) -> Result<()> {
+    let version = &package.config.version;
+    let Some(previous) = previous_interface(
+        paths,
+        version,
+        Against::LatestRelease,
+        module_interfaces,
+        Offline::No,
+    )?
+    else {
+        return Ok(());
+    };
+    let previous_version = parse_version(previous.version())?;
+    let current = PackageInterface::from_package(package, module_interfaces);
+    let diff = ApiDiff::new(&previous, &current);
+    check_version_bump(&current, &previous_version, version, &diff)
+}
+
+fn check_version_bump(
+    current: &PackageInterface,
+    previous_version: &Version,
+    version: &Version,
+    diff: &ApiDiff,
+) -> Result<()> {
+    let required = diff.required_bump();
+    match Bump::between(previous_version, version) {
+        Some(bump) if bump < required => Err(Error::ApiChangeRequiresLargerVersionBump {
+            package: current.name().clone(),
+            previous_version: previous_version.to_string(),
+            version: version.to_string(),
+            suggested_version: required.next_version(previous_version).to_string(),
+            required,
+            changes: diff
+                .changes
+                .iter()
+                .filter(|change| change.bump > bump)
+                .map(|change| change.to_string())
+                .collect(),
+        }),
+        Some(_) | None => Ok(()),
+    }
+}
+
+fn print_report(current: &PackageInterface, previous_version: &Version, diff: &ApiDiff) {
+    println!(
+        "Comparing {} v{} against v{previous_version}\n",
+        current.name(),
+        current.version()
+    );
+    if diff.changes.is_empty() {
+        println!("The public API has not changed.\n");
+        return;
+    }
+    for change in &diff.changes {
+        println!("  {:<6} {change}", change.bump.to_string());
+    }
+    println!(
+        "\nThese changes need a {} version bump.\n",
+        diff.required_bump()
+    );
+}
+
+/// The interface of the release to compare against, or `None` if it is to be
+/// the latest release on Hex and the package has not been published before.
+fn previous_interface(
+    paths: &ProjectPaths,
+    version: &Version,
+    against: Against,
+    module_interfaces: &im::HashMap<EcoString, ModuleInterface>,
+    offline: Offline,
+) -> Result<Option<PackageInterface>> {
+    let tarball = match against {
+        Against::Interface(path) => return read_interface(&path).map(Some),
+        Against::Tarball(path) => fs::read_bytes(&path)?,
+        Against::LatestRelease => match hex_release_tarball(paths, version, None, offline)? {
+            Some(tarball) => tarball,
+            None => return Ok(None),
+        },
+        Against::Release(wanted) => {
+            match hex_release_tarball(paths, version, Some(&wanted), offline)? {
+                Some(tarball) => tarball,
+                None => {
+                    return Err(Error::PreviousReleaseNotFound {
+                        package: crate::config::root_config(paths)?.name,
+                        version: Some(wanted.to_string()),
+                    });
+                }
+            }
+        }
+    };
+
+    let root = paths.build_directory().join("api-diff");
+    unpack_contents(&tarball, &root)?;
+    compile_release(paths, &root, module_interfaces).map(Some)
+}
+
+/// The tarball of the release on Hex to compare against, if there is one.
+fn hex_release_tarball(
+    paths: &ProjectPaths,
+    version: &Version,
+    wanted: Option<&Version>,
+    offline: Offline,
+) -> Result<Option<Vec<u8>>> {
+    let config = crate::config::root_config(paths)?;
+    match find_hex_release(paths, &config.name, version, wanted, offline)? {
+        Some(release) => download_release(paths, &release, offline).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_interface(path: &Utf8Path) -> Result<PackageInterface> {
+    let json = fs::read(path)?;
+    serde_json::from_str(&json).map_err(|error| Error::FileIo {
+        kind: FileKind::File,
+        action: FileIoAction::Parse,
+        path: path.to_path_buf(),
+        err: Some(error.to_string()),
+    })
+}
+
+fn parse_version(version: &str) -> Result<Version> {
+    Version::parse(version).map_err(|error| Error::InvalidVersionFormat {
+        input: version.into(),
+        error: error.to_string(),
+    })
+}
+
+/// The release with the given version, or the latest one before the version
+/// of the package if none is given.
+fn find_hex_release(
+    paths: &ProjectPaths,
+    name: &str,
+    version: &Version,
+    wanted: Option<&Version>,
+    offline: Offline,
+) -> Result<Option<ManifestPackage>> {
+    let repositories = crate::dependencies::hex_repositories(paths)?;
+    let repository = repositories.get(None)?;
+    let http = CachingHttpClient::new(matches!(offline, Offline::Yes));
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+
+    let request = hexpm::repository_v2_get_package_request(
+        name,
+        repository.credentials.as_ref(),
+        &repository.config,
+    );
+    let response = runtime.block_on(http.send(request))?;
+    let package = match hexpm::repository_v2_get_package_response(response, &repository.public_key)
+    {
+        Ok(package) => package,
+        Err(hexpm::ApiError::NotFound) => return Ok(None),
+        Err(error) => return Err(Error::hex(error)),
+    };
+
+    let release = package
+        .releases
+        .into_iter()
+        .filter(|release| match wanted {
+            Some(wanted) => release.version == *wanted,
+            None => release.version < *version,
+        })
+        .max_by(|a, b| a.version.cmp(&b.version));
+
+    Ok(release.map(|release| ManifestPackage {
+        name: name.into(),
+        version: release.version,
+        build_tools: vec![],
+        otp_app: None,
+        requirements: vec![],
+        source: ManifestPackageSource::Hex {
+            outer_checksum: Base16Checksum(release.outer_checksum),
+            repository: None,
+        },
+    }))
+}
+
+/// Downloads the tarball of a release into the global package cache, unless
+/// it is already there.
+fn download_release(
+    paths: &ProjectPaths,
+    release: &ManifestPackage,
+    offline: Offline,
+) -> Result<Vec<u8>> {
+    let http: Box<dyn gleam_core::io::HttpClient> = match offline {
+        Offline::Yes => CachingHttpClient::boxed(true),
+        Offline::No => HttpClient::boxed(),
+    };
+    let fs = Box::new(ProjectIO::new());
+    let downloader = hex::Downloader::new(fs.clone(), fs, http, Untar::boxed(), paths.clone())
+        .with_repositories(crate::dependencies::hex_repositories(paths)?);
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let _ = runtime.block_on(downloader.ensure_package_downloaded(release))?;
+
+    let ManifestPackageSource::Hex { outer_checksum, .. } = &release.source else {
+        unreachable!("Hex release with a non-Hex source")
+    };
+    fs::read_bytes(paths::global_package_cache_package_tarball(outer_checksum))
+}
+
+/// Writes the source code inside a package tarball to the given directory,
+/// replacing anything that was there before.
+fn unpack_contents(tarball: &[u8], destination: &Utf8Path) -> Result<()> {
+    fs::delete_directory(destination)?;
+    let mut archive = tar::Archive::new(tarball);
+    for entry in archive.entries().map_err(Error::expand_tar)? {
+        let entry = entry.map_err(Error::expand_tar)?;
+        let path = entry.path().map_err(Error::expand_tar)?;
+        if path.as_ref() == Utf8Path::new("contents.tar.gz") {
+            return tar::Archive::new(GzDecoder::new(entry))
+                .unpack(destination)
+                .map_err(Error::expand_tar);
+        }
+    }
+    Err(Error::ExpandTar {
+        error: "Unable to locate Hex package contents.tar.gz".into(),
+    })
+}
+
+/// Type checks the previous release of the package against the dependencies
+/// of the current one, to get its public interface.
+fn compile_release(
+    paths: &ProjectPaths,
+    root: &Utf8Path,
+    module_interfaces: &im::HashMap<EcoString, ModuleInterface>,
+) -> Result<PackageInterface> {
+    let config = crate::config::read(root.join("gleam.toml"))?;
+    let target = crate::config::root_config(paths)?.target;
+    let mut importable_modules = module_interfaces
+        .iter()
+        .filter(|(_, module)| module.package != config.name)
+        .map(|(name, module)| (name.clone(), module.clone()))
+        .collect();
+
+    let out = root.join("build");
+    let lib = paths.build_directory_for_target(Mode::Prod, target);
+    let codegen_target = match target {
+        Target::Erlang => TargetCodegenConfiguration::Erlang { app_file: None },
+        Target::JavaScript => TargetCodegenConfiguration::JavaScript {
+            emit_typescript_definitions: false,
+            emit_source_maps: false,
+            prelude_location: Utf8PathBuf::from("../prelude.mjs"),
+        },
+    };
+
+    let mut compiler = PackageCompiler::new(
+        &config,
+        Mode::Prod,
+        root,
+        &out,
+        &lib,
+        &codegen_target,
+        UniqueIdGenerator::new(),
+        ProjectIO::new(),
+    );
+    compiler.write_metadata = false;
+    compiler.perform_codegen = false;
+    compiler.copy_native_files = false;
+    compiler.compile_beam_bytecode = false;
+    let compiled = compiler
+        .compile(
+            &WarningEmitter::null(),
+            &mut importable_modules,
+            &mut im::HashMap::new(),
+            &mut StaleTracker::default(),
+            &mut HashSet::new(),
+            &NullTelemetry,
+        )
+        .into_result()?;
+
+    let mut package = Package {
+        config,
+        modules: compiled.modules,
+        cached_module_names: vec![],
+    };
+    package.attach_doc_and_module_comments();
+    Ok(PackageInterface::from_package(
+        &package,
+        &im::HashMap::new(),
+    ))
+}
@@ -84,10 +84,7 @@ impl BeamCompilerInstance {
                         accumulated_modules.push(module_content.to_string());
                     }
                 }
-                _ => match stdio {
-                    Stdio::Inherit => print!("{buf}"),
-                    Stdio::Null => {}
-                },
+                _ => forward_output(&buf, stdio, &mut io::stdout()),
             }
 
             buf.clear()
@@ -153,9 +150,37 @@ fn escape_path<T: AsRef<str>>(path: T) -> String {
     path.as_ref().replace('\\', "\\\\").replace('"', "\\\"")
 }
 
+/// Passes on the output of the Erlang compiler that isn't part of its protocol
+/// with the build tool, such as warnings from `erlc`, unless it's discarded.
+///
+fn forward_output(line: &str, stdio: Stdio, stdout: &mut impl Write) {
+    match stdio {
+        Stdio::Inherit => {
+            let _ = write!(stdout, "{line}");
+        }
+        Stdio::Null => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::escape_path;
+    use gleam_core::io::Stdio;
+
+    use super::{escape_path, forward_output};
+
+    #[test]
+    fn output_is_forwarded_to_stdout() {
+        let mut stdout = vec![];
+        forward_output("Warning: wibble\n", Stdio::Inherit, &mut stdout);
+        assert_eq!(stdout, b"Warning: wibble\n");
+    }
+
+    #[test]
+    fn discarded_output_is_not_written_to_stdout() {
+        let mut stdout = vec![];
+        forward_output("Warning: wibble\n", Stdio::Null, &mut stdout);
+        assert!(stdout.is_empty());
+    }
 
     #[test]
     fn escape_path_plain() {
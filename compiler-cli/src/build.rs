@@ -6,9 +6,10 @@ use std::{rc::Rc, time::Instant};
 use gleam_core::{
     Result,
     build::{Built, Codegen, NullTelemetry, Options, ProjectCompiler, Telemetry},
+    io::Stdio,
     manifest::Manifest,
     paths::ProjectPaths,
-    warning::WarningEmitterIO,
+    warning::{NullWarningEmitterIO, WarningEmitterIO},
 };
 
 use crate::{
@@ -17,7 +18,11 @@ use crate::{
     fs::{self, ConsoleWarningEmitter},
 };
 
-pub fn download_dependencies(paths: &ProjectPaths, telemetry: impl Telemetry) -> Result<Manifest> {
+pub fn download_dependencies(
+    paths: &ProjectPaths,
+    telemetry: impl Telemetry,
+    offline: dependencies::Offline,
+) -> Result<Manifest> {
     dependencies::resolve_and_download(
         paths,
         telemetry,
@@ -26,6 +31,7 @@ pub fn download_dependencies(paths: &ProjectPaths, telemetry: impl Telemetry) ->
         dependencies::DependencyManagerConfig {
             use_manifest: dependencies::UseManifest::Yes,
             check_major_versions: dependencies::CheckMajorVersions::No,
+            offline,
         },
     )
 }
@@ -40,26 +46,43 @@ pub(crate) fn main_with_warnings(
     manifest: Manifest,
     warnings: Rc<dyn WarningEmitterIO>,
 ) -> Result<Built> {
-    let perform_codegen = options.codegen;
-    let root_config = crate::config::root_config(paths)?;
-    let telemetry: &'static dyn Telemetry = if options.no_print_progress {
-        &NullTelemetry
-    } else {
-        &cli::Reporter
-    };
-    let target = options.target.unwrap_or(root_config.target);
-    let io = fs::ProjectIO::new();
-    // Initialise the BEAM compiler instance eagerly, so we don't have to wait
-    // for it to boot when we come to use it for the first time.
-    if target.is_erlang() {
-        io.initialise_beam_compiler()?;
-    }
-    let start = Instant::now();
-    let lock = BuildLock::new_target(paths, options.mode, target)?;
+    IncrementalBuild::new(paths, options, manifest, warnings)?.build()
+}
+
+/// A build of the project that can be performed again and again, keeping the
+/// compiler and the modules it has loaded in memory between builds so that
+/// only the modules that have changed need to be compiled again.
+///
+#[derive(Debug)]
+pub(crate) struct IncrementalBuild {
+    compiler: ProjectCompiler<fs::ProjectIO>,
+    lock: BuildLock,
+    telemetry: &'static dyn Telemetry,
+    codegen: Codegen,
+}
 
-    tracing::info!("Compiling packages");
-    let result = {
-        let _guard = lock.lock(telemetry);
+impl IncrementalBuild {
+    pub fn new(
+        paths: &ProjectPaths,
+        options: Options,
+        manifest: Manifest,
+        warnings: Rc<dyn WarningEmitterIO>,
+    ) -> Result<Self> {
+        let codegen = options.codegen;
+        let root_config = crate::config::root_config(paths)?;
+        let telemetry: &'static dyn Telemetry = if options.no_print_progress {
+            &NullTelemetry
+        } else {
+            &cli::Reporter
+        };
+        let target = options.target.unwrap_or(root_config.target);
+        let io = fs::ProjectIO::new();
+        // Initialise the BEAM compiler instance eagerly, so we don't have to wait
+        // for it to boot when we come to use it for the first time.
+        if target.is_erlang() {
+            io.initialise_beam_compiler()?;
+        }
+        let lock = BuildLock::new_target(paths, options.mode, target)?;
         let compiler = ProjectCompiler::new(
             root_config,
             options,
@@ -69,13 +92,103 @@ pub(crate) fn main_with_warnings(
             paths.clone(),
             io,
         );
-        compiler.compile()?
-    };
 
-    match perform_codegen {
-        Codegen::All | Codegen::DepsOnly => telemetry.compiled_package(start.elapsed()),
-        Codegen::None => telemetry.checked_package(start.elapsed()),
+        Ok(Self {
+            compiler,
+            lock,
+            telemetry,
+            codegen,
+        })
+    }
+
+    /// A build that prints nothing to stdout, for when stdout is used for
+    /// something else: the language server talks to the editor over it. The
+    /// output of the Erlang compiler is discarded, and warnings and progress
+    /// are not reported.
+    ///
+    pub fn without_output(
+        paths: &ProjectPaths,
+        options: Options,
+        manifest: Manifest,
+    ) -> Result<Self> {
+        let options = Options {
+            no_print_progress: true,
+            ..options
+        };
+        let mut build = Self::new(paths, options, manifest, Rc::new(NullWarningEmitterIO))?;
+        build.compiler.subprocess_stdio = Stdio::Null;
+        Ok(build)
+    }
+
+    pub fn build(&mut self) -> Result<Built> {
+        let start = Instant::now();
+
+        tracing::info!("Compiling packages");
+        let result = {
+            let _guard = self.lock.lock(self.telemetry);
+            self.compiler.recompile()?
+        };
+
+        match self.codegen {
+            Codegen::All | Codegen::DepsOnly => self.telemetry.compiled_package(start.elapsed()),
+            Codegen::None => self.telemetry.checked_package(start.elapsed()),
+        };
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use gleam_core::{
+        analyse::TargetSupport,
+        build::{Codegen, Compile, Mode, Options, Target},
+        io::Stdio,
+        manifest::Manifest,
+        paths::ProjectPaths,
     };
 
-    Ok(result)
+    use super::IncrementalBuild;
+
+    // The language server builds projects to run their programs, and it talks
+    // to the editor over stdout. Anything printed by the processes the build
+    // runs would corrupt the messages sent to the editor.
+    #[test]
+    fn build_without_output_silences_subprocesses() {
+        let directory = tempfile::tempdir().expect("temporary directory");
+        let root = Utf8Path::from_path(directory.path()).expect("UTF-8 path");
+        std::fs::write(
+            root.join("gleam.toml"),
+            "name = \"wibble\"\nversion = \"1.0.0\"\n",
+        )
+        .expect("write gleam.toml");
+        std::fs::create_dir(root.join("src")).expect("create src");
+        std::fs::write(
+            root.join("src/wibble.gleam"),
+            "pub fn main() {\n  let unused = 1\n  Nil\n}\n",
+        )
+        .expect("write module");
+
+        let options = Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            compile: Compile::All,
+            mode: Mode::Dev,
+            target: Some(Target::JavaScript),
+            no_print_progress: false,
+        };
+        let manifest = Manifest {
+            requirements: Default::default(),
+            packages: vec![],
+            vendor: None,
+        };
+        let paths = ProjectPaths::new(root.to_path_buf());
+        let mut build =
+            IncrementalBuild::without_output(&paths, options, manifest).expect("new build");
+
+        assert_eq!(build.compiler.subprocess_stdio, Stdio::Null);
+        assert!(build.build().is_ok());
+    }
 }
@@ -122,6 +122,11 @@ pub fn print_publishing_documentation() {
     print_colourful_prefix("Publishing", "documentation");
 }
 
+pub fn print_vendored(count: usize) {
+    let packages = if count == 1 { "package" } else { "packages" };
+    print_colourful_prefix("Vendored", &format!("{count} {packages}"))
+}
+
 fn print_downloading(text: &str) {
     print_colourful_prefix("Downloading", text)
 }
@@ -158,6 +163,10 @@ pub(crate) fn print_running(text: &str) {
     print_colourful_prefix("Running", text)
 }
 
+pub(crate) fn print_watching() {
+    print_colourful_prefix("Watching", "for changes")
+}
+
 pub(crate) fn print_package_changes(changes: &PackageChanges) {
     for (name, version) in changes.added.iter().sorted() {
         print_added(&format!("{name} v{version}"));
@@ -3,7 +3,7 @@
 
 use crate::{
     CompilePackage, config,
-    fs::{self, ConsoleWarningEmitter, ProjectIO},
+    fs::{self, ProjectIO},
 };
 use camino::Utf8Path;
 use ecow::EcoString;
@@ -19,13 +19,13 @@ use gleam_core::{
     uid::UniqueIdGenerator,
     warning::WarningEmitter,
 };
-use std::{collections::HashSet, rc::Rc};
+use std::collections::HashSet;
 
 pub fn command(options: CompilePackage) -> Result<()> {
     let ids = UniqueIdGenerator::new();
     let mut type_manifests = load_libraries(&ids, &options.libraries_directory)?;
     let mut defined_modules = im::HashMap::new();
-    let warnings = WarningEmitter::new(Rc::new(ConsoleWarningEmitter));
+    let warnings = WarningEmitter::new(options.diagnostics_format.warning_emitter());
     let paths = ProjectPaths::new(options.package_directory.clone());
     let config = config::read(paths.root_config())?;
 
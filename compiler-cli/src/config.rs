@@ -47,7 +47,7 @@ pub fn find_package_config_for_module(
 
 fn package_root(package: &ManifestPackage, project_paths: &ProjectPaths) -> Utf8PathBuf {
     match &package.source {
-        ManifestPackageSource::Local { path } => project_paths.root().join(path),
+        ManifestPackageSource::Local { path } => project_paths.manifest_directory().join(path),
 
         ManifestPackageSource::Hex { .. } | ManifestPackageSource::Git { .. } => {
             project_paths.build_packages_package(&package.name)
@@ -100,6 +100,7 @@ mod tests {
             requirements: vec![],
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
         };
         assert_eq!(
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! The coverage report of `gleam test --coverage`.
+//!
+//! When compiled for coverage each module of the root package's `src`
+//! directory has a coverage map written next to it, listing the functions and
+//! case clauses whose uses are counted. The test runner records the counts as
+//! lines of JSON in a file, and once the tests have finished the two are put
+//! together into an lcov report and a summary printed to the terminal.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gleam_core::{
+    Result,
+    build::Mode,
+    coverage::{COVERAGE_MAP_EXTENSION, CoverageKind, CoverageMap},
+    error::{Error, FileIoAction, FileKind},
+    paths::ProjectPaths,
+};
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::text_layout::space_table;
+
+/// The number of times a coverage point was used, as recorded by the test
+/// runner. Points that were never used are not recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct CoverageCount {
+    module: String,
+    point: usize,
+    count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    modules: Vec<ModuleCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModuleCoverage {
+    map: CoverageMap,
+    /// The count of each of the map's points, in the same order.
+    counts: Vec<u64>,
+}
+
+impl CoverageReport {
+    /// Reads the coverage maps in the given artefacts directory, along with
+    /// the counts the test runner recorded in the given file.
+    ///
+    pub fn read(
+        paths: &ProjectPaths,
+        artefact_directory: &Utf8Path,
+        counts_path: &Utf8Path,
+    ) -> Result<Self> {
+        let mut maps = vec![];
+        for entry in crate::fs::read_dir(artefact_directory)?.filter_map(Result::ok) {
+            let path = entry.into_path();
+            if !path.as_str().ends_with(COVERAGE_MAP_EXTENSION) {
+                continue;
+            }
+            let map: CoverageMap = parse(&path, &crate::fs::read(&path)?)?;
+            // The maps of modules that have since been deleted are left
+            // behind in the build directory.
+            if paths.root().join(&map.path).is_file() {
+                maps.push(map);
+            }
+        }
+        let counts = crate::fs::read(counts_path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse(counts_path, line))
+            .collect::<Result<Vec<CoverageCount>>>()?;
+        Ok(Self::new(maps, counts))
+    }
+
+    fn new(maps: Vec<CoverageMap>, counts: Vec<CoverageCount>) -> Self {
+        let counts: HashMap<_, _> = counts
+            .into_iter()
+            .map(|count| ((count.module, count.point), count.count))
+            .collect();
+        let modules = maps
+            .into_iter()
+            .sorted_by(|one, other| one.module.cmp(&other.module))
+            .map(|map| {
+                let counts = (0..map.points.len())
+                    .map(|point| {
+                        let key = (map.module.to_string(), point);
+                        counts.get(&key).copied().unwrap_or(0)
+                    })
+                    .collect();
+                ModuleCoverage { map, counts }
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// Writes the report in the lcov format, returning the path it was
+    /// written to.
+    ///
+    pub fn write(&self, paths: &ProjectPaths) -> Result<Utf8PathBuf> {
+        let path = paths
+            .build_directory_for_mode(Mode::Coverage)
+            .join("lcov.info");
+        crate::fs::write(&path, &self.lcov())?;
+        Ok(path)
+    }
+
+    fn lcov(&self) -> String {
+        let mut lcov = String::new();
+        for module in &self.modules {
+            module.lcov(&mut lcov);
+        }
+        lcov
+    }
+
+    /// A table of how many of the functions and case clauses of each module
+    /// the tests ran, along with the lines of the ones they didn't.
+    ///
+    pub fn summary(&self) -> String {
+        let mut rows = self
+            .modules
+            .iter()
+            .map(|module| {
+                vec![
+                    module.map.module.to_string(),
+                    ratio(module.functions()),
+                    ratio(module.clauses()),
+                    module.missed_lines(),
+                ]
+            })
+            .collect_vec();
+
+        let total = |counts: fn(&ModuleCoverage) -> (usize, usize)| {
+            self.modules
+                .iter()
+                .map(counts)
+                .fold((0, 0), |(hit, all), (module_hit, module_all)| {
+                    (hit + module_hit, all + module_all)
+                })
+        };
+        rows.push(vec![
+            "Total".into(),
+            ratio(total(ModuleCoverage::functions)),
+            ratio(total(ModuleCoverage::clauses)),
+            "-".into(),
+        ]);
+
+        let table = space_table(&["Module", "Functions", "Clauses", "Missed lines"], rows);
+        format!("\nCoverage\n{table}")
+    }
+}
+
+impl ModuleCoverage {
+    fn points(&self) -> impl Iterator<Item = (&CoverageKind, u32, u64)> {
+        self.map
+            .points
+            .iter()
+            .zip(&self.counts)
+            .map(|(point, count)| (&point.kind, point.line, *count))
+    }
+
+    /// How many of the module's functions were called, out of all of them.
+    fn functions(&self) -> (usize, usize) {
+        self.hit(|kind| matches!(kind, CoverageKind::Function { .. }))
+    }
+
+    /// How many of the module's case clauses were run, out of all of them.
+    fn clauses(&self) -> (usize, usize) {
+        self.hit(|kind| matches!(kind, CoverageKind::Clause { .. }))
+    }
+
+    fn hit(&self, is_kind: impl Fn(&CoverageKind) -> bool) -> (usize, usize) {
+        self.points()
+            .filter(|(kind, _, _)| is_kind(kind))
+            .fold((0, 0), |(hit, all), (_, _, count)| {
+                (hit + usize::from(count > 0), all + 1)
+            })
+    }
+
+    /// The count of each line with a coverage point on it. A line with more
+    /// than one point counts as run if any of them were.
+    fn lines(&self) -> Vec<(u32, u64)> {
+        let mut lines: Vec<(u32, u64)> = vec![];
+        for (_, line, count) in self.points().sorted_by_key(|(_, line, _)| *line) {
+            match lines.last_mut() {
+                Some((last, last_count)) if *last == line => *last_count = (*last_count).max(count),
+                Some(_) | None => lines.push((line, count)),
+            }
+        }
+        lines
+    }
+
+    /// The lines with coverage points that were never run, with consecutive
+    /// lines written as a range, or `-` if there are none.
+    fn missed_lines(&self) -> String {
+        let mut ranges: Vec<(u32, u32)> = vec![];
+        for (line, count) in self.lines() {
+            if count > 0 {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == line => *end = line,
+                Some(_) | None => ranges.push((line, line)),
+            }
+        }
+        if ranges.is_empty() {
+            return "-".into();
+        }
+        ranges
+            .into_iter()
+            .map(|(start, end)| match start == end {
+                true => start.to_string(),
+                false => format!("{start}-{end}"),
+            })
+            .join(", ")
+    }
+
+    fn lcov(&self, lcov: &mut String) {
+        lcov.push_str("TN:\n");
+        lcov.push_str(&format!("SF:{}\n", self.map.path));
+
+        for (kind, line, _) in self.points() {
+            if let CoverageKind::Function { name } = kind {
+                lcov.push_str(&format!("FN:{line},{name}\n"));
+            }
+        }
+        for (kind, _, count) in self.points() {
+            if let CoverageKind::Function { name } = kind {
+                lcov.push_str(&format!("FNDA:{count},{name}\n"));
+            }
+        }
+        let (hit, all) = self.functions();
+        lcov.push_str(&format!("FNF:{all}\nFNH:{hit}\n"));
+
+        // Each case is a block, and each of its clauses a branch.
+        for (kind, line, count) in self.points() {
+            if let CoverageKind::Clause { case, clause } = kind {
+                lcov.push_str(&format!("BRDA:{line},{case},{clause},{count}\n"));
+            }
+        }
+        let (hit, all) = self.clauses();
+        lcov.push_str(&format!("BRF:{all}\nBRH:{hit}\n"));
+
+        let lines = self.lines();
+        for (line, count) in &lines {
+            lcov.push_str(&format!("DA:{line},{count}\n"));
+        }
+        let hit = lines.iter().filter(|(_, count)| *count > 0).count();
+        lcov.push_str(&format!("LF:{}\nLH:{hit}\n", lines.len()));
+
+        lcov.push_str("end_of_record\n");
+    }
+}
+
+fn ratio((hit, all): (usize, usize)) -> String {
+    if all == 0 {
+        return "0/0".into();
+    }
+    let percent = hit as f64 * 100.0 / all as f64;
+    format!("{hit}/{all} ({percent:.1}%)")
+}
+
+fn parse<T: serde::de::DeserializeOwned>(path: &Utf8Path, json: &str) -> Result<T> {
+    serde_json::from_str(json).map_err(|error| Error::FileIo {
+        kind: FileKind::File,
+        action: FileIoAction::Parse,
+        path: path.to_path_buf(),
+        err: Some(error.to_string()),
+    })
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use super::{CoverageCount, CoverageReport};
+
+/// The coverage maps of two modules, as written by the compiler: one with
+/// a function calling another, and one with a function with a case
+/// expression.
+const MAPS: [&str; 2] = [
+    r#"{"module":"wibble/parser","path":"src/wibble/parser.gleam","points":[{"kind":{"type":"function","name":"parse"},"line":3},{"kind":{"type":"clause","case":0,"clause":0},"line":5},{"kind":{"type":"clause","case":0,"clause":1},"line":6},{"kind":{"type":"clause","case":0,"clause":2},"line":7},{"kind":{"type":"function","name":"unused"},"line":11},{"kind":{"type":"clause","case":1,"clause":0},"line":13},{"kind":{"type":"clause","case":1,"clause":1},"line":14}]}"#,
+    r#"{"module":"wobble","path":"src/wobble.gleam","points":[{"kind":{"type":"function","name":"main"},"line":1},{"kind":{"type":"function","name":"helper"},"line":5}]}"#,
+];
+
+/// The counts as recorded by the test runner.
+const COUNTS: &str = r#"
+{"module":"wobble","point":0,"count":2}
+{"module":"wobble","point":1,"count":4}
+{"module":"wibble/parser","point":0,"count":3}
+{"module":"wibble/parser","point":1,"count":2}
+{"module":"wibble/parser","point":3,"count":1}
+"#;
+
+fn report() -> CoverageReport {
+    let maps = MAPS
+        .iter()
+        .map(|map| serde_json::from_str(map).expect("Coverage map"))
+        .collect();
+    let counts = COUNTS
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str::<CoverageCount>(line).expect("Coverage count"))
+        .collect();
+    CoverageReport::new(maps, counts)
+}
+
+#[test]
+fn lcov_report() {
+    insta::assert_snapshot!(report().lcov());
+}
+
+#[test]
+fn coverage_summary() {
+    insta::assert_snapshot!(report().summary());
+}
+
+#[test]
+fn coverage_summary_without_any_modules() {
+    insta::assert_snapshot!(CoverageReport::new(vec![], vec![]).summary());
+}
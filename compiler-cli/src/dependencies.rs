@@ -18,13 +18,13 @@ use flate2::read::GzDecoder;
 use gleam_core::{
     Error, Result,
     build::{Mode, SourceFingerprint, Target, Telemetry},
-    config::PackageConfig,
+    config::{PackageConfig, Workspace},
     dependency::{self, PackageFetchError},
     error::{FileIoAction, FileKind, ShellCommandFailureReason, StandardIoAction},
-    hex::{self, HEXPM_PUBLIC_KEY},
+    hex,
     io::{HttpClient as _, TarUnpacker, WrappedReader},
     manifest::{Base16Checksum, Manifest, ManifestPackage, ManifestPackageSource, PackageChanges},
-    paths::ProjectPaths,
+    paths::{self, ProjectPaths},
     requirement::Requirement,
 };
 use hexpm::version::Version;
@@ -42,7 +42,7 @@ use crate::{
     build_lock::{BuildLock, Guard},
     cli,
     fs::{self, ProjectIO},
-    http::HttpClient,
+    http::{CachingHttpClient, HttpClient},
     text_layout::space_table,
 };
 
@@ -98,14 +98,22 @@ pub fn tree(paths: &ProjectPaths, options: TreeOptions) -> Result<()> {
 fn get_manifest_details(paths: &ProjectPaths) -> Result<(PackageConfig, Manifest)> {
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
     let config = crate::config::root_config(paths)?;
-    let package_fetcher = PackageFetcher::new(runtime.handle().clone());
+    let repositories = hex_repositories(paths)?;
+    let package_fetcher = PackageFetcher::new(
+        runtime.handle().clone(),
+        Offline::No,
+        OfflinePackages::default(),
+        repositories.clone(),
+    );
     let dependency_manager = DependencyManagerConfig {
         use_manifest: UseManifest::Yes,
         check_major_versions: CheckMajorVersions::No,
+        offline: Offline::No,
     }
     .into_dependency_manager(
         runtime.handle().clone(),
         package_fetcher,
+        repositories,
         cli::Reporter::new(),
         Mode::Dev,
     );
@@ -232,7 +240,12 @@ pub fn outdated(paths: &ProjectPaths) -> Result<()> {
         .count();
 
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
-    let package_fetcher = PackageFetcher::new(runtime.handle().clone());
+    let package_fetcher = PackageFetcher::new(
+        runtime.handle().clone(),
+        Offline::No,
+        OfflinePackages::default(),
+        hex_repositories(paths)?,
+    );
 
     let version_updates = dependency::check_for_version_updates(&manifest, &package_fetcher);
 
@@ -250,6 +263,20 @@ pub enum UseManifest {
     No,
 }
 
+/// When set to `Yes` the Hex API isn't used, and dependencies are resolved
+/// using only the packages already in the local Hex cache.
+#[derive(Debug, Clone, Copy)]
+pub enum Offline {
+    Yes,
+    No,
+}
+
+impl Offline {
+    pub fn from_flag(offline: bool) -> Self {
+        if offline { Self::Yes } else { Self::No }
+    }
+}
+
 pub fn update(paths: &ProjectPaths, packages: Vec<String>) -> Result<()> {
     let use_manifest = if packages.is_empty() {
         UseManifest::No
@@ -266,12 +293,87 @@ pub fn update(paths: &ProjectPaths, packages: Vec<String>) -> Result<()> {
         DependencyManagerConfig {
             use_manifest,
             check_major_versions: CheckMajorVersions::Yes,
+            offline: Offline::No,
         },
     )?;
 
     Ok(())
 }
 
+/// Copies the Hex packages of all the dependencies into the `vendor`
+/// directory, and references it from the manifest so that they are used
+/// rather than downloading them from Hex.
+///
+/// Git and path dependencies are not vendored.
+pub fn vendor(paths: &ProjectPaths, offline: Offline) -> Result<()> {
+    let manifest = resolve_and_download(
+        paths,
+        cli::Reporter::new(),
+        None,
+        Vec::new(),
+        DependencyManagerConfig {
+            use_manifest: UseManifest::Yes,
+            check_major_versions: CheckMajorVersions::No,
+            offline,
+        },
+    )?;
+    // The manifest returned for a member of a workspace only has the packages
+    // that member uses, so the manifest of the whole workspace is read instead.
+    let mut manifest = match paths.workspace_root() {
+        Some(_) => read_manifest_from_disc(paths)?,
+        None => manifest,
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let repositories = hex_repositories(paths)?;
+    let downloader = downloader(
+        paths,
+        Box::new(ProjectIO::new()),
+        &manifest,
+        &repositories,
+        offline,
+    );
+    let vendor_directory = paths.vendor_directory();
+    fs::mkdir(&vendor_directory)?;
+
+    let mut vendored = HashSet::new();
+    for package in &manifest.packages {
+        let ManifestPackageSource::Hex { outer_checksum, .. } = &package.source else {
+            continue;
+        };
+        let _ = runtime.block_on(downloader.ensure_package_downloaded(package))?;
+
+        // The tarball in the cache is checked again, as it could have been
+        // changed since it was downloaded.
+        let tarball = fs::read_bytes(paths::global_package_cache_package_tarball(outer_checksum))?;
+        hexpm::verify_package_tarball(&tarball, &outer_checksum.0).map_err(|error| {
+            Error::DownloadPackageError {
+                package_name: package.name.to_string(),
+                package_version: package.version.to_string(),
+                error: error.to_string(),
+            }
+        })?;
+
+        let path =
+            paths::vendored_package_tarball(&vendor_directory, &package.name, &package.version);
+        fs::write_bytes(&path, &tarball)?;
+        let _ = vendored.insert(path);
+    }
+
+    // Remove the packages left behind by versions no longer in the manifest.
+    for entry in fs::read_dir(&vendor_directory)?.filter_map(Result::ok) {
+        let path = entry.into_path();
+        if path.extension() == Some("tar") && !vendored.contains(&path) {
+            fs::delete_file(&path)?;
+        }
+    }
+
+    manifest.vendor = Some(Utf8PathBuf::from("vendor"));
+    write_manifest_to_disc(paths, &manifest)?;
+    cli::print_vendored(vendored.len());
+    Ok(())
+}
+
 /// Edit the manifest.toml file in this proejct, removing all extra requirements and packages
 /// that are no longer present in the gleam.toml config.
 pub fn cleanup<Telem: Telemetry>(paths: &ProjectPaths, telemetry: Telem) -> Result<Manifest> {
@@ -410,11 +512,18 @@ pub fn resolve_and_download<Telem: Telemetry>(
 ) -> Result<Manifest> {
     // Start event loop so we can run async functions to call the Hex API
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
-    let package_fetcher = PackageFetcher::new(runtime.handle().clone());
+    let repositories = hex_repositories(paths)?;
+    let package_fetcher = PackageFetcher::new(
+        runtime.handle().clone(),
+        config.offline,
+        OfflinePackages::read(paths, config.offline)?,
+        repositories.clone(),
+    );
 
     let dependency_manager = config.into_dependency_manager(
         runtime.handle().clone(),
         package_fetcher,
+        repositories,
         telemetry,
         Mode::Dev,
     );
@@ -422,6 +531,20 @@ pub fn resolve_and_download<Telem: Telemetry>(
     dependency_manager.resolve_and_download_versions(paths, new_package, packages_to_update)
 }
 
+/// The Hex repositories dependencies can be fetched from. The members of a
+/// workspace have their dependencies resolved together, so the repositories
+/// of all of them are used.
+pub fn hex_repositories(paths: &ProjectPaths) -> Result<hex::Repositories> {
+    crate::config::ensure_config_exists(paths)?;
+    let mut config = crate::config::root_config(paths)?;
+    if let Some(workspace_root) = paths.workspace_root() {
+        let io = ProjectIO::new();
+        let workspace = Workspace::read(workspace_root, &io)?;
+        config = workspace.dependency_config(&io, paths.root(), &config)?;
+    }
+    hex::Repositories::new(&config.hex_repositories, |name| std::env::var(name).ok())
+}
+
 fn format_versions_and_extract_longest_parts(
     versions: dependency::PackageVersionDiffs,
 ) -> Vec<Vec<String>> {
@@ -463,13 +586,16 @@ fn pretty_print_outdated_versions(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn add_missing_packages<Telem: Telemetry>(
     paths: &ProjectPaths,
     fs: Box<ProjectIO>,
     manifest: &Manifest,
     local: &LocalPackages,
     project_name: EcoString,
+    repositories: &hex::Repositories,
     telemetry: &Telem,
+    offline: Offline,
 ) -> Result<(), Error> {
     let missing_packages = local.missing_local_packages(manifest, &project_name);
 
@@ -495,8 +621,7 @@ async fn add_missing_packages<Telem: Telemetry>(
 
     // If we need to download at-least one package
     if missing_hex_packages.peek().is_some() || !missing_git_packages.is_empty() {
-        let http = HttpClient::boxed();
-        let downloader = hex::Downloader::new(fs.clone(), fs, http, Untar::boxed(), paths.clone());
+        let downloader = downloader(paths, fs, manifest, repositories, offline);
         let start = Instant::now();
         telemetry.downloading_package("packages");
         downloader
@@ -514,6 +639,28 @@ async fn add_missing_packages<Telem: Telemetry>(
     Ok(())
 }
 
+/// A downloader that uses the packages vendored by `gleam deps vendor`, and
+/// doesn't use the network when offline.
+fn downloader(
+    paths: &ProjectPaths,
+    fs: Box<ProjectIO>,
+    manifest: &Manifest,
+    repositories: &hex::Repositories,
+    offline: Offline,
+) -> hex::Downloader {
+    let http: Box<dyn gleam_core::io::HttpClient> = match offline {
+        Offline::Yes => CachingHttpClient::boxed(true),
+        Offline::No => HttpClient::boxed(),
+    };
+    let vendor_directory = manifest
+        .vendor
+        .as_ref()
+        .map(|vendor| paths.manifest_directory().join(vendor));
+    hex::Downloader::new(fs.clone(), fs, http, Untar::boxed(), paths.clone())
+        .with_repositories(repositories.clone())
+        .with_vendor_directory(vendor_directory)
+}
+
 fn remove_extra_packages<Telem: Telemetry>(
     paths: &ProjectPaths,
     local: &LocalPackages,
@@ -567,7 +714,7 @@ fn read_manifest_from_disc(paths: &ProjectPaths) -> Result<Manifest> {
 
 fn write_manifest_to_disc(paths: &ProjectPaths, manifest: &Manifest) -> Result<()> {
     let path = paths.manifest();
-    fs::write(&path, &manifest.to_toml(paths.root()))
+    fs::write(&path, &manifest.to_toml(paths.manifest_directory()))
 }
 
 // This is the container for locally pinned packages, representing the current contents of
@@ -783,6 +930,9 @@ struct ProvidedPackage {
     version: Version,
     source: ProvidedPackageSource,
     requirements: HashMap<EcoString, hexpm::version::Range>,
+    /// The repositories of the Hex packages it requires that are not fetched
+    /// from hex.pm.
+    repositories: HashMap<EcoString, EcoString>,
 }
 
 #[derive(Clone, Eq, Debug)]
@@ -803,7 +953,7 @@ impl ProvidedPackage {
                         requirement: version.clone(),
                         optional: false,
                         app: None,
-                        repository: None,
+                        repository: self.repositories.get(name).map(|name| name.to_string()),
                     },
                 )
             })
@@ -1121,10 +1271,19 @@ fn provide_package(
     };
     // Walk the requirements of the package
     let mut requirements = HashMap::new();
+    let mut repositories = HashMap::new();
     parents.push(package_name);
     for (name, requirement) in config.dependencies.into_iter() {
         let version = match requirement {
-            Requirement::Hex { version } => version,
+            Requirement::Hex {
+                version,
+                repository,
+            } => {
+                if let Some(repository) = repository {
+                    let _ = repositories.insert(name.clone(), repository);
+                }
+                version
+            }
             Requirement::Path { path } => {
                 // Recursively walk local packages
                 provide_local_package(
@@ -1152,6 +1311,7 @@ fn provide_package(
             version: config.version,
             source: package_source,
             requirements,
+            repositories,
         },
     );
     // Return the version
@@ -1215,14 +1375,27 @@ fn find_deps_to_unlock(
 async fn lookup_package(
     name: String,
     version: Version,
+    repository: Option<EcoString>,
     provided: &HashMap<EcoString, ProvidedPackage>,
+    repositories: &hex::Repositories,
+    offline: Offline,
+    offline_packages: &OfflinePackages,
 ) -> Result<ManifestPackage> {
     match provided.get(name.as_str()) {
         Some(provided_package) => Ok(provided_package.to_manifest_package(name.as_str())),
         None => {
-            let config = hexpm::Config::new();
+            let http = CachingHttpClient::new(matches!(offline, Offline::Yes));
+            let hex_repository = repositories.get(repository.as_deref())?;
             let release =
-                hex::get_package_release(&name, &version, &config, &HttpClient::new()).await?;
+                match hex::get_package_release(&name, &version, hex_repository, &http).await {
+                    Ok(release) => release,
+                    Err(error) => {
+                        return offline_packages
+                            .manifest_package(&name, &version)
+                            .cloned()
+                            .ok_or(error);
+                    }
+                };
             let build_tools = release
                 .meta
                 .build_tools
@@ -1242,33 +1415,153 @@ async fn lookup_package(
                 requirements,
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(release.outer_checksum),
+                    repository,
                 },
             })
         }
     }
 }
 
+/// The Hex packages locked in the manifest whose tarballs have already been
+/// downloaded or vendored. When offline these are used for any package Hex
+/// has no cached response for, so a project that has been built before can
+/// be resolved again without the network.
+#[derive(Debug, Default)]
+struct OfflinePackages {
+    packages: HashMap<EcoString, ManifestPackage>,
+    /// The locked versions of all the packages in the manifest, which the
+    /// requirements of the offline packages are pinned to.
+    versions: HashMap<EcoString, Version>,
+}
+
+impl OfflinePackages {
+    /// Reads the packages from the manifest on disc. There are none when
+    /// online or if the project has no manifest yet.
+    fn read(paths: &ProjectPaths, offline: Offline) -> Result<Self> {
+        if matches!(offline, Offline::No) || !paths.manifest().is_file() {
+            return Ok(Self::default());
+        }
+        let manifest = read_manifest_from_disc(paths)?;
+        let vendor_directory = manifest
+            .vendor
+            .as_ref()
+            .map(|vendor| paths.manifest_directory().join(vendor));
+        Ok(Self::new(&manifest, vendor_directory.as_deref()))
+    }
+
+    fn new(manifest: &Manifest, vendor_directory: Option<&Utf8Path>) -> Self {
+        let is_downloaded = |package: &ManifestPackage| match &package.source {
+            ManifestPackageSource::Hex { outer_checksum, .. } => {
+                paths::global_package_cache_package_tarball(outer_checksum).is_file()
+                    || vendor_directory.is_some_and(|directory| {
+                        paths::vendored_package_tarball(directory, &package.name, &package.version)
+                            .is_file()
+                    })
+            }
+            ManifestPackageSource::Git { .. } | ManifestPackageSource::Local { .. } => false,
+        };
+
+        Self {
+            packages: manifest
+                .packages
+                .iter()
+                .filter(|package| is_downloaded(package))
+                .map(|package| (package.name.clone(), package.clone()))
+                .collect(),
+            versions: manifest
+                .packages
+                .iter()
+                .map(|package| (package.name.clone(), package.version.clone()))
+                .collect(),
+        }
+    }
+
+    fn manifest_package(&self, name: &str, version: &Version) -> Option<&ManifestPackage> {
+        self.packages
+            .get(name)
+            .filter(|package| &package.version == version)
+    }
+
+    /// The package as Hex would describe it, with its locked version as the
+    /// only release.
+    fn hex_package(&self, name: &str, repository: &str) -> Option<hexpm::Package> {
+        let package = self.packages.get(name)?;
+        let ManifestPackageSource::Hex {
+            outer_checksum,
+            repository: package_repository,
+        } = &package.source
+        else {
+            return None;
+        };
+        if package_repository
+            .as_deref()
+            .unwrap_or(hex::DEFAULT_REPOSITORY)
+            != repository
+        {
+            return None;
+        }
+
+        let requirements = package
+            .requirements
+            .iter()
+            .filter_map(|requirement| {
+                let version = self.versions.get(requirement)?;
+                let dependency = hexpm::Dependency {
+                    requirement: version.clone().into(),
+                    optional: false,
+                    app: None,
+                    repository: None,
+                };
+                Some((requirement.to_string(), dependency))
+            })
+            .collect();
+        let release = hexpm::Release {
+            version: package.version.clone(),
+            requirements,
+            retirement_status: None,
+            outer_checksum: outer_checksum.0.clone(),
+            meta: (),
+        };
+        Some(hexpm::Package {
+            name: name.into(),
+            repository: repository.into(),
+            releases: vec![release],
+        })
+    }
+}
+
 struct PackageFetcher {
-    runtime_cache: RefCell<HashMap<String, Rc<hexpm::Package>>>,
+    runtime_cache: RefCell<HashMap<(String, String), Rc<hexpm::Package>>>,
     runtime: tokio::runtime::Handle,
-    http: HttpClient,
+    http: CachingHttpClient,
+    offline: Offline,
+    offline_packages: OfflinePackages,
+    repositories: hex::Repositories,
 }
 
 impl PackageFetcher {
-    pub fn new(runtime: tokio::runtime::Handle) -> Self {
+    pub fn new(
+        runtime: tokio::runtime::Handle,
+        offline: Offline,
+        offline_packages: OfflinePackages,
+        repositories: hex::Repositories,
+    ) -> Self {
         Self {
             runtime_cache: RefCell::new(HashMap::new()),
             runtime,
-            http: HttpClient::new(),
+            http: CachingHttpClient::new(matches!(offline, Offline::Yes)),
+            offline,
+            offline_packages,
+            repositories,
         }
     }
 
     /// Caches the result of `get_dependencies` so that we don't need to make a network request.
     /// Currently dependencies are fetched during initial version resolution, and then during check
     /// for major version availability.
-    fn cache_package(&self, package: &str, result: Rc<hexpm::Package>) {
+    fn cache_package(&self, package: &str, repository: &str, result: Rc<hexpm::Package>) {
         let mut runtime_cache = self.runtime_cache.borrow_mut();
-        let _ = runtime_cache.insert(package.to_string(), result);
+        let _ = runtime_cache.insert((repository.to_string(), package.to_string()), result);
     }
 }
 
@@ -1299,29 +1592,69 @@ impl TarUnpacker for Untar {
 }
 
 impl dependency::PackageFetcher for PackageFetcher {
-    fn get_dependencies(&self, package: &str) -> Result<Rc<hexpm::Package>, PackageFetchError> {
+    fn get_dependencies(
+        &self,
+        package: &str,
+        repository: &str,
+    ) -> Result<Rc<hexpm::Package>, PackageFetchError> {
         {
             let runtime_cache = self.runtime_cache.borrow();
-            let result = runtime_cache.get(package);
+            let result = runtime_cache.get(&(repository.to_string(), package.to_string()));
 
             if let Some(result) = result {
                 return Ok(result.clone());
             }
         }
 
-        tracing::debug!(package = package, "looking_up_hex_package");
-        let config = hexpm::Config::new();
-        let request = hexpm::repository_v2_get_package_request(package, None, &config);
-        let response = self
-            .runtime
-            .block_on(self.http.send(request))
+        tracing::debug!(
+            package = package,
+            repository = repository,
+            "looking_up_hex_package"
+        );
+        let hex_repository = self
+            .repositories
+            .get(Some(repository))
             .map_err(PackageFetchError::fetch_error)?;
+        let request = hexpm::repository_v2_get_package_request(
+            package,
+            hex_repository.credentials.as_ref(),
+            &hex_repository.config,
+        );
+        let response = match self.runtime.block_on(self.http.send(request)) {
+            Ok(response) => response,
+            // Packages Hex was never asked about while online can still be
+            // used if they are locked in the manifest and downloaded.
+            Err(error) => {
+                let pkg = self
+                    .offline_packages
+                    .hex_package(package, repository)
+                    .ok_or_else(|| PackageFetchError::fetch_error(error))?;
+                let pkg = Rc::new(pkg);
+                self.cache_package(package, repository, Rc::clone(&pkg));
+                return Ok(pkg);
+            }
+        };
+
+        let mut pkg =
+            hexpm::repository_v2_get_package_response(response, &hex_repository.public_key)
+                .map_err(|e| PackageFetchError::from_api_error(e, package))?;
+
+        // When offline only the versions that have already been downloaded
+        // can be used.
+        if let Offline::Yes = self.offline {
+            pkg.releases.retain(|release| {
+                let checksum = Base16Checksum(release.outer_checksum.clone());
+                paths::global_package_cache_package_tarball(&checksum).is_file()
+                    || self
+                        .offline_packages
+                        .manifest_package(package, &release.version)
+                        .is_some()
+            });
+        }
 
-        let pkg = hexpm::repository_v2_get_package_response(response, HEXPM_PUBLIC_KEY)
-            .map_err(|e| PackageFetchError::from_api_error(e, package))?;
         let pkg = Rc::new(pkg);
         let pkg_ref = Rc::clone(&pkg);
-        self.cache_package(package, pkg);
+        self.cache_package(package, repository, pkg);
         Ok(pkg_ref)
     }
 }
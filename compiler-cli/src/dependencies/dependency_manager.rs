@@ -6,8 +6,8 @@ use futures::future;
 use gleam_core::{
     Error, Result,
     build::{Mode, Telemetry},
-    config::PackageConfig,
-    dependency,
+    config::{PackageConfig, Workspace},
+    dependency, hex,
     manifest::{Manifest, ManifestPackageSource, PackageChanges, Resolved},
     paths::ProjectPaths,
     requirement::Requirement,
@@ -21,9 +21,9 @@ use crate::{
 };
 
 use super::{
-    CheckMajorVersions, LocalPackages, UseManifest, add_missing_packages, is_same_requirements,
-    lookup_package, path_dependency_configs_unchanged, provide_git_package, provide_local_package,
-    read_manifest_from_disc, remove_extra_packages, unlock_packages,
+    CheckMajorVersions, LocalPackages, Offline, OfflinePackages, UseManifest, add_missing_packages,
+    is_same_requirements, lookup_package, path_dependency_configs_unchanged, provide_git_package,
+    provide_local_package, read_manifest_from_disc, remove_extra_packages, unlock_packages,
 };
 
 /// Verifies that all specified packages exist in the manifest.
@@ -50,6 +50,9 @@ pub struct DependencyManagerConfig {
     /// When set to `Yes`, the cli will check for major version updates of direct dependencies and
     /// print them to the console if the major versions are not upgradeable due to constraints.
     pub check_major_versions: CheckMajorVersions,
+    /// When set to `Yes` dependencies are resolved and downloaded without
+    /// using the network.
+    pub offline: Offline,
 }
 
 impl DependencyManagerConfig {
@@ -57,17 +60,20 @@ impl DependencyManagerConfig {
         self,
         runtime: tokio::runtime::Handle,
         package_fetcher: P,
+        repositories: hex::Repositories,
         telemetry: Telem,
         mode: Mode,
     ) -> DependencyManager<Telem, P> {
         DependencyManager {
             runtime,
             package_fetcher,
+            repositories,
             telemetry,
 
             mode,
             use_manifest: self.use_manifest,
             check_major_versions: self.check_major_versions,
+            offline: self.offline,
         }
     }
 }
@@ -75,10 +81,12 @@ impl DependencyManagerConfig {
 pub struct DependencyManager<Telem, P> {
     runtime: tokio::runtime::Handle,
     package_fetcher: P,
+    repositories: hex::Repositories,
     mode: Mode,
     use_manifest: UseManifest,
     telemetry: Telem,
     check_major_versions: CheckMajorVersions,
+    offline: Offline,
 }
 
 impl<Telem, P> DependencyManager<Telem, P>
@@ -115,7 +123,7 @@ where
                 let same_requirements = is_same_requirements(
                     &existing_manifest.requirements,
                     &config_dependencies,
-                    paths.root(),
+                    paths.manifest_directory(),
                 )?;
 
                 // If the manifest is to be used and the requirements have not changed then there's
@@ -134,12 +142,15 @@ where
         };
 
         tracing::debug!("manifest_outdated");
-        let new_manifest = self.perform_version_resolution(
+        let mut new_manifest = self.perform_version_resolution(
             paths,
             config,
             manifest_for_resolver,
             packages_to_update,
         )?;
+        // The packages stay vendored, with any new ones being downloaded until
+        // `gleam deps vendor` is run again.
+        new_manifest.vendor = existing_manifest.vendor.clone();
         let resolved = Resolved {
             package_changes: PackageChanges::between_manifests(&existing_manifest, &new_manifest),
             manifest: new_manifest,
@@ -168,7 +179,6 @@ where
 
         // Read the project config
         let mut config = crate::config::read(paths.root_config())?;
-        let project_name = config.name.clone();
 
         // Insert the new packages to add, if it exists
         if let Some((packages, dev)) = new_package {
@@ -181,6 +191,21 @@ where
             }
         }
 
+        // The dependencies of all the members of a workspace are resolved
+        // together, so that they all use the same versions, and each member
+        // then uses the part of the manifest it needs.
+        let member_requirements = match paths.workspace_root() {
+            Some(workspace_root) => {
+                let io = ProjectIO::new();
+                let workspace = Workspace::read(workspace_root, &io)?;
+                let requirements = config.all_direct_dependencies()?;
+                config = workspace.dependency_config(&io, paths.root(), &config)?;
+                Some(requirements)
+            }
+            None => None,
+        };
+        let project_name = config.name.clone();
+
         // Determine what versions we need
         let resolved = self.resolve_versions(paths, &config, packages_to_update)?;
         let local = LocalPackages::read_from_disc(paths)?;
@@ -195,7 +220,9 @@ where
             &resolved.manifest,
             &local,
             project_name,
+            &self.repositories,
             &self.telemetry,
+            self.offline,
         ))?;
 
         if resolved.any_changes() {
@@ -225,7 +252,11 @@ where
                 );
             }
         }
-        Ok(resolved.manifest)
+
+        match member_requirements {
+            Some(requirements) => Ok(resolved.manifest.required_by(requirements)),
+            None => Ok(resolved.manifest),
+        }
     }
 
     fn perform_version_resolution(
@@ -247,11 +278,36 @@ where
         let mut provided_packages = HashMap::new();
         // The version requires of the current project
         let mut root_requirements = HashMap::new();
+        // The repositories of the packages not fetched from hex.pm, starting
+        // with those already locked in the manifest
+        let mut repositories: dependency::PackageRepositories = manifest
+            .into_iter()
+            .flat_map(|manifest| &manifest.packages)
+            .filter(|package| locked.contains_key(&package.name))
+            .filter_map(|package| match &package.source {
+                ManifestPackageSource::Hex {
+                    repository: Some(repository),
+                    ..
+                } => Some((package.name.clone(), repository.clone())),
+                ManifestPackageSource::Hex { .. }
+                | ManifestPackageSource::Git { .. }
+                | ManifestPackageSource::Local { .. } => None,
+            })
+            .collect();
 
         // Populate the provided_packages and root_requirements maps
         for (name, requirement) in dependencies.into_iter() {
             let version = match requirement {
-                Requirement::Hex { version } => version,
+                Requirement::Hex {
+                    version,
+                    repository,
+                } => {
+                    match repository {
+                        Some(repository) => _ = repositories.insert(name.clone(), repository),
+                        None => _ = repositories.remove(&name),
+                    }
+                    version
+                }
                 Requirement::Path { path } => provide_local_package(
                     name.clone(),
                     &path,
@@ -304,18 +360,32 @@ where
             config.name.clone(),
             root_requirements.into_iter(),
             &locked,
+            &mut repositories,
         )?;
 
         // Convert the hex packages and local packages into manifest packages
-        let manifest_packages = self.runtime.block_on(future::try_join_all(
-            resolved
-                .into_iter()
-                .map(|(name, version)| lookup_package(name, version, &provided_packages)),
-        ))?;
+        let offline_packages = OfflinePackages::read(project_paths, self.offline)?;
+        let manifest_packages =
+            self.runtime
+                .block_on(future::try_join_all(resolved.into_iter().map(
+                    |(name, version)| {
+                        let repository = repositories.get(name.as_str()).cloned();
+                        lookup_package(
+                            name,
+                            version,
+                            repository,
+                            &provided_packages,
+                            &self.repositories,
+                            self.offline,
+                            &offline_packages,
+                        )
+                    },
+                )))?;
 
         let manifest = Manifest {
             packages: manifest_packages,
             requirements: config.all_direct_dependencies()?,
+            vendor: None,
         };
 
         Ok(manifest)
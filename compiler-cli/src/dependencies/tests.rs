@@ -33,6 +33,7 @@ fn list_manifest_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -43,6 +44,7 @@ fn list_manifest_format() {
                 requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -53,9 +55,11 @@ fn list_manifest_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     };
     list_manifest_packages(&mut buffer, manifest).unwrap();
     assert_eq!(
@@ -83,6 +87,7 @@ fn tree_format() {
                 requirements: vec!["gleam_regexp".into(), "gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -93,6 +98,7 @@ fn tree_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -103,9 +109,11 @@ fn tree_format() {
                 requirements: vec!["gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     };
 
     let options = TreeOptions {
@@ -146,6 +154,7 @@ fn tree_package_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -156,6 +165,7 @@ fn tree_package_format() {
                 requirements: vec!["gleam_stdlib".into(), "gleam_regexp".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -166,9 +176,11 @@ fn tree_package_format() {
                 requirements: vec!["gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     };
     let options = TreeOptions {
         package: Some("gleam_regexp".to_string()),
@@ -206,6 +218,7 @@ fn tree_invert_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -216,6 +229,7 @@ fn tree_invert_format() {
                 requirements: vec!["gleam_stdlib".into(), "gleam_regexp".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -226,9 +240,11 @@ fn tree_invert_format() {
                 requirements: vec!["gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     };
     let options = TreeOptions {
         package: None,
@@ -268,6 +284,7 @@ fn list_tree_invalid_package_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -278,6 +295,7 @@ fn list_tree_invalid_package_format() {
                 requirements: vec!["gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -288,9 +306,11 @@ fn list_tree_invalid_package_format() {
                 requirements: vec!["gleam_regexp".into(), "gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     };
     let options = TreeOptions {
         package: Some("zzzzzz".to_string()),
@@ -372,6 +392,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -382,6 +403,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -392,9 +414,11 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     };
     let mut extra = LocalPackages {
         packages: [
@@ -416,6 +440,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
             &ManifestPackage {
@@ -426,6 +451,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
         ]
@@ -453,6 +479,7 @@ fn extra_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -463,9 +490,11 @@ fn extra_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![4, 5]),
+                    repository: None,
                 },
             },
         ],
+        vendor: None,
     });
     extra.sort();
     assert_eq!(
@@ -636,6 +665,7 @@ fn provided_local_to_hex() {
             ),
         ]
         .into(),
+        repositories: [("req_2".into(), "internal".into())].into(),
     };
 
     let hex_package = hexpm::Package {
@@ -662,7 +692,7 @@ fn provided_local_to_hex() {
                         requirement: hexpm::version::Range::new("== 1.0.0".into()).unwrap(),
                         optional: false,
                         app: None,
-                        repository: None,
+                        repository: Some("internal".into()),
                     },
                 ),
             ]
@@ -695,6 +725,7 @@ fn provided_git_to_hex() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let hex_package = hexpm::Package {
@@ -753,6 +784,7 @@ fn provided_local_to_manifest() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let manifest_package = ManifestPackage {
@@ -791,6 +823,7 @@ fn provided_git_to_manifest() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let manifest_package = ManifestPackage {
@@ -944,6 +977,7 @@ fn create_testable_unlock_manifest(
             requirements,
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
         })
         .collect();
@@ -955,6 +989,7 @@ fn create_testable_unlock_manifest(
                 name,
                 Requirement::Hex {
                     version: hexpm::version::Range::new(range.into()).unwrap(),
+                    repository: None,
                 },
             )
         })
@@ -963,6 +998,7 @@ fn create_testable_unlock_manifest(
     Manifest {
         packages: manifest_packages,
         requirements: root_requirements,
+        vendor: None,
     }
 }
 
@@ -1250,6 +1286,7 @@ fn manifest_package(name: &str, version: &str, requirements: Vec<EcoString>) ->
         requirements,
         source: ManifestPackageSource::Hex {
             outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+            repository: None,
         },
     }
 }
@@ -1267,6 +1304,7 @@ fn package_config(
         documentation: Docs { pages: vec![] },
         dependencies,
         dev_dependencies,
+        hex_repositories: HashMap::new(),
         repository: None,
         links: vec![],
         erlang: ErlangConfig {
@@ -1313,6 +1351,7 @@ fn test_remove_do_nothing() {
             manifest_package("a", "1.0.0", vec![]),
             manifest_package("b", "2.0.8", vec![]),
         ],
+        vendor: None,
     };
 
     let manifest_copy = manifest.clone();
@@ -1330,6 +1369,7 @@ fn test_remove_simple() {
     let mut manifest = Manifest {
         requirements: HashMap::from([("a".into(), Requirement::hex("~>1.0").unwrap())]),
         packages: vec![manifest_package("a", "1.0.0", vec![])],
+        vendor: None,
     };
 
     remove_extra_requirements(&config, &mut manifest).unwrap();
@@ -1349,6 +1389,7 @@ fn test_remove_package_with_transitive_dependencies() {
             manifest_package("b", "1.2.3", vec!["c".into()]),
             manifest_package("c", "2.0.0", vec![]),
         ],
+        vendor: None,
     };
 
     remove_extra_requirements(&config, &mut manifest).unwrap();
@@ -1375,6 +1416,7 @@ fn test_remove_package_with_shared_transitive_dependencies() {
             manifest_package("c", "2.0.0", vec![]),
             manifest_package("d", "0.1.0", vec![]),
         ],
+        vendor: None,
     };
 
     remove_extra_requirements(&config, &mut manifest).unwrap();
@@ -1407,6 +1449,7 @@ fn test_remove_package_that_is_also_a_transitive_dependency() {
             manifest_package("c", "2.0.0", vec![]),
             manifest_package("d", "0.1.0", vec![]),
         ],
+        vendor: None,
     };
 
     let manifest_copy = manifest.clone();
@@ -1507,6 +1550,7 @@ fn test_ensure_packages_exist_locally_all_present() {
             manifest_package("package_b", "2.0.0", vec![]),
             manifest_package("package_c", "3.0.0", vec![]),
         ],
+        vendor: None,
     };
 
     let packages_to_check = vec!["package_a".into(), "package_b".into()];
@@ -1523,6 +1567,7 @@ fn test_ensure_packages_exist_locally_some_missing() {
             manifest_package("package_a", "1.0.0", vec![]),
             manifest_package("package_b", "2.0.0", vec![]),
         ],
+        vendor: None,
     };
 
     let packages_to_check = vec![
@@ -1542,3 +1587,83 @@ fn test_ensure_packages_exist_locally_some_missing() {
         _ => panic!("Expected PackagesToUpdateNotExist error"),
     }
 }
+
+#[test]
+fn offline_packages_are_resolved_from_the_manifest_and_vendored_tarballs() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create a temp directory");
+    let root_path = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf())
+        .expect("Path should be valid UTF-8");
+    let paths = ProjectPaths::new(root_path);
+    let vendor_directory = paths.vendor_directory();
+    std::fs::create_dir_all(&vendor_directory).expect("Failed to create vendor directory");
+
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![
+            manifest_package("offline_wibble", "1.2.0", vec!["offline_wobble".into()]),
+            manifest_package("offline_wobble", "0.3.0", vec![]),
+            manifest_package("offline_wubble", "2.0.0", vec![]),
+        ],
+        vendor: Some(Utf8PathBuf::from("vendor")),
+    };
+    write_manifest_to_disc(&paths, &manifest).expect("Failed to write manifest");
+    // `offline_wubble` was never downloaded, so it cannot be used
+    for (name, version) in [
+        ("offline_wibble", Version::new(1, 2, 0)),
+        ("offline_wobble", Version::new(0, 3, 0)),
+    ] {
+        let tarball = paths::vendored_package_tarball(&vendor_directory, name, &version);
+        fs::write(&tarball, "").expect("Failed to write tarball");
+    }
+
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let offline_packages =
+        OfflinePackages::read(&paths, Offline::Yes).expect("Failed to read offline packages");
+    let fetcher = PackageFetcher::new(
+        runtime.handle().clone(),
+        Offline::Yes,
+        offline_packages,
+        hex::Repositories::default(),
+    );
+
+    let package = dependency::PackageFetcher::get_dependencies(&fetcher, "offline_wibble", "hexpm")
+        .expect("Package should be resolved from the manifest");
+    let versions = package
+        .releases
+        .iter()
+        .map(|release| release.version.to_string())
+        .collect_vec();
+    assert_eq!(versions, vec!["1.2.0"]);
+    let requirements = package
+        .releases
+        .iter()
+        .flat_map(|release| &release.requirements)
+        .map(|(name, dependency)| (name.as_str(), dependency.requirement.to_pubgrub().clone()))
+        .collect_vec();
+    assert_eq!(
+        requirements,
+        vec![(
+            "offline_wobble",
+            pubgrub::Range::singleton(Version::new(0, 3, 0))
+        )]
+    );
+
+    assert!(
+        dependency::PackageFetcher::get_dependencies(&fetcher, "offline_wubble", "hexpm").is_err()
+    );
+
+    let offline_packages =
+        OfflinePackages::read(&paths, Offline::Yes).expect("Failed to read offline packages");
+    let package = runtime
+        .block_on(lookup_package(
+            "offline_wobble".into(),
+            Version::new(0, 3, 0),
+            None,
+            &HashMap::new(),
+            &hex::Repositories::default(),
+            Offline::Yes,
+            &offline_packages,
+        ))
+        .expect("Package should be looked up in the manifest");
+    assert_eq!(package, manifest_package("offline_wobble", "0.3.0", vec![]));
+}
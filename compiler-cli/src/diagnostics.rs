@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use camino::Utf8PathBuf;
+use gleam_core::{
+    Error, Result,
+    diagnostic::{json::JsonDiagnostic, sarif::SarifLog},
+    warning::{Warning, WarningEmitterIO},
+};
+
+use crate::{
+    cli,
+    fs::{self, ConsoleWarningEmitter},
+};
+
+thread_local! {
+    /// The warnings and errors waiting to be printed as a SARIF log.
+    static SARIF_REPORT: RefCell<(Vec<Warning>, Vec<Error>)> =
+        const { RefCell::new((vec![], vec![])) };
+}
+
+/// How errors and warnings are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum DiagnosticsFormat {
+    /// Human readable text, printed to stderr
+    #[default]
+    Human,
+    /// One JSON object per line, printed to stdout
+    Json,
+    /// A SARIF 2.1.0 log, printed to stdout once compilation has finished
+    Sarif,
+}
+
+impl DiagnosticsFormat {
+    pub fn warning_emitter(self) -> Rc<dyn WarningEmitterIO> {
+        match self {
+            Self::Human => Rc::new(ConsoleWarningEmitter),
+            Self::Json => Rc::new(JsonWarningEmitter),
+            Self::Sarif => Rc::new(SarifWarningEmitter),
+        }
+    }
+
+    pub fn print_error(self, error: &Error) {
+        match self {
+            Self::Human => {
+                let stderr = cli::stderr_buffer_writer();
+                let mut buffer = stderr.buffer();
+                error.pretty(&mut buffer);
+                stderr.print(&buffer).expect("Error writing");
+            }
+            Self::Json => JsonDiagnostic::from_error(error)
+                .iter()
+                .for_each(print_json),
+            Self::Sarif => SARIF_REPORT.with_borrow_mut(|(_, errors)| errors.push(error.clone())),
+        }
+    }
+
+    /// Prints the diagnostics that are printed all together rather than as
+    /// soon as they are found. This is to be called once compilation has
+    /// finished.
+    ///
+    pub fn flush(self) {
+        match self {
+            Self::Human | Self::Json => (),
+            Self::Sarif => {
+                let (warnings, errors) = SARIF_REPORT.take();
+                // Paths are written relative to the directory the command is
+                // run from, which is usually the root of the repository.
+                let root = fs::get_current_directory().unwrap_or_else(|_| Utf8PathBuf::new());
+                let log = SarifLog::new(&root, &warnings, &errors);
+                let json = serde_json::to_string_pretty(&log).expect("SARIF serialisation");
+                let mut stdout = std::io::stdout().lock();
+                writeln!(stdout, "{json}").expect("Writing SARIF log to stdout");
+            }
+        }
+    }
+
+    /// Returns the result of a command. When printing machine readable
+    /// diagnostics any error is printed here and the program exits, so that it
+    /// isn't then printed again as text.
+    ///
+    pub fn report(self, result: Result<()>) -> Result<()> {
+        match (self, result) {
+            (Self::Human, result) => result,
+            (Self::Json | Self::Sarif, Ok(())) => {
+                self.flush();
+                Ok(())
+            }
+            (Self::Json | Self::Sarif, Err(error)) => {
+                tracing::error!(error = ?error, "Failed");
+                self.print_error(&error);
+                self.flush();
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct JsonWarningEmitter;
+
+impl WarningEmitterIO for JsonWarningEmitter {
+    fn emit_warning(&self, warning: Warning) {
+        print_json(&JsonDiagnostic::from_warning(&warning));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SarifWarningEmitter;
+
+impl WarningEmitterIO for SarifWarningEmitter {
+    fn emit_warning(&self, warning: Warning) {
+        SARIF_REPORT.with_borrow_mut(|(warnings, _)| warnings.push(warning));
+    }
+}
+
+fn print_json(diagnostic: &JsonDiagnostic) {
+    let json = serde_json::to_string(diagnostic).expect("Diagnostic JSON serialisation");
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{json}").expect("Writing diagnostic to stdout");
+}
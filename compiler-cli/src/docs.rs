@@ -67,7 +67,11 @@ pub fn build(paths: &ProjectPaths, options: BuildOptions) -> Result<()> {
 
     let out = paths.build_documentation_directory(&config.name);
 
-    let manifest = crate::build::download_dependencies(paths, cli::Reporter::new())?;
+    let manifest = crate::build::download_dependencies(
+        paths,
+        cli::Reporter::new(),
+        crate::dependencies::Offline::No,
+    )?;
     let dependencies = manifest
         .packages
         .iter()
@@ -192,7 +196,11 @@ pub fn publish(paths: &ProjectPaths) -> Result<()> {
     // Reset the build directory so we know the state of the project
     crate::fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, config.target))?;
 
-    let manifest = crate::build::download_dependencies(paths, cli::Reporter::new())?;
+    let manifest = crate::build::download_dependencies(
+        paths,
+        cli::Reporter::new(),
+        crate::dependencies::Offline::No,
+    )?;
     let dependencies = manifest
         .packages
         .iter()
@@ -30,7 +30,11 @@ pub fn escript(paths: &ProjectPaths) -> Result<()> {
     // Reset the directories to ensure we have a clean slate and no old code
     fs::delete_directory(&build)?;
 
-    let manifest = crate::build::download_dependencies(paths, crate::cli::Reporter::new())?;
+    let manifest = crate::build::download_dependencies(
+        paths,
+        crate::cli::Reporter::new(),
+        crate::dependencies::Offline::No,
+    )?;
 
     // Build project in production mode
     let build_options = Options {
@@ -147,7 +151,11 @@ pub(crate) fn erlang_shipment(paths: &ProjectPaths) -> Result<()> {
             target: Some(target),
             no_print_progress: false,
         },
-        crate::build::download_dependencies(paths, crate::cli::Reporter::new())?,
+        crate::build::download_dependencies(
+            paths,
+            crate::cli::Reporter::new(),
+            crate::dependencies::Offline::No,
+        )?,
     )?;
 
     for entry in fs::read_dir(&build)?.filter_map(Result::ok) {
@@ -253,7 +261,11 @@ pub fn package_interface(paths: &ProjectPaths, out: Utf8PathBuf) -> Result<()> {
             root_target_support: TargetSupport::Enforced,
             no_print_progress: false,
         },
-        crate::build::download_dependencies(paths, crate::cli::Reporter::new())?,
+        crate::build::download_dependencies(
+            paths,
+            crate::cli::Reporter::new(),
+            crate::dependencies::Offline::No,
+        )?,
     )?;
     built.root_package.attach_doc_and_module_comments();
 
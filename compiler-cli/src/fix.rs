@@ -32,7 +32,11 @@ pub fn run(paths: &ProjectPaths) -> Result<()> {
             target: None,
             no_print_progress: false,
         },
-        build::download_dependencies(paths, cli::Reporter::new())?,
+        build::download_dependencies(
+            paths,
+            cli::Reporter::new(),
+            crate::dependencies::Offline::No,
+        )?,
         warnings.clone(),
     )?;
     let warnings = warnings.take();
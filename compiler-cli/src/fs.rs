@@ -13,7 +13,7 @@ use gleam_core::{
     paths::ProjectPaths,
     warning::WarningEmitterIO,
 };
-use gleam_language_server::{DownloadDependencies, Locker, MakeLocker};
+use gleam_language_server::{DownloadDependencies, Locker, MakeLocker, RunProgram, Runnable};
 use regex::Regex;
 use std::{
     collections::HashSet,
@@ -26,7 +26,12 @@ use std::{
 
 use camino::{ReadDirUtf8, Utf8Path, Utf8PathBuf};
 
-use crate::{beam_compiler::BeamCompilerInstance, dependencies, lsp::LspLocker};
+use crate::{
+    beam_compiler::BeamCompilerInstance,
+    dependencies,
+    lsp::LspLocker,
+    run::{self, Which},
+};
 
 #[cfg(test)]
 mod tests;
@@ -240,22 +245,116 @@ impl CommandExecutor for ProjectIO {
 
         match result {
             Ok(status) => Ok(status.code().unwrap_or_default()),
+            Err(error) => Err(command_error(program, error)),
+        }
+    }
+}
 
-            Err(error) => Err(match error.kind() {
-                io::ErrorKind::NotFound => Error::ShellProgramNotFound {
-                    program,
-                    os: get_os(),
-                },
+pub(crate) fn command_error(program: String, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::NotFound => Error::ShellProgramNotFound {
+            program,
+            os: get_os(),
+        },
 
-                other => Error::ShellCommand {
-                    program,
-                    reason: ShellCommandFailureReason::IoError(other),
-                },
-            }),
-        }
+        other => Error::ShellCommand {
+            program,
+            reason: ShellCommandFailureReason::IoError(other),
+        },
     }
 }
 
+impl RunProgram for ProjectIO {
+    fn run_program(&self, paths: &ProjectPaths, runnable: &Runnable) -> Result<()> {
+        let paths = paths.clone();
+        let runnable = runnable.clone();
+
+        // Building the project can take a while, so it's built and the program
+        // run in the background, and the language server can keep on working
+        // meanwhile. Any error is written to stderr, where editors show the
+        // language server's logs along with the program's output.
+        let _ = std::thread::spawn(move || {
+            if let Err(error) = build_and_run_program(&paths, &runnable) {
+                let buffer_writer = crate::cli::stderr_buffer_writer();
+                let mut buffer = buffer_writer.buffer();
+                error.pretty(&mut buffer);
+                buffer_writer
+                    .print(&buffer)
+                    .expect("Writing error to stderr");
+            }
+        });
+        Ok(())
+    }
+}
+
+fn build_and_run_program(paths: &ProjectPaths, runnable: &Runnable) -> Result<()> {
+    let run::Prepared {
+        options,
+        manifest,
+        program,
+    } = match runnable {
+        Runnable::Main { module, target } => run::prepare(
+            paths,
+            vec![],
+            *target,
+            None,
+            Some(module.to_string()),
+            Which::Src,
+            true,
+            dependencies::Offline::No,
+        )?,
+        Runnable::Test {
+            module,
+            function,
+            target,
+        } => {
+            // Only the test the code lens is for is run.
+            let filter = run::TestFilter {
+                modules: vec![format!("{module}.{function}")],
+                ..Default::default()
+            };
+            let options = run::TestOptions {
+                filter,
+                ..Default::default()
+            };
+            run::prepare(
+                paths,
+                vec![],
+                *target,
+                None,
+                None,
+                Which::Test(options),
+                true,
+                dependencies::Offline::No,
+            )?
+        }
+    };
+    let built =
+        crate::build::IncrementalBuild::without_output(paths, options, manifest)?.build()?;
+    let Command {
+        program,
+        args,
+        env,
+        cwd,
+        stdio: _,
+    } = program.command(paths, built)?;
+
+    tracing::debug!(program=program, args=?args.join(" "), env=?env, cwd=?cwd, "command_spawn");
+    // The language server talks to the editor over stdout, so anything the
+    // program prints is sent to stderr instead, where editors show the
+    // language server's logs.
+    let mut child = std::process::Command::new(&program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(io::stderr())
+        .envs(env.iter().map(|pair| (&pair.0, &pair.1)))
+        .current_dir(cwd.unwrap_or_else(|| paths.root().to_path_buf()))
+        .spawn()
+        .map_err(|error| command_error(program, error))?;
+    let _ = child.wait();
+    Ok(())
+}
+
 impl BeamCompilerIO for ProjectIO {
     fn compile_beam(
         &self,
@@ -298,6 +397,7 @@ impl DownloadDependencies for ProjectIO {
             dependencies::DependencyManagerConfig {
                 use_manifest: dependencies::UseManifest::Yes,
                 check_major_versions: dependencies::CheckMajorVersions::No,
+                offline: dependencies::Offline::No,
             },
         )
     }
@@ -9,8 +9,9 @@ use camino::Utf8PathBuf;
 use gleam_core::{
     Error, Result,
     error::{FileIoAction, FileKind},
+    paths,
 };
-use http::{Request, Response};
+use http::{Request, Response, StatusCode, Uri};
 use reqwest::{Certificate, Client};
 
 use crate::fs;
@@ -55,6 +56,55 @@ impl gleam_core::io::HttpClient for HttpClient {
     }
 }
 
+/// A HTTP client for the read-only parts of the Hex API used to resolve
+/// dependencies. Successful responses are kept in the global cache so that
+/// when offline they can be used in place of sending the request again.
+///
+#[derive(Debug)]
+pub struct CachingHttpClient {
+    offline: bool,
+}
+
+impl CachingHttpClient {
+    pub fn new(offline: bool) -> Self {
+        Self { offline }
+    }
+
+    pub fn boxed(offline: bool) -> Box<Self> {
+        Box::new(Self::new(offline))
+    }
+}
+
+#[async_trait]
+impl gleam_core::io::HttpClient for CachingHttpClient {
+    async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>> {
+        let path = response_cache_path(request.uri());
+
+        if self.offline {
+            tracing::debug!(url = request.uri().to_string(), "http-send-offline");
+            if !path.is_file() {
+                return Err(Error::NotAvailableOffline {
+                    url: request.uri().to_string(),
+                });
+            }
+            return Ok(Response::new(fs::read_bytes(&path)?));
+        }
+
+        let response = HttpClient::new().send(request).await?;
+        if response.status() == StatusCode::OK {
+            fs::write_bytes(&path, response.body())?;
+        }
+        Ok(response)
+    }
+}
+
+/// Where the response to a request is kept, named after its URL.
+fn response_cache_path(uri: &Uri) -> Utf8PathBuf {
+    let host = uri.host().unwrap_or_default();
+    let path = uri.path().trim_matches('/');
+    paths::global_hexpm_responses_cache().join(format!("{host}/{path}.response"))
+}
+
 fn init_client() -> Result<&'static Client, Error> {
     if let Some(client) = REQWEST_CLIENT.get() {
         return Ok(client);
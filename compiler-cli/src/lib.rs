@@ -56,13 +56,16 @@
 extern crate pretty_assertions;
 
 mod add;
+mod api_diff;
 mod beam_compiler;
 mod build;
 mod build_lock;
 mod cli;
 mod compile_package;
 mod config;
+mod coverage;
 mod dependencies;
+mod diagnostics;
 mod docs;
 mod export;
 mod fix;
@@ -78,11 +81,16 @@ mod publish;
 mod remove;
 pub mod run;
 mod shell;
+mod test_report;
 mod text_layout;
+mod watch;
+mod workspace;
 
 use config::root_config;
+use diagnostics::DiagnosticsFormat;
 use fs::{get_current_directory, get_project_root};
 pub use gleam_core::error::{Error, Result};
+use test_report::{TestReport, TestReporter};
 
 use camino::Utf8PathBuf;
 use clap::{
@@ -157,6 +165,20 @@ pub enum Command {
 
         #[arg(long, help = no_print_progress_doc())]
         no_print_progress: bool,
+
+        /// Build again every time a file in the project changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Build each package of the workspace
+        #[arg(long, conflicts_with = "watch")]
+        workspace: bool,
+
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+
+        #[arg(long, value_enum, default_value_t, help = diagnostics_format_doc())]
+        diagnostics_format: DiagnosticsFormat,
     },
 
     /// Type check the project
@@ -164,6 +186,26 @@ pub enum Command {
         /// Which compilation target to use
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        /// Type check again every time a file in the project changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Type check each package of the workspace
+        #[arg(long, conflicts_with = "watch")]
+        workspace: bool,
+
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+
+        #[arg(
+            long,
+            alias = "format",
+            value_enum,
+            default_value_t,
+            help = diagnostics_format_doc()
+        )]
+        diagnostics_format: DiagnosticsFormat,
     },
 
     /// Publish the project to the Hex package repository
@@ -185,6 +227,27 @@ pub enum Command {
         /// Automatically accept confirmation prompts
         #[arg(short, long)]
         yes: bool,
+        /// Publish even if the version is too small a bump for the changes to
+        /// the public API since the previous release
+        #[arg(long)]
+        skip_semver_check: bool,
+    },
+
+    /// Compare the public API of the package with a previous release
+    ///
+    /// Every added, removed, and changed function, type, constructor, and
+    /// constant is listed along with the version bump it needs under semantic
+    /// versioning. If the version in `gleam.toml` is too small a bump for the
+    /// changes the command fails.
+    ApiDiff {
+        /// The release to compare against: a version published to Hex, or the
+        /// path of a package tarball or `package-interface.json` file.
+        /// Defaults to the latest release before the version in `gleam.toml`
+        #[arg(long)]
+        against: Option<String>,
+
+        #[arg(long, help = offline_doc())]
+        offline: bool,
     },
 
     /// Render HTML documentation for the package
@@ -297,6 +360,10 @@ pub enum Command {
         /// Only check if inputs are formatted correctly, erroring if they are not
         #[arg(long)]
         check: bool,
+
+        /// Format each package of the workspace
+        #[arg(long, conflicts_with_all = ["stdin", "files"])]
+        workspace: bool,
     },
 
     /// Rewrite deprecated Gleam code
@@ -325,13 +392,24 @@ pub enum Command {
         #[arg(long, help = no_print_progress_doc())]
         no_print_progress: bool,
 
+        /// Build and run again every time a file in the project changes
+        #[arg(long)]
+        watch: bool,
+
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+
         arguments: Vec<String>,
     },
 
     /// Run the project tests
     ///
     /// This command runs the `main` function from the `<PROJECT_NAME>_test` module.
-    #[command(trailing_var_arg = true)]
+    ///
+    /// When given filters, `--module`, `--only` or `--exclude` it instead
+    /// runs the public functions ending in `_test` that match them, found in
+    /// the modules of the `test` directory. Arguments for the tests can be
+    /// given after `--`.
     Test {
         /// Which compilation target to use
         #[arg(short, long, ignore_case = true, help = target_doc())]
@@ -341,6 +419,53 @@ pub enum Command {
         #[arg(long, ignore_case = true, help = runtime_doc())]
         runtime: Option<Runtime>,
 
+        /// Build and run the tests again every time a file in the project changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Run the tests of each package of the workspace
+        #[arg(long, conflicts_with_all = ["watch", "reporter", "coverage"])]
+        workspace: bool,
+
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+
+        #[arg(long, value_enum, default_value_t, help = diagnostics_format_doc())]
+        diagnostics_format: DiagnosticsFormat,
+
+        /// Only run the tests in these modules, or the single tests written as
+        /// `module.function`
+        filters: Vec<String>,
+
+        /// Only run the tests in this module, or the single test written as
+        /// `module.function`
+        #[arg(long = "module")]
+        modules: Vec<String>,
+
+        /// Only run the tests whose name contains this text
+        #[arg(long)]
+        only: Vec<String>,
+
+        /// Do not run the tests whose name contains this text
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Write a report of the results of the tests in this format
+        #[arg(long, value_enum, conflicts_with = "watch")]
+        reporter: Option<TestReporter>,
+
+        /// Where to write the report. Defaults to `build/test-results` with
+        /// the extension of the format
+        #[arg(long, requires = "reporter")]
+        report_path: Option<Utf8PathBuf>,
+
+        /// Record which functions and case clauses of the `src` modules the
+        /// tests run, writing an lcov report to `build/coverage/lcov.info` and
+        /// printing a summary
+        #[arg(long, conflicts_with = "watch")]
+        coverage: bool,
+
+        #[arg(last = true)]
         arguments: Vec<String>,
     },
 
@@ -360,6 +485,9 @@ pub enum Command {
         #[arg(long, help = no_print_progress_doc())]
         no_print_progress: bool,
 
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+
         arguments: Vec<String>,
     },
 
@@ -437,15 +565,40 @@ impl Command {
                 target,
                 warnings_as_errors,
                 no_print_progress,
-            } => {
-                let paths = find_project_paths(directory)?;
-                command_build(&paths, target, warnings_as_errors, no_print_progress)
-            }
-
-            Self::Check { target } => {
-                let paths = find_project_paths(directory)?;
-                command_check(&paths, target)
-            }
+                watch,
+                workspace,
+                offline,
+                diagnostics_format,
+            } => diagnostics_format.report(find_projects_paths(directory, workspace).and_then(
+                |projects| {
+                    projects.iter().try_for_each(|paths| {
+                        command_build(
+                            paths,
+                            target,
+                            warnings_as_errors,
+                            no_print_progress,
+                            watch,
+                            dependencies::Offline::from_flag(offline),
+                            diagnostics_format,
+                        )
+                    })
+                },
+            )),
+
+            Self::Check {
+                target,
+                watch,
+                workspace,
+                offline,
+                diagnostics_format,
+            } => diagnostics_format.report(find_projects_paths(directory, workspace).and_then(
+                |projects| {
+                    let offline = dependencies::Offline::from_flag(offline);
+                    projects.iter().try_for_each(|paths| {
+                        command_check(paths, target, watch, offline, diagnostics_format)
+                    })
+                },
+            )),
 
             Self::Docs(Docs::Build { open, target }) => {
                 let paths = find_project_paths(directory)?;
@@ -463,8 +616,22 @@ impl Command {
                 stdin,
                 files,
                 check,
+                workspace: false,
             } => format::run(stdin, check, files),
 
+            Self::Format {
+                stdin,
+                check,
+                workspace: true,
+                ..
+            } => {
+                let files = workspace::member_paths(&directory)?
+                    .iter()
+                    .map(|paths| paths.root().to_string())
+                    .collect();
+                format::run(stdin, check, files)
+            }
+
             Self::Fix => {
                 let paths = find_project_paths(directory)?;
                 fix::run(&paths)
@@ -475,9 +642,14 @@ impl Command {
                 dependencies::list(&paths)
             }
 
-            Self::Deps(Dependencies::Download) => {
+            Self::Deps(Dependencies::Download { offline }) => {
+                let paths = find_project_paths(directory)?;
+                download_dependencies(&paths, dependencies::Offline::from_flag(offline))
+            }
+
+            Self::Deps(Dependencies::Vendor { offline }) => {
                 let paths = find_project_paths(directory)?;
-                download_dependencies(&paths)
+                dependencies::vendor(&paths, dependencies::Offline::from_flag(offline))
             }
 
             Self::Deps(Dependencies::Outdated) => {
@@ -510,9 +682,12 @@ impl Command {
                 runtime,
                 module,
                 no_print_progress,
+                watch,
+                offline,
             } => {
                 let paths = find_project_paths(directory)?;
-                run::command(
+                let command = if watch { watch::run } else { run::command };
+                command(
                     &paths,
                     arguments,
                     target,
@@ -520,6 +695,8 @@ impl Command {
                     module,
                     run::Which::Src,
                     no_print_progress,
+                    dependencies::Offline::from_flag(offline),
+                    DiagnosticsFormat::Human,
                 )
             }
 
@@ -527,24 +704,78 @@ impl Command {
                 target,
                 arguments,
                 runtime,
-            } => {
-                let paths = find_project_paths(directory)?;
-                run::command(
+                workspace: true,
+                offline,
+                diagnostics_format,
+                filters,
+                modules,
+                only,
+                exclude,
+                ..
+            } => diagnostics_format.report(workspace::test(
+                &directory,
+                arguments,
+                target,
+                runtime,
+                run::TestFilter {
+                    modules: filters.into_iter().chain(modules).collect(),
+                    only,
+                    exclude,
+                },
+                dependencies::Offline::from_flag(offline),
+                diagnostics_format,
+            )),
+
+            Self::Test {
+                target,
+                arguments,
+                runtime,
+                watch,
+                workspace: false,
+                offline,
+                diagnostics_format,
+                filters,
+                modules,
+                only,
+                exclude,
+                reporter,
+                report_path,
+                coverage,
+            } => diagnostics_format.report(find_project_paths(directory).and_then(|paths| {
+                let command = if watch { watch::run } else { run::command };
+                let filter = run::TestFilter {
+                    modules: filters.into_iter().chain(modules).collect(),
+                    only,
+                    exclude,
+                };
+                let report = reporter.map(|reporter| TestReport {
+                    reporter,
+                    path: report_path.unwrap_or_else(|| reporter.default_path(&paths)),
+                });
+                let options = run::TestOptions {
+                    filter,
+                    report,
+                    coverage,
+                };
+                command(
                     &paths,
                     arguments,
                     target,
                     runtime,
                     None,
-                    run::Which::Test,
+                    run::Which::Test(options),
                     false,
+                    dependencies::Offline::from_flag(offline),
+                    diagnostics_format,
                 )
-            }
+            })),
 
             Self::Dev {
                 target,
                 arguments,
                 runtime,
                 no_print_progress,
+                offline,
             } => {
                 let paths = find_project_paths(directory)?;
                 run::command(
@@ -555,14 +786,28 @@ impl Command {
                     None,
                     run::Which::Dev,
                     no_print_progress,
+                    dependencies::Offline::from_flag(offline),
+                    DiagnosticsFormat::Human,
                 )
             }
 
-            Self::CompilePackage(opts) => compile_package::command(opts),
+            Self::CompilePackage(opts) => {
+                let diagnostics_format = opts.diagnostics_format;
+                diagnostics_format.report(compile_package::command(opts))
+            }
+
+            Self::Publish {
+                replace,
+                yes,
+                skip_semver_check,
+            } => {
+                let paths = find_project_paths(directory)?;
+                publish::command(&paths, replace, yes, skip_semver_check)
+            }
 
-            Self::Publish { replace, yes } => {
+            Self::ApiDiff { against, offline } => {
                 let paths = find_project_paths(directory)?;
-                publish::command(&paths, replace, yes)
+                api_diff::command(&paths, against, dependencies::Offline::from_flag(offline))
             }
 
             Self::PrintConfig => {
@@ -655,6 +900,14 @@ fn no_print_progress_doc() -> &'static str {
     "Don't print progress information"
 }
 
+fn offline_doc() -> &'static str {
+    "Don't use the network, resolving dependencies using only the packages already downloaded"
+}
+
+fn diagnostics_format_doc() -> &'static str {
+    "The format to print errors and warnings in"
+}
+
 fn runtime_doc() -> &'static str {
     "The JavaScript runtime to target. This is only available on the \
     JavaScript target"
@@ -739,6 +992,9 @@ pub struct CompilePackage {
     /// Skip Erlang to BEAM bytecode compilation
     #[arg(long = "no-beam")]
     skip_beam_compilation: bool,
+
+    #[arg(long, value_enum, default_value_t, help = diagnostics_format_doc())]
+    diagnostics_format: DiagnosticsFormat,
 }
 
 #[derive(Subcommand, Debug)]
@@ -747,7 +1003,20 @@ pub enum Dependencies {
     List,
 
     /// Download all dependency packages
-    Download,
+    Download {
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+    },
+
+    /// Copy the Hex packages of all dependencies into the `vendor` directory
+    ///
+    /// The packages are referenced from `manifest.toml`, so that they can be
+    /// built without using the network. Each package is checked against the
+    /// checksum in the manifest before it is used.
+    Vendor {
+        #[arg(long, help = offline_doc())]
+        offline: bool,
+    },
 
     /// List all outdated dependencies
     Outdated,
@@ -921,19 +1190,30 @@ pub fn main() {
     }
 }
 
-fn command_check(paths: &ProjectPaths, target: Option<Target>) -> Result<()> {
-    let _ = build::main(
+fn command_check(
+    paths: &ProjectPaths,
+    target: Option<Target>,
+    watch: bool,
+    offline: dependencies::Offline,
+    diagnostics: DiagnosticsFormat,
+) -> Result<()> {
+    let options = Options {
+        root_target_support: TargetSupport::Enforced,
+        warnings_as_errors: false,
+        codegen: Codegen::DepsOnly,
+        compile: Compile::All,
+        mode: Mode::Dev,
+        target,
+        no_print_progress: false,
+    };
+    if watch {
+        return watch::build(paths, options, offline, diagnostics);
+    }
+    let _ = build::main_with_warnings(
         paths,
-        Options {
-            root_target_support: TargetSupport::Enforced,
-            warnings_as_errors: false,
-            codegen: Codegen::DepsOnly,
-            compile: Compile::All,
-            mode: Mode::Dev,
-            target,
-            no_print_progress: false,
-        },
-        build::download_dependencies(paths, cli::Reporter::new())?,
+        options,
+        build::download_dependencies(paths, cli::Reporter::new(), offline)?,
+        diagnostics.warning_emitter(),
     )?;
     Ok(())
 }
@@ -943,25 +1223,28 @@ fn command_build(
     target: Option<Target>,
     warnings_as_errors: bool,
     no_print_progress: bool,
+    watch: bool,
+    offline: dependencies::Offline,
+    diagnostics: DiagnosticsFormat,
 ) -> Result<()> {
+    let options = Options {
+        root_target_support: TargetSupport::Enforced,
+        warnings_as_errors,
+        codegen: Codegen::All,
+        compile: Compile::All,
+        mode: Mode::Dev,
+        target,
+        no_print_progress,
+    };
+    if watch {
+        return watch::build(paths, options, offline, diagnostics);
+    }
     let manifest = if no_print_progress {
-        build::download_dependencies(paths, NullTelemetry)?
+        build::download_dependencies(paths, NullTelemetry, offline)?
     } else {
-        build::download_dependencies(paths, cli::Reporter::new())?
+        build::download_dependencies(paths, cli::Reporter::new(), offline)?
     };
-    let _ = build::main(
-        paths,
-        Options {
-            root_target_support: TargetSupport::Enforced,
-            warnings_as_errors,
-            codegen: Codegen::All,
-            compile: Compile::All,
-            mode: Mode::Dev,
-            target,
-            no_print_progress,
-        },
-        manifest,
-    )?;
+    let _ = build::main_with_warnings(paths, options, manifest, diagnostics.warning_emitter())?;
     Ok(())
 }
 
@@ -987,7 +1270,19 @@ fn initialise_logger() {
 }
 
 fn find_project_paths(current_dir: Utf8PathBuf) -> Result<ProjectPaths> {
-    get_project_root(current_dir).map(ProjectPaths::new)
+    get_project_root(current_dir).and_then(workspace::project_paths)
+}
+
+/// The paths of the projects a command applies to: each member of the
+/// workspace when given `--workspace`, otherwise the project the directory is
+/// in.
+///
+fn find_projects_paths(current_dir: Utf8PathBuf, workspace: bool) -> Result<Vec<ProjectPaths>> {
+    if workspace {
+        workspace::member_paths(&current_dir)
+    } else {
+        find_project_paths(current_dir).map(|paths| vec![paths])
+    }
 }
 
 #[cfg(test)]
@@ -996,7 +1291,7 @@ fn project_paths_at_current_directory_without_toml() -> ProjectPaths {
     ProjectPaths::new(current_dir)
 }
 
-fn download_dependencies(paths: &ProjectPaths) -> Result<()> {
+fn download_dependencies(paths: &ProjectPaths, offline: dependencies::Offline) -> Result<()> {
     _ = dependencies::resolve_and_download(
         paths,
         cli::Reporter::new(),
@@ -1005,7 +1300,47 @@ fn download_dependencies(paths: &ProjectPaths) -> Result<()> {
         dependencies::DependencyManagerConfig {
             use_manifest: dependencies::UseManifest::Yes,
             check_major_versions: dependencies::CheckMajorVersions::No,
+            offline,
         },
     )?;
     Ok(())
 }
+
+#[test]
+fn test_command_filters_tests_and_passes_arguments_after_double_dash() {
+    let command = Command::parse_from([
+        "gleam",
+        "test",
+        "wibble/parser",
+        "--module",
+        "wobble.empty_test",
+        "--only",
+        "parse",
+        "--",
+        "--wobble",
+        "1",
+    ]);
+    let Command::Test {
+        filters,
+        modules,
+        only,
+        arguments,
+        ..
+    } = command
+    else {
+        panic!("Expected the test command");
+    };
+    assert_eq!(filters, vec!["wibble/parser"]);
+    assert_eq!(modules, vec!["wobble.empty_test"]);
+    assert_eq!(only, vec!["parse"]);
+    assert_eq!(arguments, vec!["--wobble", "1"]);
+
+    let Command::Test {
+        filters, arguments, ..
+    } = Command::parse_from(["gleam", "test", "--", "wibble", "--x"])
+    else {
+        panic!("Expected the test command");
+    };
+    assert_eq!(filters, Vec::<String>::new());
+    assert_eq!(arguments, vec!["wibble", "--x"]);
+}
@@ -23,11 +23,16 @@ use itertools::Itertools;
 use sha2::Digest;
 use std::{collections::HashMap, io::Write};
 
-use crate::{build, cli, docs, fs, http::HttpClient, new::default_readme};
+use crate::{api_diff, build, cli, docs, fs, http::HttpClient, new::default_readme};
 
 const CORE_TEAM_PUBLISH_PASSWORD: &str = "Trans rights are human rights";
 
-pub fn command(paths: &ProjectPaths, replace: bool, i_am_sure: bool) -> Result<()> {
+pub fn command(
+    paths: &ProjectPaths,
+    replace: bool,
+    i_am_sure: bool,
+    skip_semver_check: bool,
+) -> Result<()> {
     let mut config = crate::config::root_config(paths)?;
 
     let should_publish = check_for_gleam_prefix(&config)?
@@ -64,6 +69,10 @@ pub fn command(paths: &ProjectPaths, replace: bool, i_am_sure: bool) -> Result<(
         &cached_modules,
     )?)?;
 
+    if !skip_semver_check {
+        api_diff::check_for_publish(paths, &compile_result, &cached_modules)?;
+    }
+
     // Ask user if this is correct
     if !generated_files_added.is_empty() {
         println!("\nGenerated files:");
@@ -411,7 +420,11 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &mut PackageConfig) -> Res
     // Reset the build directory so we know the state of the project
     fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, target))?;
 
-    let manifest = build::download_dependencies(paths, cli::Reporter::new())?;
+    let manifest = build::download_dependencies(
+        paths,
+        cli::Reporter::new(),
+        crate::dependencies::Offline::No,
+    )?;
     let dependencies = manifest
         .packages
         .iter()
@@ -616,7 +629,10 @@ fn metadata_config<'a>(
         .dependencies
         .iter()
         .map(|(name, requirement)| match requirement {
-            Requirement::Hex { version } => Ok(ReleaseRequirement {
+            Requirement::Hex {
+                version,
+                repository: None,
+            } => Ok(ReleaseRequirement {
                 name,
                 otp_app: hex_to_otp_app
                     .get(name)
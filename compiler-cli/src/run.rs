@@ -3,7 +3,7 @@
 
 use std::sync::OnceLock;
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use ecow::EcoString;
 use gleam_core::{
     analyse::TargetSupport,
@@ -11,21 +11,101 @@ use gleam_core::{
     config::{DenoFlag, PackageConfig},
     error::Error,
     io::{Command, CommandExecutor, Stdio},
-    paths::ProjectPaths,
-    type_::ModuleFunction,
+    manifest::Manifest,
+    paths::{ARTEFACT_DIRECTORY_NAME, ProjectPaths},
+    type_::{ModuleFunction, TestFunction},
     version::COMPILER_VERSION,
 };
+use itertools::Itertools;
 use regex::Regex;
 
-use crate::{config::PackageKind, fs::ProjectIO};
+use crate::{
+    config::PackageKind, coverage::CoverageReport, diagnostics::DiagnosticsFormat, fs::ProjectIO,
+    test_report::TestReport,
+};
+
+pub use crate::dependencies::Offline;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Which {
     Src,
-    Test,
+    Test(TestOptions),
     Dev,
 }
 
+/// How `gleam test` runs the tests.
+///
+#[derive(Debug, Clone, Default)]
+pub struct TestOptions {
+    pub filter: TestFilter,
+    /// Where to write a report of the results of the tests, if anywhere.
+    pub report: Option<TestReport>,
+    /// Whether to record which functions and case clauses of the `src`
+    /// modules the tests run, and report the coverage.
+    pub coverage: bool,
+}
+
+impl TestOptions {
+    /// Whether the tests are to be run by the build tool itself rather than
+    /// by the test module's `main` function.
+    fn uses_runner(&self) -> bool {
+        !self.filter.is_empty() || self.report.is_some() || self.coverage
+    }
+}
+
+/// Which test functions `gleam test` runs. When the filter is empty, and no
+/// report is to be written, the test module's `main` function is run instead,
+/// leaving it to the test framework to find the tests.
+///
+#[derive(Debug, Clone, Default)]
+pub struct TestFilter {
+    /// Modules to run the tests of, or single tests written as
+    /// `module.function`.
+    pub modules: Vec<String>,
+    /// If not empty, only the tests whose qualified name contains one of
+    /// these are run.
+    pub only: Vec<String>,
+    /// Tests whose qualified name contains one of these are not run.
+    pub exclude: Vec<String>,
+}
+
+impl TestFilter {
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.only.is_empty() && self.exclude.is_empty()
+    }
+
+    fn select(&self, tests: Vec<TestFunction>) -> Vec<TestFunction> {
+        tests
+            .into_iter()
+            .filter(|test| self.matches(test))
+            .collect()
+    }
+
+    fn matches(&self, test: &TestFunction) -> bool {
+        let name = test.qualified_name();
+        let in_modules = self.modules.is_empty()
+            || self.modules.iter().any(|filter| {
+                // Paths to the test files are accepted too.
+                let filter = filter.strip_prefix("test/").unwrap_or(filter);
+                let filter = filter.strip_suffix(".gleam").unwrap_or(filter);
+                // Test modules are usually named after the module they test
+                // with a `_test` suffix, which can be left out.
+                let module = test.module.strip_suffix("_test").unwrap_or(&test.module);
+                filter == name
+                    || filter == test.module
+                    || filter == module
+                    || test
+                        .module
+                        .strip_prefix(filter)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            });
+        let only = self.only.is_empty() || self.only.iter().any(|part| name.contains(part));
+        let excluded = self.exclude.iter().any(|part| name.contains(part));
+        in_modules && only && !excluded
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn command(
     paths: &ProjectPaths,
     arguments: Vec<String>,
@@ -34,10 +114,12 @@ pub fn command(
     module: Option<String>,
     which: Which,
     no_print_progress: bool,
+    offline: Offline,
+    diagnostics: DiagnosticsFormat,
 ) -> Result<(), Error> {
     // Don't exit on ctrl+c as it is used by child erlang shell
     ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
-    let command = setup(
+    let status = run(
         paths,
         arguments,
         target,
@@ -45,11 +127,53 @@ pub fn command(
         module,
         which,
         no_print_progress,
+        offline,
+        diagnostics,
     )?;
-    let status = ProjectIO::new().exec(command)?;
     std::process::exit(status);
 }
 
+/// Builds and runs the program, returning the status it exited with.
+///
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    paths: &ProjectPaths,
+    arguments: Vec<String>,
+    target: Option<Target>,
+    runtime: Option<Runtime>,
+    module: Option<String>,
+    which: Which,
+    no_print_progress: bool,
+    offline: Offline,
+    diagnostics: DiagnosticsFormat,
+) -> Result<i32, Error> {
+    let Prepared {
+        options,
+        manifest,
+        program,
+    } = prepare(
+        paths,
+        arguments,
+        target,
+        runtime,
+        module,
+        which,
+        no_print_progress,
+        offline,
+    )?;
+    let built =
+        crate::build::main_with_warnings(paths, options, manifest, diagnostics.warning_emitter())?;
+    // Diagnostics that are printed once compilation has finished are printed
+    // now, before the program's own output.
+    diagnostics.flush();
+    let command = program.command(paths, built)?;
+    let status = ProjectIO::new().exec(command)?;
+    program.write_test_report(paths)?;
+    program.write_coverage_report(paths)?;
+    Ok(status)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn setup(
     paths: &ProjectPaths,
     arguments: Vec<String>,
@@ -58,7 +182,63 @@ pub fn setup(
     module: Option<String>,
     which: Which,
     no_print_progress: bool,
+    offline: Offline,
 ) -> Result<Command, Error> {
+    let Prepared {
+        options,
+        manifest,
+        program,
+    } = prepare(
+        paths,
+        arguments,
+        target,
+        runtime,
+        module,
+        which,
+        no_print_progress,
+        offline,
+    )?;
+    let built = crate::build::main(paths, options, manifest)?;
+    program.command(paths, built)
+}
+
+/// Everything needed to build a project and run one of its modules.
+///
+#[derive(Debug)]
+pub(crate) struct Prepared {
+    pub options: Options,
+    pub manifest: Manifest,
+    pub program: Program,
+}
+
+/// A module to run once the project has been built.
+///
+#[derive(Debug)]
+pub(crate) struct Program {
+    module: String,
+    target: Target,
+    mode: Mode,
+    runtime: Option<Runtime>,
+    module_config: PackageConfig,
+    root_config: PackageConfig,
+    arguments: Vec<String>,
+    telemetry: &'static dyn Telemetry,
+    /// When set, the selected test functions are run rather than the module's
+    /// `main` function.
+    tests: Option<TestOptions>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn prepare(
+    paths: &ProjectPaths,
+    arguments: Vec<String>,
+    target: Option<Target>,
+    runtime: Option<Runtime>,
+    module: Option<String>,
+    which: Which,
+    no_print_progress: bool,
+    offline: Offline,
+) -> Result<Prepared, Error> {
     // Validate the module path
     if let Some(mod_path) = &module
         && !is_gleam_module(mod_path)
@@ -76,9 +256,9 @@ pub fn setup(
 
     // Download dependencies
     let manifest = if no_print_progress {
-        crate::build::download_dependencies(paths, NullTelemetry)?
+        crate::build::download_dependencies(paths, NullTelemetry, offline)?
     } else {
-        crate::build::download_dependencies(paths, crate::cli::Reporter::new())?
+        crate::build::download_dependencies(paths, crate::cli::Reporter::new(), offline)?
     };
 
     // Get the config for the module that is being run to check the target.
@@ -97,10 +277,23 @@ pub fn setup(
     // Determine which module to run
     let module = module.unwrap_or(match which {
         Which::Src => root_config.name.to_string(),
-        Which::Test => format!("{}_test", &root_config.name),
+        Which::Test(_) => format!("{}_test", &root_config.name),
         Which::Dev => format!("{}_dev", &root_config.name),
     });
 
+    let tests = match which {
+        Which::Test(options) if options.uses_runner() => Some(options),
+        Which::Src | Which::Dev | Which::Test(_) => None,
+    };
+
+    // Code compiled for coverage is built separately from the usual
+    // development build, so that switching between the two doesn't leave
+    // instrumented code behind.
+    let mode = match &tests {
+        Some(TestOptions { coverage: true, .. }) => Mode::Coverage,
+        Some(_) | None => Mode::Dev,
+    };
+
     let target = target.unwrap_or(mod_config.target);
 
     let options = Options {
@@ -113,7 +306,7 @@ pub fn setup(
             PackageKind::Root => Compile::All,
         },
         codegen: Codegen::All,
-        mode: Mode::Dev,
+        mode,
         target: Some(target),
         root_target_support: match package_kind {
             // The module we want to run is in the root package, so we make sure that the package
@@ -126,61 +319,241 @@ pub fn setup(
         no_print_progress,
     };
 
-    let built = crate::build::main(paths, options, manifest)?;
+    Ok(Prepared {
+        options,
+        manifest,
+        program: Program {
+            module,
+            target,
+            mode,
+            runtime,
+            module_config: mod_config,
+            root_config,
+            arguments,
+            telemetry,
+            tests,
+        },
+    })
+}
 
-    // A module can not be run if it does not exist or does not have a public main function.
-    let main_function = get_or_suggest_main_function(built, &module, target)?;
+impl Program {
+    /// The command that runs the program, using the modules of the given
+    /// build of the project.
+    ///
+    pub fn command(&self, paths: &ProjectPaths, built: Built) -> Result<Command, Error> {
+        let Self {
+            module,
+            target,
+            mode,
+            runtime,
+            module_config,
+            root_config,
+            arguments,
+            telemetry,
+            tests,
+        } = self;
+        let arguments = arguments.clone();
 
-    telemetry.running(&format!("{module}.main"));
+        if let Some(tests) = tests {
+            return self.test_command(paths, built, tests);
+        }
 
-    // Get the command to run the project.
-    match target {
-        Target::Erlang => match runtime {
-            Some(r) => Err(Error::InvalidRuntime {
-                target: Target::Erlang,
-                invalid_runtime: r,
-            }),
-            _ => run_erlang_command(paths, &root_config.name, &module, arguments),
-        },
-        Target::JavaScript => match runtime.unwrap_or(mod_config.javascript.runtime) {
-            Runtime::Deno => run_javascript_deno_command(
-                paths,
-                &root_config,
-                &main_function.package,
-                &module,
-                arguments,
-            ),
-            Runtime::NodeJs => {
-                run_javascript_node_command(paths, &main_function.package, &module, arguments)
+        // A module can not be run if it does not exist or does not have a public main function.
+        let main_function = get_or_suggest_main_function(built, module, *target)?;
+
+        telemetry.running(&format!("{module}.main"));
+
+        // Get the command to run the project.
+        match target {
+            Target::Erlang => match runtime {
+                Some(r) => Err(Error::InvalidRuntime {
+                    target: Target::Erlang,
+                    invalid_runtime: *r,
+                }),
+                _ => {
+                    // gleam modules are separated by `/`. Erlang modules are separated by `@`.
+                    let module = module.replace('/', "@");
+                    let package = &root_config.name;
+                    let eval = format!("{package}@@main:run({module})");
+                    run_erlang_command(paths, *mode, eval, arguments)
+                }
+            },
+            Target::JavaScript => {
+                let entrypoint =
+                    write_javascript_entrypoint(paths, *mode, &main_function.package, module)?;
+                let runtime = runtime.unwrap_or(module_config.javascript.runtime);
+                run_javascript_command(root_config, runtime, entrypoint, arguments)
             }
-            Runtime::Bun => {
-                run_javascript_bun_command(paths, &main_function.package, &module, arguments)
+        }
+    }
+
+    /// The command that runs the test functions selected by the filter,
+    /// without going through the test module's `main` function.
+    ///
+    fn test_command(
+        &self,
+        paths: &ProjectPaths,
+        built: Built,
+        options: &TestOptions,
+    ) -> Result<Command, Error> {
+        let tests = options.filter.select(built.test_functions(self.target));
+        if tests.is_empty() {
+            return Err(Error::NoMatchingTests);
+        }
+
+        let count = match tests.len() {
+            1 => "1 test".into(),
+            n => format!("{n} tests"),
+        };
+        self.telemetry.running(&count);
+
+        // The runner records the results for the report in this file, which is
+        // deleted first so that the results of an earlier run can't be
+        // mistaken for the new ones.
+        let results = match &options.report {
+            Some(_) => {
+                let path = self.test_results_path(paths);
+                crate::fs::delete_file(&path)?;
+                Some(path)
             }
-        },
+            None => None,
+        };
+        // Likewise for the counts of the coverage points.
+        let coverage = if options.coverage {
+            let path = self.coverage_counts_path(paths);
+            crate::fs::delete_file(&path)?;
+            Some(path)
+        } else {
+            None
+        };
+
+        let package = &self.root_config.name;
+        let arguments = self.arguments.clone();
+        match self.target {
+            Target::Erlang => match self.runtime {
+                Some(r) => Err(Error::InvalidRuntime {
+                    target: Target::Erlang,
+                    invalid_runtime: r,
+                }),
+                _ => {
+                    let eval =
+                        erlang_test_eval(package, &tests, results.as_deref(), coverage.as_deref());
+                    run_erlang_command(paths, self.mode, eval, arguments)
+                }
+            },
+            Target::JavaScript => {
+                let entrypoint = write_javascript_test_entrypoint(
+                    paths,
+                    self.mode,
+                    package,
+                    &tests,
+                    results.as_deref(),
+                    coverage.as_deref(),
+                )?;
+                let runtime = self
+                    .runtime
+                    .unwrap_or(self.module_config.javascript.runtime);
+                // Deno needs permission to write the results and the coverage.
+                let mut config = self.root_config.clone();
+                if let DenoFlag::Allow(allowed) = &mut config.javascript.deno.allow_write {
+                    allowed.extend(results.iter().chain(&coverage).map(|path| path.to_string()));
+                }
+                run_javascript_command(&config, runtime, entrypoint, arguments)
+            }
+        }
+    }
+
+    fn test_results_path(&self, paths: &ProjectPaths) -> Utf8PathBuf {
+        paths
+            .build_directory_for_target(self.mode, self.target)
+            .join("gleam@@test_results.jsonl")
+    }
+
+    fn coverage_counts_path(&self, paths: &ProjectPaths) -> Utf8PathBuf {
+        paths
+            .build_directory_for_target(self.mode, self.target)
+            .join("gleam@@coverage_counts.jsonl")
+    }
+
+    /// Writes the report of the tests once they have been run, if one was
+    /// asked for.
+    ///
+    pub(crate) fn write_test_report(&self, paths: &ProjectPaths) -> Result<(), Error> {
+        match self.tests.as_ref().and_then(|tests| tests.report.as_ref()) {
+            Some(report) => report.write(&self.test_results_path(paths)),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the coverage report once the tests have been run, if coverage
+    /// was asked for, and prints a summary of it.
+    ///
+    pub(crate) fn write_coverage_report(&self, paths: &ProjectPaths) -> Result<(), Error> {
+        if !self.tests.as_ref().is_some_and(|tests| tests.coverage) {
+            return Ok(());
+        }
+        let artefacts = paths
+            .build_directory_for_package(self.mode, self.target, &self.root_config.name)
+            .join(ARTEFACT_DIRECTORY_NAME);
+        let report = CoverageReport::read(paths, &artefacts, &self.coverage_counts_path(paths))?;
+        let path = report.write(paths)?;
+        print!("{}", report.summary());
+        println!("\nThe lcov report has been written to {path}");
+        Ok(())
     }
 }
 
+/// The Erlang expression that runs the given tests with the `test` function of
+/// the package's `@@main` module.
+///
+fn erlang_test_eval(
+    package: &str,
+    tests: &[TestFunction],
+    results: Option<&Utf8Path>,
+    coverage: Option<&Utf8Path>,
+) -> String {
+    let tests = tests
+        .iter()
+        .map(|test| format!("{{'{}', '{}'}}", test.module.replace("/", "@"), test.name))
+        .join(", ");
+    let results = erlang_path(results);
+    let coverage = erlang_path(coverage);
+    format!("{package}@@main:test([{tests}], {results}, {coverage})")
+}
+
+/// A path as an Erlang binary, or `undefined` if there is none.
+///
+fn erlang_path(path: Option<&Utf8Path>) -> String {
+    match path {
+        Some(path) => {
+            let path = path.as_str().replace('\\', "\\\\").replace('"', "\\\"");
+            format!("<<\"{path}\"/utf8>>")
+        }
+        None => "undefined".into(),
+    }
+}
+
+/// The command that runs the given Erlang expression with all the project's
+/// modules loaded.
+///
 fn run_erlang_command(
     paths: &ProjectPaths,
-    package: &str,
-    module: &str,
+    mode: Mode,
+    eval: String,
     arguments: Vec<String>,
 ) -> Result<Command, Error> {
     let mut args = vec![];
 
     // Specify locations of Erlang applications
-    let packages = paths.build_directory_for_target(Mode::Dev, Target::Erlang);
+    let packages = paths.build_directory_for_target(mode, Target::Erlang);
 
     for entry in crate::fs::read_dir(packages)?.filter_map(Result::ok) {
         args.push("-pa".into());
         args.push(entry.path().join("ebin").into());
     }
 
-    // gleam modules are separated by `/`. Erlang modules are separated by `@`.
-    let module = module.replace('/', "@");
-
     args.push("-eval".into());
-    args.push(format!("{package}@@main:run({module})"));
+    args.push(eval);
 
     // Don't run the Erlang shell
     args.push("-noshell".into());
@@ -200,16 +573,26 @@ fn run_erlang_command(
     })
 }
 
+fn run_javascript_command(
+    config: &PackageConfig,
+    runtime: Runtime,
+    entrypoint: Utf8PathBuf,
+    arguments: Vec<String>,
+) -> Result<Command, Error> {
+    match runtime {
+        Runtime::Deno => run_javascript_deno_command(config, entrypoint, arguments),
+        Runtime::NodeJs => run_javascript_node_command(entrypoint, arguments),
+        Runtime::Bun => run_javascript_bun_command(entrypoint, arguments),
+    }
+}
+
 fn run_javascript_bun_command(
-    paths: &ProjectPaths,
-    package: &str,
-    module: &str,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
 ) -> Result<Command, Error> {
     let mut args = vec!["run".to_string()];
-    let entry = write_javascript_entrypoint(paths, package, module)?;
 
-    args.push(entry.to_string());
+    args.push(entrypoint.to_string());
 
     for arg in arguments.into_iter() {
         args.push(arg);
@@ -225,15 +608,12 @@ fn run_javascript_bun_command(
 }
 
 fn run_javascript_node_command(
-    paths: &ProjectPaths,
-    package: &str,
-    module: &str,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
 ) -> Result<Command, Error> {
     let mut args = vec![];
-    let entry = write_javascript_entrypoint(paths, package, module)?;
 
-    args.push(entry.to_string());
+    args.push(entrypoint.to_string());
 
     for argument in arguments.into_iter() {
         args.push(argument);
@@ -250,11 +630,12 @@ fn run_javascript_node_command(
 
 fn write_javascript_entrypoint(
     paths: &ProjectPaths,
+    mode: Mode,
     package: &str,
     module: &str,
 ) -> Result<Utf8PathBuf, Error> {
     let path = paths
-        .build_directory_for_package(Mode::Dev, Target::JavaScript, package)
+        .build_directory_for_package(mode, Target::JavaScript, package)
         .to_path_buf()
         .join(format!("gleam@@private_main_v{}.mjs", COMPILER_VERSION));
     let module = format!(
@@ -266,11 +647,148 @@ main();
     Ok(path)
 }
 
-fn run_javascript_deno_command(
+fn write_javascript_test_entrypoint(
     paths: &ProjectPaths,
-    config: &PackageConfig,
+    mode: Mode,
     package: &str,
-    module: &str,
+    tests: &[TestFunction],
+    results: Option<&Utf8Path>,
+    coverage: Option<&Utf8Path>,
+) -> Result<Utf8PathBuf, Error> {
+    let path = paths
+        .build_directory_for_package(mode, Target::JavaScript, package)
+        .to_path_buf()
+        .join(format!("gleam@@private_test_v{}.mjs", COMPILER_VERSION));
+    let module = javascript_test_entrypoint(tests, results, coverage);
+    crate::fs::write(&path, &module)?;
+    Ok(path)
+}
+
+/// A module that runs each of the given tests, reporting the ones that fail,
+/// and exits with a non-zero status if any of them did. It is written to the
+/// build directory of the package, next to the compiled test modules.
+///
+fn javascript_test_entrypoint(
+    tests: &[TestFunction],
+    results: Option<&Utf8Path>,
+    coverage: Option<&Utf8Path>,
+) -> String {
+    let modules: Vec<_> = tests.iter().map(|test| &test.module).unique().collect();
+    let imports = modules
+        .iter()
+        .enumerate()
+        .map(|(index, module)| format!("import * as $module{index} from \"./{module}.mjs\";\n"))
+        .join("");
+    let tests = tests
+        .iter()
+        .map(|test| {
+            let index = modules
+                .iter()
+                .position(|module| *module == &test.module)
+                .expect("Test module imported");
+            let TestFunction { module, name } = test;
+            format!("  [\"{module}\", \"{name}\", $module{index}.{name}],\n")
+        })
+        .join("");
+    let javascript_path = |path: Option<&Utf8Path>| match path {
+        Some(path) => serde_json::to_string(path.as_str()).expect("Path serialisation"),
+        None => "null".into(),
+    };
+    let results = javascript_path(results);
+    let coverage = javascript_path(coverage);
+
+    format!(
+        r#"{imports}
+const tests = [
+{tests}];
+const resultsPath = {results};
+const coveragePath = {coverage};
+
+let inspect = (value) => {{
+  try {{
+    return JSON.stringify(value) ?? String(value);
+  }} catch {{
+    return String(value);
+  }}
+}};
+if (resultsPath !== null) {{
+  try {{
+    ({{ inspect }} = await import("../gleam_stdlib/gleam_stdlib.mjs"));
+  }} catch {{}}
+}}
+
+// The metadata attached to Gleam errors, with the values printed as strings
+// so that they can be written as JSON.
+function metadata(value) {{
+  if (Array.isArray(value)) return value.map(metadata);
+  if (typeof value !== "object" || value === null) return value;
+  const fields = {{}};
+  for (const key of Object.keys(value)) {{
+    fields[key] = key === "value" ? inspect(value[key]) : metadata(value[key]);
+  }}
+  return fields;
+}}
+
+function failure(error) {{
+  if (error?.gleam_error) {{
+    return {{ kind: error.gleam_error, message: error.message, metadata: metadata(error) }};
+  }}
+  return {{
+    kind: error?.name ?? "error",
+    message: String(error?.message ?? error),
+    metadata: {{ reason: String(error?.stack ?? error) }},
+  }};
+}}
+
+const results = [];
+let passed = 0;
+let failed = 0;
+for (const [module, name, test] of tests) {{
+  const start = performance.now();
+  let error = null;
+  try {{
+    await test();
+    passed++;
+  }} catch (caught) {{
+    failed++;
+    error = failure(caught);
+    console.error(`\ntest failed: ${{module}}.${{name}}\n`);
+    console.error(caught);
+  }}
+  const duration = Math.round((performance.now() - start) * 1000);
+  results.push({{ module, function: name, duration, failure: error }});
+}}
+
+if (resultsPath !== null) {{
+  const {{ writeFileSync }} = await import("node:fs");
+  const lines = results.map((result) => JSON.stringify(result) + "\n");
+  writeFileSync(resultsPath, lines.join(""));
+}}
+
+// The modules compiled with coverage count the uses of their coverage points
+// in this global object.
+if (coveragePath !== null) {{
+  const {{ writeFileSync }} = await import("node:fs");
+  const lines = [];
+  for (const [module, counts] of Object.entries(globalThis.gleam_coverage ?? {{}})) {{
+    for (const [point, count] of Object.entries(counts)) {{
+      lines.push(JSON.stringify({{ module, point: Number(point), count }}) + "\n");
+    }}
+  }}
+  writeFileSync(coveragePath, lines.join(""));
+}}
+
+console.log(`\n${{passed}} passed, ${{failed}} failed`);
+if (failed > 0) {{
+  globalThis.Deno ? Deno.exit(1) : process.exit(1);
+}}
+"#,
+    )
+}
+
+fn run_javascript_deno_command(
+    config: &PackageConfig,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
 ) -> Result<Command, Error> {
     let mut args = vec![];
@@ -332,7 +850,6 @@ fn run_javascript_deno_command(
         );
     }
 
-    let entrypoint = write_javascript_entrypoint(paths, package, module)?;
     args.push(entrypoint.to_string());
 
     for argument in arguments.into_iter() {
@@ -425,3 +942,104 @@ fn valid_module_names() {
         assert!(is_gleam_module(mod_name));
     }
 }
+
+#[cfg(test)]
+fn test_function(module: &str, name: &str) -> TestFunction {
+    TestFunction {
+        module: module.into(),
+        name: name.into(),
+    }
+}
+
+#[test]
+fn test_filter_matches_modules_and_single_tests() {
+    let filter = TestFilter {
+        modules: vec!["wibble/parser".into(), "test/wobble.gleam".into()],
+        ..Default::default()
+    };
+    assert!(filter.matches(&test_function("wibble/parser", "one_test")));
+    assert!(filter.matches(&test_function("wibble/parser/lexer", "one_test")));
+    assert!(filter.matches(&test_function("wobble", "one_test")));
+    assert!(!filter.matches(&test_function("wibble/parsers", "one_test")));
+    assert!(!filter.matches(&test_function("wibble", "one_test")));
+    assert!(filter.matches(&test_function("wibble/parser_test", "one_test")));
+
+    let filter = TestFilter {
+        modules: vec!["wibble.one_test".into()],
+        ..Default::default()
+    };
+    assert!(filter.matches(&test_function("wibble", "one_test")));
+    assert!(!filter.matches(&test_function("wibble", "two_test")));
+}
+
+#[test]
+fn test_filter_only_and_exclude() {
+    let filter = TestFilter {
+        only: vec!["parse".into()],
+        exclude: vec!["slow".into()],
+        ..Default::default()
+    };
+    assert!(filter.matches(&test_function("wibble", "parse_test")));
+    assert!(filter.matches(&test_function("parser", "one_test")));
+    assert!(!filter.matches(&test_function("wibble", "parse_slow_test")));
+    assert!(!filter.matches(&test_function("wibble", "lex_test")));
+}
+
+#[test]
+fn erlang_test_runner_is_given_the_tests_and_paths() {
+    let tests = [
+        test_function("wibble/parser_test", "one_test"),
+        test_function("wobble_test", "two_test"),
+    ];
+    assert_eq!(
+        erlang_test_eval("app", &tests, None, None),
+        "app@@main:test([{'wibble@parser_test', 'one_test'}, {'wobble_test', 'two_test'}], \
+undefined, undefined)"
+    );
+    assert_eq!(
+        erlang_test_eval(
+            "app",
+            &tests[..1],
+            Some(Utf8Path::new("/build/results.jsonl")),
+            Some(Utf8Path::new("C:\\build\\\"coverage\".jsonl")),
+        ),
+        "app@@main:test([{'wibble@parser_test', 'one_test'}], \
+<<\"/build/results.jsonl\"/utf8>>, <<\"C:\\\\build\\\\\\\"coverage\\\".jsonl\"/utf8>>)"
+    );
+}
+
+#[test]
+fn javascript_test_runner_imports_each_module_once() {
+    let tests = [
+        test_function("wibble/parser_test", "one_test"),
+        test_function("wobble_test", "two_test"),
+        test_function("wibble/parser_test", "three_test"),
+    ];
+    let module = javascript_test_entrypoint(&tests, None, Some(Utf8Path::new("/coverage.jsonl")));
+    assert!(module.starts_with(
+        r#"import * as $module0 from "./wibble/parser_test.mjs";
+import * as $module1 from "./wobble_test.mjs";
+
+const tests = [
+  ["wibble/parser_test", "one_test", $module0.one_test],
+  ["wobble_test", "two_test", $module1.two_test],
+  ["wibble/parser_test", "three_test", $module0.three_test],
+];
+const resultsPath = null;
+const coveragePath = "/coverage.jsonl";
+"#
+    ));
+}
+
+#[test]
+fn javascript_test_runner_entrypoint() {
+    let tests = [
+        test_function("wibble/parser_test", "one_test"),
+        test_function("wibble/parser_test", "two_test"),
+    ];
+    insta::assert_snapshot!(javascript_test_entrypoint(
+        &tests,
+        Some(Utf8Path::new("/build/results.jsonl")),
+        None
+    ));
+}
@@ -22,7 +22,11 @@ pub fn command(paths: &ProjectPaths) -> Result<(), Error> {
             target: Some(Target::Erlang),
             no_print_progress: false,
         },
-        crate::build::download_dependencies(paths, crate::cli::Reporter::new())?,
+        crate::build::download_dependencies(
+            paths,
+            crate::cli::Reporter::new(),
+            crate::dependencies::Offline::No,
+        )?,
     )?;
 
     // Don't exit on ctrl+c as it is used by child erlang shell
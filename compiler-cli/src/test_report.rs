@@ -0,0 +1,405 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Reports of the results of `gleam test`, written with `--reporter` so that
+//! they can be read by CI systems.
+//!
+//! The test runner generated by the build tool records the result of each test
+//! as a line of JSON in a results file. Once the tests have finished the
+//! results are read back and written as a report in the chosen format.
+
+#[cfg(test)]
+mod tests;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gleam_core::{
+    Result,
+    error::{Error, FileIoAction, FileKind},
+    paths::ProjectPaths,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The formats the results of the tests can be reported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum TestReporter {
+    /// JUnit XML, as understood by most CI systems
+    Junit,
+    /// A single JSON object
+    Json,
+    /// The Test Anything Protocol, version 13
+    Tap,
+}
+
+impl TestReporter {
+    /// Where the report is written if no other path is given.
+    pub fn default_path(self, paths: &ProjectPaths) -> Utf8PathBuf {
+        let extension = match self {
+            Self::Junit => "xml",
+            Self::Json => "json",
+            Self::Tap => "tap",
+        };
+        paths
+            .build_directory()
+            .join(format!("test-results.{extension}"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    pub reporter: TestReporter,
+    pub path: Utf8PathBuf,
+}
+
+impl TestReport {
+    /// Writes the report for the results the test runner recorded in the
+    /// given file.
+    ///
+    pub fn write(&self, results_path: &Utf8Path) -> Result<()> {
+        let results = read_results(results_path)?;
+        let report = match self.reporter {
+            TestReporter::Junit => junit(&results),
+            TestReporter::Json => json(&results),
+            TestReporter::Tap => tap(&results),
+        };
+        crate::fs::write(&self.path, &report)
+    }
+}
+
+/// The result of a single test, as recorded by the test runner.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct TestResult {
+    module: String,
+    function: String,
+    /// How long the test took to run, in microseconds.
+    duration: u64,
+    failure: Option<TestFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct TestFailure {
+    /// The kind of Gleam error, such as `let_assert` or `panic`, or the
+    /// class of the error if it was raised by something other than Gleam.
+    kind: String,
+    message: String,
+    /// The metadata attached to the error by the `panic`, `todo`, `assert`
+    /// and `let assert` code generated by the compiler. Its `value` fields
+    /// are printed as strings by the test runner.
+    #[serde(default)]
+    metadata: Map<String, Value>,
+}
+
+/// The metadata fields with a place of their own in the reports, rather than
+/// being listed along with the other details of the failure.
+const LOCATION_FIELDS: [&str; 7] = [
+    "gleam_error",
+    "message",
+    "file",
+    "module",
+    "function",
+    "fn",
+    "line",
+];
+
+impl TestResult {
+    fn name(&self) -> String {
+        format!("{}.{}", self.module, self.function)
+    }
+}
+
+impl TestFailure {
+    fn file(&self) -> Option<&str> {
+        self.metadata.get("file").and_then(Value::as_str)
+    }
+
+    fn line(&self) -> Option<u64> {
+        self.metadata.get("line").and_then(Value::as_u64)
+    }
+
+    fn function(&self) -> Option<&str> {
+        self.metadata.get("function").and_then(Value::as_str)
+    }
+
+    /// The rest of the metadata, such as the values of an assertion, as a
+    /// flat list of names and values. Nested fields are named with their path,
+    /// as in `left.value`.
+    ///
+    fn details(&self) -> Vec<(String, String)> {
+        let mut details = vec![];
+        for (key, value) in &self.metadata {
+            if !LOCATION_FIELDS.contains(&key.as_str()) {
+                flatten(key.clone(), value, &mut details);
+            }
+        }
+        details
+    }
+}
+
+fn flatten(name: String, value: &Value, details: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                flatten(format!("{name}.{key}"), value, details);
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                flatten(format!("{name}[{index}]"), value, details);
+            }
+        }
+        Value::String(string) => details.push((name, string.clone())),
+        Value::Null | Value::Bool(_) | Value::Number(_) => details.push((name, value.to_string())),
+    }
+}
+
+fn read_results(path: &Utf8Path) -> Result<Vec<TestResult>> {
+    crate::fs::read(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|error| Error::FileIo {
+                kind: FileKind::File,
+                action: FileIoAction::Parse,
+                path: path.to_path_buf(),
+                err: Some(error.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn seconds(microseconds: u64) -> String {
+    format!("{:.6}", microseconds as f64 / 1_000_000.0)
+}
+
+fn junit(results: &[TestResult]) -> String {
+    let failures = results.iter().filter(|result| result.failure.is_some());
+    let duration = results.iter().map(|result| result.duration).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"gleam test\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+        results.len(),
+        failures.count(),
+        seconds(duration),
+    ));
+
+    // The results are sorted, so the tests of each module are next to each
+    // other.
+    for (module, tests) in &results.iter().chunk_by(|result| &result.module) {
+        let tests: Vec<_> = tests.collect();
+        let failures = tests.iter().filter(|test| test.failure.is_some()).count();
+        let duration = tests.iter().map(|test| test.duration).sum();
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" time=\"{}\">\n",
+            xml_escape(module),
+            tests.len(),
+            seconds(duration),
+        ));
+
+        for test in tests {
+            let start = format!(
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                xml_escape(&test.module),
+                xml_escape(&test.function),
+                seconds(test.duration),
+            );
+            let Some(failure) = &test.failure else {
+                xml.push_str(&start);
+                xml.push_str("/>\n");
+                continue;
+            };
+
+            xml.push_str(&start);
+            if let Some(file) = failure.file() {
+                xml.push_str(&format!(" file=\"{}\"", xml_escape(file)));
+            }
+            if let Some(line) = failure.line() {
+                xml.push_str(&format!(" line=\"{line}\""));
+            }
+            xml.push_str(">\n");
+            xml.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                xml_escape(&failure.kind),
+                xml_escape(&failure.message),
+                xml_escape(&failure_text(failure)),
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// The body of a JUnit failure: where the test failed and the details of the
+/// failure, one per line.
+fn failure_text(failure: &TestFailure) -> String {
+    let mut lines = vec![];
+    match (failure.file(), failure.line(), failure.function()) {
+        (Some(file), Some(line), Some(function)) => {
+            lines.push(format!("{file}:{line} in {function}"));
+        }
+        (Some(file), Some(line), None) => lines.push(format!("{file}:{line}")),
+        _ => (),
+    }
+    for (name, value) in failure.details() {
+        lines.push(format!("{name}: {value}"));
+    }
+    lines.join("\n")
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => escaped.push(character),
+            // Other control characters are not allowed in XML documents.
+            character if character.is_control() => (),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct JsonReport<'a> {
+    tests: usize,
+    passed: usize,
+    failed: usize,
+    duration_microseconds: u64,
+    results: Vec<JsonTestResult<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct JsonTestResult<'a> {
+    module: &'a str,
+    function: &'a str,
+    outcome: JsonOutcome,
+    duration_microseconds: u64,
+    failure: Option<JsonFailure<'a>>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum JsonOutcome {
+    Passed,
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct JsonFailure<'a> {
+    kind: &'a str,
+    message: &'a str,
+    file: Option<&'a str>,
+    line: Option<u64>,
+    function: Option<&'a str>,
+    /// The metadata of the error other than its location, as recorded by the
+    /// test runner.
+    details: Map<String, Value>,
+}
+
+fn json(results: &[TestResult]) -> String {
+    let failed = results
+        .iter()
+        .filter(|result| result.failure.is_some())
+        .count();
+    let report = JsonReport {
+        tests: results.len(),
+        passed: results.len() - failed,
+        failed,
+        duration_microseconds: results.iter().map(|result| result.duration).sum(),
+        results: results
+            .iter()
+            .map(|result| JsonTestResult {
+                module: &result.module,
+                function: &result.function,
+                outcome: match result.failure {
+                    Some(_) => JsonOutcome::Failed,
+                    None => JsonOutcome::Passed,
+                },
+                duration_microseconds: result.duration,
+                failure: result.failure.as_ref().map(|failure| JsonFailure {
+                    kind: &failure.kind,
+                    message: &failure.message,
+                    file: failure.file(),
+                    line: failure.line(),
+                    function: failure.function(),
+                    details: failure
+                        .metadata
+                        .iter()
+                        .filter(|(key, _)| !LOCATION_FIELDS.contains(&key.as_str()))
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                }),
+            })
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&report).expect("Test report serialisation");
+    json.push('\n');
+    json
+}
+
+fn tap(results: &[TestResult]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", results.len());
+    for (index, result) in results.iter().enumerate() {
+        let number = index + 1;
+        let name = result.name();
+        let Some(failure) = &result.failure else {
+            tap.push_str(&format!("ok {number} - {name}\n"));
+            continue;
+        };
+
+        // The details of a failure are written as a YAML block. JSON strings
+        // are valid YAML strings, so they are used to escape the text.
+        tap.push_str(&format!("not ok {number} - {name}\n"));
+        tap.push_str("  ---\n");
+        tap.push_str(&format!("  message: {}\n", yaml_string(&failure.message)));
+        tap.push_str("  severity: fail\n");
+        tap.push_str(&format!("  kind: {}\n", yaml_string(&failure.kind)));
+        if failure.file().is_some() || failure.line().is_some() {
+            tap.push_str("  at:\n");
+            if let Some(file) = failure.file() {
+                tap.push_str(&format!("    file: {}\n", yaml_string(file)));
+            }
+            if let Some(line) = failure.line() {
+                tap.push_str(&format!("    line: {line}\n"));
+            }
+            if let Some(function) = failure.function() {
+                tap.push_str(&format!("    function: {}\n", yaml_string(function)));
+            }
+        }
+        let details = failure.details();
+        if !details.is_empty() {
+            tap.push_str("  details:\n");
+            for (name, value) in details {
+                tap.push_str(&format!(
+                    "    {}: {}\n",
+                    yaml_string(&name),
+                    yaml_string(&value)
+                ));
+            }
+        }
+        tap.push_str(&format!(
+            "  duration_ms: {:.3}\n",
+            result.duration as f64 / 1000.0
+        ));
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).expect("String serialisation")
+}
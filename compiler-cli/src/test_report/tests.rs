@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use super::{TestResult, json, junit, tap};
+
+/// Results as they are recorded by the test runner: a passing test, a failed
+/// `let assert`, a failed `assert` and an error from outside of Gleam.
+const RESULTS: &str = r#"
+{"module":"wibble/parser_test","function":"empty_test","duration":12,"failure":null}
+{"module":"wibble/parser_test","function":"list_test","duration":1510,"failure":{"kind":"let_assert","message":"Pattern match failed, no pattern matched the value.","metadata":{"gleam_error":"let_assert","message":"Pattern match failed, no pattern matched the value.","file":"test/wibble/parser_test.gleam","module":"wibble/parser_test","function":"list_test","line":6,"value":"Error(\"<wobble>\")","start":52,"end":68,"pattern_start":63,"pattern_end":64}}}
+{"module":"wobble_test","function":"equal_test","duration":40,"failure":{"kind":"assert","message":"Assertion failed.","metadata":{"gleam_error":"assert","message":"Assertion failed.","file":"test/wobble_test.gleam","module":"wobble_test","function":"equal_test","line":2,"kind":"binary_operator","operator":"==","left":{"kind":"literal","value":"1","start":30,"end":31},"right":{"kind":"expression","value":"2","start":35,"end":40}}}}
+{"module":"wobble_test","function":"crash_test","duration":7,"failure":{"kind":"error","message":"An error occurred outside of Gleam.","metadata":{"reason":"badarith"}}}
+"#;
+
+fn results() -> Vec<TestResult> {
+    RESULTS
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str(line).expect("Test result"))
+        .collect()
+}
+
+#[test]
+fn junit_report() {
+    insta::assert_snapshot!(junit(&results()));
+}
+
+#[test]
+fn json_report() {
+    insta::assert_snapshot!(json(&results()));
+}
+
+#[test]
+fn tap_report() {
+    insta::assert_snapshot!(tap(&results()));
+}
+
+#[test]
+fn junit_report_escapes_text() {
+    let results = vec![TestResult {
+        module: "wibble_test".into(),
+        function: "escape_test".into(),
+        duration: 1,
+        failure: Some(super::TestFailure {
+            kind: "panic".into(),
+            message: "<\"wibble\" & 'wobble'>\u{1b}".into(),
+            metadata: Default::default(),
+        }),
+    }];
+    insta::assert_snapshot!(junit(&results));
+}
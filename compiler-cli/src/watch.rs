@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::{
+    collections::HashMap,
+    process::Child,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gleam_core::{
+    Result,
+    build::{NullTelemetry, Options, Runtime, Target},
+    io::Command,
+    manifest::Manifest,
+    paths::ProjectPaths,
+};
+
+use crate::{
+    build::IncrementalBuild,
+    cli,
+    dependencies::Offline,
+    diagnostics::DiagnosticsFormat,
+    fs,
+    run::{self, Prepared, Which},
+};
+
+/// How often the project's files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Saving a file often results in a burst of changes, for example when an
+/// editor formats it or writes it using a temporary file, so we wait for the
+/// files to stop changing for this long before building again.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Builds the project, and then builds it again every time one of its files
+/// changes.
+///
+pub fn build(
+    paths: &ProjectPaths,
+    options: Options,
+    offline: Offline,
+    diagnostics: DiagnosticsFormat,
+) -> Result<()> {
+    let mut build = None;
+    watch(paths, diagnostics, |config_changed| {
+        if config_changed {
+            build = None;
+        }
+        let build = match &mut build {
+            Some(build) => build,
+            None => build.insert(incremental_build(
+                paths,
+                options.clone(),
+                offline,
+                diagnostics,
+            )?),
+        };
+        _ = build.build()?;
+        Ok(())
+    })
+}
+
+/// Builds and runs the project, and then stops it, builds it and runs it
+/// again every time one of its files changes.
+///
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    paths: &ProjectPaths,
+    arguments: Vec<String>,
+    target: Option<Target>,
+    runtime: Option<Runtime>,
+    module: Option<String>,
+    which: Which,
+    no_print_progress: bool,
+    offline: Offline,
+    diagnostics: DiagnosticsFormat,
+) -> Result<()> {
+    let mut build = None;
+    let mut running = None;
+    watch(paths, diagnostics, |config_changed| {
+        // The program is stopped before building, so that a failed build
+        // doesn't leave an outdated version of it running.
+        stop(&mut running);
+
+        if config_changed {
+            build = None;
+        }
+        let (build, program) = match &mut build {
+            Some(build) => build,
+            None => {
+                let Prepared {
+                    options,
+                    manifest,
+                    program,
+                } = run::prepare(
+                    paths,
+                    arguments.clone(),
+                    target,
+                    runtime,
+                    module.clone(),
+                    which.clone(),
+                    no_print_progress,
+                    offline,
+                )?;
+                let incremental_build =
+                    IncrementalBuild::new(paths, options, manifest, diagnostics.warning_emitter())?;
+                build.insert((incremental_build, program))
+            }
+        };
+
+        let built = build.build()?;
+        let command = program.command(paths, built)?;
+        running = Some(spawn(paths, command)?);
+        Ok(())
+    })
+}
+
+fn incremental_build(
+    paths: &ProjectPaths,
+    options: Options,
+    offline: Offline,
+    diagnostics: DiagnosticsFormat,
+) -> Result<IncrementalBuild> {
+    let manifest = download_dependencies(paths, options.no_print_progress, offline)?;
+    IncrementalBuild::new(paths, options, manifest, diagnostics.warning_emitter())
+}
+
+fn download_dependencies(
+    paths: &ProjectPaths,
+    no_print_progress: bool,
+    offline: Offline,
+) -> Result<Manifest> {
+    if no_print_progress {
+        crate::build::download_dependencies(paths, NullTelemetry, offline)
+    } else {
+        crate::build::download_dependencies(paths, cli::Reporter::new(), offline)
+    }
+}
+
+/// Calls `on_change` straight away, and then again every time the files of
+/// the project change. Its argument is true if the project's configuration or
+/// dependencies might have changed, in which case any state kept from the
+/// previous call is outdated and has to be thrown away.
+///
+/// Errors are printed rather than returned, so that they can be fixed while
+/// the project is still being watched.
+///
+fn watch(
+    paths: &ProjectPaths,
+    diagnostics: DiagnosticsFormat,
+    mut on_change: impl FnMut(bool) -> Result<()>,
+) -> Result<()> {
+    let mut files = snapshot(paths);
+    let mut config_changed = true;
+
+    loop {
+        if let Err(error) = on_change(config_changed) {
+            diagnostics.print_error(&error);
+        }
+        diagnostics.flush();
+        // The manifest is written when the dependencies are resolved as part
+        // of the build, and that alone shouldn't cause another build.
+        if let Ok(modified) = fs::modification_time(&paths.manifest()) {
+            _ = files.insert(paths.manifest(), modified);
+        }
+        cli::print_watching();
+
+        let (new_files, changed) = wait_for_changes(paths, files);
+        tracing::debug!(changed=?changed, "watched_files_changed");
+        config_changed = changed.iter().any(|path| is_config_file(paths, path));
+        files = new_files;
+    }
+}
+
+/// The files of the project, along with the last time they were modified.
+/// Files ignored by version control are not included, and neither is the
+/// `build` directory as it is written to while building.
+///
+type Snapshot = HashMap<Utf8PathBuf, SystemTime>;
+
+fn snapshot(paths: &ProjectPaths) -> Snapshot {
+    let build_directory = paths.build_directory();
+    ignore::WalkBuilder::new(paths.root())
+        .follow_links(true)
+        .filter_entry(move |entry| entry.path() != build_directory)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_type()
+                .map(|type_| type_.is_file())
+                .unwrap_or(false)
+        })
+        .filter_map(|entry| {
+            let path = Utf8PathBuf::from_path_buf(entry.into_path()).ok()?;
+            let modified = fs::modification_time(&path).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// Blocks until some of the project's files change, returning the new
+/// snapshot of the files and the paths that have changed.
+///
+fn wait_for_changes(paths: &ProjectPaths, files: Snapshot) -> (Snapshot, Vec<Utf8PathBuf>) {
+    let mut new_files = loop {
+        thread::sleep(POLL_INTERVAL);
+        let new_files = snapshot(paths);
+        if new_files != files {
+            break new_files;
+        }
+    };
+
+    loop {
+        thread::sleep(DEBOUNCE_INTERVAL);
+        let newer_files = snapshot(paths);
+        if newer_files == new_files {
+            break;
+        }
+        new_files = newer_files;
+    }
+
+    let changed = changed_paths(&files, &new_files);
+    (new_files, changed)
+}
+
+/// The paths of the files that have been added, removed or modified.
+///
+fn changed_paths(old: &Snapshot, new: &Snapshot) -> Vec<Utf8PathBuf> {
+    let removed = old.keys().filter(|path| !new.contains_key(*path));
+    let added_or_modified = new
+        .iter()
+        .filter(|(path, modified)| old.get(*path) != Some(modified))
+        .map(|(path, _)| path);
+    removed.chain(added_or_modified).cloned().collect()
+}
+
+fn is_config_file(paths: &ProjectPaths, path: &Utf8Path) -> bool {
+    path == paths.root_config() || path == paths.manifest()
+}
+
+fn spawn(paths: &ProjectPaths, command: Command) -> Result<Child> {
+    let Command {
+        program,
+        args,
+        env,
+        cwd,
+        stdio,
+    } = command;
+    tracing::debug!(program=program, args=?args.join(" "), env=?env, cwd=?cwd, "command_spawn");
+    std::process::Command::new(&program)
+        .args(args)
+        .stdin(stdio.get_process_stdio())
+        .stdout(stdio.get_process_stdio())
+        .envs(env.iter().map(|pair| (&pair.0, &pair.1)))
+        .current_dir(cwd.unwrap_or_else(|| paths.root().to_path_buf()))
+        .spawn()
+        .map_err(|error| fs::command_error(program, error))
+}
+
+fn stop(running: &mut Option<Child>) {
+    if let Some(mut child) = running.take() {
+        // The program might have already finished on its own, so there's
+        // nothing to do if it can't be killed.
+        _ = child.kill();
+        _ = child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_paths_include_added_removed_and_modified_files() {
+        let earlier = SystemTime::UNIX_EPOCH;
+        let later = earlier + Duration::from_secs(1);
+        let old = HashMap::from([
+            (Utf8PathBuf::from("src/unchanged.gleam"), earlier),
+            (Utf8PathBuf::from("src/modified.gleam"), earlier),
+            (Utf8PathBuf::from("src/removed.gleam"), earlier),
+        ]);
+        let new = HashMap::from([
+            (Utf8PathBuf::from("src/unchanged.gleam"), earlier),
+            (Utf8PathBuf::from("src/modified.gleam"), later),
+            (Utf8PathBuf::from("src/added.gleam"), later),
+        ]);
+
+        let mut changed = changed_paths(&old, &new);
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec![
+                Utf8PathBuf::from("src/added.gleam"),
+                Utf8PathBuf::from("src/modified.gleam"),
+                Utf8PathBuf::from("src/removed.gleam"),
+            ]
+        );
+    }
+
+    #[test]
+    fn config_files() {
+        let paths = ProjectPaths::new(Utf8PathBuf::from("/app"));
+        assert!(is_config_file(&paths, Utf8Path::new("/app/gleam.toml")));
+        assert!(is_config_file(&paths, Utf8Path::new("/app/manifest.toml")));
+        assert!(!is_config_file(&paths, Utf8Path::new("/app/src/app.gleam")));
+        assert!(!is_config_file(
+            &paths,
+            Utf8Path::new("/app/build/packages/wibble/gleam.toml")
+        ));
+    }
+}
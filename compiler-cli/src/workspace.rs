@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Finding the workspace a project is a member of, and the members of the
+//! workspace a command given `--workspace` applies to.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gleam_core::{
+    Error, Result,
+    build::{Runtime, Target},
+    config::Workspace,
+    paths::ProjectPaths,
+};
+
+use crate::{dependencies::Offline, diagnostics::DiagnosticsFormat, fs::ProjectIO, run};
+
+/// The paths of the project with the given root directory, which share the
+/// manifest and build directory of its workspace if it is a member of one.
+///
+pub fn project_paths(root: Utf8PathBuf) -> Result<ProjectPaths> {
+    match Workspace::containing_package(&root, &ProjectIO::new())? {
+        Some(workspace) => Ok(ProjectPaths::new_workspace_member(root, workspace.root)),
+        None => Ok(ProjectPaths::new(root)),
+    }
+}
+
+/// The paths of each member of the workspace the given directory is in, in
+/// the order they are listed in the workspace config.
+///
+pub fn member_paths(directory: &Utf8Path) -> Result<Vec<ProjectPaths>> {
+    let io = ProjectIO::new();
+    let Some(workspace) = Workspace::find(directory, &io)? else {
+        return Err(Error::WorkspaceNotFound {
+            path: directory.to_path_buf(),
+        });
+    };
+
+    // Members with the same name would overwrite each other's build
+    // artefacts, so this is checked before any of them are built.
+    let _ = workspace.member_configs(&io)?;
+
+    Ok(workspace
+        .members
+        .into_iter()
+        .map(|member| ProjectPaths::new_workspace_member(member, workspace.root.clone()))
+        .collect())
+}
+
+/// Runs the tests of each member of the workspace, exiting with a non-zero
+/// status if the tests of any of them failed. When given a filter the members
+/// with no tests matching it are skipped.
+///
+pub fn test(
+    directory: &Utf8Path,
+    arguments: Vec<String>,
+    target: Option<Target>,
+    runtime: Option<Runtime>,
+    filter: run::TestFilter,
+    offline: Offline,
+    diagnostics: DiagnosticsFormat,
+) -> Result<()> {
+    // Don't exit on ctrl+c as it is used by child erlang shell
+    ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
+
+    let mut any_ran = false;
+    let mut any_failed = false;
+    for paths in member_paths(directory)? {
+        let options = run::TestOptions {
+            filter: filter.clone(),
+            ..Default::default()
+        };
+        let status = match run::run(
+            &paths,
+            arguments.clone(),
+            target,
+            runtime,
+            None,
+            run::Which::Test(options),
+            false,
+            offline,
+            diagnostics,
+        ) {
+            Ok(status) => status,
+            Err(Error::NoMatchingTests) => continue,
+            Err(error) => return Err(error),
+        };
+        any_ran = true;
+        any_failed |= status != 0;
+    }
+
+    if !any_ran {
+        return Err(Error::NoMatchingTests);
+    }
+    std::process::exit(i32::from(any_failed));
+}
@@ -3,6 +3,7 @@
 
 #![allow(warnings)]
 
+mod dependency_cache;
 mod elixir_libraries;
 mod module_loader;
 mod native_file_copier;
@@ -14,6 +15,7 @@ mod telemetry;
 #[cfg(test)]
 mod tests;
 
+pub use self::dependency_cache::{DependencyCache, DependencyCacheKey};
 pub use self::package_compiler::PackageCompiler;
 pub use self::package_loader::StaleTracker;
 pub use self::project_compiler::{Built, Options, ProjectCompiler};
@@ -59,6 +61,7 @@ use vec1::Vec1;
     Copy,
     PartialEq,
     Eq,
+    Hash,
 )]
 #[strum(serialize_all = "lowercase")]
 #[clap(rename_all = "lower")]
@@ -221,6 +224,10 @@ pub enum Mode {
     Dev,
     Prod,
     Lsp,
+    /// Used by `gleam test --coverage`. The same as `Dev`, but the code
+    /// generated for the root package counts how often each function and case
+    /// clause is run.
+    Coverage,
 }
 
 impl Mode {
@@ -228,14 +235,14 @@ impl Mode {
     ///
     pub fn includes_dev_code(&self) -> bool {
         match self {
-            Self::Dev | Self::Lsp => true,
+            Self::Dev | Self::Lsp | Self::Coverage => true,
             Self::Prod => false,
         }
     }
 
     pub fn includes_dev_dependencies(&self) -> bool {
         match self {
-            Mode::Dev | Mode::Lsp => true,
+            Mode::Dev | Mode::Lsp | Mode::Coverage => true,
             Mode::Prod => false,
         }
     }
@@ -245,6 +252,7 @@ impl Mode {
 fn mode_includes_dev_code() {
     assert!(Mode::Dev.includes_dev_code());
     assert!(Mode::Lsp.includes_dev_code());
+    assert!(Mode::Coverage.includes_dev_code());
     assert!(!Mode::Prod.includes_dev_code());
 }
 
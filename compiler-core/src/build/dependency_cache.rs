@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+
+use crate::{
+    build::Target,
+    manifest::{ManifestPackage, ManifestPackageSource},
+    type_::ModuleInterface,
+};
+
+/// The module interfaces of Hex packages loaded by a project compiler, which
+/// can be shared with the compilers of other projects using the same packages.
+///
+/// A published Hex package never changes, so two projects depending on the
+/// same release of a package, and on the same releases of all the packages it
+/// depends on, would load the exact same modules for it. The language server
+/// runs a compiler for each project open in the editor, and sharing the
+/// modules saves each of them from loading the package from its own build
+/// directory.
+///
+/// Cloning the cache gives another handle to the same modules.
+///
+#[derive(Debug, Clone, Default)]
+pub struct DependencyCache {
+    packages: Rc<RefCell<HashMap<DependencyCacheKey, CachedPackage>>>,
+}
+
+/// A package, identified by the releases of the package and of all the
+/// packages it depends on, directly or not.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DependencyCacheKey {
+    target: Target,
+    /// The name, version, and checksum of each package, sorted by name.
+    releases: Vec<(EcoString, String, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedPackage {
+    /// The directory the package was loaded from. Each project has its own
+    /// copy of a package, so the paths of the modules have to be changed to
+    /// point to the copy of the project using them.
+    root: Utf8PathBuf,
+    modules: Vec<ModuleInterface>,
+}
+
+impl DependencyCacheKey {
+    /// The key of a package from the given manifest packages. Only Hex
+    /// packages that depend exclusively on other Hex packages can be shared,
+    /// as Git and path dependencies can change without a new version.
+    pub fn new(
+        target: Target,
+        packages: &HashMap<String, ManifestPackage>,
+        name: &str,
+    ) -> Option<Self> {
+        let mut releases = vec![];
+        let mut queue = vec![EcoString::from(name)];
+        while let Some(name) = queue.pop() {
+            if releases.iter().any(|(seen, _, _)| *seen == name) {
+                continue;
+            }
+            let package = packages.get(name.as_str())?;
+            let ManifestPackageSource::Hex { outer_checksum, .. } = &package.source else {
+                return None;
+            };
+            releases.push((name, package.version.to_string(), outer_checksum.0.clone()));
+            queue.extend(package.requirements.iter().cloned());
+        }
+        releases.sort();
+        Some(Self { target, releases })
+    }
+}
+
+impl DependencyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The modules of the package, with their paths inside `root`, if another
+    /// compiler has already loaded it.
+    pub fn get(&self, key: &DependencyCacheKey, root: &Utf8Path) -> Option<Vec<ModuleInterface>> {
+        let packages = self.packages.borrow();
+        let package = packages.get(key)?;
+        let modules = package
+            .modules
+            .iter()
+            .map(|module| {
+                let mut module = module.clone();
+                if let Ok(relative) = module.src_path.strip_prefix(&package.root) {
+                    module.src_path = root.join(relative);
+                }
+                module
+            })
+            .collect();
+        Some(modules)
+    }
+
+    pub fn insert(
+        &self,
+        key: DependencyCacheKey,
+        root: Utf8PathBuf,
+        modules: Vec<ModuleInterface>,
+    ) {
+        let package = CachedPackage { root, modules };
+        _ = self.packages.borrow_mut().insert(key, package);
+    }
+}
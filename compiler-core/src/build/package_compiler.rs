@@ -22,6 +22,7 @@ use crate::{
     },
     codegen::{Erlang, ErlangApp, JavaScript, TypeScriptDeclarations},
     config::PackageConfig,
+    coverage::{COVERAGE_MAP_EXTENSION, Coverage},
     dep_tree, error,
     io::{BeamCompilerIO, CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
     parse::extra::ModuleExtra,
@@ -31,6 +32,7 @@ use crate::{
 };
 use crate::{inline, metadata};
 use askama::Template;
+use debug_ignore::DebugIgnore;
 use ecow::EcoString;
 use itertools::Itertools;
 use std::collections::HashSet;
@@ -75,6 +77,9 @@ pub struct PackageCompiler<'a, IO> {
     pub target_support: TargetSupport,
     pub cached_warnings: CachedWarnings,
     pub check_module_conflicts: CheckModuleConflicts,
+    /// Called before each module is analysed. The language server uses this
+    /// to answer requests while a large package is being compiled.
+    pub analysis_checkpoint: DebugIgnore<Option<&'a mut dyn FnMut()>>,
 }
 
 impl<'a, IO> PackageCompiler<'a, IO>
@@ -110,6 +115,7 @@ where
             target_support: TargetSupport::NotEnforced,
             cached_warnings: CachedWarnings::Ignore,
             check_module_conflicts: CheckModuleConflicts::DoNotCheck,
+            analysis_checkpoint: DebugIgnore(None),
         }
     }
 
@@ -205,6 +211,7 @@ where
             warnings,
             self.target_support,
             incomplete_modules,
+            self.analysis_checkpoint.take(),
         );
 
         let mut modules = match outcome {
@@ -367,11 +374,41 @@ where
                 *emit_typescript_definitions,
                 *emit_source_maps,
                 prelude_location,
-            ),
+            )?,
             TargetCodegenConfiguration::Erlang { app_file } => {
-                self.perform_erlang_codegen(modules, app_file.as_ref())
+                self.perform_erlang_codegen(modules, app_file.as_ref())?
             }
         }
+
+        if self.mode == Mode::Coverage {
+            self.write_coverage_maps(modules)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the coverage points of each instrumented module to the
+    /// artefacts directory, so the counts recorded while running the tests
+    /// can be mapped back to the source code.
+    ///
+    fn write_coverage_maps(&mut self, modules: &[Module]) -> Result<()> {
+        let artefact_dir = self.out.join(paths::ARTEFACT_DIRECTORY_NAME);
+        let target = self.target.target();
+        tracing::debug!("writing_coverage_maps");
+
+        for module in modules {
+            let Some(coverage) = Coverage::for_module(module, target) else {
+                continue;
+            };
+            if coverage.is_empty() {
+                continue;
+            }
+            let map = coverage.map(module, self.root);
+            let json = serde_json::to_string(&map).expect("Coverage map serialisation");
+            let name = module.name.replace("/", "@");
+            let path = artefact_dir.join(format!("{name}.{COVERAGE_MAP_EXTENSION}"));
+            self.io.write(&path, &json)?;
+        }
+        Ok(())
     }
 
     fn perform_erlang_codegen(
@@ -402,7 +439,9 @@ where
         // we overwrite any precompiled Erlang that was included in the Hex
         // package. Otherwise we will build the potentially outdated precompiled
         // version and not the newly compiled version.
-        Erlang::new(&build_dir, &include_dir).render(io.clone(), modules, self.root)?;
+        Erlang::new(&build_dir, &include_dir)
+            .with_coverage(self.mode == Mode::Coverage)
+            .render(io.clone(), modules, self.root)?;
 
         let native_modules: Vec<EcoString> = if self.compile_beam_bytecode {
             written.extend(modules.iter().map(Module::compiled_erlang_path));
@@ -443,6 +482,7 @@ where
             prelude_location,
             &self.root,
         )
+        .with_coverage(self.mode == Mode::Coverage)
         .render(&self.io, modules, self.stdlib_package())?;
 
         if self.copy_native_files {
@@ -561,6 +601,7 @@ fn analyse(
     warnings: &WarningEmitter,
     target_support: TargetSupport,
     incomplete_modules: &mut HashSet<EcoString>,
+    mut checkpoint: Option<&mut dyn FnMut()>,
 ) -> Outcome<Vec<Module>, Error> {
     let mut modules = Vec::with_capacity(parsed_modules.len() + 1);
     let direct_dependencies = package_config.dependencies_for(mode).expect("Package deps");
@@ -588,6 +629,10 @@ fn analyse(
         extra,
     } in parsed_modules
     {
+        if let Some(checkpoint) = checkpoint.as_mut() {
+            checkpoint();
+        }
+
         tracing::debug!(module = ?name, "Type checking");
 
         // We first need to check if the module can actually be compiled.
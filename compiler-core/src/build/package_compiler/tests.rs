@@ -88,3 +88,46 @@ pub fn same_package_defining_duplicate_module() {
 
     insta::assert_snapshot!(insta::internals::AutoName, output);
 }
+
+#[test]
+pub fn analysis_checkpoint_is_called_before_each_module_is_analysed() {
+    let mut fs = InMemoryFileSystem::new();
+    for module in ["wibble", "wobble", "wubble"] {
+        fs.write(
+            Utf8Path::new(&format!("/src/{module}.gleam")),
+            "pub fn main() -> Nil { Nil }",
+        )
+        .expect("write module");
+    }
+
+    let config = PackageConfig::default();
+    let mut checkpoints = 0;
+    let mut checkpoint = || checkpoints += 1;
+    let mut compiler = PackageCompiler::new(
+        &config,
+        build::Mode::Dev,
+        Utf8Path::new("/"),
+        Utf8Path::new("/out"),
+        Utf8Path::new("/lib"),
+        &TargetCodegenConfiguration::Erlang { app_file: None },
+        UniqueIdGenerator::new(),
+        fs,
+    );
+    compiler.perform_codegen = false;
+    compiler.analysis_checkpoint = Some(&mut checkpoint as &mut dyn FnMut()).into();
+
+    let compiled = compiler
+        .compile(
+            &WarningEmitter::null(),
+            &mut im::HashMap::new(),
+            &mut im::HashMap::new(),
+            &mut StaleTracker::default(),
+            &mut HashSet::new(),
+            &NullTelemetry,
+        )
+        .into_result()
+        .expect("compile package");
+
+    assert_eq!(compiled.modules.len(), 3);
+    assert_eq!(checkpoints, 3);
+}
@@ -1649,7 +1649,7 @@ fn ensure_gleam_module_does_not_overwrite_standard_erlang_module(input: &Input)
         path: input.path.to_owned(),
     })
 }
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct StaleTracker(HashSet<EcoString>);
 
 impl StaleTracker {
@@ -5,7 +5,7 @@ use crate::{
     Error, Result, Warning,
     analyse::TargetSupport,
     build::{
-        Mode, Module, Origin, Package, Target,
+        DependencyCache, DependencyCacheKey, Mode, Module, Origin, Package, Target,
         package_compiler::{self, PackageCompiler},
         package_loader::StaleTracker,
         project_compiler,
@@ -19,7 +19,7 @@ use crate::{
     manifest::{ManifestPackage, ManifestPackageSource},
     metadata,
     paths::{self, ProjectPaths},
-    type_::{self, ModuleFunction},
+    type_::{self, ModuleFunction, TestFunction},
     uid::UniqueIdGenerator,
     version::COMPILER_VERSION,
     warning::{self, WarningEmitter, WarningEmitterIO},
@@ -58,7 +58,7 @@ const ELIXIR_EXECUTABLE: &str = "elixir";
 #[cfg(target_os = "windows")]
 const ELIXIR_EXECUTABLE: &str = "elixir.bat";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Options {
     pub mode: Mode,
     pub target: Option<Target>,
@@ -91,6 +91,19 @@ impl Built {
         }
     }
 
+    /// The test functions of the root package that can be run on the given
+    /// target, sorted by module and then by name.
+    pub fn test_functions(&self, target: Target) -> Vec<TestFunction> {
+        let compiled = self.root_package.modules.iter().map(|module| &module.name);
+        let cached = self.root_package.cached_module_names.iter();
+        compiled
+            .chain(cached)
+            .filter_map(|name| self.module_interfaces.get(name))
+            .flat_map(|interface| interface.test_functions(target))
+            .sorted()
+            .collect()
+    }
+
     pub fn minimum_required_version(&self) -> Version {
         self.module_interfaces
             .values()
@@ -101,7 +114,7 @@ impl Built {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ProjectCompiler<IO> {
     // The gleam.toml config for the root package of the project
     pub config: PackageConfig,
@@ -121,6 +134,9 @@ pub struct ProjectCompiler<IO> {
     /// We may want to silence subprocess stdout if we are running in LSP mode.
     /// The language server talks over stdio so printing would break that.
     pub subprocess_stdio: Stdio,
+    /// The language server shares the modules of Hex packages between the
+    /// compilers of the projects it has open.
+    pub dependency_cache: Option<DependencyCache>,
 }
 
 // TODO: test that tests cannot be imported into src
@@ -152,6 +168,7 @@ where
             ids: UniqueIdGenerator::new(),
             warnings: WarningEmitter::new(warning_emitter),
             subprocess_stdio: Stdio::Inherit,
+            dependency_cache: None,
             telemetry,
             packages,
             options,
@@ -200,6 +217,14 @@ where
     /// Compiles all packages in the project and returns the compiled
     /// information from the root package
     pub fn compile(mut self) -> Result<Built> {
+        self.recompile()
+    }
+
+    /// Like `compile`, but the compiler can be used again afterwards. The
+    /// modules it has already loaded are kept in memory, so compiling the
+    /// project again only compiles the modules that have become stale since.
+    ///
+    pub fn recompile(&mut self) -> Result<Built> {
         self.reset_state_for_new_compile_run();
 
         // In production mode, skip dev-only dependencies entirely so they
@@ -233,14 +258,25 @@ where
 
         Ok(Built {
             root_package,
-            module_interfaces: self.importable_modules,
+            module_interfaces: self.importable_modules.clone(),
             compiled_dependency_modules,
         })
     }
 
     pub fn compile_root_package(&mut self) -> Outcome<Package, Error> {
+        self.compile_root_package_with_checkpoint(&mut || ())
+    }
+
+    /// Like `compile_root_package`, but `checkpoint` is called before each
+    /// module of the root package is analysed.
+    ///
+    pub fn compile_root_package_with_checkpoint(
+        &mut self,
+        checkpoint: &mut dyn FnMut(),
+    ) -> Outcome<Package, Error> {
         let config = self.config.clone();
-        self.compile_gleam_package(&config, true, self.paths.root().to_path_buf())
+        let root_path = self.paths.root().to_path_buf();
+        self.compile_gleam_package(&config, true, root_path, Some(checkpoint))
             .map(
                 |Compiled {
                      modules,
@@ -347,7 +383,7 @@ where
         // longer need to have the package borrowed from self.packages.
         let package = self.packages.get(name).expect("Missing package").clone();
         let result = match usable_build_tools(&package)?.as_slice() {
-            &[BuildTool::Gleam] => self.compile_gleam_dep_package(&package),
+            &[BuildTool::Gleam] => self.share_or_compile_gleam_dep_package(&package),
             &[BuildTool::Rebar3] => self.compile_rebar3_dep_package(&package).map(|_| vec![]),
             &[BuildTool::Mix] => self.compile_mix_dep_package(&package).map(|_| vec![]),
             &[BuildTool::Mix, BuildTool::Rebar3] => self
@@ -551,19 +587,62 @@ where
         }
     }
 
+    /// If another compiler sharing the dependency cache has already loaded the
+    /// same release of a Hex package its modules are used, rather than loading
+    /// them again from the build directory.
+    fn share_or_compile_gleam_dep_package(
+        &mut self,
+        package: &ManifestPackage,
+    ) -> Result<Vec<Module>, Error> {
+        let Some(cache) = self.dependency_cache.clone() else {
+            return self.compile_gleam_dep_package(package);
+        };
+        let Some(key) = DependencyCacheKey::new(self.target(), &self.packages, &package.name)
+        else {
+            return self.compile_gleam_dep_package(package);
+        };
+
+        let root = self.paths.build_packages_package(&package.name);
+        if let Some(modules) = cache.get(&key, &root) {
+            for module in modules {
+                // The type variable ids come from the compiler that loaded the
+                // package, so they are remapped like those of a module read
+                // from the build directory.
+                let module = metadata::remap_type_variable_ids(module, self.ids.clone());
+                let origin = DefinedModuleOrigin {
+                    package_name: package.name.clone(),
+                    path: module.src_path.clone(),
+                };
+                _ = self.defined_modules.insert(module.name.clone(), origin);
+                _ = self.importable_modules.insert(module.name.clone(), module);
+            }
+            return Ok(vec![]);
+        }
+
+        let compiled = self.compile_gleam_dep_package(package)?;
+        let modules = self
+            .importable_modules
+            .values()
+            .filter(|module| module.package == package.name)
+            .cloned()
+            .collect();
+        cache.insert(key, root, modules);
+        Ok(compiled)
+    }
+
     fn compile_gleam_dep_package(
         &mut self,
         package: &ManifestPackage,
     ) -> Result<Vec<Module>, Error> {
         // TODO: Test
         let package_root = match &package.source {
-            // If the path is relative it is relative to the root of the
-            // project, not to the current working directory. The language server
-            // could have the working directory and the project root in different
-            // places.
-            ManifestPackageSource::Local { path } if path.is_relative() => {
-                self.io.canonicalise(&self.paths.root().join(path))?
-            }
+            // If the path is relative it is relative to the directory of the
+            // manifest, not to the current working directory. The language
+            // server could have the working directory and the project root in
+            // different places.
+            ManifestPackageSource::Local { path } if path.is_relative() => self
+                .io
+                .canonicalise(&self.paths.manifest_directory().join(path))?,
 
             // If the path is absolute we can use it as-is.
             ManifestPackageSource::Local { path } => path.clone(),
@@ -576,7 +655,7 @@ where
         };
         let config_path = package_root.join("gleam.toml");
         let config = PackageConfig::read(config_path, &self.io)?;
-        self.compile_gleam_package(&config, false, package_root)
+        self.compile_gleam_package(&config, false, package_root, None)
             .into_result()
             .map(|compiled| compiled.modules)
     }
@@ -586,6 +665,7 @@ where
         config: &PackageConfig,
         is_root: bool,
         root_path: Utf8PathBuf,
+        analysis_checkpoint: Option<&mut dyn FnMut()>,
     ) -> Outcome<Compiled, Error> {
         let out_path =
             self.paths
@@ -636,6 +716,9 @@ where
         compiler.compile_beam_bytecode = self.options.codegen.should_codegen(is_root);
         compiler.compile_modules = !(self.options.compile == Compile::DepsOnly && is_root);
         compiler.subprocess_stdio = self.subprocess_stdio;
+        compiler.analysis_checkpoint = analysis_checkpoint
+            .map(|checkpoint| checkpoint as &mut dyn FnMut())
+            .into();
         compiler.target_support = if is_root {
             // When compiling the root package it is context specific as to whether we need to
             // enforce that all functions have an implementation for the current target.
@@ -4,9 +4,11 @@
 use crate::{
     Result,
     build::{
-        ErlangAppCodegenConfiguration, Module, module_erlang_name, package_compiler::StdlibPackage,
+        ErlangAppCodegenConfiguration, Module, Target, module_erlang_name,
+        package_compiler::StdlibPackage,
     },
     config::PackageConfig,
+    coverage::Coverage,
     erlang,
     io::FileSystemWriter,
     javascript::{self, ModuleConfig},
@@ -25,6 +27,7 @@ use camino::Utf8Path;
 pub struct Erlang<'a> {
     build_directory: &'a Utf8Path,
     include_directory: &'a Utf8Path,
+    coverage: bool,
 }
 
 impl<'a> Erlang<'a> {
@@ -32,9 +35,17 @@ impl<'a> Erlang<'a> {
         Self {
             build_directory,
             include_directory,
+            coverage: false,
         }
     }
 
+    /// Makes the generated code count the uses of the functions and case
+    /// clauses of the `src` modules, for `gleam test --coverage`.
+    pub fn with_coverage(mut self, coverage: bool) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
     pub fn render<Writer: FileSystemWriter>(
         &self,
         writer: Writer,
@@ -59,7 +70,11 @@ impl<'a> Erlang<'a> {
         let name = format!("{erl_name}.erl");
         let path = self.build_directory.join(&name);
         let line_numbers = LineNumbers::new(&module.code);
-        let output = erlang::module(&module.ast, &line_numbers, root);
+        let coverage = self
+            .coverage
+            .then(|| Coverage::for_module(module, Target::Erlang))
+            .flatten();
+        let output = erlang::module(&module.ast, &line_numbers, root, coverage.as_ref());
         tracing::debug!(name = ?name, "Generated Erlang module");
         writer.write(&path, &output?)
     }
@@ -179,6 +194,7 @@ pub struct JavaScript<'a> {
     project_root: &'a Utf8Path,
     typescript: TypeScriptDeclarations,
     source_map: bool,
+    coverage: bool,
 }
 
 impl<'a> JavaScript<'a> {
@@ -195,9 +211,17 @@ impl<'a> JavaScript<'a> {
             project_root,
             typescript,
             source_map,
+            coverage: false,
         }
     }
 
+    /// Makes the generated code count the uses of the functions and case
+    /// clauses of the `src` modules, for `gleam test --coverage`.
+    pub fn with_coverage(mut self, coverage: bool) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
     pub fn render(
         &self,
         writer: &impl FileSystemWriter,
@@ -265,6 +289,10 @@ impl<'a> JavaScript<'a> {
         let name = format!("{js_name}.mjs");
         let path = self.output_directory.join(name);
         let line_numbers = LineNumbers::new(&module.code);
+        let coverage = self
+            .coverage
+            .then(|| Coverage::for_module(module, Target::JavaScript))
+            .flatten();
         let (output, source_map) = javascript::module(ModuleConfig {
             module: &module.ast,
             line_numbers: &line_numbers,
@@ -274,6 +302,7 @@ impl<'a> JavaScript<'a> {
             typescript: self.typescript,
             source_map: self.source_map,
             stdlib_package,
+            coverage: coverage.as_ref(),
         });
         tracing::debug!(name = ?js_name, "Generated js module");
         writer.write(&path, &output)?;
@@ -2,6 +2,7 @@
 // SPDX-FileCopyrightText: 2020 The Gleam contributors
 
 mod stale_package_remover;
+mod workspace;
 use crate::error::{FileIoAction, FileKind};
 use crate::io::FileSystemReader;
 use crate::io::ordered_map;
@@ -27,6 +28,8 @@ use crate::manifest::ManifestPackage;
 
 use crate::build::{Mode, Runtime, Target};
 
+pub use workspace::{WORKSPACE_PACKAGE_NAME, Workspace, WorkspaceConfig};
+
 fn default_version() -> Version {
     Version::parse("0.1.0").expect("default version")
 }
@@ -185,6 +188,12 @@ pub struct PackageConfig {
     pub target: Target,
     #[serde(default)]
     pub internal_modules: Option<Vec<Glob>>,
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "ordered_map"
+    )]
+    pub hex_repositories: HashMap<EcoString, HexRepositoryConfig>,
 }
 
 pub fn serialise_gleam_version<S>(
@@ -216,7 +225,7 @@ where
 impl PackageConfig {
     pub fn dependencies_for(&self, mode: Mode) -> Result<HashMap<EcoString, Requirement>> {
         match mode {
-            Mode::Dev | Mode::Lsp => self.all_direct_dependencies(),
+            Mode::Dev | Mode::Lsp | Mode::Coverage => self.all_direct_dependencies(),
             Mode::Prod => Ok(self.dependencies.clone()),
         }
     }
@@ -396,6 +405,41 @@ version = "1.0.0"
     insta::assert_snapshot!(insta::internals::AutoName, error.pretty_string());
 }
 
+#[test]
+fn hex_repositories() {
+    let toml = r#"
+name = "wibble"
+version = "1.0.0"
+
+[dependencies]
+internal_lib = { version = "~> 1.0", repo = "internal" }
+
+[hex_repositories.internal]
+api_url = "https://hex.example.com/api"
+repository_url = "https://hex.example.com/repo"
+"#;
+    let config = deserialise_config("gleam.toml", toml.into()).unwrap();
+    assert_eq!(
+        config.dependencies.get("internal_lib"),
+        Some(&Requirement::Hex {
+            version: version::Range::new("~> 1.0".into()).unwrap(),
+            repository: Some("internal".into()),
+        })
+    );
+    assert_eq!(
+        config.hex_repositories,
+        [(
+            "internal".into(),
+            HexRepositoryConfig {
+                api_url: Some("https://hex.example.com/api".into()),
+                repository_url: Some("https://hex.example.com/repo".into()),
+                public_key: None,
+            }
+        )]
+        .into()
+    );
+}
+
 #[test]
 fn locked_no_manifest() {
     let mut config = PackageConfig::default();
@@ -433,6 +477,7 @@ fn locked_no_changes() {
             manifest_package("dev1", "1.1.0", &[]),
             manifest_package("dev2", "1.2.0", &[]),
         ],
+        vendor: None,
     };
     assert_eq!(
         config.locked(Some(&manifest)).unwrap(),
@@ -459,6 +504,7 @@ fn locked_some_removed() {
             manifest_package("dev1", "1.1.0", &[]),  // Not in config
             manifest_package("dev2", "1.2.0", &[]),
         ],
+        vendor: None,
     };
     assert_eq!(
         config.locked(Some(&manifest)).unwrap(),
@@ -499,6 +545,7 @@ fn locked_some_changed() {
             manifest_package("dev1", "1.1.0", &[]),
             manifest_package("dev2", "1.2.0", &[]),
         ],
+        vendor: None,
     };
     assert_eq!(
         config.locked(Some(&manifest)).unwrap(),
@@ -544,6 +591,7 @@ fn locked_nested_are_removed_too() {
             manifest_package("2.2.2", "2.1.0", &[]),
             manifest_package("shared", "2.1.0", &[]),
         ],
+        vendor: None,
     };
     assert_eq!(
         config.locked(Some(&manifest)).unwrap(),
@@ -584,6 +632,7 @@ fn locked_unlock_new() {
             manifest_package("2", "1.1.0", &["3"]),
             manifest_package("3", "1.1.0", &[]),
         ],
+        vendor: None,
     };
     assert_eq!(
         config.locked(Some(&manifest)).unwrap(),
@@ -710,6 +759,7 @@ fn manifest_package(
             .collect(),
         source: crate::manifest::ManifestPackageSource::Hex {
             outer_checksum: Base16Checksum(vec![]),
+            repository: None,
         },
     }
 }
@@ -735,6 +785,7 @@ impl Default for PackageConfig {
             licences: Default::default(),
             links: Default::default(),
             internal_modules: Default::default(),
+            hex_repositories: Default::default(),
             target: Target::Erlang,
         }
     }
@@ -1003,6 +1054,27 @@ impl Repository {
     }
 }
 
+/// A Hex compatible package repository that dependencies can be fetched from,
+/// such as a private registry of internal packages or a mirror of hex.pm.
+///
+/// Any of these can instead be given with environment variables, see
+/// `hex::Repositories`. The key used to authenticate with the repository can
+/// only be given that way, as `gleam.toml` is published along with the
+/// package.
+#[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HexRepositoryConfig {
+    /// Where the Hex API is, which the metadata of releases is read from.
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Where the registry of the repository and the package tarballs are.
+    #[serde(default)]
+    pub repository_url: Option<String>,
+    /// The PEM encoded public key the registry is signed with.
+    #[serde(default)]
+    pub public_key: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, PartialEq, Eq, Clone)]
 pub struct Docs {
     #[serde(default)]
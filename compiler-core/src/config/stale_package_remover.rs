@@ -99,6 +99,7 @@ mod tests {
             "required_package".into(),
             Requirement::Hex {
                 version: Range::new("1.0.0".into()).unwrap(),
+                repository: None,
             },
         )]);
         let manifest = Manifest {
@@ -115,8 +116,10 @@ mod tests {
                 ],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![]),
+                    repository: None,
                 },
             }],
+            vendor: None,
         };
 
         assert_eq!(
@@ -0,0 +1,421 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Workspaces, in which several packages of one repository share a single
+//! manifest and build directory.
+//!
+//! A workspace is a directory with a `gleam-workspace.toml` file listing the
+//! directories of its member packages:
+//!
+//! ```toml
+//! members = ["packages/*", "apps/website"]
+//! ```
+//!
+//! A member ending in `/*` stands for each directory within it that has a
+//! `gleam.toml` file. The dependencies of all the members are resolved
+//! together, so they all use the same version of each package.
+
+use super::{HexRepositoryConfig, PackageConfig, deserialise_config};
+use crate::error::{FileIoAction, FileKind};
+use crate::io::FileSystemReader;
+use crate::paths::WORKSPACE_CONFIG_FILE;
+use crate::requirement::Requirement;
+use crate::{Error, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use hexpm::version::Range;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The name of the package used to resolve the dependencies of all the
+/// members of a workspace at once. It can't clash with the name of a real
+/// package.
+pub const WORKSPACE_PACKAGE_NAME: &str = "gleam@@workspace";
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    pub members: Vec<Utf8PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub root: Utf8PathBuf,
+    /// The root directories of the member packages, in the order they are
+    /// listed in the workspace config.
+    pub members: Vec<Utf8PathBuf>,
+}
+
+impl Workspace {
+    /// Finds the workspace in the given directory or the nearest of its
+    /// parents with a workspace config file.
+    ///
+    pub fn find<FS: FileSystemReader>(directory: &Utf8Path, fs: &FS) -> Result<Option<Self>> {
+        for root in directory.ancestors() {
+            if fs.is_file(&root.join(WORKSPACE_CONFIG_FILE)) {
+                return Self::read(root, fs).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds the workspace the package with the given root directory is a
+    /// member of, if any.
+    ///
+    pub fn containing_package<FS: FileSystemReader>(
+        package_root: &Utf8Path,
+        fs: &FS,
+    ) -> Result<Option<Self>> {
+        let Some(workspace) = Self::find(package_root, fs)? else {
+            return Ok(None);
+        };
+        let package_root = fs.canonicalise(package_root)?;
+        // A member that doesn't exist can't be the package, so it is skipped
+        // here rather than being an error.
+        let is_member = workspace.members.iter().any(|member| {
+            fs.canonicalise(member)
+                .is_ok_and(|member| member == package_root)
+        });
+        Ok(is_member.then_some(workspace))
+    }
+
+    pub fn read<FS: FileSystemReader>(root: &Utf8Path, fs: &FS) -> Result<Self> {
+        let path = root.join(WORKSPACE_CONFIG_FILE);
+        let toml = fs.read(&path)?;
+        let config: WorkspaceConfig = toml::from_str(&toml).map_err(|e| Error::FileIo {
+            action: FileIoAction::Parse,
+            kind: FileKind::File,
+            path: path.clone(),
+            err: Some(e.to_string()),
+        })?;
+
+        let mut members = vec![];
+        for member in config.members {
+            if member.file_name() != Some("*") {
+                members.push(root.join(member));
+                continue;
+            }
+            let directory = root.join(member.parent().unwrap_or(Utf8Path::new("")));
+            let mut found: Vec<_> = fs
+                .read_dir(&directory)?
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.into_path())
+                .filter(|path| fs.is_file(&path.join("gleam.toml")))
+                .collect();
+            found.sort();
+            members.extend(found);
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            members,
+        })
+    }
+
+    /// The config of each member, along with the member's root directory.
+    ///
+    pub fn member_configs<FS: FileSystemReader>(
+        &self,
+        fs: &FS,
+    ) -> Result<Vec<(Utf8PathBuf, PackageConfig)>> {
+        let mut configs: Vec<(Utf8PathBuf, PackageConfig)> = vec![];
+        for member in &self.members {
+            let path = member.join("gleam.toml");
+            let config = deserialise_config(&path, fs.read(&path)?)?;
+            if let Some((first, _)) = configs.iter().find(|(_, other)| other.name == config.name) {
+                return Err(Error::DuplicateWorkspaceMember {
+                    name: config.name,
+                    first: first.clone(),
+                    second: member.clone(),
+                });
+            }
+            configs.push((member.clone(), config));
+        }
+        Ok(configs)
+    }
+
+    /// The config used to resolve the dependencies of the whole workspace: a
+    /// package that depends on everything its members depend on. The config
+    /// of the given member is used in place of the one on disc, so that
+    /// dependencies being added to it are taken into account.
+    ///
+    pub fn dependency_config<FS: FileSystemReader>(
+        &self,
+        fs: &FS,
+        member_root: &Utf8Path,
+        member_config: &PackageConfig,
+    ) -> Result<PackageConfig> {
+        let mut dependencies: HashMap<EcoString, Requirement> = HashMap::new();
+        let mut hex_repositories: HashMap<EcoString, HexRepositoryConfig> = HashMap::new();
+        let member_root = fs.canonicalise(member_root)?;
+
+        for (root, config) in self.member_configs(fs)? {
+            let root = fs.canonicalise(&root)?;
+            let config = if root == member_root {
+                member_config
+            } else {
+                &config
+            };
+            for (name, requirement) in config.all_direct_dependencies()? {
+                let requirement = match requirement {
+                    // The paths of path dependencies are relative to the member
+                    // they are a dependency of.
+                    Requirement::Path { path } => Requirement::Path {
+                        path: fs.canonicalise(&root.join(path))?,
+                    },
+                    requirement @ (Requirement::Hex { .. } | Requirement::Git { .. }) => {
+                        requirement
+                    }
+                };
+                let requirement = match dependencies.remove(&name) {
+                    Some(existing) => {
+                        combine_requirements(&self.root, &name, existing, requirement)?
+                    }
+                    None => requirement,
+                };
+                let _ = dependencies.insert(name, requirement);
+            }
+            for (name, repository) in &config.hex_repositories {
+                match hex_repositories.get(name) {
+                    Some(existing) if existing != repository => {
+                        return Err(Error::InvalidHexRepository {
+                            name: name.clone(),
+                            reason: "members of the workspace configure it differently".into(),
+                        });
+                    }
+                    Some(_) => (),
+                    None => _ = hex_repositories.insert(name.clone(), repository.clone()),
+                }
+            }
+        }
+
+        Ok(PackageConfig {
+            name: WORKSPACE_PACKAGE_NAME.into(),
+            dependencies,
+            hex_repositories,
+            ..Default::default()
+        })
+    }
+}
+
+/// Two members of the workspace can depend on the same package as long as
+/// they get it from the same place. Version requirements for a Hex package
+/// from the same repository are combined, so that the version chosen
+/// satisfies both.
+///
+fn combine_requirements(
+    root: &Utf8Path,
+    name: &EcoString,
+    one: Requirement,
+    other: Requirement,
+) -> Result<Requirement> {
+    if one == other {
+        return Ok(one);
+    }
+    match (&one, &other) {
+        (
+            Requirement::Hex {
+                version: left,
+                repository,
+            },
+            Requirement::Hex {
+                version: right,
+                repository: other_repository,
+            },
+        ) if repository == other_repository
+            && !left.as_str().contains(" or ")
+            && !right.as_str().contains(" or ") =>
+        {
+            let range = format!("{left} and {right}");
+            let version =
+                Range::new(range.clone()).map_err(|error| Error::InvalidVersionFormat {
+                    input: range,
+                    error: error.to_string(),
+                })?;
+            Ok(Requirement::Hex {
+                version,
+                repository: repository.clone(),
+            })
+        }
+        _ => Err(Error::ProvidedDependencyConflict {
+            package: name.to_string(),
+            source_1: one.to_toml(root),
+            source_2: other.to_toml(root),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{FileSystemWriter, memory::InMemoryFileSystem};
+
+    fn write(fs: &InMemoryFileSystem, path: &str, content: &str) {
+        fs.write(Utf8Path::new(path), content).unwrap();
+    }
+
+    fn hex(version: &str) -> Requirement {
+        Requirement::hex(version).unwrap()
+    }
+
+    fn workspace_with_members() -> InMemoryFileSystem {
+        let fs = InMemoryFileSystem::new();
+        write(
+            &fs,
+            "/ws/gleam-workspace.toml",
+            r#"members = ["packages/*", "apps/web"]"#,
+        );
+        write(
+            &fs,
+            "/ws/packages/one/gleam.toml",
+            r#"name = "one"
+[dependencies]
+wibble = ">= 1.0.0 and < 2.0.0"
+two = { path = "../two" }"#,
+        );
+        write(
+            &fs,
+            "/ws/packages/two/gleam.toml",
+            r#"name = "two"
+[dev-dependencies]
+wibble = ">= 1.2.0 and < 2.0.0"
+wobble = "~> 3.0""#,
+        );
+        write(&fs, "/ws/packages/README.md", "");
+        write(&fs, "/ws/apps/web/gleam.toml", r#"name = "web""#);
+        fs
+    }
+
+    #[test]
+    fn read_expands_globs() {
+        let fs = workspace_with_members();
+        let workspace = Workspace::read(Utf8Path::new("/ws"), &fs).unwrap();
+        assert_eq!(
+            workspace,
+            Workspace {
+                root: "/ws".into(),
+                members: vec![
+                    "/ws/packages/one".into(),
+                    "/ws/packages/two".into(),
+                    "/ws/apps/web".into(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn find_in_parent_directory() {
+        let fs = workspace_with_members();
+        let workspace = Workspace::find(Utf8Path::new("/ws/packages/one/src"), &fs).unwrap();
+        assert_eq!(
+            workspace.map(|workspace| workspace.root),
+            Some("/ws".into())
+        );
+    }
+
+    #[test]
+    fn find_outside_a_workspace() {
+        let fs = workspace_with_members();
+        assert_eq!(Workspace::find(Utf8Path::new("/other"), &fs).unwrap(), None);
+    }
+
+    #[test]
+    fn containing_package_of_member() {
+        let fs = workspace_with_members();
+        let workspace = Workspace::containing_package(Utf8Path::new("/ws/apps/web"), &fs).unwrap();
+        assert_eq!(
+            workspace.map(|workspace| workspace.root),
+            Some("/ws".into())
+        );
+    }
+
+    #[test]
+    fn containing_package_of_non_member() {
+        let fs = workspace_with_members();
+        write(&fs, "/ws/examples/demo/gleam.toml", r#"name = "demo""#);
+        let workspace =
+            Workspace::containing_package(Utf8Path::new("/ws/examples/demo"), &fs).unwrap();
+        assert_eq!(workspace, None);
+    }
+
+    #[test]
+    fn dependency_config_combines_members() {
+        let fs = workspace_with_members();
+        let workspace = Workspace::read(Utf8Path::new("/ws"), &fs).unwrap();
+        let member_config = PackageConfig {
+            name: "web".into(),
+            dependencies: [("wubble".into(), hex("~> 1.0"))].into(),
+            ..Default::default()
+        };
+        let config = workspace
+            .dependency_config(&fs, Utf8Path::new("/ws/apps/web"), &member_config)
+            .unwrap();
+
+        assert_eq!(config.name, WORKSPACE_PACKAGE_NAME);
+        assert_eq!(
+            config.dependencies,
+            [
+                (
+                    "wibble".into(),
+                    hex(">= 1.0.0 and < 2.0.0 and >= 1.2.0 and < 2.0.0")
+                ),
+                ("wobble".into(), hex("~> 3.0")),
+                ("wubble".into(), hex("~> 1.0")),
+                // The path was relative to the member, so it is made absolute.
+                // The in-memory file system doesn't resolve `..` when doing so.
+                (
+                    "two".into(),
+                    Requirement::Path {
+                        path: "/ws/packages/one/../two".into()
+                    }
+                ),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn dependency_config_conflicting_sources() {
+        let fs = workspace_with_members();
+        let workspace = Workspace::read(Utf8Path::new("/ws"), &fs).unwrap();
+        let member_config = PackageConfig {
+            name: "web".into(),
+            dependencies: [(
+                "wobble".into(),
+                Requirement::Git {
+                    git: "https://example.com/wobble.git".into(),
+                    ref_: "main".into(),
+                },
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let error = workspace
+            .dependency_config(&fs, Utf8Path::new("/ws/apps/web"), &member_config)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::ProvidedDependencyConflict {
+                package: "wobble".into(),
+                source_1: r#"{ version = "~> 3.0" }"#.into(),
+                source_2: r#"{ git = "https://example.com/wobble.git", ref = "main" }"#.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn duplicate_member_names() {
+        let fs = workspace_with_members();
+        write(&fs, "/ws/apps/web/gleam.toml", r#"name = "two""#);
+        let workspace = Workspace::read(Utf8Path::new("/ws"), &fs).unwrap();
+        assert_eq!(
+            workspace.member_configs(&fs).unwrap_err(),
+            Error::DuplicateWorkspaceMember {
+                name: "two".into(),
+                first: "/ws/packages/two".into(),
+                second: "/ws/apps/web".into(),
+            }
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Code coverage for `gleam test --coverage`.
+//!
+//! When compiling in `Mode::Coverage` the code generated for the modules of
+//! the root package's `src` directory counts how many times each function is
+//! called and each case clause is run. Each of these places in the code is a
+//! `CoveragePoint`, numbered in the order they appear in the module.
+//!
+//! The points of each module are written to a `CoverageMap` next to the
+//! compiled module, so that once the tests have run the counts can be mapped
+//! back to the lines of the `.gleam` source file.
+
+use std::{collections::HashMap, sync::Arc};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{
+        SrcSpan, TypedClause, TypedExpr, TypedFunction, TypedModule,
+        visit::{self, Visit},
+    },
+    build::{Module, Target},
+    exhaustiveness::CompiledCase,
+    line_numbers::LineNumbers,
+    type_::Type,
+};
+
+/// The extension of the files the coverage maps are written to, in the
+/// artefacts directory of the package.
+pub const COVERAGE_MAP_EXTENSION: &str = "coverage.json";
+
+/// A place in the code whose uses are counted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CoveragePoint {
+    pub kind: CoverageKind,
+    /// The line of the source file the point starts on, starting from 1.
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum CoverageKind {
+    /// A module function, counted each time it is called.
+    Function { name: EcoString },
+    /// A clause of a case expression, counted each time it matches. Cases are
+    /// numbered in the order they appear in the module, and clauses in the
+    /// order they appear in their case.
+    Clause { case: usize, clause: usize },
+}
+
+/// The coverage points of a module, as written next to the compiled module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CoverageMap {
+    pub module: EcoString,
+    /// The path of the module's source file, relative to the root of the
+    /// package.
+    pub path: Utf8PathBuf,
+    pub points: Vec<CoveragePoint>,
+}
+
+/// The coverage points of a module, used by the code generators to find the
+/// index of the counter for a function or a case clause.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    points: Vec<CoveragePoint>,
+    indexes: HashMap<SrcSpan, usize>,
+}
+
+impl Coverage {
+    /// The coverage points of a module. Only the modules of the `src`
+    /// directory are instrumented, as the test and dev modules are not what
+    /// the tests are checking.
+    ///
+    pub fn for_module(module: &Module, target: Target) -> Option<Self> {
+        if !module.origin.is_src() {
+            return None;
+        }
+        Some(Self::new(&module.ast, &module.code, target))
+    }
+
+    /// The coverage points of a module's functions that have Gleam code to
+    /// run on the target.
+    ///
+    pub fn new(module: &TypedModule, code: &str, target: Target) -> Self {
+        let line_numbers = LineNumbers::new(code);
+        let mut collector = PointCollector {
+            coverage: Self::default(),
+            line_numbers: &line_numbers,
+            cases: 0,
+        };
+        let functions = module
+            .definitions
+            .functions
+            .iter()
+            .filter(|function| is_instrumented(module, function, target))
+            .sorted_by_key(|function| function.location.start);
+        for function in functions {
+            collector.add_function(function);
+        }
+        collector.coverage
+    }
+
+    /// The index of the counter for the function or case clause at the given
+    /// location, if it is instrumented.
+    ///
+    pub fn index(&self, location: SrcSpan) -> Option<usize> {
+        self.indexes.get(&location).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn map(self, module: &Module, package_root: &Utf8Path) -> CoverageMap {
+        let path = module
+            .input_path
+            .strip_prefix(package_root)
+            .unwrap_or(&module.input_path)
+            .to_path_buf();
+        CoverageMap {
+            module: module.name.clone(),
+            path,
+            points: self.points,
+        }
+    }
+}
+
+/// Functions implemented with an external function for the target have no
+/// Gleam code of their own to count, and unused functions have no code at
+/// all.
+fn is_instrumented(module: &TypedModule, function: &TypedFunction, target: Target) -> bool {
+    let external = match target {
+        Target::Erlang => function.external_erlang.is_some(),
+        Target::JavaScript => function.external_javascript.is_some(),
+    };
+    !external
+        && function.implementations.supports(target)
+        && !module
+            .unused_definition_positions
+            .contains(&function.location.start)
+}
+
+struct PointCollector<'a> {
+    coverage: Coverage,
+    line_numbers: &'a LineNumbers,
+    cases: usize,
+}
+
+impl PointCollector<'_> {
+    fn add_point(&mut self, location: SrcSpan, kind: CoverageKind) {
+        let index = self.coverage.points.len();
+        self.coverage.points.push(CoveragePoint {
+            kind,
+            line: self.line_numbers.line_number(location.start),
+        });
+        let _ = self.coverage.indexes.insert(location, index);
+    }
+
+    fn add_function(&mut self, function: &TypedFunction) {
+        let Some((_, name)) = &function.name else {
+            return;
+        };
+        self.add_point(
+            function.location,
+            CoverageKind::Function { name: name.clone() },
+        );
+        visit::visit_typed_function(self, function);
+    }
+}
+
+impl<'ast> Visit<'ast> for PointCollector<'_> {
+    fn visit_typed_expr_case(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        subjects: &'ast [TypedExpr],
+        clauses: &'ast [TypedClause],
+        compiled_case: &'ast CompiledCase,
+    ) {
+        let case = self.cases;
+        self.cases += 1;
+        for (index, clause) in clauses.iter().enumerate() {
+            self.add_point(
+                clause.location,
+                CoverageKind::Clause {
+                    case,
+                    clause: index,
+                },
+            );
+        }
+        visit::visit_typed_expr_case(self, location, type_, subjects, clauses, compiled_case);
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::{cell::RefCell, cmp::Reverse, collections::HashMap, rc::Rc};
 
-use crate::{Error, Result, manifest};
+use crate::{Error, Result, hex::DEFAULT_REPOSITORY, manifest};
 
 use ecow::EcoString;
 use hexpm::{
@@ -15,14 +15,26 @@ use thiserror::Error;
 
 pub type PackageVersions = HashMap<String, Version>;
 
+/// The name of the Hex repository each package is fetched from, for those not
+/// fetched from hex.pm.
+pub type PackageRepositories = HashMap<EcoString, EcoString>;
+
 type PubgrubRange = pubgrub::Range<Version>;
 
+/// Resolves the versions of the dependencies.
+///
+/// The repositories of packages known to be fetched from a repository other
+/// than hex.pm are given, and the repositories of the other packages resolved
+/// are added to them. A package is fetched from the same repository as the
+/// package that depends on it, unless its requirement names another.
+///
 pub fn resolve_versions<Requirements>(
     package_fetcher: &impl PackageFetcher,
     provided_packages: HashMap<EcoString, hexpm::Package>,
     root_name: EcoString,
     dependencies: Requirements,
     locked: &HashMap<EcoString, Version>,
+    repositories: &mut PackageRepositories,
 ) -> Result<PackageVersions>
 where
     Requirements: Iterator<Item = (EcoString, Range)>,
@@ -53,16 +65,29 @@ where
         }],
     };
 
-    let packages = pubgrub::resolve(
-        &DependencyProvider::new(package_fetcher, provided_packages, root, locked, exact_deps),
-        root_name.as_str().into(),
-        root_version,
-    )
-    .map_err(|error| Error::dependency_resolution_failed(error, root_name.clone()))?
-    .into_iter()
-    .filter(|(name, _)| name.as_str() != root_name.as_str())
-    .collect();
-
+    let provider = DependencyProvider::new(
+        package_fetcher,
+        provided_packages,
+        root,
+        locked,
+        exact_deps,
+        repositories.clone(),
+    );
+    let packages: PackageVersions =
+        pubgrub::resolve(&provider, root_name.as_str().into(), root_version)
+            .map_err(|error| Error::dependency_resolution_failed(error, root_name.clone()))?
+            .into_iter()
+            .filter(|(name, _)| name.as_str() != root_name.as_str())
+            .collect();
+
+    *repositories = provider
+        .repositories
+        .into_inner()
+        .into_iter()
+        .filter(|(name, repository)| {
+            packages.contains_key(name.as_str()) && repository != DEFAULT_REPOSITORY
+        })
+        .collect();
     Ok(packages)
 }
 
@@ -71,15 +96,21 @@ where
 */
 pub type PackageVersionDiffs = HashMap<String, (Version, Version)>;
 
-fn resolve_versions_diffs(
+fn resolve_versions_diffs<'a>(
     package_fetcher: &impl PackageFetcher,
-    versions: PackageVersions,
+    packages: impl Iterator<Item = &'a manifest::ManifestPackage>,
     check_major_versions: bool,
 ) -> PackageVersionDiffs {
-    versions
-        .iter()
-        .filter_map(|(package, version)| {
-            let Ok(hex_package) = package_fetcher.get_dependencies(package) else {
+    packages
+        .filter_map(|manifest_package| {
+            let manifest::ManifestPackageSource::Hex { repository, .. } = &manifest_package.source
+            else {
+                return None;
+            };
+            let package = &manifest_package.name;
+            let version = &manifest_package.version;
+            let repository = repository.as_deref().unwrap_or(DEFAULT_REPOSITORY);
+            let Ok(hex_package) = package_fetcher.get_dependencies(package, repository) else {
                 return None;
             };
 
@@ -117,28 +148,18 @@ pub fn check_for_major_version_updates(
     manifest: &manifest::Manifest,
     package_fetcher: &impl PackageFetcher,
 ) -> PackageVersionDiffs {
-    let versions: PackageVersions = manifest
-        .packages
-        .iter()
-        .filter(|manifest_package| {
-            // It makes sense to check for major version upgrades only for
-            // packages that are actually published.
-            // git and path dependencies are skipped.
-            manifest_package.is_hex()
-                && manifest
-                    .requirements
-                    .iter()
-                    .any(|(required_package, _)| manifest_package.name == *required_package)
-        })
-        .map(|manifest_package| {
-            (
-                manifest_package.name.to_string(),
-                manifest_package.version.clone(),
-            )
-        })
-        .collect();
+    let packages = manifest.packages.iter().filter(|manifest_package| {
+        // It makes sense to check for major version upgrades only for
+        // packages that are actually published.
+        // git and path dependencies are skipped.
+        manifest_package.is_hex()
+            && manifest
+                .requirements
+                .iter()
+                .any(|(required_package, _)| manifest_package.name == *required_package)
+    });
 
-    resolve_versions_diffs(package_fetcher, versions, true)
+    resolve_versions_diffs(package_fetcher, packages, true)
 }
 
 /// Check for version updates for direct and transitive dependencies that are
@@ -147,19 +168,12 @@ pub fn check_for_version_updates(
     manifest: &manifest::Manifest,
     package_fetcher: &impl PackageFetcher,
 ) -> PackageVersionDiffs {
-    let versions = manifest
+    let packages = manifest
         .packages
         .iter()
-        .filter(|manifest_package| manifest_package.is_hex())
-        .map(|manifest_package| {
-            (
-                manifest_package.name.to_string(),
-                manifest_package.version.clone(),
-            )
-        })
-        .collect();
+        .filter(|manifest_package| manifest_package.is_hex());
 
-    resolve_versions_diffs(package_fetcher, versions, false)
+    resolve_versions_diffs(package_fetcher, packages, false)
 }
 
 // If the string would parse to an exact version then return the version
@@ -236,7 +250,13 @@ but it is locked to {locked_version}, which is incompatible.",
 }
 
 pub trait PackageFetcher {
-    fn get_dependencies(&self, package: &str) -> Result<Rc<hexpm::Package>, PackageFetchError>;
+    /// Fetches the releases of the package from the Hex repository of the
+    /// given name.
+    fn get_dependencies(
+        &self,
+        package: &str,
+        repository: &str,
+    ) -> Result<Rc<hexpm::Package>, PackageFetchError>;
 }
 
 #[derive(Debug, Error)]
@@ -270,6 +290,7 @@ impl PackageFetchError {
             | hexpm::ApiError::InvalidCredentials
             | hexpm::ApiError::InvalidPackageNameFormat(_)
             | hexpm::ApiError::IncorrectPayloadSignature
+            | hexpm::ApiError::InvalidPublicKey
             | hexpm::ApiError::InvalidVersionFormat(_)
             | hexpm::ApiError::InvalidVersionRequirementFormat(_)
             | hexpm::ApiError::IncorrectChecksum
@@ -293,6 +314,9 @@ pub struct DependencyProvider<'a, T: PackageFetcher> {
     // patch.
     exact_only: &'a HashMap<String, Version>,
     optional_dependencies: RefCell<HashMap<EcoString, pubgrub::Range<Version>>>,
+    /// The repository each package is fetched from, recorded as the packages
+    /// that depend on them are fetched.
+    repositories: RefCell<PackageRepositories>,
 }
 
 impl<'a, T> DependencyProvider<'a, T>
@@ -305,6 +329,7 @@ where
         root: hexpm::Package,
         locked: &'a HashMap<EcoString, Version>,
         exact_only: &'a HashMap<String, Version>,
+        repositories: PackageRepositories,
     ) -> Self {
         let _ = packages.insert(root.name.as_str().into(), root);
         Self {
@@ -313,9 +338,18 @@ where
             remote,
             exact_only,
             optional_dependencies: RefCell::new(Default::default()),
+            repositories: RefCell::new(repositories),
         }
     }
 
+    fn repository(&self, package: &str) -> EcoString {
+        self.repositories
+            .borrow()
+            .get(package)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_REPOSITORY.into())
+    }
+
     /// Download information about the package from the registry into the local
     /// store. Does nothing if the packages are already known.
     ///
@@ -331,7 +365,7 @@ where
     ) -> Result<(), PackageFetchError> {
         let mut packages = self.packages.borrow_mut();
         if packages.get(name).is_none() {
-            let package = self.remote.get_dependencies(name)?;
+            let package = self.remote.get_dependencies(name, &self.repository(name))?;
             // mut (therefore clone) is required here in order to sort the releases
             let mut package = (*package).clone();
             // Sort the packages from newest to oldest, pres after all others
@@ -384,8 +418,19 @@ where
             )));
         }
 
+        let repository = self.repository(package);
         let mut deps: Map<PackageName, PubgrubRange> = Default::default();
         for (name, dependency) in &release.requirements {
+            let dependency_repository = match &dependency.repository {
+                Some(dependency_repository) => dependency_repository.into(),
+                None => repository.clone(),
+            };
+            let _ = self
+                .repositories
+                .borrow_mut()
+                .entry(name.into())
+                .or_insert(dependency_repository);
+
             let mut range = dependency.requirement.to_pubgrub().clone();
             let mut opt_deps = self.optional_dependencies.borrow_mut();
             // if it's optional and it was not provided yet, store and skip
@@ -492,9 +537,14 @@ mod tests {
     }
 
     impl PackageFetcher for Remote {
-        fn get_dependencies(&self, package: &str) -> Result<Rc<hexpm::Package>, PackageFetchError> {
+        fn get_dependencies(
+            &self,
+            package: &str,
+            repository: &str,
+        ) -> Result<Rc<hexpm::Package>, PackageFetchError> {
             self.deps
                 .get(package)
+                .filter(|package| package.repository == repository)
                 .map(Rc::clone)
                 .ok_or(PackageFetchError::NotFoundError(package.to_string()))
         }
@@ -582,6 +632,7 @@ mod tests {
             "app".into(),
             vec![("gleam_stdlib".into(), Range::new("~> 0.1".into()).unwrap())].into_iter(),
             &vec![locked_stdlib].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -600,6 +651,7 @@ mod tests {
             "app".into(),
             vec![].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(result, vec![].into_iter().collect())
@@ -613,6 +665,7 @@ mod tests {
             "app".into(),
             vec![("gleam_stdlib".into(), Range::new("~> 0.1".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -631,6 +684,7 @@ mod tests {
             "app".into(),
             vec![("gleam_otp".into(), Range::new("~> 0.1".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -656,6 +710,7 @@ mod tests {
             )]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -684,6 +739,7 @@ mod tests {
             ]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -715,6 +771,7 @@ mod tests {
             ]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         );
         assert!(result.is_err());
     }
@@ -734,6 +791,7 @@ mod tests {
             ]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -762,6 +820,7 @@ mod tests {
             "app".into(),
             vec![("gleam_otp".into(), Range::new("~> 0.1.0".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -787,6 +846,7 @@ mod tests {
             )]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -815,6 +875,7 @@ mod tests {
             &vec![("package_with_retired".into(), Version::new(0, 2, 0))]
                 .into_iter()
                 .collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -841,6 +902,7 @@ mod tests {
             )]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -862,6 +924,7 @@ mod tests {
             "app".into(),
             vec![("gleam_otp".into(), Range::new("0.3.0-rc1".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -883,6 +946,7 @@ mod tests {
             "app".into(),
             vec![("unknown".into(), Range::new("~> 0.1".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap_err();
         match err {
@@ -903,6 +967,7 @@ the package unknown was not found in the package repository"
             "app".into(),
             vec![("gleam_stdlib".into(), Range::new("~> 99.0".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap_err();
     }
@@ -921,6 +986,7 @@ the package unknown was not found in the package repository"
             &vec![("gleam_stdlib".into(), Version::new(0, 2, 0))]
                 .into_iter()
                 .collect(),
+            &mut HashMap::new(),
         )
         .unwrap_err();
 
@@ -942,6 +1008,7 @@ but it is locked to 0.2.0, which is incompatible."
             "app".into(),
             vec![("gleam_stdlib".into(), Range::new("0.1.0".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         )
         .unwrap();
         assert_eq!(
@@ -978,18 +1045,21 @@ but it is locked to 0.2.0, which is incompatible."
                     EcoString::from("package_depends_on_indirect_pkg"),
                     requirement::Requirement::Hex {
                         version: Range::new("> 0.1.0 and <= 1.0.0".into()).unwrap(),
+                        repository: None,
                     },
                 ),
                 (
                     EcoString::from("direct_pkg_with_major_version"),
                     requirement::Requirement::Hex {
                         version: Range::new("> 0.1.0 and <= 2.0.0".into()).unwrap(),
+                        repository: None,
                     },
                 ),
                 (
                     EcoString::from("depends_on_old_version_of_direct_pkg"),
                     requirement::Requirement::Hex {
                         version: Range::new("> 0.1.0 and <= 1.0.0".into()).unwrap(),
+                        repository: None,
                     },
                 ),
             ]
@@ -1004,6 +1074,7 @@ but it is locked to 0.2.0, which is incompatible."
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 2, 3]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -1014,6 +1085,7 @@ but it is locked to 0.2.0, which is incompatible."
                     requirements: vec!["direct_pkg_with_major_version".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 2, 3]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -1024,6 +1096,7 @@ but it is locked to 0.2.0, which is incompatible."
                     requirements: vec!["indirect_pkg_with_major_version".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 2, 3]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -1034,9 +1107,11 @@ but it is locked to 0.2.0, which is incompatible."
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 2, 3]),
+                        repository: None,
                     },
                 },
             ],
+            vendor: None,
         };
         let result = check_for_major_version_updates(&manifest, &make_remote());
 
@@ -1079,6 +1154,7 @@ but it is locked to 0.2.0, which is incompatible."
                     commit: "commit".into(),
                 },
             }],
+            vendor: None,
         };
 
         // There's a package on hex with the same name of the git dependency
@@ -1109,6 +1185,7 @@ but it is locked to 0.2.0, which is incompatible."
                     path: "path".into(),
                 },
             }],
+            vendor: None,
         };
 
         // There's a package on hex with the same name of the local dependency
@@ -1174,6 +1251,69 @@ but it is locked to 0.2.0, which is incompatible."
         }
     }
 
+    #[test]
+    fn resolution_with_other_repository() {
+        let mut remote = remote(vec![
+            ("gleam_stdlib", vec![release("0.1.0", vec![])]),
+            ("internal_dep", vec![release("1.0.0", vec![])]),
+        ]);
+        let mut internal = release("1.0.0", vec![("internal_dep", ">= 1.0.0")]);
+        let _ = internal.requirements.insert(
+            "gleam_stdlib".into(),
+            Dependency {
+                requirement: Range::new(">= 0.1.0".into()).unwrap(),
+                optional: false,
+                app: None,
+                repository: Some("hexpm".into()),
+            },
+        );
+        let _ = remote.deps.insert(
+            "internal_pkg".into(),
+            Rc::new(hexpm::Package {
+                name: "internal_pkg".into(),
+                repository: "internal".into(),
+                releases: vec![internal],
+            }),
+        );
+        let internal_dep = Rc::make_mut(remote.deps.get_mut("internal_dep").unwrap());
+        internal_dep.repository = "internal".into();
+
+        let mut repositories = vec![("internal_pkg".into(), "internal".into())]
+            .into_iter()
+            .collect();
+        let result = resolve_versions(
+            &remote,
+            HashMap::new(),
+            "app".into(),
+            vec![("internal_pkg".into(), Range::new("~> 1.0".into()).unwrap())].into_iter(),
+            &vec![].into_iter().collect(),
+            &mut repositories,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                ("gleam_stdlib".into(), Version::parse("0.1.0").unwrap()),
+                ("internal_dep".into(), Version::parse("1.0.0").unwrap()),
+                ("internal_pkg".into(), Version::parse("1.0.0").unwrap()),
+            ]
+            .into_iter()
+            .collect()
+        );
+        // The dependency without a repository of its own is fetched from the
+        // same repository as the package depending on it.
+        assert_eq!(
+            repositories,
+            vec![
+                ("internal_dep".into(), "internal".into()),
+                ("internal_pkg".into(), "internal".into()),
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
     fn remote(dependencies: Vec<(&str, Vec<Release<()>>)>) -> Remote {
         let mut deps = HashMap::new();
         for (package, releases) in dependencies {
@@ -1236,6 +1376,7 @@ but it is locked to 0.2.0, which is incompatible."
             ]
             .into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         );
 
         if let Err(error @ Error::DependencyResolutionNoSolution { .. }) = result {
@@ -1259,6 +1400,7 @@ but it is locked to 0.2.0, which is incompatible."
             "app".into(),
             vec![("wibble".into(), Range::new("1.0.0".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         );
 
         if let Err(error @ Error::DependencyResolutionNoSolution { .. }) = result {
@@ -1282,6 +1424,7 @@ but it is locked to 0.2.0, which is incompatible."
             "app".into(),
             vec![("wibble".into(), Range::new("> 2.0.0".into()).unwrap())].into_iter(),
             &vec![].into_iter().collect(),
+            &mut HashMap::new(),
         );
 
         if let Err(error @ Error::DependencyResolutionNoSolution { .. }) = result {
@@ -12,6 +12,9 @@ use termcolor::Buffer;
 
 use crate::{ast::SrcSpan, error::wrap};
 
+pub mod json;
+pub mod sarif;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Level {
     Error,
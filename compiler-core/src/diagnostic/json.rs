@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! The machine readable JSON representation of a `Diagnostic`, used by
+//! `--diagnostics-format json`.
+//!
+//! Each diagnostic is serialised as a single JSON object. Fields are never
+//! removed or changed in meaning without bumping `SCHEMA_VERSION`, but new
+//! fields may be added at any time, so consumers should ignore fields they
+//! don't know about.
+//!
+//! ```json
+//! {
+//!   "schema-version": 1,
+//!   "code": "unknown-variable",
+//!   "severity": "error",
+//!   "title": "Unknown variable",
+//!   "message": "The name `x` is not in scope here.",
+//!   "hint": null,
+//!   "path": "src/app.gleam",
+//!   "labels": [
+//!     {
+//!       "style": "primary",
+//!       "path": "src/app.gleam",
+//!       "message": null,
+//!       "start": { "byte": 25, "line": 2, "column": 3 },
+//!       "end": { "byte": 26, "line": 2, "column": 4 }
+//!     }
+//!   ],
+//!   "fixes": []
+//! }
+//! ```
+//!
+//! Diagnostics that don't refer to a specific place in the code, such as
+//! errors reading a file, have a `null` path and no labels.
+//!
+//! The `code` identifies the kind of error or warning, such as
+//! `unknown-variable` or `unused-imported-value`, and never changes once it
+//! has been released. Warnings use the same ids as the rules of the SARIF log.
+//!
+//! The `fixes` are changes to the code that get rid of the problem, for the
+//! diagnostics the compiler knows how to fix. Each one replaces the code
+//! between its `start` and `end` positions in the diagnostic's file with its
+//! `replacement` text:
+//!
+//! ```json
+//! {
+//!   "replacement": "javascript",
+//!   "start": { "byte": 10, "line": 1, "column": 11 },
+//!   "end": { "byte": 13, "line": 1, "column": 14 }
+//! }
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+use camino::Utf8PathBuf;
+use serde::Serialize;
+
+use super::{Diagnostic, Label, Level, Location};
+use crate::{
+    Error,
+    line_numbers::LineNumbers,
+    warning::{Warning, WarningFix},
+};
+
+/// The version of the JSON diagnostics schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonDiagnostic {
+    schema_version: u32,
+    /// Identifies the kind of error or warning.
+    code: String,
+    severity: JsonSeverity,
+    /// A short summary of the problem.
+    title: String,
+    /// A longer explanation of the problem, which may be empty.
+    message: String,
+    hint: Option<String>,
+    /// The file the diagnostic is about, if any.
+    path: Option<Utf8PathBuf>,
+    /// The places in the code the diagnostic refers to. If there are any, the
+    /// first one is the primary label pointing at the problem itself.
+    labels: Vec<JsonLabel>,
+    /// The changes to the code that fix the problem, if the compiler knows of
+    /// any.
+    fixes: Vec<JsonFix>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonLabel {
+    style: JsonLabelStyle,
+    path: Utf8PathBuf,
+    message: Option<String>,
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonLabelStyle {
+    Primary,
+    Secondary,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonFix {
+    replacement: String,
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+/// A position in a source file. The byte offset starts from 0, while lines
+/// and columns start from 1. Columns are counted in UTF-16 code units, the
+/// same as the language server protocol.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonPosition {
+    byte: u32,
+    line: u32,
+    column: u32,
+}
+
+impl JsonDiagnostic {
+    pub fn from_warning(warning: &Warning) -> Self {
+        Self::new(
+            warning.rule().id,
+            &warning.to_diagnostic(),
+            &warning.fixes(),
+        )
+    }
+
+    /// An error can be made of several diagnostics, such as when there are
+    /// type errors in more than one place.
+    ///
+    pub fn from_error(error: &Error) -> Vec<Self> {
+        error
+            .diagnostic_codes()
+            .into_iter()
+            .zip(error.to_diagnostics())
+            .map(|(code, diagnostic)| Self::new(code, &diagnostic, &[]))
+            .collect()
+    }
+
+    fn new(code: &str, diagnostic: &Diagnostic, fixes: &[WarningFix]) -> Self {
+        let Diagnostic {
+            title,
+            text,
+            level,
+            location,
+            hint,
+        } = diagnostic;
+
+        let severity = match level {
+            Level::Error => JsonSeverity::Error,
+            Level::Warning => JsonSeverity::Warning,
+        };
+
+        let fixes = match location {
+            Some(location) => {
+                let line_numbers = LineNumbers::new(&location.src);
+                fixes
+                    .iter()
+                    .map(|fix| JsonFix::new(fix, &line_numbers))
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            code: code.into(),
+            severity,
+            title: title.clone(),
+            message: text.clone(),
+            hint: hint.clone(),
+            path: location.as_ref().map(|location| location.path.clone()),
+            labels: location.as_ref().map(labels).unwrap_or_default(),
+            fixes,
+        }
+    }
+}
+
+fn labels(location: &Location) -> Vec<JsonLabel> {
+    let line_numbers = LineNumbers::new(&location.src);
+    let primary = JsonLabel::new(
+        JsonLabelStyle::Primary,
+        &location.label,
+        &location.path,
+        &line_numbers,
+    );
+
+    let secondary = location
+        .extra_labels
+        .iter()
+        .map(|extra| match &extra.src_info {
+            Some((src, path)) => JsonLabel::new(
+                JsonLabelStyle::Secondary,
+                &extra.label,
+                path,
+                &LineNumbers::new(src),
+            ),
+            None => JsonLabel::new(
+                JsonLabelStyle::Secondary,
+                &extra.label,
+                &location.path,
+                &line_numbers,
+            ),
+        });
+
+    std::iter::once(primary).chain(secondary).collect()
+}
+
+impl JsonLabel {
+    fn new(
+        style: JsonLabelStyle,
+        label: &Label,
+        path: &Utf8PathBuf,
+        line_numbers: &LineNumbers,
+    ) -> Self {
+        Self {
+            style,
+            path: path.clone(),
+            message: label.text.clone(),
+            start: JsonPosition::new(label.span.start, line_numbers),
+            end: JsonPosition::new(label.span.end, line_numbers),
+        }
+    }
+}
+
+impl JsonFix {
+    fn new(fix: &WarningFix, line_numbers: &LineNumbers) -> Self {
+        Self {
+            replacement: fix.replacement.to_string(),
+            start: JsonPosition::new(fix.location.start, line_numbers),
+            end: JsonPosition::new(fix.location.end, line_numbers),
+        }
+    }
+}
+
+impl JsonPosition {
+    fn new(byte: u32, line_numbers: &LineNumbers) -> Self {
+        let position = line_numbers.line_and_column_number(byte);
+        Self {
+            byte,
+            line: position.line,
+            column: position.column,
+        }
+    }
+}
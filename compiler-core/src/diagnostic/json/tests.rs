@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::collections::HashMap;
+
+use camino::Utf8PathBuf;
+use vec1::vec1;
+
+use super::JsonDiagnostic;
+use crate::{
+    Error,
+    ast::SrcSpan,
+    build::Target,
+    diagnostic::{Diagnostic, ExtraLabel, Label, Level, Location},
+    error::FailedModule,
+    type_::{self, printer::Names},
+    warning::{DeprecatedSyntaxWarning, Warning},
+};
+
+fn to_json(code: &str, diagnostic: Diagnostic) -> String {
+    serde_json::to_string_pretty(&JsonDiagnostic::new(code, &diagnostic, &[]))
+        .expect("serialise diagnostic")
+}
+
+fn warning_to_json(warning: Warning) -> String {
+    serde_json::to_string_pretty(&JsonDiagnostic::from_warning(&warning))
+        .expect("serialise diagnostic")
+}
+
+fn error_to_json(error: Error) -> String {
+    JsonDiagnostic::from_error(&error)
+        .iter()
+        .map(|diagnostic| serde_json::to_string_pretty(diagnostic).expect("serialise diagnostic"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn diagnostic_without_location() {
+    insta::assert_snapshot!(to_json(
+        "hex-session-revoked",
+        Diagnostic {
+            title: "Hex session revoked".into(),
+            text: "Your Hex session has been revoked or has expired.".into(),
+            level: Level::Error,
+            location: None,
+            hint: Some("Run 'gleam hex authenticate' to log in again.".into()),
+        },
+    ));
+}
+
+#[test]
+fn diagnostic_with_location() {
+    let src = "pub fn main() {\n  let x = 1\n  \"Ä\" <> y\n}\n";
+    insta::assert_snapshot!(to_json(
+        "unknown-variable",
+        Diagnostic {
+            title: "Unknown variable".into(),
+            text: "The name `y` is not in scope here.".into(),
+            level: Level::Error,
+            location: Some(Location {
+                src: src.into(),
+                path: Utf8PathBuf::from("src/app.gleam"),
+                label: Label {
+                    text: None,
+                    span: SrcSpan::new(38, 39),
+                },
+                extra_labels: vec![ExtraLabel {
+                    src_info: None,
+                    label: Label {
+                        text: Some("Did you mean this?".into()),
+                        span: SrcSpan::new(22, 23),
+                    },
+                }],
+            }),
+            hint: None,
+        },
+    ));
+}
+
+#[test]
+fn diagnostic_with_label_in_another_file() {
+    insta::assert_snapshot!(to_json(
+        "unused-value",
+        Diagnostic {
+            title: "Unused value".into(),
+            text: "".into(),
+            level: Level::Warning,
+            location: Some(Location {
+                src: "import other\n\npub fn main() {\n  other.value\n}\n".into(),
+                path: Utf8PathBuf::from("src/app.gleam"),
+                label: Label {
+                    text: Some("This value is never used".into()),
+                    span: SrcSpan::new(32, 43),
+                },
+                extra_labels: vec![ExtraLabel {
+                    src_info: Some((
+                        "pub const value = 1\n".into(),
+                        Utf8PathBuf::from("src/other.gleam"),
+                    )),
+                    label: Label {
+                        text: Some("Defined here".into()),
+                        span: SrcSpan::new(10, 15),
+                    },
+                }],
+            }),
+            hint: None,
+        },
+    ));
+}
+
+#[test]
+fn warning_with_fix() {
+    let src = "@external(erl, \"wibble\", \"wobble\")\npub fn wibble() -> Nil\n";
+    insta::assert_snapshot!(warning_to_json(Warning::DeprecatedSyntax {
+        path: Utf8PathBuf::from("src/app.gleam"),
+        src: src.into(),
+        warning: DeprecatedSyntaxWarning::DeprecatedTargetShorthand {
+            target: Target::Erlang,
+            location: SrcSpan::new(10, 13),
+        },
+    }));
+}
+
+#[test]
+fn warning_without_fix() {
+    insta::assert_snapshot!(warning_to_json(Warning::EmptyModule {
+        path: Utf8PathBuf::from("src/app.gleam"),
+        name: "app".into(),
+    }));
+}
+
+#[test]
+fn type_errors_each_have_their_own_code() {
+    let src = "pub fn main() {\n  echo\n  1.0e400\n}\n";
+    let failed_module = FailedModule {
+        path: Utf8PathBuf::from("src/app.gleam"),
+        src: src.into(),
+        errors: vec1![
+            type_::Error::EchoWithNoFollowingExpression {
+                location: SrcSpan::new(18, 22),
+            },
+            type_::Error::LiteralFloatOutOfRange {
+                location: SrcSpan::new(25, 32),
+            },
+        ],
+        names: Box::new(Names::new()),
+    };
+    insta::assert_snapshot!(error_to_json(Error::Type {
+        skipped_modules: vec![],
+        failed_modules: HashMap::from([("app".into(), failed_module)]),
+    }));
+}
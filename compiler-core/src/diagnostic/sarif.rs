@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! A [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! log of the warnings and errors of a build, used by
+//! `--diagnostics-format sarif` so that warnings can be tracked by code
+//! scanning tools.
+//!
+//! Each warning is reported under the id of its `WarningRule`, and errors are
+//! all reported under the `compile-error` rule. Only the rules that have
+//! results are included in the log.
+
+#[cfg(test)]
+mod tests;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+
+use super::{Diagnostic, Label, Level, Location};
+use crate::{
+    error::Error,
+    line_numbers::LineNumbers,
+    version::COMPILER_VERSION,
+    warning::{Warning, WarningRule},
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+
+const COMPILE_ERROR: WarningRule = WarningRule {
+    id: "compile-error",
+    help: "The code could not be compiled.",
+};
+
+#[derive(Serialize, Debug)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize, Debug)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize, Debug)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    short_description: Message,
+    help: Message,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize, Debug)]
+struct Configuration {
+    level: SarifLevel,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum SarifLevel {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: SarifLevel,
+    message: Message,
+    locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize, Debug)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize, Debug)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+/// Lines and columns start from 1, and columns are counted in UTF-16 code
+/// units, which is the default for SARIF.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+impl SarifLog {
+    /// Paths inside of `root` are written relative to it, so that the log
+    /// doesn't depend on where the project is on disk.
+    ///
+    pub fn new(root: &Utf8Path, warnings: &[Warning], errors: &[Error]) -> Self {
+        let warnings = warnings.iter().map(|warning| {
+            let path = Some(warning_path(warning));
+            (warning.rule(), warning.to_diagnostic(), path)
+        });
+        let errors = errors.iter().flat_map(|error| {
+            error
+                .to_diagnostics()
+                .into_iter()
+                .map(|diagnostic| (COMPILE_ERROR, diagnostic, None))
+        });
+        let reported: Vec<_> = warnings.chain(errors).collect();
+
+        let mut rules: Vec<_> = reported
+            .iter()
+            .map(|(rule, diagnostic, _)| (*rule, diagnostic.level.to_sarif()))
+            .collect();
+        rules.sort_by_key(|(rule, _)| rule.id);
+        rules.dedup_by_key(|(rule, _)| rule.id);
+
+        let results = reported
+            .into_iter()
+            .map(|(rule, diagnostic, path)| {
+                let rule_index = rules
+                    .iter()
+                    .position(|(other, _)| other.id == rule.id)
+                    .expect("Rule of reported diagnostic");
+                SarifResult::new(root, rule, rule_index, &diagnostic, path)
+            })
+            .collect();
+
+        let rules = rules
+            .into_iter()
+            .map(|(rule, level)| Rule {
+                id: rule.id,
+                short_description: Message {
+                    text: rule.help.into(),
+                },
+                help: Message {
+                    text: rule.help.into(),
+                },
+                default_configuration: Configuration { level },
+            })
+            .collect();
+
+        Self {
+            schema: SCHEMA,
+            version: VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "gleam",
+                        version: COMPILER_VERSION,
+                        information_uri: "https://gleam.run",
+                        rules,
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+impl SarifResult {
+    fn new(
+        root: &Utf8Path,
+        rule: WarningRule,
+        rule_index: usize,
+        diagnostic: &Diagnostic,
+        path: Option<&Utf8PathBuf>,
+    ) -> Self {
+        let (locations, related_locations) = match &diagnostic.location {
+            Some(location) => {
+                let (primary, related) = locations(root, location);
+                (vec![primary], related)
+            }
+            // Some diagnostics are about a whole file rather than a specific
+            // place in it, so they only point to the file.
+            None => match path {
+                Some(path) => (vec![file_location(root, path)], vec![]),
+                None => (vec![], vec![]),
+            },
+        };
+
+        Self {
+            rule_id: rule.id,
+            rule_index,
+            level: diagnostic.level.to_sarif(),
+            message: Message {
+                text: message(diagnostic),
+            },
+            locations,
+            related_locations,
+        }
+    }
+}
+
+/// The whole text of a diagnostic, as it would be printed without its labels.
+fn message(diagnostic: &Diagnostic) -> String {
+    let Diagnostic {
+        title, text, hint, ..
+    } = diagnostic;
+    let mut message = title.clone();
+    if !text.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(text);
+    }
+    if let Some(hint) = hint {
+        message.push_str("\n\nHint: ");
+        message.push_str(hint);
+    }
+    message
+}
+
+fn locations(root: &Utf8Path, location: &Location) -> (SarifLocation, Vec<SarifLocation>) {
+    let line_numbers = LineNumbers::new(&location.src);
+    let primary = label_location(root, &location.path, &line_numbers, &location.label, None);
+
+    let related = location
+        .extra_labels
+        .iter()
+        .enumerate()
+        .map(|(index, extra)| match &extra.src_info {
+            Some((src, path)) => label_location(
+                root,
+                path,
+                &LineNumbers::new(src),
+                &extra.label,
+                Some(index),
+            ),
+            None => label_location(
+                root,
+                &location.path,
+                &line_numbers,
+                &extra.label,
+                Some(index),
+            ),
+        })
+        .collect();
+
+    (primary, related)
+}
+
+fn label_location(
+    root: &Utf8Path,
+    path: &Utf8Path,
+    line_numbers: &LineNumbers,
+    label: &Label,
+    id: Option<usize>,
+) -> SarifLocation {
+    let start = line_numbers.line_and_column_number(label.span.start);
+    let end = line_numbers.line_and_column_number(label.span.end);
+    SarifLocation {
+        id,
+        physical_location: PhysicalLocation {
+            artifact_location: artifact_location(root, path),
+            region: Some(Region {
+                start_line: start.line,
+                start_column: start.column,
+                end_line: end.line,
+                end_column: end.column,
+                byte_offset: label.span.start,
+                byte_length: label.span.end - label.span.start,
+            }),
+        },
+        message: label.text.clone().map(|text| Message { text }),
+    }
+}
+
+fn file_location(root: &Utf8Path, path: &Utf8Path) -> SarifLocation {
+    SarifLocation {
+        id: None,
+        physical_location: PhysicalLocation {
+            artifact_location: artifact_location(root, path),
+            region: None,
+        },
+        message: None,
+    }
+}
+
+fn artifact_location(root: &Utf8Path, path: &Utf8Path) -> ArtifactLocation {
+    let path = path.strip_prefix(root).unwrap_or(path);
+    ArtifactLocation {
+        uri: path.as_str().replace('\\', "/"),
+    }
+}
+
+fn warning_path(warning: &Warning) -> &Utf8PathBuf {
+    match warning {
+        Warning::Type { path, .. }
+        | Warning::InvalidSource { path }
+        | Warning::DeprecatedSyntax { path, .. }
+        | Warning::EmptyModule { path, .. }
+        | Warning::DetachedDocComment { path, .. } => path,
+    }
+}
+
+impl Level {
+    fn to_sarif(self) -> SarifLevel {
+        match self {
+            Level::Error => SarifLevel::Error,
+            Level::Warning => SarifLevel::Warning,
+        }
+    }
+}
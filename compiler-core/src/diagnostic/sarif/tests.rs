@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use super::SarifLog;
+use crate::{ast::SrcSpan, error::Error, type_, version::COMPILER_VERSION, warning::Warning};
+
+fn to_sarif(warnings: &[Warning], errors: &[Error]) -> String {
+    let log = SarifLog::new(Utf8Path::new("/app"), warnings, errors);
+    serde_json::to_string_pretty(&log)
+        .expect("serialise SARIF log")
+        // The version changes with every release, so it's left out of the
+        // snapshots.
+        .replace(COMPILER_VERSION, "<compiler version>")
+}
+
+fn unused_imported_value(start: u32) -> Warning {
+    Warning::Type {
+        path: Utf8PathBuf::from("/app/src/app.gleam"),
+        src: "import wibble.{wobble, wubble}\n".into(),
+        warning: Box::new(type_::Warning::UnusedImportedValue {
+            location: SrcSpan::new(start, start + 6),
+            name: "wobble".into(),
+        }),
+    }
+}
+
+#[test]
+fn no_warnings() {
+    insta::assert_snapshot!(to_sarif(&[], &[]));
+}
+
+#[test]
+fn warnings_share_a_rule() {
+    insta::assert_snapshot!(to_sarif(
+        &[unused_imported_value(15), unused_imported_value(23)],
+        &[]
+    ));
+}
+
+#[test]
+fn warning_about_a_whole_file() {
+    insta::assert_snapshot!(to_sarif(
+        &[Warning::EmptyModule {
+            path: Utf8PathBuf::from("/app/src/empty.gleam"),
+            name: "empty".into(),
+        }],
+        &[]
+    ));
+}
+
+#[test]
+fn errors_and_warnings() {
+    insta::assert_snapshot!(to_sarif(
+        &[unused_imported_value(15)],
+        &[Error::JavaScriptPreludeRequired]
+    ));
+}
@@ -6,6 +6,7 @@ mod pattern;
 mod tests;
 
 use crate::build::{Target, module_erlang_name};
+use crate::coverage::Coverage;
 use crate::erlang::pattern::{PatternPrinter, StringPatternAssignment};
 use crate::strings::{convert_string_escape_chars, to_snake_case};
 use crate::type_::is_prelude_module;
@@ -59,6 +60,10 @@ pub struct Generator<'a> {
     /// Wether `echo` has been used in this module, we're gonna need to know
     /// this in order to add the code needed by the pretty printing.
     echo_used: bool,
+
+    /// If the module is being compiled for `gleam test --coverage`, the
+    /// functions and case clauses whose uses are counted.
+    coverage: Option<&'a Coverage>,
 }
 
 /// This is a generator that takes care of generating the code for a single
@@ -80,6 +85,7 @@ impl<'a> Generator<'a> {
         module: &'a TypedModule,
         line_numbers: &'a LineNumbers,
         module_root: &'a Utf8Path,
+        coverage: Option<&'a Coverage>,
     ) -> Self {
         let module_source_path = module
             .type_info
@@ -96,6 +102,7 @@ impl<'a> Generator<'a> {
             line_numbers,
             needs_doc_attribute: false,
             echo_used: false,
+            coverage,
         }
     }
 
@@ -219,6 +226,13 @@ impl<'a> Generator<'a> {
             module
         };
 
+        let module = match self.coverage {
+            Some(coverage) if !coverage.is_empty() => module
+                .append(lines(2))
+                .append(coverage_hit_function(&self.module.name)),
+            Some(_) | None => module,
+        };
+
         Ok(module.append(line()))
     }
 }
@@ -294,10 +308,11 @@ impl<'a, 'generator> FunctionGenerator<'a, 'generator> {
         }
 
         let (arguments, body) = match function.external_erlang.as_ref() {
-            None => (
-                self.fun_arguments(&function.arguments),
-                self.statement_sequence(&function.body),
-            ),
+            None => {
+                let arguments = self.fun_arguments(&function.arguments);
+                let body = self.statement_sequence(&function.body);
+                (arguments, self.with_coverage_hit(function.location, body))
+            }
 
             Some((module, external_function_name, _location)) => {
                 let arguments = self.external_fun_arguments(&function.arguments);
@@ -322,6 +337,19 @@ impl<'a, 'generator> FunctionGenerator<'a, 'generator> {
         ])
     }
 
+    /// When compiling for coverage, counts a use of the function or case clause
+    /// at the given location before running its code.
+    fn with_coverage_hit(&self, location: SrcSpan, body: Document<'a>) -> Document<'a> {
+        match self
+            .module_generator
+            .coverage
+            .and_then(|coverage| coverage.index(location))
+        {
+            Some(index) => docvec!["'gleam@@coverage_hit'(", index, "),", line(), body],
+            None => body,
+        }
+    }
+
     /// Generates all the attributes that need to go before a function, like
     /// a `-file` attribute, a `-doc` one, a `-spec` one, etc.
     fn function_attributes(&mut self, function: &'a TypedFunction) -> Document<'a> {
@@ -1696,6 +1724,7 @@ impl<'a, 'generator> FunctionGenerator<'a, 'generator> {
 
             let guard = self.optional_clause_guard(guard.as_ref(), guards, &assignments_map);
             let then = self.clause_consequence(then, assignments).group();
+            let then = self.with_coverage_hit(clause.location, then);
             branches_docs.push(docvec![
                 pattern,
                 guard,
@@ -2643,12 +2672,35 @@ pub fn module<'a>(
     module: &'a TypedModule,
     line_numbers: &'a LineNumbers,
     root: &'a Utf8Path,
+    coverage: Option<&'a Coverage>,
 ) -> Result<String> {
-    Ok(Generator::new(module, line_numbers, root)
+    Ok(Generator::new(module, line_numbers, root, coverage)
         .module_document()?
         .to_pretty_string(MAX_COLUMNS))
 }
 
+/// The function used to count the uses of the functions and case clauses of a
+/// module compiled for coverage. The counts are kept in an ETS table created by
+/// the test runner in `gleam@@main`, and nothing is counted if there is none.
+fn coverage_hit_function(module: &str) -> Document<'_> {
+    docvec![
+        "'gleam@@coverage_hit'(Index) ->",
+        docvec![
+            line(),
+            "Key = {",
+            string(module),
+            ", Index},",
+            line(),
+            "try ets:update_counter('gleam@@coverage', Key, 1, {Key, 0})",
+            line(),
+            "catch error:badarg -> 0",
+            line(),
+            "end.",
+        ]
+        .nest(INDENT),
+    ]
+}
+
 fn register_function_exports(
     function: &TypedFunction,
     exports: &mut Vec<Document<'_>>,
@@ -7,6 +7,7 @@ use camino::Utf8PathBuf;
 
 use crate::analyse::TargetSupport;
 use crate::config::PackageConfig;
+use crate::coverage::Coverage;
 use crate::type_::PRELUDE_MODULE_NAME;
 use crate::warning::WarningEmitter;
 use crate::{build, inline};
@@ -24,6 +25,7 @@ mod bit_arrays;
 mod case;
 mod conditional_compilation;
 mod consts;
+mod coverage;
 mod custom_types;
 mod documentation;
 mod echo;
@@ -49,6 +51,23 @@ pub fn compile_test_project(
     src_path: &str,
     dependencies: Vec<(&str, &str, &str)>,
 ) -> String {
+    let built_module = compile_test_module(src, src_path, dependencies);
+    render(&built_module, None)
+}
+
+/// Compiles the module as `gleam test --coverage` does, counting the uses of
+/// its functions and case clauses.
+pub fn compile_test_project_with_coverage(src: &str) -> String {
+    let built_module = compile_test_module(src, "/root/project/src/my/mod.gleam", vec![]);
+    let coverage = Coverage::for_module(&built_module, Target::Erlang);
+    render(&built_module, coverage.as_ref())
+}
+
+pub fn compile_test_module(
+    src: &str,
+    src_path: &str,
+    dependencies: Vec<(&str, &str, &str)>,
+) -> build::Module {
     let mut modules = im::HashMap::new();
     let ids = UniqueIdGenerator::new();
     // DUPE: preludeinsertion
@@ -127,11 +146,14 @@ pub fn compile_test_project(
         extra: parsed.extra,
         dependencies: vec![],
     };
-    let root = Utf8Path::new("/root");
     built_module.attach_doc_and_module_comments();
+    built_module
+}
 
-    let line_numbers = LineNumbers::new(src);
-    module(&built_module.ast, &line_numbers, root)
+fn render(built_module: &build::Module, coverage: Option<&Coverage>) -> String {
+    let root = Utf8Path::new("/root");
+    let line_numbers = LineNumbers::new(&built_module.code);
+    module(&built_module.ast, &line_numbers, root, coverage)
         .unwrap()
         .replace(
             std::include_str!("../../templates/echo.erl"),
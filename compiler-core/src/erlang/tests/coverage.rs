@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use camino::Utf8Path;
+
+use crate::{
+    build::Target,
+    coverage::Coverage,
+    erlang::tests::{compile_test_module, compile_test_project_with_coverage},
+};
+
+fn compile_with_coverage(src: &str) -> String {
+    let compiled = compile_test_project_with_coverage(src);
+    format!("----- SOURCE CODE\n{src}\n\n----- COMPILED ERLANG\n{compiled}")
+}
+
+#[test]
+fn functions_are_counted() {
+    insta::assert_snapshot!(compile_with_coverage(
+        r#"
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(x) {
+  x + 1
+}
+"#
+    ));
+}
+
+#[test]
+fn case_clauses_are_counted() {
+    insta::assert_snapshot!(compile_with_coverage(
+        r#"
+pub fn main(x) {
+  case x {
+    1 | 2 -> "small"
+    n if n > 100 -> "big"
+    _ -> {
+      let y = x * 2
+      case y {
+        4 -> "four"
+        _ -> "other"
+      }
+    }
+  }
+}
+"#
+    ));
+}
+
+#[test]
+fn external_functions_are_not_counted() {
+    insta::assert_snapshot!(compile_with_coverage(
+        r#"
+@external(erlang, "erlang", "abs")
+pub fn abs(x: Int) -> Int
+
+pub fn main() {
+  abs(-1)
+}
+"#
+    ));
+}
+
+fn coverage_map(path: &str, src: &str, target: Target) -> Option<String> {
+    let module = compile_test_module(src, path, vec![]);
+    let coverage = Coverage::for_module(&module, target)?;
+    let map = coverage.map(&module, Utf8Path::new("/root/project"));
+    Some(serde_json::to_string_pretty(&map).expect("Coverage map serialisation"))
+}
+
+#[test]
+fn functions_and_clauses_have_points() {
+    insta::assert_snapshot!(
+        coverage_map(
+            "/root/project/src/my/mod.gleam",
+            r#"
+pub fn main(x) {
+  case x {
+    0 -> wibble(x)
+    _ -> {
+      let y = case x > 10 {
+        True -> 1
+        False -> 2
+      }
+      y
+    }
+  }
+}
+
+fn wibble(x) {
+  x + 1
+}
+"#,
+            Target::Erlang,
+        )
+        .expect("Coverage map")
+    );
+}
+
+#[test]
+fn external_functions_have_no_points_for_their_target() {
+    insta::assert_snapshot!(
+        coverage_map(
+            "/root/project/src/my/mod.gleam",
+            r#"
+@external(erlang, "erlang", "abs")
+pub fn abs(x: Int) -> Int {
+  case x < 0 {
+    True -> 0 - x
+    False -> x
+  }
+}
+"#,
+            Target::Erlang,
+        )
+        .expect("Coverage map")
+    );
+}
+
+#[test]
+fn external_functions_have_points_for_other_targets() {
+    insta::assert_snapshot!(
+        coverage_map(
+            "/root/project/src/my/mod.gleam",
+            r#"
+@external(erlang, "erlang", "abs")
+pub fn abs(x: Int) -> Int {
+  case x < 0 {
+    True -> 0 - x
+    False -> x
+  }
+}
+"#,
+            Target::JavaScript,
+        )
+        .expect("Coverage map")
+    );
+}
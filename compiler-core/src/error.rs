@@ -9,7 +9,9 @@ use crate::dependency::{PackageFetcher, ResolutionError};
 use crate::diagnostic::{Diagnostic, ExtraLabel, Label, Location};
 
 use crate::derivation_tree::DerivationTreePrinter;
+use crate::package_interface::diff::Bump;
 use crate::parse::error::ParseErrorDetails;
+use crate::paths::WORKSPACE_CONFIG_FILE;
 use crate::strings::{to_snake_case, to_upper_camel_case};
 use crate::type_::collapse_links;
 use crate::type_::error::{
@@ -262,6 +264,9 @@ pub enum Error {
     #[error("{module}'s main function does not support the current target")]
     MainFunctionDoesNotSupportTarget { module: EcoString, target: Target },
 
+    #[error("no tests match the given filters")]
+    NoMatchingTests,
+
     #[error("{input} is not a valid version. {error}")]
     InvalidVersionFormat { input: String, error: String },
 
@@ -303,6 +308,22 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
         error: String,
     },
 
+    #[error("vendored package {package_name} {package_version} has the wrong checksum")]
+    VendoredPackageChecksumMismatch {
+        package_name: String,
+        package_version: String,
+        path: Utf8PathBuf,
+    },
+
+    #[error("{url} is not available offline")]
+    NotAvailableOffline { url: String },
+
+    #[error("unknown hex repository {name}")]
+    UnknownHexRepository { name: EcoString },
+
+    #[error("invalid hex repository {name}: {reason}")]
+    InvalidHexRepository { name: EcoString, reason: String },
+
     #[error("{0}")]
     Http(String),
 
@@ -322,6 +343,16 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
     #[error("The package {0} is listed in dependencies and dev_dependencies")]
     DuplicateDependency(EcoString),
 
+    #[error("The workspace has more than one package named {name}")]
+    DuplicateWorkspaceMember {
+        name: EcoString,
+        first: Utf8PathBuf,
+        second: Utf8PathBuf,
+    },
+
+    #[error("No workspace found in {path} or its parents")]
+    WorkspaceNotFound { path: Utf8PathBuf },
+
     #[error("Expected package {expected} at path {path} but found {found} instead")]
     WrongDependencyProvided {
         path: Utf8PathBuf,
@@ -408,6 +439,22 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
         wrongfully_allowed_version: SmallVersion,
     },
 
+    #[error("The changes to the public API of {package} need a {required} version bump")]
+    ApiChangeRequiresLargerVersionBump {
+        package: EcoString,
+        previous_version: String,
+        version: String,
+        suggested_version: String,
+        required: Bump,
+        changes: Vec<String>,
+    },
+
+    #[error("No previous release of {package} to compare against")]
+    PreviousReleaseNotFound {
+        package: EcoString,
+        version: Option<String>,
+    },
+
     #[error("Failed to encrypt local Hex API key")]
     FailedToEncryptLocalHexApiKey { detail: String },
 
@@ -532,6 +579,7 @@ impl Error {
             | hexpm::ApiError::UnexpectedResponse(_, _)
             | hexpm::ApiError::InvalidPackageNameFormat(_)
             | hexpm::ApiError::IncorrectPayloadSignature
+            | hexpm::ApiError::InvalidPublicKey
             | hexpm::ApiError::InvalidProtobuf(_)
             | hexpm::ApiError::InvalidVersionFormat(_)
             | hexpm::ApiError::NotFound
@@ -1029,6 +1077,117 @@ errors."
             .collect()
     }
 
+    /// The codes of the diagnostics returned by `to_diagnostics`, one for each
+    /// of them and in the same order. A code identifies the kind of error, and
+    /// must never change once it has been released so that tools outside the
+    /// compiler can rely on it.
+    ///
+    pub fn diagnostic_codes(&self) -> Vec<&'static str> {
+        let code = match self {
+            Error::Type {
+                skipped_modules: _,
+                failed_modules,
+            } => {
+                return failed_modules
+                    .values()
+                    .sorted_by_key(|failed_module| &failed_module.path)
+                    .flat_map(|failed_module| failed_module.errors.iter())
+                    .map(crate::type_::Error::code)
+                    .collect_vec();
+            }
+            Error::Parse { .. } => "syntax-error",
+            Error::UnknownImport { .. } => "unknown-import",
+            Error::DuplicateModule { .. } => "duplicate-module",
+            Error::DuplicateSourceFile { .. } => "duplicate-source-file",
+            Error::DuplicateNativeErlangModule { .. } => "duplicate-native-erlang-module",
+            Error::ClashingGleamModuleAndNativeFileName { .. } => {
+                "clashing-gleam-module-and-native-file-name"
+            }
+            Error::ImportCycle { .. } => "import-cycle",
+            Error::PackageCycle { .. } => "package-cycle",
+            Error::FileIo { .. } => "file-io-error",
+            Error::NonUtf8Path { .. } => "non-utf8-path",
+            Error::GitInitialization { .. } => "git-initialization-failed",
+            Error::StandardIo { .. } => "standard-io-error",
+            Error::Format { .. } => "unformatted-files",
+            Error::Hex(_) => "hex-error",
+            Error::ExpandTar { .. } => "expand-tar-error",
+            Error::AddTar { .. } => "add-tar-error",
+            Error::TarFinish(_) => "tar-finish-error",
+            Error::ZipAdd { .. } => "zip-add-error",
+            Error::ZipFinish(_) => "zip-finish-error",
+            Error::Gzip(_) => "gzip-error",
+            Error::ShellProgramNotFound { .. } => "shell-program-not-found",
+            Error::ShellCommand { .. } => "shell-command-failed",
+            Error::InvalidProjectName { .. } => "invalid-project-name",
+            Error::InvalidModuleName { .. } => "invalid-module-name",
+            Error::ModuleDoesNotExist { .. } => "module-does-not-exist",
+            Error::ModuleDoesNotHaveMainFunction { .. } => "module-does-not-have-main-function",
+            Error::MainFunctionIsPrivate { .. } => "main-function-is-private",
+            Error::MainFunctionHasWrongArity { .. } => "main-function-has-wrong-arity",
+            Error::MainFunctionDoesNotSupportTarget { .. } => {
+                "main-function-does-not-support-target"
+            }
+            Error::NoMatchingTests => "no-matching-tests",
+            Error::InvalidVersionFormat { .. } => "invalid-version-format",
+            Error::IncompatibleLockedVersion { .. } => "incompatible-locked-version",
+            Error::ProjectRootAlreadyExist { .. } => "project-root-already-exist",
+            Error::OutputFilesAlreadyExist { .. } => "output-files-already-exist",
+            Error::RemovedPackagesNotExist { .. } => "removed-packages-not-exist",
+            Error::PackagesToUpdateNotExist { .. } => "packages-to-update-not-exist",
+            Error::UnableToFindProjectRoot { .. } => "unable-to-find-project-root",
+            Error::VersionDoesNotMatch { .. } => "version-does-not-match",
+            Error::ForbiddenWarnings { .. } => "forbidden-warnings",
+            Error::InvalidRuntime { .. } => "invalid-runtime",
+            Error::DownloadPackageError { .. } => "download-package-failed",
+            Error::VendoredPackageChecksumMismatch { .. } => "vendored-package-checksum-mismatch",
+            Error::NotAvailableOffline { .. } => "not-available-offline",
+            Error::UnknownHexRepository { .. } => "unknown-hex-repository",
+            Error::InvalidHexRepository { .. } => "invalid-hex-repository",
+            Error::Http(_) => "http-error",
+            Error::DependencyCanonicalizationFailed(_) => "dependency-canonicalization-failed",
+            Error::DependencyResolutionNoSolution { .. } => "dependency-resolution-no-solution",
+            Error::DependencyResolutionError(_) => "dependency-resolution-failed",
+            Error::DuplicateDependency(_) => "duplicate-dependency",
+            Error::DuplicateWorkspaceMember { .. } => "duplicate-workspace-member",
+            Error::WorkspaceNotFound { .. } => "workspace-not-found",
+            Error::WrongDependencyProvided { .. } => "wrong-dependency-provided",
+            Error::ProvidedDependencyConflict { .. } => "provided-dependency-conflict",
+            Error::MissingHexPublishFields { .. } => "missing-hex-publish-fields",
+            Error::PublishNonHexDependencies { .. } => "publish-non-hex-dependencies",
+            Error::UnsupportedBuildTool { .. } => "unsupported-build-tool",
+            Error::FailedToOpenDocs { .. } => "failed-to-open-docs",
+            Error::IncompatibleCompilerVersion { .. } => "incompatible-compiler-version",
+            Error::JavaScriptPreludeRequired => "java-script-prelude-required",
+            Error::CannotPublishTodo { .. } => "cannot-publish-todo",
+            Error::CannotPublishEcho { .. } => "cannot-publish-echo",
+            Error::CannotPublishLeakedInternalType { .. } => "cannot-publish-leaked-internal-type",
+            Error::CannotPublishEmptyModules { .. } => "cannot-publish-empty-modules",
+            Error::CannotPublishWithInvalidReadme { .. } => "cannot-publish-with-invalid-readme",
+            Error::HexPackageSquatting => "hex-package-squatting",
+            Error::CannotPublishWithDefaultMain { .. } => "cannot-publish-with-default-main",
+            Error::CorruptManifest => "corrupt-manifest",
+            Error::GleamModuleWouldOverwriteStandardErlangModule { .. } => {
+                "gleam-module-would-overwrite-standard-erlang-module"
+            }
+            Error::HexPublishReplaceRequired { .. } => "hex-publish-replace-required",
+            Error::HexPublishAccessDenied { .. } => "hex-publish-access-denied",
+            Error::CannotPublishWrongVersion { .. } => "cannot-publish-wrong-version",
+            Error::ApiChangeRequiresLargerVersionBump { .. } => {
+                "api-change-requires-larger-version-bump"
+            }
+            Error::PreviousReleaseNotFound { .. } => "previous-release-not-found",
+            Error::FailedToEncryptLocalHexApiKey { .. } => "failed-to-encrypt-local-hex-api-key",
+            Error::FailedToDecryptLocalHexApiKey { .. } => "failed-to-decrypt-local-hex-api-key",
+            Error::CannotAddSelfAsDependency { .. } => "cannot-add-self-as-dependency",
+            Error::HexSessionRevoked => "hex-session-revoked",
+            Error::IncorrectHexOneTimePassword => "incorrect-hex-one-time-password",
+            Error::TarPathOutsideOfProjectRoot { .. } => "tar-path-outside-of-project-root",
+            Error::CouldNotCreateTempFile { .. } => "could-not-create-temp-file",
+        };
+        vec![code]
+    }
+
     pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
         match self {
             Error::HexSessionRevoked => vec![Diagnostic {
@@ -1254,6 +1413,18 @@ target, so it cannot be run.",
                 hint: None,
             }],
 
+            Error::NoMatchingTests => vec![Diagnostic {
+                title: "No matching tests".into(),
+                text: wrap(
+                    "No test functions match the given filters. A test function \
+is a public function with no arguments whose name ends with `_test`, defined in a \
+module in the `test` directory.",
+                ),
+                level: Level::Error,
+                location: None,
+                hint: None,
+            }],
+
             Error::MainFunctionHasWrongArity { module, arity } => vec![Diagnostic {
                 title: "Main function has wrong arity".into(),
                 text: wrap_format!(
@@ -1397,6 +1568,58 @@ resulting in compilation errors!"
                 }]
             }
 
+            Error::ApiChangeRequiresLargerVersionBump {
+                package,
+                previous_version,
+                version,
+                suggested_version,
+                required,
+                changes,
+            } => {
+                vec![Diagnostic {
+                    title: "Version bump too small".into(),
+                    text: format!(
+                        "The public API of {package} changed since v{previous_version} in ways that \
+need a {required} version bump, but the version in `gleam.toml` is v{version}.
+
+{}",
+                        changes
+                            .iter()
+                            .map(|change| format!("  - {change}"))
+                            .join("\n")
+                    ),
+                    level: Level::Error,
+                    hint: Some(format!(
+                        "Set the version in `gleam.toml` to v{suggested_version} or later.
+If you are sure the version is correct, publish with `--skip-semver-check`."
+                    )),
+                    location: None,
+                }]
+            }
+
+            Error::PreviousReleaseNotFound { package, version } => {
+                let text = match version {
+                    Some(version) => format!(
+                        "There is no release of {package} with the version `{version}` \
+to compare the public API against."
+                    ),
+                    None => format!(
+                        "There is no earlier release of {package} to compare the public API against."
+                    ),
+                };
+                vec![Diagnostic {
+                    title: "Previous release not found".into(),
+                    text,
+                    level: Level::Error,
+                    hint: Some(
+                        "Use `--against` with the path of a package tarball or \
+`package-interface.json` file to compare against that instead."
+                            .into(),
+                    ),
+                    location: None,
+                }]
+            }
+
             Error::CannotPublishLeakedInternalType { unfinished } => vec![Diagnostic {
                 title: "Cannot publish unfinished code".into(),
                 text: format!(
@@ -2150,6 +2373,73 @@ The error from the package manager client was:
                 }]
             }
 
+            Error::VendoredPackageChecksumMismatch {
+                package_name,
+                package_version,
+                path,
+            } => {
+                let text = format!(
+                    "The vendored copy of `{package_name}` {package_version} at `{path}`
+does not have the checksum recorded for it in the manifest, so it may
+have been modified or corrupted."
+                );
+                vec![Diagnostic {
+                    title: "Vendored package checksum mismatch".into(),
+                    text,
+                    hint: Some(
+                        "Run `gleam deps vendor` to copy the package into the vendor directory again."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                }]
+            }
+
+            Error::NotAvailableOffline { url } => {
+                let text = format!(
+                    "The `--offline` flag was given, but `{url}`
+has not been downloaded before and so is not in the local cache."
+                );
+                vec![Diagnostic {
+                    title: "Not available offline".into(),
+                    text,
+                    hint: Some(
+                        "Run the command without `--offline` while connected to the internet \
+to add it to the cache."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                }]
+            }
+
+            Error::UnknownHexRepository { name } => {
+                let text = format!(
+                    "A package is to be fetched from the Hex repository `{name}`,
+but there is no repository with that name in `gleam.toml`."
+                );
+                vec![Diagnostic {
+                    title: "Unknown Hex repository".into(),
+                    text,
+                    hint: Some(format!(
+                        "Add the repository to `gleam.toml` in a `[hex_repositories.{name}]` table."
+                    )),
+                    location: None,
+                    level: Level::Error,
+                }]
+            }
+
+            Error::InvalidHexRepository { name, reason } => {
+                let text = format!("The Hex repository `{name}` could not be used: {reason}.");
+                vec![Diagnostic {
+                    title: "Invalid Hex repository".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }]
+            }
+
             Error::Http(error) => {
                 let text = format!(
                     "A HTTP request failed.
@@ -2288,6 +2578,42 @@ dev_dependencies sections of the gleam.toml file."
                 }]
             }
 
+            Error::DuplicateWorkspaceMember {
+                name,
+                first,
+                second,
+            } => {
+                let text = format!(
+                    "The packages at `{first}` and `{second}` are both named `{name}`.
+The members of a workspace share a build directory, so each of them
+must have a different name."
+                );
+                vec![Diagnostic {
+                    title: "Duplicate workspace member".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                }]
+            }
+
+            Error::WorkspaceNotFound { path } => {
+                let text = format!(
+                    "No `{WORKSPACE_CONFIG_FILE}` file was found in `{path}` or any of its
+parent directories."
+                );
+                vec![Diagnostic {
+                    title: "Workspace not found".into(),
+                    text,
+                    hint: Some(format!(
+                        "Create a `{WORKSPACE_CONFIG_FILE}` file listing the packages of the
+workspace, for example `members = [\"packages/*\"]`."
+                    )),
+                    location: None,
+                    level: Level::Error,
+                }]
+            }
+
             Error::MissingHexPublishFields {
                 description_missing,
                 licence_missing,
@@ -1,9 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2021 The Gleam contributors
 
-use camino::Utf8Path;
+use std::collections::HashMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::ValueEnum;
 use debug_ignore::DebugIgnore;
+use ecow::EcoString;
 use flate2::read::GzDecoder;
 use futures::future;
 use hexpm::{ApiError, WriteActionCredentials, version::Version};
@@ -12,6 +15,7 @@ use tar::Archive;
 
 use crate::{
     Error, Result,
+    config::HexRepositoryConfig,
     io::{FileSystemReader, FileSystemWriter, HttpClient, TarUnpacker},
     manifest::{ManifestPackage, ManifestPackageSource},
     paths::{self, ProjectPaths},
@@ -57,6 +61,7 @@ pub async fn publish_package<Http: HttpClient>(
         | ApiError::UnexpectedResponse(..)
         | ApiError::InvalidPackageNameFormat(_)
         | ApiError::IncorrectPayloadSignature
+        | ApiError::InvalidPublicKey
         | ApiError::InvalidProtobuf(_)
         | ApiError::InvalidVersionFormat(_)
         | ApiError::NotFound
@@ -187,14 +192,144 @@ pub async fn remove_api_key<Http: HttpClient>(
     hexpm::api_remove_api_key_response(response).map_err(Error::hex)
 }
 
+/// The name of the repository of hex.pm, which packages are fetched from
+/// unless their requirement names another.
+pub const DEFAULT_REPOSITORY: &str = "hexpm";
+
+/// A Hex repository, with everything needed to fetch packages from it.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    pub config: hexpm::Config,
+    /// The PEM encoded public key the registry is signed with.
+    pub public_key: Vec<u8>,
+    pub credentials: Option<hexpm::Credentials>,
+}
+
+impl Repository {
+    pub fn hexpm() -> Self {
+        Self {
+            config: hexpm::Config::new(),
+            public_key: HEXPM_PUBLIC_KEY.to_vec(),
+            credentials: None,
+        }
+    }
+}
+
+/// The Hex repositories packages can be fetched from, by name.
+#[derive(Debug, Clone)]
+pub struct Repositories {
+    repositories: HashMap<EcoString, Repository>,
+}
+
+impl Default for Repositories {
+    fn default() -> Self {
+        Self {
+            repositories: [(DEFAULT_REPOSITORY.into(), Repository::hexpm())].into(),
+        }
+    }
+}
+
+impl Repositories {
+    /// The repositories configured in `gleam.toml`, along with hex.pm.
+    ///
+    /// Each setting can be given with an environment variable instead, named
+    /// after the repository, such as `GLEAM_HEX_REPOSITORY_INTERNAL_API_URL`
+    /// for the `api_url` of the `internal` repository. The key used to
+    /// authenticate with a repository is read from the `API_KEY` variable.
+    /// For hex.pm any setting that isn't given defaults to that of hex.pm, so
+    /// `GLEAM_HEX_REPOSITORY_HEXPM_REPOSITORY_URL` can be set to use a mirror.
+    ///
+    pub fn new(
+        configs: &HashMap<EcoString, HexRepositoryConfig>,
+        environment_variable: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut repositories = Self::default();
+        let names = configs
+            .keys()
+            .cloned()
+            .chain(std::iter::once(DEFAULT_REPOSITORY.into()));
+        for name in names {
+            let config = configs.get(&name).cloned().unwrap_or_default();
+            let setting = |key: &str, value: Option<String>| {
+                environment_variable(&repository_environment_variable(&name, key)).or(value)
+            };
+            let api_url = setting("API_URL", config.api_url);
+            let repository_url = setting("REPOSITORY_URL", config.repository_url);
+            let public_key = setting("PUBLIC_KEY", config.public_key);
+            let credentials =
+                setting("API_KEY", None).map(|key| hexpm::Credentials::ApiKey(key.into()));
+
+            let defaults = Repository::hexpm();
+            let is_default = name == DEFAULT_REPOSITORY;
+            let missing = |setting: &str| Error::InvalidHexRepository {
+                name: name.clone(),
+                reason: format!("it has no `{setting}`"),
+            };
+            let repository = Repository {
+                config: hexpm::Config {
+                    api_base: match api_url {
+                        Some(url) => parse_base_url(&name, &url)?,
+                        None if is_default => defaults.config.api_base,
+                        None => return Err(missing("api_url")),
+                    },
+                    repository_base: match repository_url {
+                        Some(url) => parse_base_url(&name, &url)?,
+                        None if is_default => defaults.config.repository_base,
+                        None => return Err(missing("repository_url")),
+                    },
+                },
+                public_key: match public_key {
+                    Some(key) => key.into_bytes(),
+                    None if is_default => defaults.public_key,
+                    None => return Err(missing("public_key")),
+                },
+                credentials,
+            };
+            let _ = repositories.repositories.insert(name, repository);
+        }
+        Ok(repositories)
+    }
+
+    /// The repository with the given name, or hex.pm if no name is given.
+    pub fn get(&self, name: Option<&str>) -> Result<&Repository> {
+        let name = name.unwrap_or(DEFAULT_REPOSITORY);
+        self.repositories
+            .get(name)
+            .ok_or_else(|| Error::UnknownHexRepository { name: name.into() })
+    }
+}
+
+/// The environment variable a setting of a repository can be given with.
+fn repository_environment_variable(repository: &str, setting: &str) -> String {
+    let repository = repository.to_uppercase().replace('-', "_");
+    format!("GLEAM_HEX_REPOSITORY_{repository}_{setting}")
+}
+
+/// Requests are made by appending a path to the URL, so it must end with a
+/// slash to not replace its last segment.
+fn parse_base_url(repository: &EcoString, url: &str) -> Result<http::Uri> {
+    let url = if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{url}/")
+    };
+    url.parse().map_err(|_| Error::InvalidHexRepository {
+        name: repository.clone(),
+        reason: format!("`{url}` is not a valid URL"),
+    })
+}
+
 #[derive(Debug)]
 pub struct Downloader {
     fs_reader: DebugIgnore<Box<dyn FileSystemReader>>,
     fs_writer: DebugIgnore<Box<dyn FileSystemWriter>>,
     http: DebugIgnore<Box<dyn HttpClient>>,
     untar: DebugIgnore<Box<dyn TarUnpacker>>,
-    hex_config: hexpm::Config,
+    repositories: Repositories,
     paths: ProjectPaths,
+    /// The directory of vendored package tarballs, which are used in place of
+    /// downloading the packages.
+    vendor_directory: Option<Utf8PathBuf>,
 }
 
 impl Downloader {
@@ -210,17 +345,31 @@ impl Downloader {
             fs_writer: DebugIgnore(fs_writer),
             http: DebugIgnore(http),
             untar: DebugIgnore(untar),
-            hex_config: hexpm::Config::new(),
+            repositories: Repositories::default(),
             paths,
+            vendor_directory: None,
         }
     }
 
+    pub fn with_repositories(mut self, repositories: Repositories) -> Self {
+        self.repositories = repositories;
+        self
+    }
+
+    pub fn with_vendor_directory(mut self, vendor_directory: Option<Utf8PathBuf>) -> Self {
+        self.vendor_directory = vendor_directory;
+        self
+    }
+
     pub async fn ensure_package_downloaded(
         &self,
         package: &ManifestPackage,
     ) -> Result<bool, Error> {
-        let outer_checksum = match &package.source {
-            ManifestPackageSource::Hex { outer_checksum } => outer_checksum,
+        let (outer_checksum, repository) = match &package.source {
+            ManifestPackageSource::Hex {
+                outer_checksum,
+                repository,
+            } => (outer_checksum, repository),
             ManifestPackageSource::Git { .. } | ManifestPackageSource::Local { .. } => {
                 panic!("Attempt to download non-hex package from hex")
             }
@@ -235,17 +384,37 @@ impl Downloader {
             );
             return Ok(false);
         }
+
+        if let Some(vendored_path) = self.vendored_tarball(package) {
+            tracing::info!(
+                package = package.name.as_str(),
+                version = %package.version,
+                "copying_vendored_package_to_cache"
+            );
+            let tarball = self.fs_reader.read_bytes(&vendored_path)?;
+            hexpm::verify_package_tarball(&tarball, &outer_checksum.0).map_err(|_| {
+                Error::VendoredPackageChecksumMismatch {
+                    package_name: package.name.to_string(),
+                    package_version: package.version.to_string(),
+                    path: vendored_path,
+                }
+            })?;
+            self.fs_writer.write_bytes(&tarball_path, &tarball)?;
+            return Ok(false);
+        }
+
         tracing::info!(
             package = &package.name.as_str(),
             version = %package.version,
             "downloading_package_to_cache"
         );
 
+        let repository = self.repositories.get(repository.as_deref())?;
         let request = hexpm::repository_get_package_tarball_request(
             &package.name,
             &package.version.to_string(),
-            None,
-            &self.hex_config,
+            repository.credentials.as_ref(),
+            &repository.config,
         );
         let response = self.http.send(request).await?;
 
@@ -259,6 +428,14 @@ impl Downloader {
         Ok(true)
     }
 
+    /// The path of the vendored tarball of the package, if it has been
+    /// vendored.
+    fn vendored_tarball(&self, package: &ManifestPackage) -> Option<Utf8PathBuf> {
+        let directory = self.vendor_directory.as_ref()?;
+        let path = paths::vendored_package_tarball(directory, &package.name, &package.version);
+        self.fs_reader.is_file(&path).then_some(path)
+    }
+
     pub async fn ensure_package_in_build_directory(
         &self,
         package: &ManifestPackage,
@@ -273,7 +450,7 @@ impl Downloader {
         let destination = self.paths.build_packages_package(&package.name);
 
         let outer_checksum = match &package.source {
-            ManifestPackageSource::Hex { outer_checksum } => outer_checksum,
+            ManifestPackageSource::Hex { outer_checksum, .. } => outer_checksum,
             ManifestPackageSource::Git { .. } | ManifestPackageSource::Local { .. } => {
                 panic!("Attempt to download non-hex package from hex")
             }
@@ -360,7 +537,7 @@ pub async fn publish_documentation<Http: HttpClient>(
 pub async fn get_package_release<Http: HttpClient>(
     name: &str,
     version: &Version,
-    config: &hexpm::Config,
+    repository: &Repository,
     http: &Http,
 ) -> Result<hexpm::Release<hexpm::ReleaseMeta>> {
     let version = version.to_string();
@@ -369,7 +546,91 @@ pub async fn get_package_release<Http: HttpClient>(
         version = version.as_str(),
         "looking_up_package_release"
     );
-    let request = hexpm::api_get_package_release_request(name, &version, None, config);
+    let request = hexpm::api_get_package_release_request(
+        name,
+        &version,
+        repository.credentials.as_ref(),
+        &repository.config,
+    );
     let response = http.send(request).await?;
     hexpm::api_get_package_release_response(response).map_err(Error::hex)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn internal_config() -> HashMap<EcoString, HexRepositoryConfig> {
+        [(
+            "internal-packages".into(),
+            HexRepositoryConfig {
+                api_url: Some("https://hex.example.com/api".into()),
+                repository_url: None,
+                public_key: Some("public key".into()),
+            },
+        )]
+        .into()
+    }
+
+    #[test]
+    fn repositories_default_to_hexpm() {
+        let repositories = Repositories::new(&HashMap::new(), |_| None).unwrap();
+        let repository = repositories.get(None).unwrap();
+        let hexpm = hexpm::Config::new();
+        assert_eq!(repository.config.api_base, hexpm.api_base);
+        assert_eq!(repository.config.repository_base, hexpm.repository_base);
+        assert_eq!(repository.public_key, HEXPM_PUBLIC_KEY);
+        assert!(repository.credentials.is_none());
+        assert!(repositories.get(Some("internal")).is_err());
+    }
+
+    #[test]
+    fn repositories_hexpm_mirror() {
+        let repositories = Repositories::new(&HashMap::new(), |name| {
+            (name == "GLEAM_HEX_REPOSITORY_HEXPM_REPOSITORY_URL")
+                .then(|| "https://mirror.example.com".into())
+        })
+        .unwrap();
+        let repository = repositories.get(Some("hexpm")).unwrap();
+        assert_eq!(
+            repository.config.repository_base,
+            "https://mirror.example.com/"
+        );
+        assert_eq!(repository.config.api_base, hexpm::Config::new().api_base);
+    }
+
+    #[test]
+    fn repositories_settings_from_environment() {
+        let repositories = Repositories::new(&internal_config(), |name| match name {
+            "GLEAM_HEX_REPOSITORY_INTERNAL_PACKAGES_REPOSITORY_URL" => {
+                Some("https://hex.example.com/repo/".into())
+            }
+            "GLEAM_HEX_REPOSITORY_INTERNAL_PACKAGES_API_KEY" => Some("secret".into()),
+            _ => None,
+        })
+        .unwrap();
+        let repository = repositories.get(Some("internal-packages")).unwrap();
+        assert_eq!(repository.config.api_base, "https://hex.example.com/api/");
+        assert_eq!(
+            repository.config.repository_base,
+            "https://hex.example.com/repo/"
+        );
+        assert_eq!(repository.public_key, b"public key");
+        assert!(matches!(
+            &repository.credentials,
+            Some(hexpm::Credentials::ApiKey(key)) if key == "secret"
+        ));
+    }
+
+    #[test]
+    fn repositories_missing_setting() {
+        let error = Repositories::new(&internal_config(), |_| None).unwrap_err();
+        assert_eq!(
+            error,
+            Error::InvalidHexRepository {
+                name: "internal-packages".into(),
+                reason: "it has no `repository_url`".into(),
+            }
+        );
+    }
+}
@@ -20,6 +20,7 @@ use sourcemap::SourceMap;
 use crate::build::Target;
 use crate::build::package_compiler::StdlibPackage;
 use crate::codegen::TypeScriptDeclarations;
+use crate::coverage::Coverage;
 use crate::line_numbers::LineColumn;
 use crate::type_::{PRELUDE_MODULE_NAME, RecordAccessor};
 use crate::{
@@ -110,6 +111,9 @@ pub struct Generator<'a> {
     /// Relative path to the module, surrounded in `"`s to make it a string, and with `\`s escaped
     /// to `\\`.
     src_path: EcoString,
+    /// If the module is being compiled for `gleam test --coverage`, the
+    /// functions and case clauses whose uses are counted.
+    coverage: Option<&'a Coverage>,
 }
 
 impl<'a, 'doc> Generator<'a> {
@@ -123,6 +127,7 @@ impl<'a, 'doc> Generator<'a> {
             src: _,
             path: _,
             project_root,
+            coverage,
         } = config;
         let current_module_name_segments_count = module.name.split('/').count();
 
@@ -153,6 +158,7 @@ impl<'a, 'doc> Generator<'a> {
                 None
             },
             stdlib_package,
+            coverage,
         }
     }
 
@@ -375,6 +381,7 @@ impl<'a, 'doc> Generator<'a> {
         );
 
         let echo_definition = self.echo_definition(arena, &mut imports);
+        let echo_definition = docvec![arena, echo_definition, self.coverage_definition(arena)];
         let sourcemap_reference = self.sourcemap_reference(arena);
         let type_reference = self.type_reference(arena);
         let filepath_definition = self.filepath_definition(arena);
@@ -452,6 +459,27 @@ impl<'a, 'doc> Generator<'a> {
         ]
     }
 
+    /// The function used to count the uses of the functions and case clauses of
+    /// a module compiled for coverage. The counts are kept in a global object so
+    /// that the test runner can find them once the tests have finished.
+    fn coverage_definition(&self, arena: &'doc DocumentArena<'a, 'doc>) -> Document<'a, 'doc> {
+        match self.coverage {
+            Some(coverage) if !coverage.is_empty() => docvec![
+                arena,
+                LINE_DOCUMENT,
+                eco_format!(
+                    r#"function $coverage_hit(index) {{
+  const counts = ((globalThis.gleam_coverage ??= {{}})["{}"] ??= {{}});
+  counts[index] = (counts[index] ?? 0) + 1;
+}}"#,
+                    self.module.name
+                ),
+                LINE_DOCUMENT
+            ],
+            Some(_) | None => EMPTY_DOCUMENT,
+        }
+    }
+
     fn register_prelude_usage(
         &self,
         arena: &'doc DocumentArena<'a, 'doc>,
@@ -1117,6 +1145,7 @@ impl<'a, 'doc> Generator<'a> {
             &mut self.tracker,
             self.module_scope.clone(),
             self.source_map_builder.clone(),
+            self.coverage,
         );
 
         let document = generator.constant_expression(arena, Context::Constant, value);
@@ -1213,6 +1242,7 @@ impl<'a, 'doc> Generator<'a> {
             &mut self.tracker,
             self.module_scope.clone(),
             self.source_map_builder.clone(),
+            self.coverage,
         );
 
         let head = if function.publicity.is_private() {
@@ -1226,6 +1256,7 @@ impl<'a, 'doc> Generator<'a> {
             function.body.as_slice(),
             function.arguments.as_slice(),
         );
+        let body = generator.with_coverage_hit(arena, function.location, body);
 
         Some(docvec![
             arena,
@@ -1341,6 +1372,7 @@ pub struct ModuleConfig<'a> {
     pub stdlib_package: StdlibPackage,
     pub path: &'a Utf8Path,
     pub project_root: &'a Utf8Path,
+    pub coverage: Option<&'a Coverage>,
 }
 
 pub fn module(config: ModuleConfig<'_>) -> (String, Option<SourceMap>) {
@@ -405,6 +405,7 @@ impl<'a, 'doc> CasePrinter<'_, '_, 'a, '_, 'doc> {
         let clause = &clauses.get(clause_index).expect("invalid clause index");
         let body = &clause.then;
 
+        let generator = &mut self.variables.expression_generator;
         if let Some(subject_index) = clause.returned_subject() {
             let variable = self
                 .assignments
@@ -412,17 +413,11 @@ impl<'a, 'doc> CasePrinter<'_, '_, 'a, '_, 'doc> {
                 .expect("case with no subjects")
                 .name();
 
-            BodyExpression::Variable(
-                self.variables
-                    .expression_generator
-                    .wrap_return(arena, variable.to_doc(arena)),
-            )
+            let body = generator.wrap_return(arena, variable.to_doc(arena));
+            BodyExpression::Variable(generator.with_coverage_hit(arena, clause.location, body))
         } else {
-            BodyExpression::Expressions(
-                self.variables
-                    .expression_generator
-                    .expression_flattening_blocks(arena, body),
-            )
+            let body = generator.expression_flattening_blocks(arena, body);
+            BodyExpression::Expressions(generator.with_coverage_hit(arena, clause.location, body))
         }
     }
 
@@ -269,6 +269,10 @@ pub(crate) struct Generator<'module, 'ast, 'doc> {
     ///
     statement_level: Vec<Document<'ast, 'doc>>,
 
+    /// If the module is being compiled for `gleam test --coverage`, the
+    /// functions and case clauses whose uses are counted.
+    coverage: Option<&'module Coverage>,
+
     /// This will be true if we've generated a `let assert` statement that we know
     /// is guaranteed to throw.
     /// This means we can stop code generation for all the following statements
@@ -289,6 +293,7 @@ impl<'module, 'a, 'doc> Generator<'module, 'a, 'doc> {
         tracker: &'module mut UsageTracker,
         initial_scope_vars: im::HashMap<EcoString, usize>,
         source_map_builder: Option<Rc<RefCell<DebugIgnore<sourcemap::SourceMapBuilder>>>>,
+        coverage: Option<&'module Coverage>,
     ) -> Self {
         let mut current_scope = Scope::new(initial_scope_vars);
         let mut current_function = CurrentFunction::Module;
@@ -317,6 +322,26 @@ impl<'module, 'a, 'doc> Generator<'module, 'a, 'doc> {
             statement_level: Vec::new(),
             let_assert_always_panics: false,
             source_map_builder,
+            coverage,
+        }
+    }
+
+    /// When compiling for coverage, counts a use of the function or case clause
+    /// at the given location before running its code.
+    pub fn with_coverage_hit(
+        &self,
+        arena: &'doc DocumentArena<'a, 'doc>,
+        location: SrcSpan,
+        body: Document<'a, 'doc>,
+    ) -> Document<'a, 'doc> {
+        match self.coverage.and_then(|coverage| coverage.index(location)) {
+            Some(index) => docvec![
+                arena,
+                eco_format!("$coverage_hit({index});"),
+                LINE_DOCUMENT,
+                body
+            ],
+            None => body,
         }
     }
 
@@ -5,6 +5,7 @@ use crate::{
     analyse::TargetSupport,
     build::{Origin, Target},
     config::PackageConfig,
+    coverage::Coverage,
     inline,
     javascript::*,
     uid::UniqueIdGenerator,
@@ -21,6 +22,7 @@ mod bools;
 mod case;
 mod case_clause_guards;
 mod consts;
+mod coverage;
 mod custom_types;
 mod echo;
 mod externals;
@@ -213,7 +215,16 @@ pub fn compile(src: &str, deps: Vec<(&str, &str, &str)>) -> TypedModule {
 }
 
 pub fn compile_js(src: &str, deps: Vec<(&str, &str, &str)>) -> String {
+    compile_js_module(src, deps, false)
+}
+
+pub fn compile_js_with_coverage(src: &str) -> String {
+    compile_js_module(src, vec![], true)
+}
+
+fn compile_js_module(src: &str, deps: Vec<(&str, &str, &str)>, coverage: bool) -> String {
     let ast = compile(src, deps);
+    let coverage = coverage.then(|| Coverage::new(&ast, src, Target::JavaScript));
     let line_numbers = LineNumbers::new(src);
     let stdlib_package = StdlibPackage::Present;
     let (output, _) = module(ModuleConfig {
@@ -225,6 +236,7 @@ pub fn compile_js(src: &str, deps: Vec<(&str, &str, &str)>) -> String {
         stdlib_package,
         path: Utf8Path::new("src/module.gleam"),
         project_root: "project/root".into(),
+        coverage: coverage.as_ref(),
     });
 
     output.replace(
@@ -246,6 +258,7 @@ pub fn compile_js_with_source_map(src: &str, deps: Vec<(&str, &str, &str)>) -> (
         stdlib_package,
         path: Utf8Path::new("src/module.gleam"),
         project_root: "project/root".into(),
+        coverage: None,
     });
     let source_map = source_map.expect("source map should always be present");
 
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use crate::javascript::tests::compile_js_with_coverage;
+
+fn compile_with_coverage(src: &str) -> String {
+    let compiled = compile_js_with_coverage(src);
+    format!("----- SOURCE CODE\n{src}\n\n----- COMPILED JAVASCRIPT\n{compiled}")
+}
+
+#[test]
+fn functions_are_counted() {
+    insta::assert_snapshot!(compile_with_coverage(
+        r#"
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(x) {
+  x + 1
+}
+"#
+    ));
+}
+
+#[test]
+fn case_clauses_are_counted() {
+    insta::assert_snapshot!(compile_with_coverage(
+        r#"
+pub fn main(x) {
+  case x {
+    1 | 2 -> "small"
+    n if n > 100 -> "big"
+    _ -> {
+      let y = x * 2
+      case y {
+        4 -> "four"
+        _ -> "other"
+      }
+    }
+  }
+}
+"#
+    ));
+}
+
+#[test]
+fn external_functions_are_not_counted() {
+    insta::assert_snapshot!(compile_with_coverage(
+        r#"
+@external(javascript, "./ffi.mjs", "abs")
+pub fn abs(x: Int) -> Int
+
+pub fn main() {
+  abs(-1)
+}
+"#
+    ));
+}
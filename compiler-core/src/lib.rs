@@ -68,6 +68,7 @@ pub mod bit_array;
 pub mod build;
 pub mod codegen;
 pub mod config;
+pub mod coverage;
 pub mod dependency;
 pub mod diagnostic;
 pub mod docs;
@@ -21,6 +21,10 @@ pub struct Manifest {
     pub requirements: HashMap<EcoString, Requirement>,
     #[serde(serialize_with = "sorted_vec")]
     pub packages: Vec<ManifestPackage>,
+    /// The directory the Hex packages have been vendored into by `gleam deps
+    /// vendor`, relative to the manifest, if they have been.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<Utf8PathBuf>,
 }
 
 impl Manifest {
@@ -33,6 +37,7 @@ impl Manifest {
         let Self {
             requirements,
             packages,
+            vendor,
         } = self;
 
         buffer.push_str(
@@ -47,6 +52,13 @@ impl Manifest {
 ",
         );
 
+        // Vendor directory
+        if let Some(vendor) = vendor {
+            buffer.push_str("vendor = \"");
+            buffer.push_str(&vendor.as_str().replace('\\', "/"));
+            buffer.push_str("\"\n\n");
+        }
+
         // Packages
         buffer.push_str("packages = [\n");
         for ManifestPackage {
@@ -91,10 +103,18 @@ impl Manifest {
             }
 
             match source {
-                ManifestPackageSource::Hex { outer_checksum } => {
+                ManifestPackageSource::Hex {
+                    outer_checksum,
+                    repository,
+                } => {
                     buffer.push_str(r#", source = "hex", outer_checksum = ""#);
                     buffer.push_str(&outer_checksum.base_16_encoded_string());
                     buffer.push('"');
+                    if let Some(repository) = repository {
+                        buffer.push_str(r#", repository = ""#);
+                        buffer.push_str(repository);
+                        buffer.push('"');
+                    }
                 }
                 ManifestPackageSource::Git { repo, commit } => {
                     buffer.push_str(r#", source = "git", repo = ""#);
@@ -125,6 +145,43 @@ impl Manifest {
 
         buffer
     }
+
+    /// The part of the manifest needed by a package with the given
+    /// requirements: the packages it depends on, directly or through other
+    /// packages.
+    ///
+    /// The members of a workspace share one manifest with the dependencies of
+    /// all of them, and each member uses just the part it needs.
+    ///
+    pub fn required_by(&self, requirements: HashMap<EcoString, Requirement>) -> Self {
+        let packages: HashMap<&EcoString, &ManifestPackage> = self
+            .packages
+            .iter()
+            .map(|package| (&package.name, package))
+            .collect();
+
+        let mut required = std::collections::HashSet::new();
+        let mut to_visit = requirements.keys().collect_vec();
+        while let Some(name) = to_visit.pop() {
+            if !required.insert(name) {
+                continue;
+            }
+            if let Some(package) = packages.get(name) {
+                to_visit.extend(&package.requirements);
+            }
+        }
+
+        Self {
+            packages: self
+                .packages
+                .iter()
+                .filter(|package| required.contains(&package.name))
+                .cloned()
+                .collect(),
+            requirements,
+            vendor: self.vendor.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -230,6 +287,7 @@ impl Default for ManifestPackage {
             version: Version::new(1, 0, 0),
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
         }
     }
@@ -239,7 +297,13 @@ impl Default for ManifestPackage {
 #[serde(tag = "source")]
 pub enum ManifestPackageSource {
     #[serde(rename = "hex")]
-    Hex { outer_checksum: Base16Checksum },
+    Hex {
+        outer_checksum: Base16Checksum,
+        /// The name of the Hex repository the package is fetched from, if not
+        /// hex.pm.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        repository: Option<EcoString>,
+    },
     #[serde(rename = "git")]
     Git { repo: EcoString, commit: EcoString },
     #[serde(rename = "local")]
@@ -320,6 +384,7 @@ mod tests {
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -330,6 +395,7 @@ mod tests {
                     requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -340,6 +406,7 @@ mod tests {
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -371,9 +438,11 @@ mod tests {
                     requirements: vec!["gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 46]),
+                        repository: None,
                     },
                 },
             ],
+            vendor: None,
         };
 
         let buffer = manifest.to_toml(HOME.into());
@@ -435,6 +504,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -445,6 +515,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -455,6 +526,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -486,9 +558,11 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec!["gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 46]),
+                        repository: None,
                     },
                 },
             ],
+            vendor: None,
         };
 
         let buffer = manifest.to_toml(HOME.into());
@@ -684,6 +758,7 @@ mod manifest_update_tests {
             requirements: vec![],
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
         };
 
@@ -697,6 +772,7 @@ mod manifest_update_tests {
                 package("removed1", Version::new(10, 0, 0)),
                 package("removed2", Version::new(20, 1, 0)),
             ],
+            vendor: None,
         };
 
         let new = Manifest {
@@ -709,6 +785,7 @@ mod manifest_update_tests {
                 package("changed1", Version::new(5, 0, 0)),
                 package("changed2", Version::new(3, 0, 0)),
             ],
+            vendor: None,
         };
 
         let mut changes = PackageChanges::between_manifests(&old, &new);
@@ -764,13 +841,16 @@ mod manifest_update_tests {
             packages: vec![package(ManifestPackageSource::Local {
                 path: "wibble".into(),
             })],
+            vendor: None,
         };
 
         let new = Manifest {
             requirements: HashMap::new(),
             packages: vec![package(ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             })],
+            vendor: None,
         };
 
         let changes = PackageChanges::between_manifests(&old, &new);
@@ -854,3 +934,128 @@ gleam_stdlib = { version = ">= 0.58.0 and < 2.0.0" }
     let manifest: Result<Manifest, _> = toml::from_str(toml);
     assert!(manifest.is_ok());
 }
+
+#[test]
+fn required_by_keeps_transitive_dependencies() {
+    let package = |name: &str, requirements: &[&str]| ManifestPackage {
+        name: name.into(),
+        requirements: requirements.iter().map(|name| (*name).into()).collect(),
+        ..Default::default()
+    };
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![
+            package("one", &["two"]),
+            package("two", &["three"]),
+            package("three", &[]),
+            package("unrelated", &["three"]),
+        ],
+        vendor: None,
+    };
+    let requirements: HashMap<EcoString, Requirement> =
+        [("one".into(), Requirement::hex("~> 1.0").unwrap())].into();
+
+    let required = manifest.required_by(requirements.clone());
+    assert_eq!(required.requirements, requirements);
+    assert_eq!(
+        required
+            .packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect_vec(),
+        vec!["one", "two", "three"]
+    );
+}
+
+#[test]
+fn manifest_toml_format_with_vendor() {
+    let manifest = Manifest {
+        requirements: [("wibble".into(), Requirement::hex("~> 1.0").unwrap())].into(),
+        packages: vec![ManifestPackage {
+            name: "wibble".into(),
+            version: Version::new(1, 0, 0),
+            build_tools: ["gleam".into()].into(),
+            otp_app: None,
+            requirements: vec![],
+            source: ManifestPackageSource::Hex {
+                outer_checksum: Base16Checksum(vec![1, 22]),
+                repository: None,
+            },
+        }],
+        vendor: Some("vendor".into()),
+    };
+
+    let toml = manifest.to_toml("/home/louis/wobble".into());
+    assert_eq!(
+        toml,
+        r#"# Do not manually edit this file, it is managed by Gleam.
+#
+# This file locks the dependency versions used, to make your build
+# deterministic and to prevent unexpected versions from being included
+# in your application.
+#
+# You should check this file into your source control repository.
+
+vendor = "vendor"
+
+packages = [
+  { name = "wibble", version = "1.0.0", build_tools = ["gleam"], requirements = [], source = "hex", outer_checksum = "0116" },
+]
+
+[requirements]
+wibble = { version = "~> 1.0" }
+"#
+    );
+
+    let parsed: Manifest = toml::from_str(&toml).unwrap();
+    assert_eq!(parsed.vendor, Some("vendor".into()));
+}
+
+#[test]
+fn manifest_toml_format_with_repository() {
+    let manifest = Manifest {
+        requirements: [(
+            "wibble".into(),
+            Requirement::Hex {
+                version: hexpm::version::Range::new("~> 1.0".into()).unwrap(),
+                repository: Some("internal".into()),
+            },
+        )]
+        .into(),
+        packages: vec![ManifestPackage {
+            name: "wibble".into(),
+            version: Version::new(1, 0, 0),
+            build_tools: ["gleam".into()].into(),
+            otp_app: None,
+            requirements: vec![],
+            source: ManifestPackageSource::Hex {
+                outer_checksum: Base16Checksum(vec![1, 22]),
+                repository: Some("internal".into()),
+            },
+        }],
+        vendor: None,
+    };
+
+    let toml = manifest.to_toml("/home/louis/wobble".into());
+    assert_eq!(
+        toml,
+        r#"# Do not manually edit this file, it is managed by Gleam.
+#
+# This file locks the dependency versions used, to make your build
+# deterministic and to prevent unexpected versions from being included
+# in your application.
+#
+# You should check this file into your source control repository.
+
+packages = [
+  { name = "wibble", version = "1.0.0", build_tools = ["gleam"], requirements = [], source = "hex", outer_checksum = "0116", repository = "internal" },
+]
+
+[requirements]
+wibble = { version = "~> 1.0", repo = "internal" }
+"#
+    );
+
+    let parsed: Manifest = toml::from_str(&toml).unwrap();
+    assert_eq!(parsed, manifest);
+}
@@ -34,7 +34,13 @@ pub fn decode(
         .map(|(module, _)| remap_type_variable_ids(module, ids))
 }
 
-fn remap_type_variable_ids(module: ModuleInterface, ids: UniqueIdGenerator) -> ModuleInterface {
+/// Gives the type variables of a module loaded by another compiler new ids
+/// from this compiler's generator, so they can't collide with the ids of the
+/// type variables it creates.
+pub(crate) fn remap_type_variable_ids(
+    module: ModuleInterface,
+    ids: UniqueIdGenerator,
+) -> ModuleInterface {
     RemapIds::new(ids).module(module)
 }
 
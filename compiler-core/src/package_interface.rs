@@ -4,8 +4,9 @@
 use std::{collections::HashMap, ops::Deref};
 
 use ecow::EcoString;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+pub mod diff;
 #[cfg(test)]
 mod tests;
 
@@ -20,7 +21,7 @@ use crate::{
 use crate::build::Package;
 
 /// The public interface of a package that gets serialised as a json object.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct PackageInterface {
     name: EcoString,
@@ -32,7 +33,7 @@ pub struct PackageInterface {
     modules: HashMap<EcoString, ModuleInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct ModuleInterface {
     /// A vector with the lines composing the module's documentation (that is
@@ -52,7 +53,7 @@ pub struct ModuleInterface {
     functions: HashMap<EcoString, FunctionInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypeDefinitionInterface {
     /// The definition's documentation comment (that is every line preceded by
@@ -75,7 +76,7 @@ pub struct TypeDefinitionInterface {
     constructors: Vec<TypeConstructorInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypeConstructorInterface {
     /// The constructor's documentation comment (that is every line preceded by
@@ -99,7 +100,7 @@ pub struct TypeConstructorInterface {
     parameters: Vec<ParameterInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypeAliasInterface {
     /// The constructor's documentation comment (that is every line preceded by
@@ -122,7 +123,7 @@ pub struct TypeAliasInterface {
     alias: TypeInterface,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct ConstantInterface {
     /// The constant's documentation comment (that is every line preceded by
@@ -139,7 +140,7 @@ pub struct ConstantInterface {
 
 /// A module's function. This differs from a simple `Fn` type as its arguments
 /// can be labelled.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct FunctionInterface {
     /// The function's documentation comment (that is every line preceded by
@@ -155,7 +156,7 @@ pub struct FunctionInterface {
 }
 
 /// Informations about how a value is implemented.
-#[derive(Debug, Serialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct ImplementationsInterface {
     /// Set to `true` if the const/function has a pure Gleam implementation
@@ -277,7 +278,7 @@ impl ImplementationsInterface {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct DeprecationInterface {
     /// The reason for the deprecation.
@@ -295,7 +296,7 @@ impl DeprecationInterface {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(tag = "kind")]
 #[serde(rename_all = "kebab-case")]
 pub enum TypeInterface {
@@ -343,7 +344,7 @@ pub enum TypeInterface {
     },
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct ParameterInterface {
     /// If the parameter is labelled this will hold the label's name.
@@ -362,6 +363,14 @@ pub struct ParameterInterface {
 }
 
 impl PackageInterface {
+    pub fn name(&self) -> &EcoString {
+        &self.name
+    }
+
+    pub fn version(&self) -> &EcoString {
+        &self.version
+    }
+
     pub fn from_package(
         package: &Package,
         cached_modules: &im::HashMap<EcoString, type_::ModuleInterface>,
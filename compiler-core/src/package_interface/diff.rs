@@ -0,0 +1,524 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Comparing the public interface of two releases of a package, to tell what
+//! kind of version bump the changes between them need under semantic
+//! versioning.
+
+use std::{collections::HashMap, fmt};
+
+use ecow::{EcoString, eco_format};
+use hexpm::version::Version;
+use itertools::Itertools;
+
+use super::{
+    ConstantInterface, DeprecationInterface, FunctionInterface, ImplementationsInterface,
+    ModuleInterface, PackageInterface, ParameterInterface, TypeAliasInterface,
+    TypeConstructorInterface, TypeDefinitionInterface,
+};
+
+/// The part of a version that has to be incremented for a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    /// Nothing that can be used by other packages changed, or only the
+    /// documentation did.
+    Patch,
+    /// Something was added or deprecated, so code written for the previous
+    /// release still works.
+    Minor,
+    /// Something was removed or changed, so code written for the previous
+    /// release may no longer compile.
+    Major,
+}
+
+impl Bump {
+    /// The bump made going from one version to another, or `None` if the
+    /// second version is not the greater.
+    ///
+    /// Before 1.0.0 the minor version is the one incremented for breaking
+    /// changes and the patch version for all others, as is the convention
+    /// for Hex packages.
+    ///
+    pub fn between(previous: &Version, current: &Version) -> Option<Bump> {
+        if current <= previous {
+            return None;
+        }
+        let breaking = current.major > previous.major
+            || (current.major == 0 && current.minor > previous.minor);
+        let bump = if breaking {
+            Bump::Major
+        } else if current.major == 0 || current.minor > previous.minor {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        };
+        Some(bump)
+    }
+
+    /// The smallest version after the given one that makes this bump.
+    pub fn next_version(&self, previous: &Version) -> Version {
+        let Version {
+            major,
+            minor,
+            patch,
+            ..
+        } = *previous;
+        match self {
+            Bump::Major if major == 0 => Version::new(0, minor + 1, 0),
+            Bump::Major => Version::new(major + 1, 0, 0),
+            Bump::Minor if major == 0 => Version::new(0, minor, patch + 1),
+            Bump::Minor => Version::new(major, minor + 1, 0),
+            Bump::Patch => Version::new(major, minor, patch + 1),
+        }
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Patch => f.write_str("patch"),
+            Bump::Minor => f.write_str("minor"),
+            Bump::Major => f.write_str("major"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A change to a single item of the public interface of a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub module: EcoString,
+    /// What changed, such as `function parse` or `type Wibble`. This is empty
+    /// for a change to the module itself.
+    pub item: EcoString,
+    pub kind: ChangeKind,
+    pub bump: Bump,
+    /// How a changed item changed.
+    pub detail: Option<EcoString>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Change {
+            module,
+            item,
+            kind,
+            bump: _,
+            detail,
+        } = self;
+        match (kind, item.is_empty()) {
+            (ChangeKind::Added, true) => write!(f, "added module `{module}`")?,
+            (ChangeKind::Removed, true) => write!(f, "removed module `{module}`")?,
+            (ChangeKind::Changed, true) => write!(f, "changed module `{module}`")?,
+            (ChangeKind::Added, false) => write!(f, "added {item} to `{module}`")?,
+            (ChangeKind::Removed, false) => write!(f, "removed {item} from `{module}`")?,
+            (ChangeKind::Changed, false) => write!(f, "changed {item} in `{module}`")?,
+        }
+        match detail {
+            Some(detail) => write!(f, ": {detail}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The changes between the public interfaces of two releases of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ApiDiff {
+    pub changes: Vec<Change>,
+}
+
+impl ApiDiff {
+    pub fn new(previous: &PackageInterface, current: &PackageInterface) -> ApiDiff {
+        let mut diff = ApiDiff::default();
+        for name in sorted_keys(&previous.modules, &current.modules) {
+            match (previous.modules.get(name), current.modules.get(name)) {
+                (Some(_), None) => diff.push(name, "", ChangeKind::Removed, Bump::Major, None),
+                (None, Some(_)) => diff.push(name, "", ChangeKind::Added, Bump::Minor, None),
+                (Some(previous), Some(current)) => diff.module(name, previous, current),
+                (None, None) => (),
+            }
+        }
+        diff
+    }
+
+    /// The bump needed for all the changes, which is a patch one if there are
+    /// no changes.
+    pub fn required_bump(&self) -> Bump {
+        self.changes
+            .iter()
+            .map(|change| change.bump)
+            .max()
+            .unwrap_or(Bump::Patch)
+    }
+
+    fn push(
+        &mut self,
+        module: &EcoString,
+        item: impl Into<EcoString>,
+        kind: ChangeKind,
+        bump: Bump,
+        detail: Option<EcoString>,
+    ) {
+        self.changes.push(Change {
+            module: module.clone(),
+            item: item.into(),
+            kind,
+            bump,
+            detail,
+        });
+    }
+
+    fn changed(&mut self, module: &EcoString, item: &EcoString, bump: Bump, detail: EcoString) {
+        self.push(
+            module,
+            item.clone(),
+            ChangeKind::Changed,
+            bump,
+            Some(detail),
+        );
+    }
+
+    fn module(&mut self, name: &EcoString, previous: &ModuleInterface, current: &ModuleInterface) {
+        if previous.documentation != current.documentation {
+            let detail = "its documentation changed".into();
+            self.push(name, "", ChangeKind::Changed, Bump::Patch, Some(detail));
+        }
+        self.items(name, "type", &previous.types, &current.types, Self::type_);
+        self.items(
+            name,
+            "type alias",
+            &previous.type_aliases,
+            &current.type_aliases,
+            Self::type_alias,
+        );
+        self.items(
+            name,
+            "constant",
+            &previous.constants,
+            &current.constants,
+            Self::constant,
+        );
+        self.items(
+            name,
+            "function",
+            &previous.functions,
+            &current.functions,
+            Self::function,
+        );
+    }
+
+    fn items<T>(
+        &mut self,
+        module: &EcoString,
+        kind: &str,
+        previous: &HashMap<EcoString, T>,
+        current: &HashMap<EcoString, T>,
+        compare: impl Fn(&mut Self, &EcoString, &EcoString, &T, &T),
+    ) {
+        for name in sorted_keys(previous, current) {
+            let item = eco_format!("{kind} `{name}`");
+            match (previous.get(name), current.get(name)) {
+                (Some(_), None) => self.push(module, item, ChangeKind::Removed, Bump::Major, None),
+                (None, Some(_)) => self.push(module, item, ChangeKind::Added, Bump::Minor, None),
+                (Some(previous), Some(current)) => compare(self, module, &item, previous, current),
+                (None, None) => (),
+            }
+        }
+    }
+
+    fn type_(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &TypeDefinitionInterface,
+        current: &TypeDefinitionInterface,
+    ) {
+        let TypeDefinitionInterface {
+            documentation,
+            deprecation,
+            parameters,
+            constructors,
+        } = current;
+
+        if previous.parameters != *parameters {
+            let detail = eco_format!(
+                "its number of type parameters changed from {} to {parameters}",
+                previous.parameters
+            );
+            self.changed(module, item, Bump::Major, detail);
+        }
+
+        match (previous.constructors.is_empty(), constructors.is_empty()) {
+            // An opaque type can't be constructed or pattern matched on by
+            // other packages, so making its constructors public adds to what
+            // they can do.
+            (true, false) => {
+                let detail = "its constructors are now public".into();
+                self.changed(module, item, Bump::Minor, detail);
+            }
+            (false, true) => {
+                let detail = "its constructors are no longer public".into();
+                self.changed(module, item, Bump::Major, detail);
+            }
+            (true, true) => (),
+            (false, false) => self.constructors(module, item, &previous.constructors, constructors),
+        }
+
+        self.deprecation(module, item, &previous.deprecation, deprecation);
+        self.documentation(module, item, &previous.documentation, documentation);
+    }
+
+    fn constructors(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &[TypeConstructorInterface],
+        current: &[TypeConstructorInterface],
+    ) {
+        let previous: HashMap<_, _> = previous.iter().map(|c| (c.name.clone(), c)).collect();
+        let current: HashMap<_, _> = current.iter().map(|c| (c.name.clone(), c)).collect();
+        for name in sorted_keys(&previous, &current) {
+            match (previous.get(name), current.get(name)) {
+                (Some(_), None) => {
+                    let detail = eco_format!("its constructor `{name}` was removed");
+                    self.changed(module, item, Bump::Major, detail);
+                }
+                // Pattern matching on the type is no longer exhaustive if it
+                // doesn't handle the new constructor.
+                (None, Some(_)) => {
+                    let detail = eco_format!("the constructor `{name}` was added");
+                    self.changed(module, item, Bump::Major, detail);
+                }
+                (Some(previous), Some(current)) => {
+                    let what = eco_format!("the constructor `{name}`");
+                    self.parameters(
+                        module,
+                        item,
+                        &what,
+                        &previous.parameters,
+                        &current.parameters,
+                    );
+                    if previous.documentation != current.documentation {
+                        let detail = eco_format!("the documentation of {what} changed");
+                        self.changed(module, item, Bump::Patch, detail);
+                    }
+                }
+                (None, None) => (),
+            }
+        }
+    }
+
+    fn type_alias(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &TypeAliasInterface,
+        current: &TypeAliasInterface,
+    ) {
+        let TypeAliasInterface {
+            documentation,
+            deprecation,
+            parameters,
+            alias,
+        } = current;
+
+        if previous.parameters != *parameters {
+            let detail = eco_format!(
+                "its number of type parameters changed from {} to {parameters}",
+                previous.parameters
+            );
+            self.changed(module, item, Bump::Major, detail);
+        }
+        if previous.alias != *alias {
+            let detail = "the type it is an alias of changed".into();
+            self.changed(module, item, Bump::Major, detail);
+        }
+        self.deprecation(module, item, &previous.deprecation, deprecation);
+        self.documentation(module, item, &previous.documentation, documentation);
+    }
+
+    fn constant(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &ConstantInterface,
+        current: &ConstantInterface,
+    ) {
+        let ConstantInterface {
+            documentation,
+            deprecation,
+            implementations,
+            type_,
+        } = current;
+
+        if previous.type_ != *type_ {
+            let detail = "its type changed".into();
+            self.changed(module, item, Bump::Major, detail);
+        }
+        self.implementations(module, item, &previous.implementations, implementations);
+        self.deprecation(module, item, &previous.deprecation, deprecation);
+        self.documentation(module, item, &previous.documentation, documentation);
+    }
+
+    fn function(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &FunctionInterface,
+        current: &FunctionInterface,
+    ) {
+        let FunctionInterface {
+            documentation,
+            deprecation,
+            implementations,
+            parameters,
+            return_,
+        } = current;
+
+        self.parameters(module, item, "it", &previous.parameters, parameters);
+        if previous.return_ != *return_ {
+            let detail = "its return type changed".into();
+            self.changed(module, item, Bump::Major, detail);
+        }
+        self.implementations(module, item, &previous.implementations, implementations);
+        self.deprecation(module, item, &previous.deprecation, deprecation);
+        self.documentation(module, item, &previous.documentation, documentation);
+    }
+
+    /// Compares the parameters of a function or the fields of a constructor.
+    /// Arguments can always be given without a label, so only a label being
+    /// removed or renamed breaks the code using it.
+    fn parameters(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        what: &str,
+        previous: &[ParameterInterface],
+        current: &[ParameterInterface],
+    ) {
+        if previous.len() != current.len() {
+            let detail = eco_format!(
+                "the number of arguments of {what} changed from {} to {}",
+                previous.len(),
+                current.len()
+            );
+            self.changed(module, item, Bump::Major, detail);
+            return;
+        }
+
+        for (index, (previous, current)) in previous.iter().zip(current).enumerate() {
+            let position = index + 1;
+            if previous.type_ != current.type_ {
+                let detail = eco_format!("the type of argument {position} of {what} changed");
+                self.changed(module, item, Bump::Major, detail);
+            }
+            match (&previous.label, &current.label) {
+                (Some(label), None) => {
+                    let detail = eco_format!("the label `{label}` of {what} was removed");
+                    self.changed(module, item, Bump::Major, detail);
+                }
+                (Some(previous), Some(current)) if previous != current => {
+                    let detail =
+                        eco_format!("the label `{previous}` of {what} was renamed to `{current}`");
+                    self.changed(module, item, Bump::Major, detail);
+                }
+                (None, Some(label)) => {
+                    let detail = eco_format!("the label `{label}` was added to {what}");
+                    self.changed(module, item, Bump::Minor, detail);
+                }
+                (Some(_), Some(_)) | (None, None) => (),
+            }
+        }
+    }
+
+    fn implementations(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &ImplementationsInterface,
+        current: &ImplementationsInterface,
+    ) {
+        let targets = [
+            (
+                "Erlang",
+                previous.can_run_on_erlang,
+                current.can_run_on_erlang,
+            ),
+            (
+                "JavaScript",
+                previous.can_run_on_javascript,
+                current.can_run_on_javascript,
+            ),
+        ];
+        for (target, previous, current) in targets {
+            match (previous, current) {
+                (true, false) => {
+                    let detail = eco_format!("it can no longer be used on {target}");
+                    self.changed(module, item, Bump::Major, detail);
+                }
+                (false, true) => {
+                    let detail = eco_format!("it can now be used on {target}");
+                    self.changed(module, item, Bump::Minor, detail);
+                }
+                (true, true) | (false, false) => (),
+            }
+        }
+    }
+
+    fn deprecation(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &Option<DeprecationInterface>,
+        current: &Option<DeprecationInterface>,
+    ) {
+        match (previous, current) {
+            (None, Some(_)) => self.changed(module, item, Bump::Minor, "it was deprecated".into()),
+            (Some(_), None) => {
+                let detail = "it is no longer deprecated".into();
+                self.changed(module, item, Bump::Patch, detail);
+            }
+            (Some(previous), Some(current)) if previous != current => {
+                let detail = "its deprecation message changed".into();
+                self.changed(module, item, Bump::Patch, detail);
+            }
+            (Some(_), Some(_)) | (None, None) => (),
+        }
+    }
+
+    fn documentation(
+        &mut self,
+        module: &EcoString,
+        item: &EcoString,
+        previous: &Option<EcoString>,
+        current: &Option<EcoString>,
+    ) {
+        if previous != current {
+            let detail = "its documentation changed".into();
+            self.changed(module, item, Bump::Patch, detail);
+        }
+    }
+}
+
+/// The keys of both maps, sorted so that changes are always reported in the
+/// same order.
+fn sorted_keys<'a, T>(
+    previous: &'a HashMap<EcoString, T>,
+    current: &'a HashMap<EcoString, T>,
+) -> Vec<&'a EcoString> {
+    previous
+        .keys()
+        .chain(current.keys())
+        .unique()
+        .sorted()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
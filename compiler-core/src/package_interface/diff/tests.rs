@@ -0,0 +1,433 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use hexpm::version::Version;
+use itertools::Itertools;
+
+use crate::package_interface::tests::package_interface;
+
+use super::{ApiDiff, Bump};
+
+fn diff(previous: &str, current: &str) -> (Bump, String) {
+    let previous = package_interface(None, previous, None);
+    let current = package_interface(None, current, None);
+    let diff = ApiDiff::new(&previous, &current);
+    let changes = diff
+        .changes
+        .iter()
+        .map(|change| format!("{}: {change}", change.bump))
+        .join("\n");
+    (diff.required_bump(), changes)
+}
+
+fn version(version: &str) -> Version {
+    Version::parse(version).expect("version")
+}
+
+#[test]
+fn no_changes() {
+    let src = "pub fn main() { 1 }";
+    assert_eq!(diff(src, src), (Bump::Patch, "".into()));
+}
+
+#[test]
+fn private_changes_are_ignored() {
+    assert_eq!(
+        diff("fn wibble() { 1 }", "fn wibble(a) { a }"),
+        (Bump::Patch, "".into())
+    );
+}
+
+#[test]
+fn added_function() {
+    assert_eq!(
+        diff(
+            "pub fn main() { 1 }",
+            "pub fn main() { 1 }\npub fn wobble() { 2 }"
+        ),
+        (
+            Bump::Minor,
+            "minor: added function `wobble` to `my/module`".into()
+        )
+    );
+}
+
+#[test]
+fn removed_function() {
+    assert_eq!(
+        diff(
+            "pub fn main() { 1 }\npub fn wobble() { 2 }",
+            "pub fn main() { 1 }"
+        ),
+        (
+            Bump::Major,
+            "major: removed function `wobble` from `my/module`".into()
+        )
+    );
+}
+
+#[test]
+fn function_made_private() {
+    assert_eq!(
+        diff("pub fn wobble() { 2 }", "fn wobble() { 2 }"),
+        (
+            Bump::Major,
+            "major: removed function `wobble` from `my/module`".into()
+        )
+    );
+}
+
+#[test]
+fn changed_function_arity() {
+    assert_eq!(
+        diff("pub fn wobble(a: Int) { a }", "pub fn wobble(a: Int, b: Int) { a + b }"),
+        (
+            Bump::Major,
+            "major: changed function `wobble` in `my/module`: the number of arguments of it changed from 1 to 2"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn changed_function_parameter_type() {
+    assert_eq!(
+        diff(
+            "pub fn wobble(a: Int) { a }",
+            "pub fn wobble(a: Float) { a }"
+        ),
+        (
+            Bump::Major,
+            "major: changed function `wobble` in `my/module`: the type of argument 1 of it changed
+major: changed function `wobble` in `my/module`: its return type changed"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn generic_function_renamed_type_variables() {
+    assert_eq!(
+        diff(
+            "pub fn wobble(a: a, b: b) -> a { a }",
+            "pub fn wobble(a: x, b: y) -> x { a }"
+        ),
+        (Bump::Patch, "".into())
+    );
+}
+
+#[test]
+fn generic_function_changed_type_variables() {
+    assert_eq!(
+        diff(
+            "pub fn wobble(a: a, b: b) -> a { a }",
+            "pub fn wobble(a: a, b: a) -> a { b }"
+        ),
+        (
+            Bump::Major,
+            "major: changed function `wobble` in `my/module`: the type of argument 2 of it changed"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn added_label() {
+    assert_eq!(
+        diff(
+            "pub fn wobble(a: Int) { a }",
+            "pub fn wobble(value a: Int) { a }"
+        ),
+        (
+            Bump::Minor,
+            "minor: changed function `wobble` in `my/module`: the label `value` was added to it"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn removed_label() {
+    assert_eq!(
+        diff(
+            "pub fn wobble(value a: Int) { a }",
+            "pub fn wobble(a: Int) { a }"
+        ),
+        (
+            Bump::Major,
+            "major: changed function `wobble` in `my/module`: the label `value` of it was removed"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn renamed_label() {
+    assert_eq!(
+        diff(
+            "pub fn wobble(value a: Int) { a }",
+            "pub fn wobble(number a: Int) { a }"
+        ),
+        (
+            Bump::Major,
+            "major: changed function `wobble` in `my/module`: the label `value` of it was renamed to `number`"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn deprecated_function() {
+    assert_eq!(
+        diff(
+            "pub fn wobble() { 1 }",
+            "@deprecated(\"Use wibble\")\npub fn wobble() { 1 }"
+        ),
+        (
+            Bump::Minor,
+            "minor: changed function `wobble` in `my/module`: it was deprecated".into()
+        )
+    );
+}
+
+#[test]
+fn changed_documentation() {
+    assert_eq!(
+        diff(
+            "/// One\npub fn wobble() { 1 }",
+            "/// Two\npub fn wobble() { 1 }"
+        ),
+        (
+            Bump::Patch,
+            "patch: changed function `wobble` in `my/module`: its documentation changed".into()
+        )
+    );
+}
+
+#[test]
+fn function_no_longer_supports_target() {
+    assert_eq!(
+        diff(
+            "pub fn wobble() { 1 }",
+            "@external(erlang, \"wibble\", \"wobble\")\npub fn wobble() -> Int"
+        ),
+        (
+            Bump::Major,
+            "major: changed function `wobble` in `my/module`: it can no longer be used on JavaScript"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn function_now_supports_target() {
+    assert_eq!(
+        diff(
+            "@external(erlang, \"wibble\", \"wobble\")\npub fn wobble() -> Int",
+            "pub fn wobble() { 1 }"
+        ),
+        (
+            Bump::Minor,
+            "minor: changed function `wobble` in `my/module`: it can now be used on JavaScript"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn changed_constant_type() {
+    assert_eq!(
+        diff("pub const wobble = 1", "pub const wobble = 1.0"),
+        (
+            Bump::Major,
+            "major: changed constant `wobble` in `my/module`: its type changed".into()
+        )
+    );
+}
+
+#[test]
+fn added_type() {
+    assert_eq!(
+        diff("", "pub type Wibble { Wibble }"),
+        (
+            Bump::Minor,
+            "minor: added type `Wibble` to `my/module`".into()
+        )
+    );
+}
+
+#[test]
+fn added_constructor() {
+    assert_eq!(
+        diff(
+            "pub type Wibble { Wibble }",
+            "pub type Wibble { Wibble Wobble }"
+        ),
+        (
+            Bump::Major,
+            "major: changed type `Wibble` in `my/module`: the constructor `Wobble` was added"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn removed_constructor() {
+    assert_eq!(
+        diff(
+            "pub type Wibble { Wibble Wobble }",
+            "pub type Wibble { Wibble }"
+        ),
+        (
+            Bump::Major,
+            "major: changed type `Wibble` in `my/module`: its constructor `Wobble` was removed"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn changed_constructor_field() {
+    assert_eq!(
+        diff(
+            "pub type Wibble { Wibble(a: Int) }",
+            "pub type Wibble { Wibble(a: String) }"
+        ),
+        (
+            Bump::Major,
+            "major: changed type `Wibble` in `my/module`: the type of argument 1 of the constructor `Wibble` changed"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn changed_type_parameters() {
+    assert_eq!(
+        diff(
+            "pub type Wibble(a) { Wibble(a) }",
+            "pub type Wibble(a, b) { Wibble(a) }"
+        ),
+        (
+            Bump::Major,
+            "major: changed type `Wibble` in `my/module`: its number of type parameters changed from 1 to 2"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn type_made_opaque() {
+    assert_eq!(
+        diff(
+            "pub type Wibble { Wibble }",
+            "pub opaque type Wibble { Wibble }"
+        ),
+        (
+            Bump::Major,
+            "major: changed type `Wibble` in `my/module`: its constructors are no longer public"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn type_no_longer_opaque() {
+    assert_eq!(
+        diff(
+            "pub opaque type Wibble { Wibble }",
+            "pub type Wibble { Wibble }"
+        ),
+        (
+            Bump::Minor,
+            "minor: changed type `Wibble` in `my/module`: its constructors are now public".into()
+        )
+    );
+}
+
+#[test]
+fn changed_type_alias() {
+    assert_eq!(
+        diff("pub type Wibble = Int", "pub type Wibble = Float"),
+        (
+            Bump::Major,
+            "major: changed type alias `Wibble` in `my/module`: the type it is an alias of changed"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn changes_are_sorted() {
+    assert_eq!(
+        diff(
+            "pub fn b() { 1 }\npub fn c() { 1 }",
+            "pub fn a() { 1 }\npub fn c(_x) { 1 }"
+        ),
+        (
+            Bump::Major,
+            "minor: added function `a` to `my/module`
+major: removed function `b` from `my/module`
+major: changed function `c` in `my/module`: the number of arguments of it changed from 0 to 1"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn bump_between_versions() {
+    assert_eq!(Bump::between(&version("1.2.3"), &version("1.2.3")), None);
+    assert_eq!(Bump::between(&version("1.2.3"), &version("1.2.2")), None);
+    assert_eq!(
+        Bump::between(&version("1.2.3"), &version("1.2.4")),
+        Some(Bump::Patch)
+    );
+    assert_eq!(
+        Bump::between(&version("1.2.3"), &version("1.3.0")),
+        Some(Bump::Minor)
+    );
+    assert_eq!(
+        Bump::between(&version("1.2.3"), &version("2.0.0")),
+        Some(Bump::Major)
+    );
+}
+
+#[test]
+fn bump_between_versions_before_1_0_0() {
+    assert_eq!(
+        Bump::between(&version("0.2.3"), &version("0.2.4")),
+        Some(Bump::Minor)
+    );
+    assert_eq!(
+        Bump::between(&version("0.2.3"), &version("0.3.0")),
+        Some(Bump::Major)
+    );
+    assert_eq!(
+        Bump::between(&version("0.2.3"), &version("1.0.0")),
+        Some(Bump::Major)
+    );
+}
+
+#[test]
+fn next_version() {
+    assert_eq!(
+        Bump::Patch.next_version(&version("1.2.3")),
+        version("1.2.4")
+    );
+    assert_eq!(
+        Bump::Minor.next_version(&version("1.2.3")),
+        version("1.3.0")
+    );
+    assert_eq!(
+        Bump::Major.next_version(&version("1.2.3")),
+        version("2.0.0")
+    );
+    assert_eq!(
+        Bump::Minor.next_version(&version("0.2.3")),
+        version("0.2.4")
+    );
+    assert_eq!(
+        Bump::Major.next_version(&version("0.2.3")),
+        version("0.3.0")
+    );
+}
@@ -60,6 +60,14 @@ pub fn compile_package(
     src: &str,
     dep: Option<(&str, &str, &str)>,
 ) -> String {
+    serde_json::to_string_pretty(&package_interface(module_name, src, dep)).expect("to json")
+}
+
+pub fn package_interface(
+    module_name: Option<&str>,
+    src: &str,
+    dep: Option<(&str, &str, &str)>,
+) -> PackageInterface {
     let mut modules = im::HashMap::new();
     let ids = UniqueIdGenerator::new();
     // DUPE: preludeinsertion
@@ -140,11 +148,7 @@ pub fn compile_package(
     };
     module.attach_doc_and_module_comments();
     let package: Package = package_from_module(module);
-    serde_json::to_string_pretty(&PackageInterface::from_package(
-        &package,
-        &Default::default(),
-    ))
-    .expect("to json")
+    PackageInterface::from_package(&package, &Default::default())
 }
 
 fn package_from_module(module: Module) -> Package {
@@ -167,6 +171,7 @@ fn package_from_module(module: Module) -> Package {
             documentation: Docs { pages: vec![] },
             dependencies: std::collections::HashMap::new(),
             dev_dependencies: std::collections::HashMap::new(),
+            hex_repositories: std::collections::HashMap::new(),
             repository: None,
             links: vec![],
             erlang: ErlangConfig::default(),
@@ -7,17 +7,35 @@ use crate::{
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
+use hexpm::version::Version;
 
 pub const ARTEFACT_DIRECTORY_NAME: &str = "_gleam_artefacts";
 
+/// The name of the file that lists the packages of a workspace, found in the
+/// root directory of the workspace.
+pub const WORKSPACE_CONFIG_FILE: &str = "gleam-workspace.toml";
+
 #[derive(Debug, Clone)]
 pub struct ProjectPaths {
     root: Utf8PathBuf,
+    /// The root of the workspace the project is a member of, if any. The
+    /// members of a workspace share its manifest and build directory.
+    workspace_root: Option<Utf8PathBuf>,
 }
 
 impl ProjectPaths {
     pub fn new(root: Utf8PathBuf) -> Self {
-        Self { root }
+        Self {
+            root,
+            workspace_root: None,
+        }
+    }
+
+    pub fn new_workspace_member(root: Utf8PathBuf, workspace_root: Utf8PathBuf) -> Self {
+        Self {
+            root,
+            workspace_root: Some(workspace_root),
+        }
     }
 
     pub fn at_filesystem_root() -> Self {
@@ -34,6 +52,24 @@ impl ProjectPaths {
         &self.root
     }
 
+    pub fn workspace_root(&self) -> Option<&Utf8Path> {
+        self.workspace_root.as_deref()
+    }
+
+    /// The directory of the manifest, which the paths of the local packages
+    /// listed in it are relative to. For a member of a workspace this is the
+    /// root of the workspace rather than of the project.
+    ///
+    pub fn manifest_directory(&self) -> &Utf8Path {
+        self.workspace_root().unwrap_or(&self.root)
+    }
+
+    /// The config of the workspace the project is a member of, if any.
+    pub fn workspace_config(&self) -> Option<Utf8PathBuf> {
+        self.workspace_root()
+            .map(|root| root.join(WORKSPACE_CONFIG_FILE))
+    }
+
     pub fn root_config(&self) -> Utf8PathBuf {
         self.root.join("gleam.toml")
     }
@@ -43,7 +79,7 @@ impl ProjectPaths {
     }
 
     pub fn manifest(&self) -> Utf8PathBuf {
-        self.root.join("manifest.toml")
+        self.manifest_directory().join("manifest.toml")
     }
 
     pub fn src_directory(&self) -> Utf8PathBuf {
@@ -59,7 +95,7 @@ impl ProjectPaths {
     }
 
     pub fn build_directory(&self) -> Utf8PathBuf {
-        self.root.join("build")
+        self.manifest_directory().join("build")
     }
 
     pub fn build_packages_directory(&self) -> Utf8PathBuf {
@@ -146,6 +182,22 @@ impl ProjectPaths {
         self.build_packages_directory()
             .join(format!("{}.config_fingerprint", dependency_name))
     }
+
+    /// The directory `gleam deps vendor` copies the Hex packages of the
+    /// project into, next to the manifest.
+    pub fn vendor_directory(&self) -> Utf8PathBuf {
+        self.manifest_directory().join("vendor")
+    }
+}
+
+/// The tarball of a Hex package that has been vendored into the given
+/// directory.
+pub fn vendored_package_tarball(
+    vendor_directory: &Utf8Path,
+    name: &str,
+    version: &Version,
+) -> Utf8PathBuf {
+    vendor_directory.join(format!("{name}-{version}.tar"))
 }
 
 pub fn global_package_cache_package_tarball(checksum: &Base16Checksum) -> Utf8PathBuf {
@@ -168,6 +220,12 @@ fn global_packages_cache() -> Utf8PathBuf {
     global_hexpm_cache().join("packages")
 }
 
+/// The responses of the Hex API used to resolve dependencies, kept so that
+/// they can be resolved again when offline.
+pub fn global_hexpm_responses_cache() -> Utf8PathBuf {
+    global_hexpm_cache().join("responses")
+}
+
 pub fn default_global_gleam_cache() -> Utf8PathBuf {
     Utf8PathBuf::from_path_buf(
         dirs_next::cache_dir()
@@ -200,4 +258,15 @@ fn paths() {
         global_package_cache_package_tarball(&Base16Checksum(vec![0x3A, 0x21, 0xF4]))
             .ends_with("hex/hexpm/packages/3A21F4.tar")
     );
+
+    assert!(global_hexpm_responses_cache().ends_with("hex/hexpm/responses"));
+
+    assert_eq!(
+        vendored_package_tarball(
+            Utf8Path::new("/app/vendor"),
+            "wibble",
+            &Version::new(1, 2, 3)
+        ),
+        Utf8PathBuf::from("/app/vendor/wibble-1.2.3.tar")
+    );
 }
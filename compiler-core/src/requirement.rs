@@ -20,6 +20,10 @@ pub enum Requirement {
     Hex {
         #[serde(deserialize_with = "deserialise_range")]
         version: Range,
+        /// The name of the Hex repository the package is fetched from, if not
+        /// hex.pm.
+        #[serde(default, rename = "repo")]
+        repository: Option<EcoString>,
     },
 
     Path {
@@ -40,6 +44,7 @@ impl Requirement {
                 input: range.to_string(),
                 error: e.to_string(),
             })?,
+            repository: None,
         })
     }
 
@@ -56,9 +61,18 @@ impl Requirement {
 
     pub fn to_toml(&self, root_path: &Utf8Path) -> String {
         match self {
-            Requirement::Hex { version: range } => {
+            Requirement::Hex {
+                version: range,
+                repository: None,
+            } => {
                 format!(r#"{{ version = "{range}" }}"#)
             }
+            Requirement::Hex {
+                version: range,
+                repository: Some(repository),
+            } => {
+                format!(r#"{{ version = "{range}", repo = "{repository}" }}"#)
+            }
             Requirement::Path { path } => {
                 format!(
                     r#"{{ path = "{}" }}"#,
@@ -79,9 +93,17 @@ impl Serialize for Requirement {
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(1))?;
+        let mut map = serializer.serialize_map(None)?;
         match self {
-            Requirement::Hex { version: range } => map.serialize_entry("version", range)?,
+            Requirement::Hex {
+                version: range,
+                repository,
+            } => {
+                map.serialize_entry("version", range)?;
+                if let Some(repository) = repository {
+                    map.serialize_entry("repo", repository)?;
+                }
+            }
             Requirement::Path { path } => map.serialize_entry("path", path)?,
             Requirement::Git { git: url, ref_ } => {
                 map.serialize_entry("git", url)?;
@@ -173,6 +195,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_requirement_with_repository() {
+        let toml = r#"
+            internal = { version = "~> 1.0", repo = "internal" }
+        "#;
+        let deps: HashMap<String, Requirement> = toml::from_str(toml).unwrap();
+        let requirement = Requirement::Hex {
+            version: Range::new("~> 1.0".into()).unwrap(),
+            repository: Some("internal".into()),
+        };
+        assert_eq!(deps["internal"], requirement);
+        assert_eq!(
+            requirement.to_toml("/".into()),
+            r#"{ version = "~> 1.0", repo = "internal" }"#
+        );
+    }
+
     #[test]
     fn read_wrong_version() {
         let toml = r#"
@@ -15,7 +15,7 @@ pub mod printer;
 pub mod tests;
 
 use camino::Utf8PathBuf;
-use ecow::EcoString;
+use ecow::{EcoString, eco_format};
 pub use environment::*;
 pub use error::{Error, Problems, UnifyErrorSituation, Warning};
 pub(crate) use expression::ExprTyper;
@@ -1011,6 +1011,22 @@ pub struct ModuleFunction {
     pub package: EcoString,
 }
 
+/// A function that `gleam test` can run as a test: a public function with no
+/// arguments whose name ends with `_test`, defined in a `test` module.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TestFunction {
+    pub module: EcoString,
+    pub name: EcoString,
+}
+
+impl TestFunction {
+    /// The name of the function qualified with its module, for example
+    /// `wibble/parser.parse_int_test`.
+    pub fn qualified_name(&self) -> EcoString {
+        eco_format!("{}.{}", self.module, self.name)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ModuleInterface {
     pub name: EcoString,
@@ -1220,6 +1236,35 @@ impl ModuleInterface {
         })
     }
 
+    /// The test functions defined in this module that can be run on the given
+    /// target, sorted by name. Modules that are not `test` modules have none.
+    pub fn test_functions(&self, target: Target) -> Vec<TestFunction> {
+        if !self.origin.is_test() {
+            return vec![];
+        }
+
+        self.values
+            .iter()
+            .filter(|(name, value)| {
+                name.ends_with("_test")
+                    && value.publicity.is_importable()
+                    && matches!(
+                        &value.variant,
+                        ValueConstructorVariant::ModuleFn {
+                            arity: 0,
+                            implementations,
+                            ..
+                        } if implementations.supports(target)
+                    )
+            })
+            .map(|(name, _)| TestFunction {
+                module: self.name.clone(),
+                name: name.clone(),
+            })
+            .sorted()
+            .collect_vec()
+    }
+
     pub fn public_value_names(&self) -> Vec<EcoString> {
         self.values
             .iter()
@@ -11,6 +11,7 @@ use crate::{
     exhaustiveness::ImpossibleBitArraySegmentPattern,
     parse::LiteralFloatValue,
     type_::{Type, expression::ComparisonOutcome, printer::Names},
+    warning::WarningRule,
 };
 
 use ecow::EcoString;
@@ -1317,6 +1318,99 @@ pub enum UnreachablePatternReason {
 }
 
 impl Error {
+    /// A code identifying the kind of error, which must never change once it
+    /// has been released so that tools outside the compiler can rely on it.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::InvalidImport { .. } => "invalid-import",
+            Error::BitArraySegmentError { .. } => "invalid-bit-array-segment",
+            Error::UnknownLabels { .. } => "unknown-labels",
+            Error::UnknownVariable { .. } => "unknown-variable",
+            Error::UnknownType { .. } => "unknown-type",
+            Error::QualifiedTypeMissingName { .. } => "qualified-type-missing-name",
+            Error::UnknownModule { .. } => "unknown-module",
+            Error::UnknownModuleType { .. } => "unknown-module-type",
+            Error::UnknownModuleValue { .. } => "unknown-module-value",
+            Error::ModuleAliasUsedAsName { .. } => "module-alias-used-as-name",
+            Error::NotFn { .. } => "not-a-function",
+            Error::UnknownRecordField { .. } => "unknown-record-field",
+            Error::IncorrectArity { .. } => "incorrect-arity",
+            Error::UnsafeRecordUpdate { .. } => "unsafe-record-update",
+            Error::UnnecessarySpreadOperator { .. } => "unnecessary-spread-operator",
+            Error::IncorrectTypeArity { .. } => "incorrect-type-arity",
+            Error::CouldNotUnify { .. } => "type-mismatch",
+            Error::RecursiveType { .. } => "recursive-type",
+            Error::DuplicateName { .. } => "duplicate-name",
+            Error::DuplicateImport { .. } => "duplicate-import",
+            Error::DuplicateTypeName { .. } => "duplicate-type-name",
+            Error::DuplicateArgument { .. } => "duplicate-argument",
+            Error::DuplicateField { .. } => "duplicate-field",
+            Error::PrivateTypeLeak { .. } => "private-type-leak",
+            Error::UnexpectedLabelledArg { .. } => "unexpected-labelled-argument",
+            Error::PositionalArgumentAfterLabelled { .. } => "positional-argument-after-labelled",
+            Error::IncorrectNumClausePatterns { .. } => "incorrect-number-of-clause-patterns",
+            Error::NonLocalClauseGuardVariable { .. } => "non-local-clause-guard-variable",
+            Error::ExtraVarInAlternativePattern { .. } => "extra-variable-in-alternative-pattern",
+            Error::MissingVarInAlternativePattern { .. } => {
+                "missing-variable-in-alternative-pattern"
+            }
+            Error::DuplicateVarInPattern { .. } => "duplicate-variable-in-pattern",
+            Error::OutOfBoundsTupleIndex { .. } => "out-of-bounds-tuple-index",
+            Error::NotATuple { .. } => "not-a-tuple",
+            Error::NotATupleUnbound { .. } => "not-a-tuple-unbound",
+            Error::RecordAccessUnknownType { .. } => "record-access-unknown-type",
+            Error::RecordUpdateInvalidConstructor { .. } => "record-update-invalid-constructor",
+            Error::UnexpectedTypeHole { .. } => "unexpected-type-hole",
+            Error::ReservedModuleName { .. } => "reserved-module-name",
+            Error::KeywordInModuleName { .. } => "keyword-in-module-name",
+            Error::NotExhaustivePatternMatch { .. } => "not-exhaustive-pattern-match",
+            Error::ArgumentNameAlreadyUsed { .. } => "argument-name-already-used",
+            Error::UnlabelledAfterlabelled { .. } => "unlabelled-after-labelled",
+            Error::RecursiveTypeAlias { .. } => "recursive-type-alias",
+            Error::ExternalMissingAnnotation { .. } => "external-missing-annotation",
+            Error::NoImplementation { .. } => "no-implementation",
+            Error::UnsupportedExpressionTarget { .. } => "unsupported-expression-target",
+            Error::InvalidExternalJavascriptModule { .. } => "invalid-external-javascript-module",
+            Error::InvalidExternalJavascriptFunction { .. } => {
+                "invalid-external-javascript-function"
+            }
+            Error::InexhaustiveCaseExpression { .. } => "inexhaustive-case-expression",
+            Error::MissingCaseBody { .. } => "missing-case-body",
+            Error::InexhaustiveLetAssignment { .. } => "inexhaustive-let-assignment",
+            Error::UnusedTypeAliasParameter { .. } => "unused-type-alias-parameter",
+            Error::DuplicateTypeParameter { .. } => "duplicate-type-parameter",
+            Error::UnsupportedPublicFunctionTarget { .. } => "unsupported-public-function-target",
+            Error::NotFnInUse { .. } => "use-not-a-function",
+            Error::UseFnIncorrectArity { .. } => "use-function-incorrect-arity",
+            Error::UseCallbackIncorrectArity { .. } => "use-callback-incorrect-arity",
+            Error::UseFnDoesntTakeCallback { .. } => "use-function-does-not-take-callback",
+            Error::BadName { .. } => "invalid-name",
+            Error::AllVariantsDeprecated { .. } => "all-variants-deprecated",
+            Error::DeprecatedVariantOnDeprecatedType { .. } => {
+                "deprecated-variant-on-deprecated-type"
+            }
+            Error::LiteralFloatOutOfRange { .. } => "literal-float-out-of-range",
+            Error::EchoWithNoFollowingExpression { .. } => "echo-with-no-following-expression",
+            Error::StringConcatenationWithAddInt { .. } => "string-concatenation-with-add-int",
+            Error::FloatOperatorOnInts { .. } => "float-operator-on-ints",
+            Error::IntOperatorOnFloats { .. } => "int-operator-on-floats",
+            Error::DoubleVariableAssignmentInBitArray { .. } => {
+                "double-variable-assignment-in-bit-array"
+            }
+            Error::NonUtf8StringAssignmentInBitArray { .. } => {
+                "non-utf8-string-assignment-in-bit-array"
+            }
+            Error::PrivateOpaqueType { .. } => "private-opaque-type",
+            Error::SrcImportingDevDependency { .. } => "src-importing-dev-dependency",
+            Error::TypeUsedAsAConstructor { .. } => "type-used-as-a-constructor",
+            Error::ExternalTypeWithConstructors { .. } => "external-type-with-constructors",
+            Error::LowercaseBoolPattern { .. } => "lowercase-bool-pattern",
+            Error::RecordUpdateVariantWithNoFields { .. } => "record-update-variant-with-no-fields",
+            Error::TodoConstant { .. } => "todo-constant",
+        }
+    }
+
     // Location where the error started
     pub fn start_location(&self) -> u32 {
         match self {
@@ -1420,6 +1514,166 @@ impl Error {
 }
 
 impl Warning {
+    /// The rule this warning is an instance of. Each variant has its own rule,
+    /// and a rule's id must never change once it has been released.
+    ///
+    pub fn rule(&self) -> WarningRule {
+        match self {
+            Warning::Todo { .. } => WarningRule {
+                id: "todo",
+                help: "A `todo` expression was found, which will crash the program when run.",
+            },
+            Warning::ImplicitlyDiscardedResult { .. } => WarningRule {
+                id: "unused-result",
+                help: "A `Result` value was discarded without being checked for errors.",
+            },
+            Warning::UnusedLiteral { .. } => WarningRule {
+                id: "unused-literal",
+                help: "A literal value was created but never used.",
+            },
+            Warning::UnusedValue { .. } => WarningRule {
+                id: "unused-value",
+                help: "A value was computed but never used.",
+            },
+            Warning::NoFieldsRecordUpdate { .. } => WarningRule {
+                id: "record-update-without-fields",
+                help: "A record update changes no fields, so it can be removed.",
+            },
+            Warning::AllFieldsRecordUpdate { .. } => WarningRule {
+                id: "record-update-with-all-fields",
+                help: "A record update changes every field, so the record being updated is not needed.",
+            },
+            Warning::UnusedType { .. } => WarningRule {
+                id: "unused-type",
+                help: "A type is defined or imported but never used.",
+            },
+            Warning::UnusedConstructor { .. } => WarningRule {
+                id: "unused-constructor",
+                help: "A type constructor is defined or imported but never used.",
+            },
+            Warning::UnusedImportedValue { .. } => WarningRule {
+                id: "unused-imported-value",
+                help: "A value is imported but never used.",
+            },
+            Warning::UnusedImportedModule { .. } => WarningRule {
+                id: "unused-imported-module",
+                help: "A module is imported but never used.",
+            },
+            Warning::UnusedImportedModuleAlias { .. } => WarningRule {
+                id: "unused-imported-module-alias",
+                help: "A module is imported with an alias that is never used.",
+            },
+            Warning::UnusedPrivateModuleConstant { .. } => WarningRule {
+                id: "unused-private-constant",
+                help: "A private constant is defined but never used.",
+            },
+            Warning::UnusedPrivateFunction { .. } => WarningRule {
+                id: "unused-private-function",
+                help: "A private function is defined but never used.",
+            },
+            Warning::UnusedVariable { .. } => WarningRule {
+                id: "unused-variable",
+                help: "A variable is assigned but never used.",
+            },
+            Warning::UnnecessaryDoubleIntNegation { .. } => WarningRule {
+                id: "double-int-negation",
+                help: "An `Int` is negated twice, which has no effect.",
+            },
+            Warning::UnnecessaryDoubleBoolNegation { .. } => WarningRule {
+                id: "double-bool-negation",
+                help: "A `Bool` is negated twice, which has no effect.",
+            },
+            Warning::InefficientEmptyListCheck { .. } => WarningRule {
+                id: "inefficient-empty-list-check",
+                help: "The length of a list is computed just to check whether it is empty.",
+            },
+            Warning::TransitiveDependencyImported { .. } => WarningRule {
+                id: "transitive-dependency-imported",
+                help: "A module is imported from a package that is not a direct dependency.",
+            },
+            Warning::DeprecatedItem { .. } => WarningRule {
+                id: "deprecated-item",
+                help: "A deprecated value, type or module is used.",
+            },
+            Warning::UnreachableCasePattern { .. } => WarningRule {
+                id: "unreachable-case-pattern",
+                help: "A case pattern can never match, so its clause is never run.",
+            },
+            Warning::UnusedDiscardPattern { .. } => WarningRule {
+                id: "unused-discard-pattern",
+                help: "A discard pattern is used where it has no effect.",
+            },
+            Warning::CaseMatchOnLiteralCollection { .. } => WarningRule {
+                id: "case-on-literal-collection",
+                help: "A case expression matches on a literal collection instead of on multiple subjects.",
+            },
+            Warning::CaseMatchOnLiteralValue { .. } => WarningRule {
+                id: "case-on-literal-value",
+                help: "A case expression matches on a literal value, so its outcome is already known.",
+            },
+            Warning::OpaqueExternalType { .. } => WarningRule {
+                id: "opaque-external-type",
+                help: "An external type is marked as opaque, which has no effect.",
+            },
+            Warning::RedundantAssertAssignment { .. } => WarningRule {
+                id: "redundant-let-assert",
+                help: "A `let assert` pattern always matches, so a plain `let` can be used.",
+            },
+            Warning::AssertAssignmentOnImpossiblePattern { .. } => WarningRule {
+                id: "impossible-let-assert",
+                help: "A `let assert` pattern can never match, so it will always crash.",
+            },
+            Warning::TodoOrPanicUsedAsFunction { .. } => WarningRule {
+                id: "todo-or-panic-used-as-function",
+                help: "`todo` or `panic` is called like a function instead of using `as` for its message.",
+            },
+            Warning::UnreachableCodeAfterPanic { .. } => WarningRule {
+                id: "unreachable-code",
+                help: "Code comes after an expression that always crashes, so it is never run.",
+            },
+            Warning::RedundantPipeFunctionCapture { .. } => WarningRule {
+                id: "redundant-pipe-function-capture",
+                help: "A function capture is piped into, which is the same as calling the function directly.",
+            },
+            Warning::FeatureRequiresHigherGleamVersion { .. } => WarningRule {
+                id: "feature-requires-higher-gleam-version",
+                help: "A feature is used that needs a newer Gleam version than the package's `gleam` constraint allows.",
+            },
+            Warning::JavaScriptIntUnsafe { .. } => WarningRule {
+                id: "javascript-unsafe-int",
+                help: "An `Int` is outside the range that can be safely represented on JavaScript.",
+            },
+            Warning::AssertLiteralBool { .. } => WarningRule {
+                id: "assert-literal-bool",
+                help: "`assert` is used on a literal `Bool`, so its outcome is already known.",
+            },
+            Warning::BitArraySegmentTruncatedValue { .. } => WarningRule {
+                id: "truncated-bit-array-segment",
+                help: "A bit array segment's value does not fit in its size and will be truncated.",
+            },
+            Warning::ModuleImportedTwice { .. } => WarningRule {
+                id: "module-imported-twice",
+                help: "The same module is imported more than once.",
+            },
+            Warning::TopLevelDefinitionShadowsImport { .. } => WarningRule {
+                id: "definition-shadows-import",
+                help: "A top level definition has the same name as an imported value.",
+            },
+            Warning::RedundantComparison { .. } => WarningRule {
+                id: "redundant-comparison",
+                help: "A comparison always has the same outcome.",
+            },
+            Warning::UnusedRecursiveArgument { .. } => WarningRule {
+                id: "unused-recursive-argument",
+                help: "A function argument is only ever passed unchanged to recursive calls.",
+            },
+            Warning::JavaScriptBitArrayUnsafeInt { .. } => WarningRule {
+                id: "javascript-unsafe-bit-array-int",
+                help: "A bit array integer segment is too big to be read safely on JavaScript.",
+            },
+        }
+    }
+
     pub(crate) fn location(&self) -> SrcSpan {
         match self {
             Warning::Todo { location, .. }
@@ -187,6 +187,25 @@ pub enum Warning {
     },
 }
 
+/// A kind of warning, identified by an id that stays the same across compiler
+/// versions so that tools outside the compiler can track and suppress it.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WarningRule {
+    pub id: &'static str,
+    /// A sentence describing what the warning is about.
+    pub help: &'static str,
+}
+
+/// A change to the code that gets rid of a warning: the code at the location
+/// is to be replaced with the replacement.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningFix {
+    pub location: SrcSpan,
+    pub replacement: EcoString,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Copy)]
 pub enum DeprecatedSyntaxWarning {
     /// If someone uses the deprecated syntax to append to a list:
@@ -251,7 +270,101 @@ pub enum DeprecatedSyntaxWarning {
     },
 }
 
+impl DeprecatedSyntaxWarning {
+    pub fn rule(&self) -> WarningRule {
+        match self {
+            DeprecatedSyntaxWarning::DeprecatedListPrepend { .. } => WarningRule {
+                id: "deprecated-list-prepend-syntax",
+                help: "A list is prepended to using the deprecated syntax without a comma before `..`.",
+            },
+            DeprecatedSyntaxWarning::DeprecatedListPattern { .. } => WarningRule {
+                id: "deprecated-list-pattern-syntax",
+                help: "A list pattern uses the deprecated syntax without a comma before `..`.",
+            },
+            DeprecatedSyntaxWarning::DeprecatedListCatchAllPattern { .. } => WarningRule {
+                id: "deprecated-list-catch-all-pattern",
+                help: "The `[..]` pattern is used to match any list, instead of `_`.",
+            },
+            DeprecatedSyntaxWarning::DeprecatedRecordSpreadPattern { .. } => WarningRule {
+                id: "deprecated-record-spread-pattern-syntax",
+                help: "A record pattern uses the deprecated syntax without a comma before `..`.",
+            },
+            DeprecatedSyntaxWarning::DeprecatedEmptyClauseGuard { .. } => WarningRule {
+                id: "deprecated-empty-clause-guard",
+                help: "A case clause has an `if` guard with no condition.",
+            },
+            DeprecatedSyntaxWarning::DeprecatedTargetShorthand { .. } => WarningRule {
+                id: "deprecated-target-shorthand",
+                help: "A target is referred to using its deprecated short name.",
+            },
+        }
+    }
+}
+
+impl DeprecatedSyntaxWarning {
+    /// The change that replaces the deprecated syntax, if it can be made
+    /// without knowing more about the code than the warning does.
+    ///
+    pub fn fix(&self) -> Option<WarningFix> {
+        let (location, replacement) = match self {
+            DeprecatedSyntaxWarning::DeprecatedListPrepend { location }
+            | DeprecatedSyntaxWarning::DeprecatedListPattern { location } => (location, ", .."),
+            DeprecatedSyntaxWarning::DeprecatedListCatchAllPattern { location } => (location, "_"),
+            DeprecatedSyntaxWarning::DeprecatedEmptyClauseGuard { location } => (location, ""),
+            DeprecatedSyntaxWarning::DeprecatedTargetShorthand { location, target } => {
+                let full_name = match target {
+                    Target::Erlang => "erlang",
+                    Target::JavaScript => "javascript",
+                };
+                (location, full_name)
+            }
+            // The comma has to go after the last argument rather than before
+            // the spread, and where that is isn't known.
+            DeprecatedSyntaxWarning::DeprecatedRecordSpreadPattern { .. } => return None,
+        };
+        Some(WarningFix {
+            location: *location,
+            replacement: replacement.into(),
+        })
+    }
+}
+
 impl Warning {
+    /// The rule this warning is an instance of. A rule's id must never change
+    /// once it has been released.
+    ///
+    pub fn rule(&self) -> WarningRule {
+        match self {
+            Warning::Type { warning, .. } => warning.rule(),
+            Warning::InvalidSource { .. } => WarningRule {
+                id: "invalid-module-name",
+                help: "A module's name is not valid, so it is not compiled.",
+            },
+            Warning::DeprecatedSyntax { warning, .. } => warning.rule(),
+            Warning::EmptyModule { .. } => WarningRule {
+                id: "empty-module",
+                help: "A module contains no public definitions.",
+            },
+            Warning::DetachedDocComment { .. } => WarningRule {
+                id: "detached-doc-comment",
+                help: "A documentation comment is not attached to a definition.",
+            },
+        }
+    }
+
+    /// The changes to the code that get rid of this warning, if the compiler
+    /// knows of any. They are all in the file the warning is in.
+    ///
+    pub fn fixes(&self) -> Vec<WarningFix> {
+        match self {
+            Warning::DeprecatedSyntax { warning, .. } => warning.fix().into_iter().collect(),
+            Warning::Type { .. }
+            | Warning::InvalidSource { .. }
+            | Warning::EmptyModule { .. }
+            | Warning::DetachedDocComment { .. } => vec![],
+        }
+    }
+
     pub fn to_diagnostic(&self) -> Diagnostic {
         match self {
             Warning::InvalidSource { path } => Diagnostic {
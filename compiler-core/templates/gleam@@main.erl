@@ -1,5 +1,5 @@
 -module('{{ application }}@@main').
--export([run/1, main/1]).
+-export([run/1, main/1, test/3]).
 
 -define(red, "\e[31;1m").
 -define(grey, "\e[90m").
@@ -21,6 +21,110 @@ run(Module) ->
             init:stop(1)
     end.
 
+%%% Used by `gleam test` to run the given test functions, rather than the
+%%% test module's main function. If given a path the results of the tests are
+%%% written to it, one JSON object per line, for the build tool to report.
+%%% Likewise if given a coverage path the counts of the coverage points of the
+%%% modules compiled with coverage are written to it.
+test(Tests, ResultsPath, CoveragePath) ->
+    io:setopts(standard_io, [binary, {encoding, utf8}]),
+    io:setopts(standard_error, [{encoding, utf8}]),
+    start_coverage(CoveragePath),
+    {ok, _} = application:ensure_all_started('{{ application }}'),
+    Results = [run_test(Test) || Test <- Tests],
+    write_test_results(ResultsPath, Results),
+    write_coverage(CoveragePath),
+    Failed = length([R || {_, _, _, {failed, _, _}} = R <- Results]),
+    Passed = length(Tests) - Failed,
+    io:format("~n~B passed, ~B failed~n", [Passed, Failed]),
+    case Failed of
+        0 -> init:stop(0);
+        _ -> init:stop(1)
+    end.
+
+run_test({Module, Function}) ->
+    Start = erlang:monotonic_time(microsecond),
+    Outcome =
+        try
+            Module:Function(),
+            passed
+        catch
+            Class:Reason:StackTrace ->
+                Name = module_name(Module),
+                io:format(standard_error, "~ntest failed: ~ts.~ts~n~n", [Name, Function]),
+                print_error_with_stacktrace(Class, Reason, StackTrace),
+                {failed, Class, Reason}
+        end,
+    Duration = erlang:monotonic_time(microsecond) - Start,
+    {Module, Function, Duration, Outcome}.
+
+write_test_results(undefined, _) ->
+    ok;
+write_test_results(Path, Results) ->
+    Lines = [[json(test_result(Result)), $\n] || Result <- Results],
+    ok = file:write_file(Path, unicode:characters_to_binary(Lines)).
+
+test_result({Module, Function, Duration, Outcome}) ->
+    #{
+        module => module_name(Module),
+        function => atom_to_binary(Function),
+        duration => Duration,
+        failure => test_failure(Outcome)
+    }.
+
+test_failure(passed) ->
+    null;
+test_failure({failed, _, #{gleam_error := Kind} = Error}) ->
+    #{kind => Kind, message => error_message(Error), metadata => Error};
+test_failure({failed, Class, Reason}) ->
+    Details = #{reason => unicode:characters_to_binary(print_term(Reason))},
+    #{kind => Class, message => error_message(Reason), metadata => Details}.
+
+%% The table the code compiled with coverage counts the uses of its coverage
+%% points in. When it doesn't exist the counts are not recorded.
+start_coverage(undefined) ->
+    ok;
+start_coverage(_) ->
+    'gleam@@coverage' = ets:new('gleam@@coverage', [named_table, public, set]),
+    ok.
+
+write_coverage(undefined) ->
+    ok;
+write_coverage(Path) ->
+    Counts = lists:sort(ets:tab2list('gleam@@coverage')),
+    Lines = [[json(coverage_count(Count)), $\n] || Count <- Counts],
+    ok = file:write_file(Path, unicode:characters_to_binary(Lines)).
+
+coverage_count({Key, Count}) ->
+    {Module, Point} = Key,
+    #{module => Module, point => Point, count => Count}.
+
+module_name(Module) ->
+    unicode:characters_to_binary(string:replace(atom_to_binary(Module), "@", "/", all)).
+
+%% Just enough JSON for the test results. The values in the metadata of Gleam
+%% errors can be anything, so they are printed as strings.
+json(null) -> <<"null">>;
+json(true) -> <<"true">>;
+json(false) -> <<"false">>;
+json(A) when is_atom(A) -> json(atom_to_binary(A));
+json(I) when is_integer(I) -> integer_to_binary(I);
+json(B) when is_binary(B) -> [$", [json_char(C) || <<C/utf8>> <= B], $"];
+json(L) when is_list(L) -> [$[, lists:join($,, [json(X) || X <- L]), $]];
+json(M) when is_map(M) ->
+    Fields = [[json(K), $:, json_field(K, V)] || {K, V} <- maps:to_list(M)],
+    [${, lists:join($,, Fields), $}];
+json(T) -> json(unicode:characters_to_binary(print_term(T))).
+
+json_field(value, V) -> json(unicode:characters_to_binary(print_term(V)));
+json_field(_, V) -> json(V).
+
+json_char($") -> <<"\\\"">>;
+json_char($\\) -> <<"\\\\">>;
+json_char($\n) -> <<"\\n">>;
+json_char(C) when C < 32 -> io_lib:format("\\u~4.16.0B", [C]);
+json_char(C) -> <<C/utf8>>.
+
 %%% Used by escripts
 main(_) ->
     run('{{ application }}').
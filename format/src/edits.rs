@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use camino::Utf8Path;
+use ecow::EcoString;
+use gleam_core::{Result, ast::SrcSpan};
+use similar::{Algorithm, DiffTag};
+use std::ops::Range;
+
+/// A change the formatter makes to the source code of a module: the code at
+/// `location` is replaced with `new_text`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub location: SrcSpan,
+    pub new_text: String,
+}
+
+/// Formats a module, returning the smallest edits needed to turn its source
+/// code into the formatted one, sorted by their position.
+///
+/// The whole module is always formatted, so each of its parts is formatted in
+/// the context it appears in. This means the edits falling within a part of
+/// the module, like a function or a single case clause, can be applied on their
+/// own to only format that part and leave the rest of the code untouched.
+///
+pub fn edits(src: &EcoString, path: &Utf8Path) -> Result<Vec<Edit>> {
+    let mut formatted = String::new();
+    crate::pretty(&mut formatted, src, path)?;
+    Ok(diff(src, &formatted))
+}
+
+/// Formats only the part of a module at the given location, which has to be
+/// made of whole top level definitions, returning the edits to make to the
+/// source code of the module. Errors in the rest of the module don't stop the
+/// part from being formatted.
+///
+pub fn edits_in(src: &EcoString, path: &Utf8Path, location: SrcSpan) -> Result<Vec<Edit>> {
+    let start = location.start as usize;
+    let end = location.end as usize;
+
+    // The rest of the module is blanked out rather than cut off, so that the
+    // locations of any errors in the part still match the whole module.
+    let mut part = String::with_capacity(src.len());
+    for (index, char) in src.char_indices() {
+        if (start..end).contains(&index) || char == '\n' {
+            part.push(char);
+        } else {
+            part.extend(std::iter::repeat_n(' ', char.len_utf8()));
+        }
+    }
+
+    let mut formatted = String::new();
+    crate::pretty(&mut formatted, &part.into(), path)?;
+    let edits = diff(&src[start..end], &formatted)
+        .into_iter()
+        .map(|edit| Edit {
+            location: SrcSpan::new(
+                edit.location.start + location.start,
+                edit.location.end + location.start,
+            ),
+            new_text: edit.new_text,
+        })
+        .collect();
+    Ok(edits)
+}
+
+/// The edits changing code within the given range. Edits that reach out of
+/// the range are narrowed down to the characters they change, and only those
+/// within the range are kept, so the code around it is never changed.
+///
+/// An insertion is kept if it's inside the range or at one of its ends.
+///
+pub fn clip(src: &str, edits: Vec<Edit>, range: SrcSpan) -> Vec<Edit> {
+    let within = |location: SrcSpan| range.start <= location.start && location.end <= range.end;
+    edits
+        .into_iter()
+        .flat_map(|edit| {
+            if within(edit.location) {
+                vec![edit]
+            } else if edit.location.start <= range.end && range.start <= edit.location.end {
+                character_edits(src, &edit)
+                    .into_iter()
+                    .filter(|edit| within(edit.location))
+                    .collect()
+            } else {
+                vec![]
+            }
+        })
+        .collect()
+}
+
+/// Splits an edit into the smallest edits changing the same characters.
+///
+fn character_edits(src: &str, edit: &Edit) -> Vec<Edit> {
+    let old = &src[edit.location.start as usize..edit.location.end as usize];
+    let (old_chars, old_offsets) = characters(old);
+    let (new_chars, new_offsets) = characters(&edit.new_text);
+
+    let mut edits: Vec<Edit> = vec![];
+    for operation in similar::capture_diff_slices(Algorithm::Myers, &old_chars, &new_chars) {
+        let (tag, old_range, new_range) = operation.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        let start = edit.location.start + old_offsets[old_range.start] as u32;
+        let end = edit.location.start + old_offsets[old_range.end] as u32;
+        let new_text = &edit.new_text[new_offsets[new_range.start]..new_offsets[new_range.end]];
+        match edits.last_mut() {
+            // A deletion followed by an insertion is a single replacement.
+            Some(previous) if previous.location.end == start => {
+                previous.location.end = end;
+                previous.new_text.push_str(new_text);
+            }
+            _ => edits.push(Edit {
+                location: SrcSpan::new(start, end),
+                new_text: new_text.into(),
+            }),
+        }
+    }
+    edits
+}
+
+/// The characters of a string, and the byte offset at which each of them
+/// starts followed by the total length.
+///
+fn characters(text: &str) -> (Vec<char>, Vec<usize>) {
+    let mut offsets = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    offsets.push(text.len());
+    (text.chars().collect(), offsets)
+}
+
+fn diff(old: &str, new: &str) -> Vec<Edit> {
+    let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+    let old_offsets = line_offsets(&old_lines);
+    let new_offsets = line_offsets(&new_lines);
+
+    // The ranges of old lines that are replaced by ranges of new lines.
+    let mut hunks: Vec<(Range<usize>, Range<usize>)> = vec![];
+    for operation in similar::capture_diff_slices(Algorithm::Myers, &old_lines, &new_lines) {
+        let (tag, old_range, new_range) = operation.as_tag_tuple();
+        match hunks.last_mut() {
+            _ if tag == DiffTag::Equal => (),
+            // A deletion followed by an insertion is a single replacement.
+            Some((previous_old, previous_new))
+                if previous_old.end == old_range.start && previous_new.end == new_range.start =>
+            {
+                previous_old.end = old_range.end;
+                previous_new.end = new_range.end;
+            }
+            _ => hunks.push((old_range, new_range)),
+        }
+    }
+
+    let mut edits = vec![];
+    for (old_range, new_range) in hunks {
+        // If the formatter only changed the whitespace of some lines, each of
+        // them can be changed on its own without breaking the code. Otherwise
+        // the lines have to be replaced all together: for example, applying
+        // just part of the changes that move an argument from one line to the
+        // next would lose it.
+        let lines_can_be_split = old_range.len() == new_range.len()
+            && old_lines[old_range.clone()]
+                .iter()
+                .zip(&new_lines[new_range.clone()])
+                .all(|(old_line, new_line)| same_ignoring_whitespace(old_line, new_line));
+        let hunks = if lines_can_be_split {
+            old_range
+                .zip(new_range)
+                .map(|(old_line, new_line)| (old_line..old_line + 1, new_line..new_line + 1))
+                .collect()
+        } else {
+            vec![(old_range, new_range)]
+        };
+
+        for (old_range, new_range) in hunks {
+            let location = SrcSpan::new(
+                old_offsets[old_range.start] as u32,
+                old_offsets[old_range.end] as u32,
+            );
+            let new_text = &new[new_offsets[new_range.start]..new_offsets[new_range.end]];
+            edits.push(shrink(old, location, new_text));
+        }
+    }
+    edits
+}
+
+fn same_ignoring_whitespace(one: &str, other: &str) -> bool {
+    one.chars()
+        .filter(|char| !char.is_whitespace())
+        .eq(other.chars().filter(|char| !char.is_whitespace()))
+}
+
+/// The byte offset at which each line starts, followed by the total length.
+///
+fn line_offsets(lines: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(lines.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for line in lines {
+        offset += line.len();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// Edits are found comparing entire lines, but often only a few characters of
+/// those lines are changed. This removes the text both the old and new lines
+/// start and end with, so only the characters that actually change are
+/// replaced.
+///
+fn shrink(old: &str, location: SrcSpan, new_text: &str) -> Edit {
+    let old_text = &old[location.start as usize..location.end as usize];
+
+    let prefix = common_length(old_text.chars(), new_text.chars());
+    let suffix = common_length(
+        old_text[prefix..].chars().rev(),
+        new_text[prefix..].chars().rev(),
+    );
+
+    Edit {
+        location: SrcSpan::new(location.start + prefix as u32, location.end - suffix as u32),
+        new_text: new_text[prefix..new_text.len() - suffix].into(),
+    }
+}
+
+/// The length in bytes of the longest sequence of characters two strings
+/// start with.
+///
+fn common_length(one: impl Iterator<Item = char>, other: impl Iterator<Item = char>) -> usize {
+    one.zip(other)
+        .take_while(|(one, other)| one == other)
+        .map(|(char, _)| char.len_utf8())
+        .sum()
+}
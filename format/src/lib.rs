@@ -4,6 +4,10 @@
 #[cfg(test)]
 mod tests;
 
+mod edits;
+
+pub use edits::{Edit, clip, edits, edits_in};
+
 use camino::Utf8Path;
 use ecow::{EcoString, eco_format};
 use gleam_core::{
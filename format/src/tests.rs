@@ -13,6 +13,7 @@ mod conditional_compilation;
 mod constant;
 mod custom_type;
 mod echo;
+mod edits;
 mod external_fn;
 mod external_types;
 mod function;
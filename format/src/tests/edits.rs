@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use camino::Utf8Path;
+use ecow::EcoString;
+use gleam_core::ast::SrcSpan;
+use pretty_assertions::assert_eq;
+
+use crate::{Edit, clip, edits, edits_in};
+
+fn format_edits(src: &str) -> Vec<Edit> {
+    edits(&EcoString::from(src), Utf8Path::new("<stdin>")).expect("valid module")
+}
+
+fn apply(src: &str, edits: &[Edit]) -> String {
+    let mut src = src.to_string();
+    for edit in edits.iter().rev() {
+        src.replace_range(
+            edit.location.start as usize..edit.location.end as usize,
+            &edit.new_text,
+        );
+    }
+    src
+}
+
+fn formatted(src: &str) -> String {
+    let mut formatted = String::new();
+    crate::pretty(&mut formatted, &src.into(), Utf8Path::new("<stdin>")).expect("valid module");
+    formatted
+}
+
+#[test]
+fn formatted_module_has_no_edits() {
+    let src = "pub fn main() {
+  1
+}
+";
+    assert_eq!(format_edits(src), vec![]);
+}
+
+#[test]
+fn applying_all_edits_gives_the_formatted_module() {
+    let src = r#"import gleam/io
+pub fn main() {
+    let x = [1,2,3]
+  io.println( "Hello" )
+}
+type Wibble { Wibble(Int)   Wobble }
+"#;
+
+    assert_eq!(apply(src, &format_edits(src)), formatted(src));
+}
+
+#[test]
+fn edits_only_replace_what_changes() {
+    let src = "pub fn main() {
+  let x = [1,2]
+  x
+}
+";
+
+    assert_eq!(
+        format_edits(src),
+        vec![Edit {
+            location: SrcSpan::new(29, 29),
+            new_text: " ".into(),
+        }]
+    );
+}
+
+#[test]
+fn edits_are_separate_for_each_changed_part() {
+    let src = "pub fn one() {
+    1
+}
+
+pub fn two() {
+  2
+}
+
+pub fn three() {
+    3
+}
+";
+
+    assert_eq!(
+        format_edits(src),
+        vec![
+            Edit {
+                location: SrcSpan::new(17, 19),
+                new_text: "".into(),
+            },
+            Edit {
+                location: SrcSpan::new(65, 67),
+                new_text: "".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn edits_with_unicode() {
+    let src = r#"pub fn main() {
+  "🦀🦀"<>"é"
+}
+"#;
+
+    assert_eq!(apply(src, &format_edits(src)), formatted(src));
+}
+
+#[test]
+fn edits_changing_the_number_of_lines() {
+    let src = "pub fn main() { wibble(1, 2) |> wobble }
+
+
+
+fn wibble(a, b) { a + b }
+fn wobble(a) { a }
+";
+
+    assert_eq!(apply(src, &format_edits(src)), formatted(src));
+}
+
+#[test]
+fn edits_to_the_indentation_of_consecutive_lines_are_separate() {
+    let src = "pub fn main() {
+    1
+    2
+}
+";
+
+    assert_eq!(
+        format_edits(src),
+        vec![
+            Edit {
+                location: SrcSpan::new(18, 20),
+                new_text: "".into(),
+            },
+            Edit {
+                location: SrcSpan::new(24, 26),
+                new_text: "".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn edits_in_part_of_module_ignore_the_rest_of_it() {
+    let src = "pub fn one() {
+    1+1
+}
+
+pub fn two( {
+";
+    let edits = edits_in(
+        &EcoString::from(src),
+        Utf8Path::new("<stdin>"),
+        SrcSpan::new(0, 25),
+    )
+    .expect("valid definition");
+
+    assert_eq!(
+        apply(src, &edits),
+        "pub fn one() {
+  1 + 1
+}
+
+pub fn two( {
+"
+    );
+}
+
+#[test]
+fn clipped_edits_only_change_characters_within_the_range() {
+    let src = "pub fn main() {
+  wibble(   1,
+    2)
+}
+";
+    // The edit joining the two lines is narrowed down to removing the spaces
+    // before `1`, the only change it makes within the range.
+    let range = SrcSpan::new(16, 29);
+    let edits = clip(src, format_edits(src), range);
+
+    assert_eq!(
+        apply(src, &edits),
+        "pub fn main() {
+  wibble(1,
+    2)
+}
+"
+    );
+}
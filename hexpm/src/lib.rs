@@ -321,8 +321,7 @@ pub fn repository_v2_get_versions_body(
 ) -> Result<HashMap<String, Vec<Version>>, ApiError> {
     let signed = Signed::decode(protobuf_bytes.as_slice())?;
 
-    let payload =
-        verify_payload(signed, public_key).map_err(|_| ApiError::IncorrectPayloadSignature)?;
+    let payload = verify_payload(signed, public_key)?;
 
     let versions = Versions::decode(payload.as_slice())?
         .packages
@@ -391,8 +390,7 @@ pub fn repository_v2_package_parse_body(
 ) -> Result<Package, ApiError> {
     let signed = Signed::decode(protobuf_bytes.as_slice())?;
 
-    let payload =
-        verify_payload(signed, public_key).map_err(|_| ApiError::IncorrectPayloadSignature)?;
+    let payload = verify_payload(signed, public_key)?;
 
     let package = proto::package::Package::decode(payload.as_slice())?;
     let releases = package
@@ -744,9 +742,15 @@ pub enum ApiError {
     #[error("The given package name {0} is not valid")]
     InvalidPackageNameFormat(String),
 
-    #[error("The payload signature does not match the downloaded payload")]
+    #[error(
+        "The signature of the data downloaded from the package repository does not match \
+its public key, so it may have been tampered with"
+    )]
     IncorrectPayloadSignature,
 
+    #[error("The public key of the package repository is not a valid PEM encoded public key")]
+    InvalidPublicKey,
+
     #[error(transparent)]
     InvalidProtobuf(#[from] prost::DecodeError),
 
@@ -807,6 +811,12 @@ impl ApiError {
     }
 }
 
+/// Check that a package tarball obtained from somewhere other than the
+/// repository, such as a vendored copy, has the given sha256 digest.
+pub fn verify_package_tarball(tarball: &[u8], checksum: &[u8]) -> Result<(), ApiError> {
+    read_and_check_body(tarball, checksum).map(|_| ())
+}
+
 /// Read a body and ensure it has the given sha256 digest.
 fn read_and_check_body(reader: impl std::io::Read, checksum: &[u8]) -> Result<Vec<u8>, ApiError> {
     use std::io::Read;
@@ -1020,10 +1030,10 @@ fn validate_package_and_version(package: &str, version: &str) -> Result<(), ApiE
 // https://github.com/hexpm/specifications/blob/master/registry-v2.md#signing
 //
 fn verify_payload(mut signed: Signed, pem_public_key: &[u8]) -> Result<Vec<u8>, ApiError> {
-    let (_, pem) = x509_parser::pem::parse_x509_pem(pem_public_key)
-        .map_err(|_| ApiError::IncorrectPayloadSignature)?;
+    let (_, pem) =
+        x509_parser::pem::parse_x509_pem(pem_public_key).map_err(|_| ApiError::InvalidPublicKey)?;
     let (_, spki) = x509_parser::prelude::SubjectPublicKeyInfo::from_der(&pem.contents)
-        .map_err(|_| ApiError::IncorrectPayloadSignature)?;
+        .map_err(|_| ApiError::InvalidPublicKey)?;
     let payload = std::mem::take(&mut signed.payload);
     let verification = ring::signature::UnparsedPublicKey::new(
         &ring::signature::RSA_PKCS1_2048_8192_SHA512,
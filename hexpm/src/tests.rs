@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2026 The Gleam contributors
 
-use std::{convert::TryFrom, io::Cursor};
+use std::{
+    convert::TryFrom,
+    io::{Cursor, Write},
+};
 
 use super::*;
 use serde_json::json;
@@ -698,6 +701,157 @@ fn get_repository_versions_from_bytes_malformed() {
     assert!(versions_error.is_invalid_protobuf());
 }
 
+// The registry payloads are signed with a keypair generated for these tests,
+// so tampering can be checked for without the private key of hex.pm.
+const SIGNING_PRIVATE_KEY: &[u8] = std::include_bytes!("../test/signing_private_key");
+const SIGNING_PUBLIC_KEY: &[u8] = std::include_bytes!("../test/signing_public_key");
+
+fn sign(payload: Vec<u8>) -> Signed {
+    let key_pair = ring::signature::RsaKeyPair::from_der(SIGNING_PRIVATE_KEY).unwrap();
+    let mut signature = vec![0; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &ring::signature::RSA_PKCS1_SHA512,
+            &ring::rand::SystemRandom::new(),
+            &payload,
+            &mut signature,
+        )
+        .unwrap();
+    Signed {
+        payload,
+        signature: Some(signature),
+    }
+}
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn wibble_package_payload(version: &str) -> Vec<u8> {
+    proto::package::Package {
+        name: "wibble".into(),
+        repository: "hexpm".into(),
+        releases: vec![proto::package::Release {
+            version: version.into(),
+            inner_checksum: vec![],
+            dependencies: vec![],
+            retired: None,
+            outer_checksum: Some(vec![1, 2, 3]),
+        }],
+    }
+    .encode_to_vec()
+}
+
+fn wibble_versions_payload(version: &str) -> Vec<u8> {
+    Versions {
+        repository: "hexpm".into(),
+        packages: vec![proto::versions::VersionsPackage {
+            name: "wibble".into(),
+            versions: vec![version.into()],
+            retired: vec![],
+        }],
+    }
+    .encode_to_vec()
+}
+
+#[test]
+fn signed_package_response_ok() {
+    let body = gzip(&sign(wibble_package_payload("1.0.0")).encode_to_vec());
+    let response = make_response(200, body);
+
+    let package = crate::repository_v2_get_package_response(response, SIGNING_PUBLIC_KEY).unwrap();
+
+    assert_eq!(
+        package,
+        Package {
+            name: "wibble".into(),
+            repository: "hexpm".into(),
+            releases: vec![Release {
+                version: Version::new(1, 0, 0),
+                requirements: HashMap::new(),
+                retirement_status: None,
+                outer_checksum: vec![1, 2, 3],
+                meta: (),
+            }],
+        }
+    );
+}
+
+#[test]
+fn signed_package_tampered_payload() {
+    let mut signed = sign(wibble_package_payload("1.0.0"));
+    signed.payload = wibble_package_payload("6.6.6");
+
+    let error =
+        crate::repository_v2_package_parse_body(&signed.encode_to_vec(), SIGNING_PUBLIC_KEY)
+            .unwrap_err();
+
+    assert!(matches!(error, ApiError::IncorrectPayloadSignature));
+}
+
+#[test]
+fn signed_package_missing_signature() {
+    let mut signed = sign(wibble_package_payload("1.0.0"));
+    signed.signature = None;
+
+    let error =
+        crate::repository_v2_package_parse_body(&signed.encode_to_vec(), SIGNING_PUBLIC_KEY)
+            .unwrap_err();
+
+    assert!(matches!(error, ApiError::IncorrectPayloadSignature));
+}
+
+#[test]
+fn signed_package_other_public_key() {
+    let signed = sign(wibble_package_payload("1.0.0"));
+
+    let error = crate::repository_v2_package_parse_body(
+        &signed.encode_to_vec(),
+        std::include_bytes!("../test/public_key"),
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, ApiError::IncorrectPayloadSignature));
+}
+
+#[test]
+fn signed_package_invalid_public_key() {
+    let signed = sign(wibble_package_payload("1.0.0"));
+
+    let error =
+        crate::repository_v2_package_parse_body(&signed.encode_to_vec(), b"not a public key")
+            .unwrap_err();
+
+    assert!(matches!(error, ApiError::InvalidPublicKey));
+}
+
+#[test]
+fn signed_versions_response_ok() {
+    let body = gzip(&sign(wibble_versions_payload("1.0.0")).encode_to_vec());
+    let response = make_response(200, body);
+
+    let versions =
+        crate::repository_v2_get_versions_response(response, SIGNING_PUBLIC_KEY).unwrap();
+
+    assert_eq!(
+        versions,
+        [("wibble".into(), vec![Version::new(1, 0, 0)])].into()
+    );
+}
+
+#[test]
+fn signed_versions_tampered_payload() {
+    let mut signed = sign(wibble_versions_payload("1.0.0"));
+    signed.payload = wibble_versions_payload("6.6.6");
+
+    let error = crate::repository_v2_get_versions_body(&signed.encode_to_vec(), SIGNING_PUBLIC_KEY)
+        .unwrap_err();
+
+    assert!(matches!(error, ApiError::IncorrectPayloadSignature));
+}
+
 #[test]
 fn get_repository_tarball_request() {
     let config = Config::new();
@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use gleam_core::{
+    ast::SrcSpan,
+    build::Module,
+    reference::ReferenceKind,
+    type_::{ModuleInterface, ValueConstructorVariant},
+};
+use itertools::Itertools;
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Range, SymbolKind,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compiler::ModuleSourceInformation, engine::make_deprecated_symbol_tag, src_span_to_lsp_range,
+    url_from_path,
+};
+
+/// The information stored in the `data` field of a call hierarchy item, so
+/// that we know which function it refers to when the client asks for its
+/// incoming or outgoing calls.
+///
+#[derive(Debug, Serialize, Deserialize)]
+struct ItemData {
+    module: EcoString,
+    name: EcoString,
+}
+
+/// Builds call hierarchies out of the references tracked by the compiler for
+/// each module, so calls can be followed across the whole project.
+///
+/// Any reference to a function counts as a call: this includes functions used
+/// in pipelines, captured with `function(_, 1)`, or passed as arguments to
+/// other functions.
+///
+pub struct CallHierarchy<'a> {
+    pub modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    pub sources: &'a HashMap<EcoString, ModuleSourceInformation>,
+    /// The compiled modules of the root package, these are the only ones we
+    /// have the AST for.
+    pub compiled: &'a HashMap<EcoString, Module>,
+}
+
+impl CallHierarchy<'_> {
+    /// Returns the item for the top level function with the given name, or
+    /// `None` if there's no such function.
+    ///
+    pub fn item(&self, module_name: &EcoString, name: &EcoString) -> Option<CallHierarchyItem> {
+        let module = self.modules.get(module_name)?;
+        let value = module.values.get(name)?;
+        let ValueConstructorVariant::ModuleFn {
+            location,
+            module: defining_module,
+            ..
+        } = &value.variant
+        else {
+            return None;
+        };
+        // Functions imported in the module are not defined there.
+        if defining_module != module_name {
+            return None;
+        }
+
+        let source = self.sources.get(module_name)?;
+        let uri = url_from_path(source.path.as_str())?;
+
+        // If we have the function's AST we can point to its entire definition,
+        // otherwise we only know where its head is.
+        let function = self.compiled.get(module_name).and_then(|module| {
+            module.ast.definitions.functions.iter().find(|function| {
+                matches!(&function.name, Some((_, function_name)) if function_name == name)
+            })
+        });
+        let (range, selection_range) = match function {
+            Some(function) => {
+                let name_location = function.name.as_ref().map(|(location, _)| *location);
+                (
+                    function.full_location(),
+                    name_location.unwrap_or(function.location),
+                )
+            }
+            None => (*location, *location),
+        };
+
+        let data = ItemData {
+            module: module_name.clone(),
+            name: name.clone(),
+        };
+
+        Some(CallHierarchyItem {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            tags: make_deprecated_symbol_tag(&value.deprecation),
+            detail: Some(module_name.to_string()),
+            uri,
+            range: src_span_to_lsp_range(range, &source.line_numbers),
+            selection_range: src_span_to_lsp_range(selection_range, &source.line_numbers),
+            data: serde_json::to_value(data).ok(),
+        })
+    }
+
+    /// Finds all the functions referencing the function of the given item,
+    /// along with the ranges where they reference it.
+    ///
+    pub fn incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        let Some(ItemData { module, name }) = item_data(item) else {
+            return vec![];
+        };
+
+        let mut calls = vec![];
+        for caller_module in self.modules.values() {
+            if caller_module.name != module
+                && !caller_module.references.imported_modules.contains(&module)
+            {
+                continue;
+            }
+            let Some(references) = caller_module
+                .references
+                .value_references
+                .get(&(module.clone(), name.clone()))
+            else {
+                continue;
+            };
+            let Some(source) = self.sources.get(&caller_module.name) else {
+                continue;
+            };
+
+            let definitions = ValueDefinitions::new(caller_module);
+            let callers = references
+                .iter()
+                .filter(|reference| is_call(&reference.kind))
+                .filter_map(|reference| {
+                    let caller = definitions.enclosing_function(reference.location)?;
+                    Some((caller, reference.location))
+                })
+                .into_group_map();
+
+            for (caller, locations) in callers {
+                let Some(from) = self.item(&caller_module.name, caller) else {
+                    continue;
+                };
+                calls.push(CallHierarchyIncomingCall {
+                    from,
+                    from_ranges: lsp_ranges(locations, source),
+                });
+            }
+        }
+
+        calls.sort_by(|one, other| {
+            (&one.from.uri, start(&one.from.range))
+                .cmp(&(&other.from.uri, start(&other.from.range)))
+        });
+        calls
+    }
+
+    /// Finds all the functions referenced by the function of the given item,
+    /// along with the ranges where it references them.
+    ///
+    pub fn outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        let Some(ItemData { module, name }) = item_data(item) else {
+            return vec![];
+        };
+        let Some(caller_module) = self.modules.get(&module) else {
+            return vec![];
+        };
+        let Some(source) = self.sources.get(&module) else {
+            return vec![];
+        };
+
+        let definitions = ValueDefinitions::new(caller_module);
+        let mut calls = vec![];
+        for ((called_module, called_name), references) in &caller_module.references.value_references
+        {
+            let locations = references
+                .iter()
+                .filter(|reference| is_call(&reference.kind))
+                .map(|reference| reference.location)
+                .filter(|location| definitions.enclosing_function(*location) == Some(&name))
+                .collect_vec();
+            if locations.is_empty() {
+                continue;
+            }
+            // Record constructors and constants are referenced too, but those
+            // are not functions so no item can be built for them.
+            let Some(to) = self.item(called_module, called_name) else {
+                continue;
+            };
+            calls.push(CallHierarchyOutgoingCall {
+                to,
+                from_ranges: lsp_ranges(locations, source),
+            });
+        }
+
+        calls.sort_by_key(|call| call.from_ranges.first().map(start));
+        calls
+    }
+}
+
+fn item_data(item: &CallHierarchyItem) -> Option<ItemData> {
+    serde_json::from_value(item.data.clone()?).ok()
+}
+
+/// Imports and the name of a definition are also tracked as references, but
+/// they can't call a function.
+///
+fn is_call(kind: &ReferenceKind) -> bool {
+    match kind {
+        ReferenceKind::Qualified { .. } | ReferenceKind::Unqualified | ReferenceKind::Alias => true,
+        ReferenceKind::Import(_) | ReferenceKind::Definition => false,
+    }
+}
+
+fn lsp_ranges(mut locations: Vec<SrcSpan>, source: &ModuleSourceInformation) -> Vec<Range> {
+    locations.sort_by_key(|location| location.start);
+    locations
+        .into_iter()
+        .map(|location| src_span_to_lsp_range(location, &source.line_numbers))
+        .collect()
+}
+
+fn start(range: &Range) -> (u32, u32) {
+    (range.start.line, range.start.character)
+}
+
+/// The top level values defined in a module, sorted by where they start.
+///
+struct ValueDefinitions<'a> {
+    definitions: Vec<(u32, &'a EcoString, bool)>,
+}
+
+impl<'a> ValueDefinitions<'a> {
+    fn new(module: &'a ModuleInterface) -> Self {
+        let definitions = module
+            .values
+            .iter()
+            .filter_map(|(name, value)| {
+                let (location, defining_module, is_function) = match &value.variant {
+                    ValueConstructorVariant::ModuleFn {
+                        location, module, ..
+                    } => (location, module, true),
+                    ValueConstructorVariant::ModuleConstant {
+                        location, module, ..
+                    }
+                    | ValueConstructorVariant::Record {
+                        location, module, ..
+                    } => (location, module, false),
+                    ValueConstructorVariant::LocalVariable { .. } => return None,
+                };
+                (*defining_module == module.name).then_some((location.start, name, is_function))
+            })
+            .sorted()
+            .collect();
+        Self { definitions }
+    }
+
+    /// Top level definitions can't be nested, so the definition a reference
+    /// appears in is the last one starting before it. Returns its name if that
+    /// is a function.
+    ///
+    fn enclosing_function(&self, location: SrcSpan) -> Option<&'a EcoString> {
+        let index = self
+            .definitions
+            .partition_point(|(start, _, _)| *start <= location.start);
+        let (_, name, is_function) = self.definitions.get(index.checked_sub(1)?)?;
+        is_function.then_some(*name)
+    }
+}
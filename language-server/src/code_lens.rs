@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use ecow::EcoString;
+use gleam_core::{
+    ast::TypedFunction,
+    build::{Module, Origin, Target},
+    line_numbers::LineNumbers,
+    reference::ReferenceKind,
+    type_::ModuleInterface,
+};
+use itertools::Itertools;
+use lsp_types::{CodeLens, Command, Uri as Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{Runnable, src_span_to_lsp_range};
+
+/// The command run by the "Run" code lenses. Its only argument is a
+/// [`RunArguments`].
+///
+pub const RUN_COMMAND: &str = "gleam.run";
+
+/// The arguments of the [`RUN_COMMAND`]. The path of the module the lens is in
+/// is used to find the project it belongs to.
+///
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunArguments {
+    pub uri: Url,
+    pub runnable: Runnable,
+}
+
+/// The information stored in the `data` field of a reference count lens, so
+/// that we know which function to count the references of when the client
+/// asks to resolve it.
+///
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReferencesData {
+    pub uri: Url,
+    module: EcoString,
+    name: EcoString,
+}
+
+impl ReferencesData {
+    pub fn from_lens(lens: &CodeLens) -> Option<Self> {
+        serde_json::from_value(lens.data.clone()?).ok()
+    }
+}
+
+/// Returns the code lenses for all the top level functions of a module:
+/// - `pub fn main()` can be run, both on the project's target and on the other
+///   one if the function supports it.
+/// - Public `_test` functions in the `test` directory can be run as tests.
+/// - Each public function shows how many times it is referenced. Counting
+///   references can be slow so this is left unresolved until the client asks
+///   for it.
+///
+pub fn code_lenses(module: &Module, uri: &Url, project_target: Target) -> Vec<CodeLens> {
+    let line_numbers = LineNumbers::new(&module.code);
+    let mut lenses = vec![];
+
+    // Functions are stored in the order they were analysed in, so they're
+    // sorted to return the lenses in the order they appear in the module.
+    let functions = module
+        .ast
+        .definitions
+        .functions
+        .iter()
+        .sorted_by_key(|function| function.location.start);
+
+    for function in functions {
+        let Some((_, name)) = &function.name else {
+            continue;
+        };
+        if !function.publicity.is_importable() {
+            continue;
+        }
+        let range = src_span_to_lsp_range(function.location, &line_numbers);
+        let run_lens = |title: &str, runnable: Runnable| {
+            let arguments = RunArguments {
+                uri: uri.clone(),
+                runnable,
+            };
+            let command = Command {
+                title: title.into(),
+                tooltip: None,
+                command: RUN_COMMAND.into(),
+                arguments: Some(vec![
+                    serde_json::to_value(arguments).expect("run arguments to json"),
+                ]),
+            };
+            CodeLens {
+                range,
+                command: Some(command),
+                data: None,
+            }
+        };
+
+        if is_main_function(function) {
+            lenses.push(run_lens(
+                "Run",
+                Runnable::Main {
+                    module: module.name.clone(),
+                    target: None,
+                },
+            ));
+
+            let other_target = match project_target {
+                Target::Erlang => Target::JavaScript,
+                Target::JavaScript => Target::Erlang,
+            };
+            if function.implementations.supports(other_target) {
+                let title = match other_target {
+                    Target::Erlang => "Run on Erlang",
+                    Target::JavaScript => "Run on JavaScript",
+                };
+                lenses.push(run_lens(
+                    title,
+                    Runnable::Main {
+                        module: module.name.clone(),
+                        target: Some(other_target),
+                    },
+                ));
+            }
+        }
+
+        if module.origin == Origin::Test && is_test_function(function) {
+            lenses.push(run_lens(
+                "Run test",
+                Runnable::Test {
+                    module: module.name.clone(),
+                    function: name.clone(),
+                    target: None,
+                },
+            ));
+        }
+
+        let data = ReferencesData {
+            uri: uri.clone(),
+            module: module.name.clone(),
+            name: name.clone(),
+        };
+        lenses.push(CodeLens {
+            range,
+            command: None,
+            data: Some(serde_json::to_value(data).expect("references data to json")),
+        });
+    }
+
+    lenses
+}
+
+fn is_main_function(function: &TypedFunction) -> bool {
+    matches!(&function.name, Some((_, name)) if name == "main") && function.arguments.is_empty()
+}
+
+fn is_test_function(function: &TypedFunction) -> bool {
+    matches!(&function.name, Some((_, name)) if name.ends_with("_test"))
+        && function.arguments.is_empty()
+}
+
+/// Fills in the title of a reference count lens, counting the references to
+/// its function across all the modules of the project.
+///
+pub fn resolve_code_lens(
+    mut lens: CodeLens,
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+) -> CodeLens {
+    let Some(data) = ReferencesData::from_lens(&lens) else {
+        return lens;
+    };
+
+    let key = (data.module, data.name);
+    let count: usize = modules
+        .values()
+        .filter_map(|module| module.references.value_references.get(&key))
+        .flatten()
+        .filter(|reference| reference.kind != ReferenceKind::Definition)
+        .count();
+
+    let title = match count {
+        1 => "1 reference".into(),
+        count => format!("{count} references"),
+    };
+    // This lens is just informative, so there's no command to run when it is
+    // clicked.
+    lens.command = Some(Command {
+        title,
+        tooltip: None,
+        command: "".into(),
+        arguments: None,
+    });
+    lens
+}
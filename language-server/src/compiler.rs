@@ -8,7 +8,7 @@ use itertools::Itertools;
 use gleam_core::{
     Error, Result, Warning,
     analyse::TargetSupport,
-    build::{self, Mode, Module, NullTelemetry, Outcome, ProjectCompiler},
+    build::{self, Mode, Module, NullTelemetry, Outcome, Package, ProjectCompiler},
     config::PackageConfig,
     io::{BeamCompilerIO, CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
     line_numbers::LineNumbers,
@@ -99,29 +99,25 @@ where
         })
     }
 
-    pub fn compile(&mut self) -> Outcome<Vec<Utf8PathBuf>, Error> {
+    /// Starts compiling the project, compiling any changed dependencies
+    /// straight away. The root package is compiled by the returned
+    /// compilation, and in the meantime this compiler keeps a copy of the
+    /// project compiler as it was, so that the project's last analysis can
+    /// still be used to answer requests.
+    ///
+    pub fn start_compilation(&mut self) -> Result<RootPackageCompilation<IO>, Error> {
         // Lock the build directory to ensure to ensure we are the only one compiling
-        let _lock_guard: LockGuard = match self.locker.lock_for_build() {
-            Ok(it) => it,
-            Err(err) => return err.into(),
-        };
+        let lock_guard: LockGuard = self.locker.lock_for_build()?;
 
         // Verify that the build directory was created using the same version of
         // Gleam as we are running. If it is not then we discard the build
         // directory as the cache files may be in a different format.
-        if let Err(e) = self
-            .project_compiler
-            .check_gleam_version_and_build_configuration()
-        {
-            return e.into();
-        }
+        self.project_compiler
+            .check_gleam_version_and_build_configuration()?;
 
         self.project_compiler.reset_state_for_new_compile_run();
 
-        let compiled_dependencies = match self.project_compiler.compile_dependencies() {
-            Ok(it) => it,
-            Err(err) => return err.into(),
-        };
+        let compiled_dependencies = self.project_compiler.compile_dependencies()?;
 
         // Store the compiled dependency module information
         for module in &compiled_dependencies {
@@ -162,20 +158,38 @@ where
         // we don't bother them with diagnostics for them.
         let _ = self.take_warnings();
 
-        // Compile the root package, that is, the one that the programmer is
-        // working in.
-        let (modules, error) = match self.project_compiler.compile_root_package() {
+        let last_analysis = self.project_compiler.clone();
+        Ok(RootPackageCompilation {
+            project_compiler: std::mem::replace(&mut self.project_compiler, last_analysis),
+            compiled_dependencies: compiled_dependencies
+                .into_iter()
+                .map(|m| m.input_path)
+                .collect_vec(),
+            _lock_guard: lock_guard,
+        })
+    }
+
+    /// Registers the outcome of compiling the root package, returning the
+    /// paths of the modules that have been compiled.
+    ///
+    pub fn finish_compilation(
+        &mut self,
+        compilation: RootPackageCompilation<IO>,
+        outcome: Outcome<Package, Error>,
+    ) -> Outcome<Vec<Utf8PathBuf>, Error> {
+        let RootPackageCompilation {
+            project_compiler,
+            compiled_dependencies: mut compiled_modules,
+            _lock_guard,
+        } = compilation;
+        self.project_compiler = project_compiler;
+
+        let (modules, error) = match outcome {
             Outcome::Ok(package) => (package.modules, None),
             Outcome::PartialFailure(package, error) => (package.modules, Some(error)),
             Outcome::TotalFailure(error) => (vec![], Some(error)),
         };
 
-        // Record the compiled dependency modules
-        let mut compiled_modules = compiled_dependencies
-            .into_iter()
-            .map(|m| m.input_path)
-            .collect_vec();
-
         // Store the compiled module information
         for module in modules {
             let path = module.input_path.as_os_str().to_string_lossy().to_string();
@@ -196,6 +210,33 @@ where
     }
 }
 
+/// A compilation of the root package of a project, started with
+/// `LspProjectCompiler::start_compilation`.
+///
+#[derive(Debug)]
+pub struct RootPackageCompilation<IO> {
+    project_compiler: ProjectCompiler<IO>,
+    compiled_dependencies: Vec<Utf8PathBuf>,
+    _lock_guard: LockGuard,
+}
+
+impl<IO> RootPackageCompilation<IO>
+where
+    IO: CommandExecutor + FileSystemWriter + FileSystemReader + BeamCompilerIO + Clone,
+{
+    /// Compiles the root package, that is, the one that the programmer is
+    /// working in. `checkpoint` is called before each of its modules is
+    /// analysed.
+    ///
+    pub fn compile_root_package(
+        &mut self,
+        checkpoint: &mut dyn FnMut(),
+    ) -> Outcome<Package, Error> {
+        self.project_compiler
+            .compile_root_package_with_checkpoint(checkpoint)
+    }
+}
+
 impl<IO> LspProjectCompiler<IO> {
     pub fn take_warnings(&mut self) -> Vec<Warning> {
         self.warnings.take()
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use serde::Deserialize;
+
+/// The configuration of the language server, as sent by the client in the
+/// `initializationOptions` of the `initialize` request. For example:
+///
+/// ```json
+/// {
+///   "inlayHints": {
+///     "variableTypes": true,
+///     "pipelines": true,
+///     "parameterLabels": false
+///   }
+/// }
+/// ```
+///
+/// Any missing option takes its default value, and invalid options are
+/// ignored rather than preventing the language server from starting.
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Configuration {
+    pub inlay_hints: InlayHintsConfig,
+}
+
+impl Configuration {
+    pub fn from_initialization_options(options: Option<&serde_json::Value>) -> Self {
+        let Some(options) = options else {
+            return Self::default();
+        };
+
+        match Self::deserialize(options) {
+            Ok(configuration) => configuration,
+            Err(error) => {
+                tracing::warn!(%error, "invalid_language_server_configuration");
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Which categories of inlay hints the language server should show.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InlayHintsConfig {
+    /// The inferred type of `let` and `use` bindings without an annotation.
+    pub variable_types: bool,
+    /// The type of each step of a pipeline spanning multiple lines.
+    pub pipelines: bool,
+    /// The labels of arguments passed positionally to a function or record
+    /// constructor.
+    pub parameter_labels: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            variable_types: true,
+            pipelines: true,
+            parameter_labels: true,
+        }
+    }
+}
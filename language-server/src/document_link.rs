@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Document links make parts of a module clickable in the editor: the name of
+//! each imported module opens its source, the path of a JavaScript file used
+//! by an `@external` opens that file, and `[name](url)` links in doc comments
+//! open their URL.
+
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use gleam_core::{ast::SrcSpan, build::Module, io::FileSystemReader, line_numbers::LineNumbers};
+use lsp_types::{DocumentLink, Uri as Url};
+
+use crate::{
+    compiler::ModuleSourceInformation, implementation::javascript_module_path,
+    src_span_to_lsp_range, url_from_path,
+};
+
+pub fn document_links(
+    io: &impl FileSystemReader,
+    module: &Module,
+    sources: &HashMap<EcoString, ModuleSourceInformation>,
+) -> Vec<DocumentLink> {
+    let line_numbers = LineNumbers::new(&module.code);
+    let mut links = vec![];
+
+    for import in &module.ast.definitions.imports {
+        let Some(target) = sources
+            .get(&import.module)
+            .and_then(|source| url_from_path(source.path.as_str()))
+        else {
+            continue;
+        };
+        links.push(link(import.module_location, target, &line_numbers));
+    }
+
+    let definitions = &module.ast.definitions;
+    let function_externals = definitions
+        .functions
+        .iter()
+        .filter_map(|function| function.external_javascript.as_ref());
+    let type_externals = definitions
+        .custom_types
+        .iter()
+        .filter_map(|custom_type| custom_type.external_javascript.as_ref());
+    for (javascript_module, _, attribute_location) in function_externals.chain(type_externals) {
+        let Some(target) = javascript_module_path(&module.input_path, javascript_module)
+            .filter(|path| io.is_file(path))
+            .and_then(|path| url_from_path(path.as_str()))
+        else {
+            continue;
+        };
+        let Some(location) = string_location(&module.code, *attribute_location, javascript_module)
+        else {
+            continue;
+        };
+        links.push(link(location, target, &line_numbers));
+    }
+
+    for comment in module
+        .extra
+        .doc_comments
+        .iter()
+        .chain(&module.extra.module_comments)
+    {
+        for (location, target) in markdown_links(&module.code, *comment) {
+            links.push(link(location, target, &line_numbers));
+        }
+    }
+
+    links.sort_by_key(|link| (link.range.start.line, link.range.start.character));
+    links
+}
+
+fn link(location: SrcSpan, target: Url, line_numbers: &LineNumbers) -> DocumentLink {
+    DocumentLink {
+        range: src_span_to_lsp_range(location, line_numbers),
+        target: Some(target),
+        tooltip: None,
+        data: None,
+    }
+}
+
+/// The location of the contents of the string literal `"value"` inside the
+/// given span of the code, without its quotes.
+fn string_location(code: &str, span: SrcSpan, value: &EcoString) -> Option<SrcSpan> {
+    let text = code.get(span.start as usize..span.end as usize)?;
+    let offset = text.find(&format!("\"{value}\""))? as u32 + 1;
+    let start = span.start + offset;
+    Some(SrcSpan::new(start, start + value.len() as u32))
+}
+
+/// The `[name](url)` links in a comment, with the location of the whole
+/// link. Only absolute URLs are linked, as there's nothing a relative one
+/// could point to.
+fn markdown_links(code: &str, comment: SrcSpan) -> Vec<(SrcSpan, Url)> {
+    let Some(text) = code.get(comment.start as usize..comment.end as usize) else {
+        return vec![];
+    };
+
+    let mut links = vec![];
+    let mut rest_start = 0;
+    while let Some(separator) = text[rest_start..].find("](") {
+        let separator = rest_start + separator;
+        rest_start = separator + 2;
+
+        let Some(open) = text[..separator].rfind('[') else {
+            continue;
+        };
+        let url_start = separator + 2;
+        let Some(url_length) = text[url_start..].find(')') else {
+            break;
+        };
+        let url_end = url_start + url_length;
+        let Ok(target) = Url::parse(&text[url_start..url_end]) else {
+            continue;
+        };
+
+        rest_start = url_end + 1;
+        let start = comment.start + open as u32;
+        let end = comment.start + rest_start as u32;
+        links.push((SrcSpan::new(start, end), target));
+    }
+    links
+}
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2023 The Gleam contributors
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use ecow::{EcoString, eco_format};
 use gleam_core::{
     Error, Result, Warning,
@@ -49,6 +49,7 @@ use crate::{
 
 use super::{
     DownloadDependencies, MakeLocker,
+    call_hierarchy::CallHierarchy,
     code_action::{
         AddAnnotations, AddMissingTypeParameter, AddOmittedLabels, AnnotateTopLevelDefinitions,
         CodeActionBuilder, CollapseNestedCase, ConvertFromUse, ConvertToFunctionCall,
@@ -63,9 +64,14 @@ use super::{
         code_action_convert_unqualified_constructor_to_qualified, code_action_generate_type,
         code_action_import_module, code_action_inexhaustive_let_to_case,
     },
+    code_lens,
     compiler::LspProjectCompiler,
     completer::Completer,
+    configuration::InlayHintsConfig,
+    document_link,
     files::FileSystemProxy,
+    implementation::{ExternalFunction, find_implementations},
+    inlay_hints, linked_editing, lsp_range_to_src_span,
     progress::ProgressReporter,
     reference::{
         FindVariableReferences, Referenced, VariableReferenceKind, find_label_references,
@@ -75,7 +81,8 @@ use super::{
         RenameOutcome, RenameTarget, Renamed, rename_label, rename_local_variable,
         rename_module_entity,
     },
-    signature_help, src_span_to_lsp_range,
+    selection_range, semantic_tokens, signature_help, src_span_to_lsp_range, url_from_path,
+    workspace_symbol::{self, ScoredSymbol},
 };
 
 #[derive(Debug, PartialEq, Eq)]
@@ -107,6 +114,10 @@ pub struct LanguageServerEngine<IO, Reporter> {
     compiled_since_last_feedback: bool,
     error: Option<Error>,
 
+    /// Whether the root package is being compiled. Warnings are only taken
+    /// once it has finished, rather than by requests answered in the meantime.
+    compiling: bool,
+
     // Used to publish progress notifications to the client without waiting for
     // the usual request-response loop.
     progress_reporter: Reporter,
@@ -166,6 +177,7 @@ where
         Ok(Self {
             modules_compiled_since_last_feedback: vec![],
             compiled_since_last_feedback: false,
+            compiling: false,
             progress_reporter,
             compiler,
             paths,
@@ -174,18 +186,41 @@ where
         })
     }
 
+    #[cfg(test)]
     pub fn compile_please(&mut self) -> Response<()> {
-        self.respond(Self::compile)
+        self.compile_please_with_checkpoint(&mut |_| ())
     }
 
-    /// Compile the project if we are in one. Otherwise do nothing.
-    fn compile(&mut self) -> Result<(), Error> {
-        self.compiled_since_last_feedback = true;
+    /// Like `compile_please`, but `checkpoint` is called with the engine
+    /// before each module of the project is analysed. Until the compilation
+    /// finishes the engine answers requests using the last analysis of the
+    /// project.
+    ///
+    pub fn compile_please_with_checkpoint(
+        &mut self,
+        checkpoint: &mut dyn FnMut(&mut Self),
+    ) -> Response<()> {
+        self.respond(|this| this.compile(checkpoint))
+    }
 
+    /// Compile the project if we are in one. Otherwise do nothing.
+    fn compile(&mut self, checkpoint: &mut dyn FnMut(&mut Self)) -> Result<(), Error> {
         self.progress_reporter.compilation_started();
-        let outcome = self.compiler.compile();
+        let outcome = match self.compiler.start_compilation() {
+            Ok(mut compilation) => {
+                self.compiling = true;
+                let outcome = compilation.compile_root_package(&mut || checkpoint(self));
+                self.compiling = false;
+                self.compiler.finish_compilation(compilation, outcome)
+            }
+            Err(error) => error.into(),
+        };
         self.progress_reporter.compilation_finished();
 
+        // This is only set now so requests answered while compiling don't
+        // report the compilation as their own.
+        self.compiled_since_last_feedback = true;
+
         let result = outcome
             // Register which modules have changed
             .map(|modules| self.modules_compiled_since_last_feedback.extend(modules))
@@ -253,6 +288,43 @@ where
         })
     }
 
+    pub(crate) fn goto_implementation(
+        &mut self,
+        params: lsp::ImplementationParams,
+    ) -> Response<Vec<lsp::Location>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let Some((_, node)) = this.module_node_at_position(&params, module) else {
+                return Ok(vec![]);
+            };
+            let importable_modules = this.compiler.project_compiler.get_importable_modules();
+            let Some(function) =
+                ExternalFunction::from_located(&node, &module.name, importable_modules)
+            else {
+                return Ok(vec![]);
+            };
+            let Some(source) = this.compiler.get_source(&function.module) else {
+                return Ok(vec![]);
+            };
+
+            let io = &this.compiler.project_compiler.io;
+            let locations = find_implementations(io, &function, Utf8Path::new(&source.path))
+                .into_iter()
+                .filter_map(|implementation| {
+                    let uri = url_from_path(implementation.path.as_str())?;
+                    let line_numbers = LineNumbers::new(&implementation.src);
+                    let range = src_span_to_lsp_range(implementation.span, &line_numbers);
+                    Some(lsp::Location { uri, range })
+                })
+                .collect_vec();
+
+            Ok(locations)
+        })
+    }
+
     fn definition_location_to_lsp_location(
         &self,
         line_numbers: &LineNumbers,
@@ -716,6 +788,218 @@ where
         })
     }
 
+    pub fn workspace_symbol(
+        &mut self,
+        params: &lsp::WorkspaceSymbolParams,
+    ) -> Response<Vec<ScoredSymbol>> {
+        self.respond(|this| {
+            let root_package = &this.compiler.project_compiler.config.name;
+            let mut symbols = vec![];
+
+            for (name, module) in this.compiler.project_compiler.get_importable_modules() {
+                // The prelude has no source code we could point to.
+                let Some(source) = this.compiler.get_source(name) else {
+                    continue;
+                };
+                let include_private = module.package == *root_package;
+                symbols.extend(workspace_symbol::module_symbols(
+                    module,
+                    source,
+                    include_private,
+                    &params.query,
+                ));
+            }
+
+            Ok(symbols)
+        })
+    }
+
+    pub fn semantic_tokens_full(
+        &mut self,
+        params: lsp::SemanticTokensParams,
+    ) -> Response<Option<lsp::SemanticTokens>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            let tokens =
+                semantic_tokens::semantic_tokens(&module.ast, &line_numbers, modules, None);
+            Ok(Some(tokens))
+        })
+    }
+
+    pub fn semantic_tokens_range(
+        &mut self,
+        params: lsp::SemanticTokensRangeParams,
+    ) -> Response<Option<lsp::SemanticTokens>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let range = lsp_range_to_src_span(params.range, &line_numbers);
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            let tokens =
+                semantic_tokens::semantic_tokens(&module.ast, &line_numbers, modules, Some(range));
+            Ok(Some(tokens))
+        })
+    }
+
+    pub fn inlay_hints(
+        &mut self,
+        params: lsp::InlayHintParams,
+        config: InlayHintsConfig,
+    ) -> Response<Vec<lsp::InlayHint>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let range = lsp_range_to_src_span(params.range, &line_numbers);
+            Ok(inlay_hints::inlay_hints(
+                &module.ast,
+                &line_numbers,
+                config,
+                range,
+            ))
+        })
+    }
+
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyItem>>> {
+        self.respond(|this| {
+            let position = &params.text_document_position_params;
+            let Some((lines, found)) = this.node_at_position(position) else {
+                return Ok(None);
+            };
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let byte_index = lines.byte_index(position.position);
+
+            let Some(Referenced::ModuleValue {
+                module,
+                name,
+                location,
+                ..
+            }) = reference_for_ast_node(found, &module.name)
+            else {
+                return Ok(None);
+            };
+            if !location.contains(byte_index) {
+                return Ok(None);
+            }
+
+            Ok(this
+                .call_hierarchy()
+                .item(&module, &name)
+                .map(|item| vec![item]))
+        })
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyIncomingCall>>> {
+        self.respond(|this| Ok(Some(this.call_hierarchy().incoming_calls(&params.item))))
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyOutgoingCall>>> {
+        self.respond(|this| Ok(Some(this.call_hierarchy().outgoing_calls(&params.item))))
+    }
+
+    fn call_hierarchy(&self) -> CallHierarchy<'_> {
+        CallHierarchy {
+            modules: self.compiler.project_compiler.get_importable_modules(),
+            sources: &self.compiler.sources,
+            compiled: &self.compiler.modules,
+        }
+    }
+
+    pub fn code_lens(&mut self, params: lsp::CodeLensParams) -> Response<Vec<lsp::CodeLens>> {
+        self.respond(|this| {
+            let uri = &params.text_document.uri;
+            let Some(module) = this.module_for_uri(uri) else {
+                return Ok(vec![]);
+            };
+            let target = this.compiler.project_compiler.config.target;
+            Ok(code_lens::code_lenses(module, uri, target))
+        })
+    }
+
+    pub fn resolve_code_lens(&mut self, lens: lsp::CodeLens) -> Response<lsp::CodeLens> {
+        self.respond(|this| {
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            Ok(code_lens::resolve_code_lens(lens, modules))
+        })
+    }
+
+    pub fn document_link(
+        &mut self,
+        params: lsp::DocumentLinkParams,
+    ) -> Response<Vec<lsp::DocumentLink>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let io = &this.compiler.project_compiler.io;
+            let sources = &this.compiler.sources;
+            Ok(document_link::document_links(io, module, sources))
+        })
+    }
+
+    pub fn selection_range(
+        &mut self,
+        params: lsp::SelectionRangeParams,
+    ) -> Response<Vec<lsp::SelectionRange>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            Ok(params
+                .positions
+                .iter()
+                .map(|position| {
+                    selection_range::selection_range(&module.ast, &line_numbers, *position)
+                })
+                .collect())
+        })
+    }
+
+    pub fn linked_editing_range(
+        &mut self,
+        params: lsp::LinkedEditingRangeParams,
+    ) -> Response<Option<lsp::LinkedEditingRanges>> {
+        self.respond(|this| {
+            let Some((lines, found)) = this.node_at_position(&params.text_document_position_params)
+            else {
+                return Ok(None);
+            };
+            let Some(module) =
+                this.module_for_uri(&params.text_document_position_params.text_document.uri)
+            else {
+                return Ok(None);
+            };
+            let Some(referenced) = reference_for_ast_node(found, &module.name) else {
+                return Ok(None);
+            };
+
+            let byte_index = lines.byte_index(params.text_document_position_params.position);
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            Ok(linked_editing::linked_editing_ranges(
+                module, modules, &lines, referenced, byte_index,
+            ))
+        })
+    }
+
     pub fn folding_range(
         &mut self,
         params: lsp::FoldingRangeParams,
@@ -1250,7 +1534,11 @@ where
 
     fn respond<T>(&mut self, handler: impl FnOnce(&mut Self) -> Result<T>) -> Response<T> {
         let result = handler(self);
-        let warnings = self.take_warnings();
+        let warnings = if self.compiling {
+            vec![]
+        } else {
+            self.take_warnings()
+        };
         // TODO: test. Ensure hover doesn't report as compiled
         let compilation = if self.compiled_since_last_feedback {
             let modules = std::mem::take(&mut self.modules_compiled_since_last_feedback);
@@ -1520,6 +1808,21 @@ Unused labelled fields:
         self.compiler.modules.get(&module_name)
     }
 
+    /// Whether the module at this URI was last analysed with its current
+    /// code, so that requests about it can be answered without waiting for
+    /// the project to be compiled.
+    ///
+    pub fn has_current_analysis(&self, uri: &Url) -> bool {
+        let Some(module) = self.module_for_uri(uri) else {
+            return false;
+        };
+        self.compiler
+            .project_compiler
+            .io
+            .read(&module.input_path)
+            .is_ok_and(|code| code == module.code)
+    }
+
     #[cfg(test)]
     pub fn path_for_module_name(&self, module_name: &str) -> Utf8PathBuf {
         let src_directory = self.paths.src_directory();
@@ -2406,7 +2709,7 @@ fn get_doc_marker_position(content_pos: u32) -> u32 {
     content_pos.saturating_sub(3)
 }
 
-fn make_deprecated_symbol_tag(deprecation: &Deprecation) -> Option<Vec<SymbolTag>> {
+pub(crate) fn make_deprecated_symbol_tag(deprecation: &Deprecation) -> Option<Vec<SymbolTag>> {
     deprecation
         .is_deprecated()
         .then(|| vec![SymbolTag::Deprecated])
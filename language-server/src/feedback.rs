@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2023 The Gleam contributors
 
-use gleam_core::{Error, Warning, diagnostic::Diagnostic};
+use gleam_core::{Error, Warning, build::SourceFingerprint, diagnostic::Diagnostic};
 use std::collections::{HashMap, HashSet};
 
 use camino::Utf8PathBuf;
@@ -187,6 +187,22 @@ impl FeedbackBookKeeper {
         }
     }
 
+    /// Removes all the diagnostics of the project, for when it is no longer
+    /// open in the editor.
+    ///
+    pub fn clear(&mut self) -> Feedback {
+        let mut feedback = Feedback::none();
+        let files_with_diagnostics = self
+            .files_with_warnings
+            .drain()
+            .chain(self.files_with_errors.drain())
+            .chain(self.skipped_files_diagnostics.drain().map(|(path, _)| path));
+        for path in files_with_diagnostics {
+            feedback.unset_existing_diagnostics(path);
+        }
+        feedback
+    }
+
     pub fn open_file(&mut self, file: Utf8PathBuf) -> Feedback {
         let _ = self.open_files.insert(file.clone());
         let mut feedback = Feedback::none();
@@ -206,6 +222,99 @@ impl FeedbackBookKeeper {
     }
 }
 
+/// The diagnostics each file currently has, so that clients using the pull
+/// model can ask for them at any time instead of having them pushed after each
+/// compilation.
+///
+/// Each time the diagnostics of a file change they get a new version. Together
+/// with the fingerprint of the file's source this makes up the result id sent
+/// to the client, so that if it asks again for diagnostics that haven't changed
+/// we can tell it to keep using the ones it already has.
+///
+#[derive(Debug, Default)]
+pub struct DiagnosticsStore {
+    files: HashMap<Utf8PathBuf, StoredDiagnostics>,
+    latest_version: u64,
+}
+
+#[derive(Debug)]
+struct StoredDiagnostics {
+    version: u64,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiagnosticsReport {
+    /// The diagnostics haven't changed since the client last asked for them.
+    Unchanged { result_id: String },
+    Full {
+        result_id: String,
+        diagnostics: Vec<Diagnostic>,
+    },
+}
+
+impl DiagnosticsStore {
+    /// Records the diagnostics of a feedback, replacing any existing ones for
+    /// the same files. Returns true if the diagnostics of any file changed.
+    ///
+    pub fn update(&mut self, feedback: &Feedback) -> bool {
+        let mut changed = false;
+        for (path, diagnostics) in &feedback.diagnostics {
+            let unchanged = self
+                .files
+                .get(path)
+                .is_some_and(|stored| &stored.diagnostics == diagnostics);
+            if unchanged {
+                continue;
+            }
+
+            changed = true;
+            self.latest_version += 1;
+            let stored = StoredDiagnostics {
+                version: self.latest_version,
+                diagnostics: diagnostics.clone(),
+            };
+            _ = self.files.insert(path.clone(), stored);
+        }
+        changed
+    }
+
+    /// All the files that have, or used to have, diagnostics.
+    ///
+    pub fn paths(&self) -> impl Iterator<Item = &Utf8PathBuf> {
+        self.files.keys()
+    }
+
+    /// The diagnostics of a file with the given source. If the client already
+    /// has them, as told by the previous result id it got, there's no need to
+    /// send them again.
+    ///
+    pub fn report(
+        &self,
+        path: &Utf8PathBuf,
+        source: &str,
+        previous_result_id: Option<&str>,
+    ) -> DiagnosticsReport {
+        let (version, diagnostics) = match self.files.get(path) {
+            Some(stored) => (stored.version, stored.diagnostics.as_slice()),
+            None => (0, [].as_slice()),
+        };
+        let result_id = format!(
+            "{}-{version}",
+            SourceFingerprint::new(source).to_numerical_string()
+        );
+
+        if previous_result_id == Some(result_id.as_str()) {
+            DiagnosticsReport::Unchanged { result_id }
+        } else {
+            DiagnosticsReport::Full {
+                result_id,
+                diagnostics: diagnostics.to_vec(),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -218,6 +327,7 @@ mod tests {
         parse::error::{ParseError, ParseErrorType},
         type_,
     };
+    use itertools::Itertools;
 
     #[test]
     fn feedback() {
@@ -367,6 +477,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn clearing_unsets_all_diagnostics() {
+        let mut book_keeper = FeedbackBookKeeper::default();
+        let file1 = Utf8PathBuf::from("src/file1.gleam");
+        let file2 = Utf8PathBuf::from("src/file2.gleam");
+
+        let warning = Warning::Type {
+            path: file1.clone(),
+            src: "src".into(),
+            warning: Box::new(type_::Warning::NoFieldsRecordUpdate {
+                location: SrcSpan::new(1, 2),
+            }),
+        };
+        let error = Error::Parse {
+            path: file2.clone(),
+            src: "blah".into(),
+            error: Box::new(ParseError {
+                error: ParseErrorType::ConcatPatternVariableLeftHandSide,
+                location: SrcSpan::new(1, 4),
+            }),
+        };
+        _ = book_keeper.build_with_error(error, Compilation::Yes(vec![]), vec![warning]);
+
+        assert_eq!(
+            Feedback {
+                diagnostics: HashMap::from([(file1, vec![]), (file2, vec![])]),
+                messages: vec![],
+            },
+            book_keeper.clear()
+        );
+
+        // Once cleared there is nothing left to unset.
+        assert_eq!(Feedback::none(), book_keeper.clear());
+    }
+
     // https://github.com/gleam-lang/gleam/issues/2093
     #[test]
     fn successful_compilation_removes_error_diagnostic() {
@@ -789,4 +934,98 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn diagnostics_store_reports_unchanged_diagnostics() {
+        let mut store = DiagnosticsStore::default();
+        let file1 = Utf8PathBuf::from("src/file1.gleam");
+        let diagnostic = Diagnostic {
+            location: None,
+            hint: None,
+            text: "Error 1".to_string(),
+            title: "Error 1".to_string(),
+            level: Level::Error,
+        };
+
+        let changed = store.update(&Feedback {
+            diagnostics: HashMap::from([(file1.clone(), vec![diagnostic.clone()])]),
+            messages: vec![],
+        });
+        assert!(changed);
+
+        let DiagnosticsReport::Full {
+            result_id,
+            diagnostics,
+        } = store.report(&file1, "src", None)
+        else {
+            panic!("expected a full report");
+        };
+        assert_eq!(diagnostics, vec![diagnostic.clone()]);
+
+        // Asking again with the same result id means the client already has
+        // the latest diagnostics.
+        assert_eq!(
+            store.report(&file1, "src", Some(&result_id)),
+            DiagnosticsReport::Unchanged {
+                result_id: result_id.clone()
+            }
+        );
+
+        // Getting the same diagnostics again doesn't change anything.
+        let changed = store.update(&Feedback {
+            diagnostics: HashMap::from([(file1.clone(), vec![diagnostic])]),
+            messages: vec![],
+        });
+        assert!(!changed);
+        assert_eq!(
+            store.report(&file1, "src", Some(&result_id)),
+            DiagnosticsReport::Unchanged {
+                result_id: result_id.clone()
+            }
+        );
+
+        // If the source has changed the diagnostics have to be sent again.
+        assert!(matches!(
+            store.report(&file1, "changed src", Some(&result_id)),
+            DiagnosticsReport::Full { .. }
+        ));
+    }
+
+    #[test]
+    fn diagnostics_store_reports_removed_diagnostics() {
+        let mut store = DiagnosticsStore::default();
+        let file1 = Utf8PathBuf::from("src/file1.gleam");
+        let diagnostic = Diagnostic {
+            location: None,
+            hint: None,
+            text: "Error 1".to_string(),
+            title: "Error 1".to_string(),
+            level: Level::Error,
+        };
+
+        _ = store.update(&Feedback {
+            diagnostics: HashMap::from([(file1.clone(), vec![diagnostic])]),
+            messages: vec![],
+        });
+        let DiagnosticsReport::Full { result_id, .. } = store.report(&file1, "src", None) else {
+            panic!("expected a full report");
+        };
+
+        let changed = store.update(&Feedback {
+            diagnostics: HashMap::from([(file1.clone(), vec![])]),
+            messages: vec![],
+        });
+        assert!(changed);
+
+        let DiagnosticsReport::Full {
+            result_id: new_result_id,
+            diagnostics,
+        } = store.report(&file1, "src", Some(&result_id))
+        else {
+            panic!("expected a full report");
+        };
+        assert_ne!(result_id, new_result_id);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(store.paths().collect_vec(), vec![&file1]);
+    }
 }
@@ -13,9 +13,14 @@ use gleam_core::{
         BeamCompilerIO, Command, CommandExecutor, FileSystemReader, FileSystemWriter, ReadDir,
         Stdio, WrappedReader, memory::InMemoryFileSystem,
     },
+    line_numbers::LineNumbers,
+    paths::ProjectPaths,
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
+use lsp_types::{Position, TextDocumentContentChangeEvent};
+
+use crate::{RunProgram, Runnable};
 
 // A proxy intended for `LanguageServer` to use when files are modified in
 // memory but not yet saved to disc by the client.
@@ -53,6 +58,20 @@ where
         write_result
     }
 
+    /// Applies the changes made to a file in the editor to its cached
+    /// content. If the file isn't cached yet the changes are applied to its
+    /// content on disk.
+    ///
+    pub fn apply_mem_cache_changes(
+        &mut self,
+        path: &Utf8Path,
+        changes: &[TextDocumentContentChangeEvent],
+    ) -> Result<()> {
+        let mut content = self.read(path)?;
+        apply_changes(&mut content, changes);
+        self.write_mem_cache(path, &content)
+    }
+
     pub fn delete_mem_cache(&self, path: &Utf8Path) -> Result<()> {
         if self.edit_cache.is_directory(path) {
             self.edit_cache.delete_directory(path)
@@ -62,6 +81,44 @@ where
     }
 }
 
+/// Applies the changes sent by the client in a `textDocument/didChange`
+/// notification to a document's text. The changes are applied in order, so the
+/// range of each one refers to the text resulting from the previous ones.
+///
+pub fn apply_changes(text: &mut String, changes: &[TextDocumentContentChangeEvent]) {
+    for change in changes {
+        match change {
+            TextDocumentContentChangeEvent::TextDocumentContentChangeWholeDocument(change) => {
+                text.clone_from(&change.text)
+            }
+            TextDocumentContentChangeEvent::TextDocumentContentChangePartial(change) => {
+                let line_numbers = LineNumbers::new(text);
+                let start = byte_index(text, &line_numbers, change.range.start);
+                let end = byte_index(text, &line_numbers, change.range.end).max(start);
+                text.replace_range(start..end, &change.text);
+            }
+        }
+    }
+}
+
+/// Converts a position sent by the client to a byte index in the text.
+///
+/// The LSP specification says that a character offset greater than the length
+/// of its line defaults back to the end of the line, while `LineNumbers` would
+/// carry on to the following lines. The end of a line is before its line
+/// ending, be it `\n` or `\r\n`, so that an edit can't split a `\r\n` in two.
+///
+fn byte_index(text: &str, line_numbers: &LineNumbers, position: Position) -> usize {
+    let line_end = match line_numbers.line_starts.get(position.line as usize + 1) {
+        Some(next_line_start) => {
+            let line = &text[..*next_line_start as usize - 1];
+            line.strip_suffix('\r').unwrap_or(line).len()
+        }
+        None => text.len(),
+    };
+    (line_numbers.byte_index(position) as usize).min(line_end)
+}
+
 // All write operations goes to disk (for mem-cache use the dedicated `_mem_cache` methods)
 impl<IO> FileSystemWriter for FileSystemProxy<IO>
 where
@@ -169,6 +226,15 @@ where
     }
 }
 
+impl<IO> RunProgram for FileSystemProxy<IO>
+where
+    IO: RunProgram,
+{
+    fn run_program(&self, paths: &ProjectPaths, runnable: &Runnable) -> Result<()> {
+        self.io.run_program(paths, runnable)
+    }
+}
+
 impl<IO> BeamCompilerIO for FileSystemProxy<IO>
 where
     IO: BeamCompilerIO,
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use camino::Utf8Path;
+use ecow::EcoString;
+use gleam_core::{Result, ast::SrcSpan, line_numbers::LineNumbers};
+use gleam_format::Edit;
+use lsp_types::{Position, Range, TextEdit};
+
+use crate::{lsp_range_to_src_span, src_span_to_lsp_range};
+
+/// Formats the part of a module within the given range, leaving the rest of it
+/// untouched.
+///
+/// Only the top level definitions the range overlaps with are formatted, so
+/// errors elsewhere in the module don't stop them from being formatted.
+///
+pub fn range_formatting(src: &EcoString, path: &Utf8Path, range: Range) -> Result<Vec<TextEdit>> {
+    let line_numbers = LineNumbers::new(src);
+    let range = lsp_range_to_src_span(range, &line_numbers);
+    let definitions = enclosing_definitions(src, range);
+    let edits = gleam_format::edits_in(src, path, definitions)?;
+    Ok(text_edits(src, edits, range, &line_numbers))
+}
+
+/// Formats the code right before the given position, after a character was
+/// typed: a closing brace formats the definition it closes up to the brace, a
+/// new line formats the line that was just ended.
+///
+/// While typing the code will often be invalid, so rather than reporting an
+/// error this just doesn't format anything if the definition can't be parsed.
+///
+pub fn on_type_formatting(
+    src: &EcoString,
+    path: &Utf8Path,
+    position: Position,
+    character: &str,
+) -> Vec<TextEdit> {
+    let line_numbers = LineNumbers::new(src);
+    let byte_index = line_numbers.byte_index(position);
+
+    let region = match character {
+        "}" => {
+            let definition = enclosing_definitions(src, SrcSpan::new(byte_index, byte_index));
+            Some(SrcSpan::new(definition.start, byte_index))
+        }
+        "\n" => ended_line(&line_numbers, position.line),
+        _ => None,
+    };
+    let Some(region) = region else {
+        return vec![];
+    };
+    let definitions = enclosing_definitions(src, region);
+    let Ok(edits) = gleam_format::edits_in(src, path, definitions) else {
+        return vec![];
+    };
+
+    // Edits reaching out of the region could change the code the programmer
+    // is currently typing, so those are left alone.
+    text_edits(src, edits, region, &line_numbers)
+}
+
+/// The location of the top level definitions overlapping the given span, from
+/// the start of the first one to the end of the line the last one ends on.
+///
+/// A definition is found by its first line having no indentation, rather than
+/// by parsing the module, as the rest of the module could be invalid. Comments
+/// and attributes are part of the definition they come before.
+///
+fn enclosing_definitions(src: &str, span: SrcSpan) -> SrcSpan {
+    let mut starts = vec![];
+    let mut previous_line = "";
+    let mut offset = 0;
+    for line in src.split_inclusive('\n') {
+        let continues_previous = previous_line.starts_with('@')
+            || (previous_line.starts_with("//") && !previous_line.starts_with("////"));
+        if offset == 0 || (starts_definition(line) && !continues_previous) {
+            starts.push(offset as u32);
+        }
+        previous_line = line;
+        offset += line.len();
+    }
+
+    let start = starts
+        .iter()
+        .rev()
+        .find(|start| **start <= span.start)
+        .copied()
+        .unwrap_or(0);
+    let next_start = starts
+        .iter()
+        .find(|next| **next >= span.end && **next > span.start)
+        .map(|next| *next as usize)
+        .unwrap_or(src.len());
+
+    // The blank lines between definitions are not part of either of them, the
+    // formatter would remove them from the end of the last one.
+    let code_end = start as usize + src[start as usize..next_start].trim_end().len();
+    let end = match src[code_end..next_start].find('\n') {
+        Some(newline) => code_end + newline + 1,
+        None => next_start,
+    };
+    SrcSpan::new(start, end as u32)
+}
+
+fn starts_definition(line: &str) -> bool {
+    ["pub ", "fn ", "type ", "const ", "import ", "@", "//"]
+        .iter()
+        .any(|start| line.starts_with(start))
+}
+
+/// The line before the given one, without its final newline.
+///
+fn ended_line(line_numbers: &LineNumbers, line: u32) -> Option<SrcSpan> {
+    let previous_line = line.checked_sub(1)?;
+    let start = *line_numbers.line_starts.get(previous_line as usize)?;
+    let end = *line_numbers.line_starts.get(line as usize)?;
+    Some(SrcSpan::new(start, end - 1))
+}
+
+fn text_edits(
+    src: &str,
+    edits: Vec<Edit>,
+    range: SrcSpan,
+    line_numbers: &LineNumbers,
+) -> Vec<TextEdit> {
+    gleam_format::clip(src, edits, range)
+        .into_iter()
+        .map(|edit| TextEdit {
+            range: src_span_to_lsp_range(edit.location, line_numbers),
+            new_text: edit.new_text,
+        })
+        .collect()
+}
@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+//! Go-to-implementation for `@external` functions, which jumps from the Gleam
+//! declaration to the function in the Erlang, Elixir, or JavaScript file that
+//! implements it.
+//!
+//! The native files are not parsed, instead they are scanned line by line for
+//! something that looks like the definition of the function. If the function
+//! can't be found the start of the file is used.
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use gleam_core::{
+    ast::{SrcSpan, TypedExpr},
+    build::{Located, UnqualifiedImport},
+    io::{DirWalker, FileSystemReader},
+    type_::{ModuleInterface, ModuleValueConstructor, ValueConstructor, ValueConstructorVariant},
+};
+
+/// A function with an `@external` implementation for at least one target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalFunction {
+    /// The Gleam module the function is declared in.
+    pub module: EcoString,
+    pub erlang: Option<(EcoString, EcoString)>,
+    pub javascript: Option<(EcoString, EcoString)>,
+}
+
+impl ExternalFunction {
+    /// The external function the node refers to, if any. `module` is the name
+    /// of the module the node is in.
+    pub fn from_located(
+        located: &Located<'_>,
+        module: &EcoString,
+        importable_modules: &im::HashMap<EcoString, ModuleInterface>,
+    ) -> Option<Self> {
+        let function = match located {
+            Located::ModuleFunction(function) => Self {
+                module: module.clone(),
+                erlang: function
+                    .external_erlang
+                    .as_ref()
+                    .map(|(module, function, _)| (module.clone(), function.clone())),
+                javascript: function
+                    .external_javascript
+                    .as_ref()
+                    .map(|(module, function, _)| (module.clone(), function.clone())),
+            },
+
+            Located::Expression {
+                expression: TypedExpr::Var { constructor, .. },
+                ..
+            } => Self::from_value_constructor(constructor)?,
+
+            Located::Expression {
+                expression:
+                    TypedExpr::ModuleSelect {
+                        constructor:
+                            ModuleValueConstructor::Fn {
+                                module,
+                                external_erlang,
+                                external_javascript,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => Self {
+                module: module.clone(),
+                erlang: external_erlang.clone(),
+                javascript: external_javascript.clone(),
+            },
+
+            Located::UnqualifiedImport(UnqualifiedImport {
+                module,
+                name,
+                is_type: false,
+                ..
+            }) => {
+                Self::from_value_constructor(importable_modules.get(*module)?.values.get(*name)?)?
+            }
+
+            _ => return None,
+        };
+
+        if function.erlang.is_none() && function.javascript.is_none() {
+            None
+        } else {
+            Some(function)
+        }
+    }
+
+    fn from_value_constructor(constructor: &ValueConstructor) -> Option<Self> {
+        match &constructor.variant {
+            ValueConstructorVariant::ModuleFn {
+                module,
+                external_erlang,
+                external_javascript,
+                ..
+            } => Some(Self {
+                module: module.clone(),
+                erlang: external_erlang.clone(),
+                javascript: external_javascript.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The native definition of an external function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Implementation {
+    pub path: Utf8PathBuf,
+    pub src: String,
+    /// The location of the name of the function, or the start of the file if
+    /// it could not be found.
+    pub span: SrcSpan,
+}
+
+/// Finds the native files implementing the function, given the path of the
+/// Gleam module that declares it.
+pub fn find_implementations(
+    io: &impl FileSystemReader,
+    function: &ExternalFunction,
+    module_path: &Utf8Path,
+) -> Vec<Implementation> {
+    let mut implementations = vec![];
+
+    if let Some((erlang_module, name)) = &function.erlang {
+        let package_root = package_root(module_path, &function.module);
+        let implementation = match erlang_module.strip_prefix("Elixir.") {
+            Some(elixir_module) => package_root
+                .and_then(|root| find_elixir_implementation(io, &root, elixir_module, name)),
+            None => package_root
+                .and_then(|root| find_native_file(io, &root, &format!("{erlang_module}.erl")))
+                .and_then(|path| {
+                    let src = io.read(&path).ok()?;
+                    let span = find_erlang_function(&src, name);
+                    Some(implementation(path, src, span))
+                }),
+        };
+        implementations.extend(implementation);
+    }
+
+    if let Some((javascript_module, name)) = &function.javascript {
+        let implementation = javascript_module_path(module_path, javascript_module)
+            .filter(|path| io.is_file(path))
+            .and_then(|path| {
+                let src = io.read(&path).ok()?;
+                let span = find_javascript_function(&src, name);
+                Some(implementation(path, src, span))
+            });
+        implementations.extend(implementation);
+    }
+
+    implementations
+}
+
+fn implementation(path: Utf8PathBuf, src: String, span: Option<SrcSpan>) -> Implementation {
+    Implementation {
+        path,
+        src,
+        span: span.unwrap_or_default(),
+    }
+}
+
+/// The root directory of the package a module belongs to. A module named
+/// `wibble/wobble` is found at `<root>/src/wibble/wobble.gleam`, or in the
+/// `test` or `dev` directory in place of `src`.
+fn package_root(module_path: &Utf8Path, module_name: &str) -> Option<Utf8PathBuf> {
+    let mut directory = module_path.parent()?;
+    for _ in module_name.split('/').skip(1) {
+        directory = directory.parent()?;
+    }
+    directory.parent().map(Utf8Path::to_path_buf)
+}
+
+/// Native files can be anywhere in the directories of a package that contain
+/// Gleam modules, which are the ones `NativeFileCopier` copies them from.
+fn source_files(io: &impl FileSystemReader, package_root: &Utf8Path) -> Vec<Utf8PathBuf> {
+    ["src", "test", "dev"]
+        .into_iter()
+        .map(|directory| package_root.join(directory))
+        .filter(|directory| io.is_directory(directory))
+        .flat_map(|directory| {
+            DirWalker::new(directory)
+                .into_file_iter(io)
+                .filter_map(Result::ok)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn find_native_file(
+    io: &impl FileSystemReader,
+    package_root: &Utf8Path,
+    file_name: &str,
+) -> Option<Utf8PathBuf> {
+    source_files(io, package_root)
+        .into_iter()
+        .find(|path| path.file_name() == Some(file_name))
+}
+
+/// Elixir files don't have to be named after the module they define, so each
+/// one is searched for the definition of the module.
+fn find_elixir_implementation(
+    io: &impl FileSystemReader,
+    package_root: &Utf8Path,
+    module: &str,
+    name: &str,
+) -> Option<Implementation> {
+    source_files(io, package_root)
+        .into_iter()
+        .filter(|path| path.extension() == Some("ex"))
+        .find_map(|path| {
+            let src = io.read(&path).ok()?;
+            let module_start = find_elixir_module(&src, module)?;
+            let span = find_elixir_function(&src[module_start..], name).map(|span| SrcSpan {
+                start: span.start + module_start as u32,
+                end: span.end + module_start as u32,
+            });
+            Some(implementation(path, src, span))
+        })
+}
+
+/// The path of a JavaScript module imported by an `@external`, which is
+/// relative to the Gleam module. Modules from packages, such as `node:fs`,
+/// have no path.
+pub fn javascript_module_path(
+    module_path: &Utf8Path,
+    javascript_module: &str,
+) -> Option<Utf8PathBuf> {
+    if !javascript_module.starts_with("./") && !javascript_module.starts_with("../") {
+        return None;
+    }
+    let mut path = Utf8PathBuf::new();
+    for component in module_path.parent()?.join(javascript_module).components() {
+        match component {
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir => {
+                let _ = path.pop();
+            }
+            Utf8Component::Prefix(_) | Utf8Component::RootDir | Utf8Component::Normal(_) => {
+                path.push(component)
+            }
+        }
+    }
+    Some(path)
+}
+
+/// The lines of the source code, with the byte index each one starts at.
+fn lines(src: &str) -> impl Iterator<Item = (usize, &str)> {
+    src.split_inclusive('\n').scan(0, |start, line| {
+        let line_start = *start;
+        *start += line.len();
+        Some((line_start, line.trim_end()))
+    })
+}
+
+fn name_span(line_start: usize, offset: usize, name: &str) -> SrcSpan {
+    let start = (line_start + offset) as u32;
+    SrcSpan {
+        start,
+        end: start + name.len() as u32,
+    }
+}
+
+/// Whether the text starts with the given name, and not a longer name that
+/// begins with it.
+fn starts_with_name(text: &str, name: &str) -> bool {
+    text.strip_prefix(name).is_some_and(|rest| {
+        !rest
+            .chars()
+            .next()
+            .is_some_and(|char| char.is_alphanumeric() || char == '_' || char == '$')
+    })
+}
+
+/// Erlang function clauses start at the beginning of a line with the name of
+/// the function, which may be quoted, followed by its arguments.
+fn find_erlang_function(src: &str, name: &str) -> Option<SrcSpan> {
+    let quoted = format!("'{name}'");
+    lines(src).find_map(|(line_start, line)| {
+        [name, quoted.as_str()].into_iter().find_map(|candidate| {
+            let rest = line.strip_prefix(candidate)?;
+            rest.trim_start()
+                .starts_with('(')
+                .then(|| name_span(line_start, 0, candidate))
+        })
+    })
+}
+
+/// The byte index of the line defining the module with the given name.
+fn find_elixir_module(src: &str, module: &str) -> Option<usize> {
+    lines(src).find_map(|(line_start, line)| {
+        let rest = line.trim_start().strip_prefix("defmodule ")?;
+        starts_with_name(rest.trim_start(), module).then_some(line_start)
+    })
+}
+
+fn find_elixir_function(src: &str, name: &str) -> Option<SrcSpan> {
+    lines(src).find_map(|(line_start, line)| {
+        let trimmed = line.trim_start();
+        let rest = ["def ", "defdelegate "]
+            .into_iter()
+            .find_map(|keyword| trimmed.strip_prefix(keyword))?;
+        let rest = rest.trim_start();
+        starts_with_name(rest, name).then(|| name_span(line_start, line.len() - rest.len(), name))
+    })
+}
+
+/// Only top level definitions are considered: exported or not functions,
+/// and variables that functions may be assigned to.
+fn find_javascript_function(src: &str, name: &str) -> Option<SrcSpan> {
+    lines(src).find_map(|(line_start, line)| {
+        let rest = line.strip_prefix("export ").unwrap_or(line);
+        let rest = rest.strip_prefix("async ").unwrap_or(rest);
+        let rest = [
+            "function* ",
+            "function *",
+            "function ",
+            "const ",
+            "let ",
+            "var ",
+        ]
+        .into_iter()
+        .find_map(|keyword| rest.strip_prefix(keyword))?;
+        let rest = rest.trim_start();
+        starts_with_name(rest, name).then(|| name_span(line_start, line.len() - rest.len(), name))
+    })
+}
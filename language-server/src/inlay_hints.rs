@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+use gleam_core::{
+    ast::{
+        self, ArgNames, FunctionLiteralKind, Pattern, PipelineAssignmentKind, SrcSpan, TypedArg,
+        TypedAssignment, TypedExpr, TypedModule, TypedPipelineAssignment, TypedUse, visit::Visit,
+    },
+    line_numbers::LineNumbers,
+    type_::{Type, TypedCallArg, printer::Printer},
+};
+use lsp_types::{InlayHint, InlayHintKind};
+
+use crate::{configuration::InlayHintsConfig, src_span_to_lsp_range};
+
+/// Computes the inlay hints of the categories enabled in the configuration for
+/// the part of the module in the given range.
+///
+pub fn inlay_hints(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    config: InlayHintsConfig,
+    range: SrcSpan,
+) -> Vec<InlayHint> {
+    let mut collector = InlayHintsCollector {
+        config,
+        range,
+        line_numbers,
+        printer: Printer::new(&module.names),
+        hints: vec![],
+    };
+    collector.visit_typed_module(module);
+    collector.hints
+}
+
+struct InlayHintsCollector<'a> {
+    config: InlayHintsConfig,
+    range: SrcSpan,
+    line_numbers: &'a LineNumbers,
+    printer: Printer<'a>,
+    hints: Vec<InlayHint>,
+}
+
+impl InlayHintsCollector<'_> {
+    fn push_type_hint(&mut self, byte_index: u32, type_: &Type) {
+        if !self.range.contains(byte_index) {
+            return;
+        }
+        let label = format!(": {}", self.printer.print_type(type_));
+        self.push(byte_index, label, InlayHintKind::Type);
+    }
+
+    fn push(&mut self, byte_index: u32, label: String, kind: InlayHintKind) {
+        let position =
+            src_span_to_lsp_range(SrcSpan::new(byte_index, byte_index), self.line_numbers).start;
+        let is_parameter = kind == InlayHintKind::Parameter;
+        self.hints.push(InlayHint {
+            position,
+            label: label.into(),
+            kind: Some(kind),
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: is_parameter.then_some(true),
+            data: None,
+        });
+    }
+
+    fn is_multiline(&self, location: SrcSpan) -> bool {
+        self.line_numbers.line_number(location.start) != self.line_numbers.line_number(location.end)
+    }
+}
+
+/// Variables introduced by the compiler when desugaring pipelines, function
+/// captures and `use` expressions start with an underscore, which is
+/// something a variable written by a programmer can never be referenced by.
+///
+fn is_generated_variable(expression: &TypedExpr) -> bool {
+    matches!(expression, TypedExpr::Var { name, .. } if name.starts_with('_'))
+}
+
+impl<'ast> Visit<'ast> for InlayHintsCollector<'_> {
+    fn visit_typed_function(&mut self, fun: &'ast ast::TypedFunction) {
+        // Type variables are named independently in each function.
+        self.printer.clear_type_variables();
+        ast::visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        // The patterns of a `use` are destructured by assignments generated
+        // by the compiler, those are taken care of when visiting the `use`.
+        if self.config.variable_types
+            && assignment.annotation.is_none()
+            && !is_generated_variable(&assignment.value)
+            && !matches!(assignment.pattern, Pattern::Discard { .. })
+        {
+            let end = assignment.pattern.location().end;
+            self.push_type_hint(end, &assignment.pattern.type_());
+        }
+
+        ast::visit::visit_typed_assignment(self, assignment);
+    }
+
+    fn visit_typed_use(&mut self, use_: &'ast TypedUse) {
+        if self.config.variable_types {
+            // A `use` binding a single variable becomes an argument of the
+            // callback function...
+            for argument in use_callback_arguments(use_) {
+                if let ArgNames::Named { name, location } = &argument.names
+                    && argument.annotation.is_none()
+                    && !name.starts_with('_')
+                {
+                    self.push_type_hint(location.end, &argument.type_);
+                }
+            }
+
+            // ...while any other pattern is destructured by an assignment at
+            // the start of the callback's body.
+            for assignment in &use_.assignments {
+                if assignment.annotation.is_some()
+                    || matches!(assignment.pattern, Pattern::Discard { .. })
+                {
+                    continue;
+                }
+                let end = assignment.pattern.location().end;
+                self.push_type_hint(end, &assignment.pattern.type_());
+            }
+        }
+
+        ast::visit::visit_typed_use(self, use_);
+    }
+
+    fn visit_typed_expr_pipeline(
+        &mut self,
+        location: &'ast SrcSpan,
+        first_value: &'ast TypedPipelineAssignment,
+        assignments: &'ast [(TypedPipelineAssignment, PipelineAssignmentKind)],
+        finally: &'ast TypedExpr,
+        finally_kind: &'ast PipelineAssignmentKind,
+    ) {
+        // Pipelines written on a single line are short enough that the
+        // hints would only get in the way.
+        if self.config.pipelines && self.is_multiline(*location) {
+            let steps =
+                std::iter::once(first_value).chain(assignments.iter().map(|(step, _)| step));
+            for step in steps {
+                self.push_type_hint(step.location.end, &step.value.type_());
+            }
+            self.push_type_hint(finally.location().end, &finally.type_());
+        }
+
+        ast::visit::visit_typed_expr_pipeline(
+            self,
+            location,
+            first_value,
+            assignments,
+            finally,
+            finally_kind,
+        );
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        arguments: &'ast [TypedCallArg],
+        open_parenthesis: &'ast Option<u32>,
+    ) {
+        if self.config.parameter_labels
+            && let Some(field_map) = fun.field_map()
+        {
+            // The arguments of a typed call are sorted in the same order as the
+            // function's parameters, so each one's index is the index of the
+            // parameter it's passed to.
+            let labels = field_map.indices_to_labels();
+            for (index, argument) in arguments.iter().enumerate() {
+                let Some(label) = labels.get(&(index as u32)) else {
+                    continue;
+                };
+                if !should_show_label(label, argument) {
+                    continue;
+                }
+                let start = argument.location.start;
+                if self.range.contains(start) {
+                    self.push(start, format!("{label}:"), InlayHintKind::Parameter);
+                }
+            }
+        }
+
+        ast::visit::visit_typed_expr_call(self, location, type_, fun, arguments, open_parenthesis);
+    }
+}
+
+fn use_callback_arguments(use_: &TypedUse) -> &[TypedArg] {
+    let TypedExpr::Call { arguments, .. } = use_.call.as_ref() else {
+        return &[];
+    };
+    match arguments.last().map(|argument| &argument.value) {
+        Some(TypedExpr::Fn {
+            arguments,
+            kind: FunctionLiteralKind::Use { .. },
+            ..
+        }) => arguments,
+        _ => &[],
+    }
+}
+
+fn should_show_label(label: &EcoString, argument: &TypedCallArg) -> bool {
+    if argument.label.is_some() || argument.is_implicit() || is_generated_variable(&argument.value)
+    {
+        return false;
+    }
+
+    // Passing a variable with the same name as the label already makes it
+    // clear what the argument is.
+    !matches!(&argument.value, TypedExpr::Var { name, .. } if name == label)
+}
@@ -53,20 +53,31 @@
     clippy::len_without_is_empty
 )]
 
+mod call_hierarchy;
 mod code_action;
+mod code_lens;
 mod compiler;
 mod completer;
+mod configuration;
+mod document_link;
 mod edits;
 mod engine;
 mod feedback;
 mod files;
+mod formatting;
+mod implementation;
+mod inlay_hints;
+mod linked_editing;
 mod messages;
 mod progress;
 mod reference;
 mod rename;
 mod router;
+mod selection_range;
+mod semantic_tokens;
 mod server;
 mod signature_help;
+mod workspace_symbol;
 
 #[cfg(test)]
 mod tests;
@@ -74,11 +85,13 @@ mod tests;
 pub use server::LanguageServer;
 
 use camino::Utf8PathBuf;
+use ecow::EcoString;
 use gleam_core::{
     Result, ast::SrcSpan, build::Target, line_numbers::LineNumbers, manifest::Manifest,
     paths::ProjectPaths,
 };
 use lsp_types::{Position, Range, TextEdit, Uri as Url};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
 #[derive(Debug)]
@@ -96,6 +109,33 @@ pub trait DownloadDependencies {
     fn download_dependencies(&self, paths: &ProjectPaths) -> Result<Manifest>;
 }
 
+/// Something that can be run from one of the language server's code lenses.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Runnable {
+    /// The `main` function of a module, run like `gleam run --module` does.
+    Main {
+        module: EcoString,
+        target: Option<Target>,
+    },
+    /// A test function, which is run on its own like
+    /// `gleam test --module module.function` does.
+    Test {
+        module: EcoString,
+        function: EcoString,
+        target: Option<Target>,
+    },
+}
+
+pub trait RunProgram {
+    /// Starts building the project and running the given program in the
+    /// background, without waiting for either to finish. Nothing is printed to
+    /// stdout, as the language server uses it to talk to the editor.
+    ///
+    fn run_program(&self, paths: &ProjectPaths, runnable: &Runnable) -> Result<()>;
+}
+
 pub fn src_span_to_lsp_range(location: SrcSpan, line_numbers: &LineNumbers) -> Range {
     let start = line_numbers.line_and_column_number(location.start);
     let end = line_numbers.line_and_column_number(location.end);
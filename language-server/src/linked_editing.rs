@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::collections::HashSet;
+
+use ecow::EcoString;
+use gleam_core::{
+    ast::SrcSpan,
+    build::Module,
+    line_numbers::LineNumbers,
+    reference::{LabelSyntax, RecordLabel},
+    type_::{ModuleInterface, error::VariableSyntax},
+};
+use itertools::Itertools;
+use lsp_types::LinkedEditingRanges;
+
+use crate::{
+    reference::{
+        FindVariableReferences, Referenced, VariableReferenceKind, reference_for_ast_node,
+    },
+    src_span_to_lsp_range,
+};
+
+/// Something whose occurrences in a module are all linked, so that editing one
+/// of them edits all the others as well.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Linked {
+    LocalVariable {
+        definition_location: SrcSpan,
+        name: EcoString,
+        kind: VariableReferenceKind,
+    },
+    Label(RecordLabel),
+}
+
+/// Finds the ranges that have to be edited together with the variable or label
+/// found at the given position:
+/// - A local variable is linked to all its usages, and to its definitions in
+///   each alternative of a `case` pattern (`A(x) | B(x)`).
+/// - A record label is linked to its definitions and all its usages, as long
+///   as it's only ever used in this module.
+///
+/// A label shorthand (`label:`) is both a label and a variable, so the two are
+/// linked together and editing either one edits the other as well.
+///
+/// Editing ranges one character at a time has to always result in valid code,
+/// so if any of the linked occurrences can't be edited along with the others
+/// no ranges are returned at all and renaming has to be used instead.
+///
+pub fn linked_editing_ranges(
+    module: &Module,
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+    line_numbers: &LineNumbers,
+    referenced: Referenced,
+    byte_index: u32,
+) -> Option<LinkedEditingRanges> {
+    let linked = match referenced {
+        Referenced::LocalVariable { location, .. } | Referenced::Label { location, .. }
+            if !location.contains(byte_index) =>
+        {
+            return None;
+        }
+        Referenced::LocalVariable { .. } | Referenced::Label { .. } => linked(referenced)?,
+        Referenced::ModuleName { .. }
+        | Referenced::ModuleValue { .. }
+        | Referenced::ModuleType { .. }
+        | Referenced::TypeVariable { .. } => return None,
+    };
+
+    let interface = modules.get(&module.name)?;
+    let mut pending = vec![linked];
+    let mut visited = HashSet::new();
+    let mut spans = HashSet::new();
+
+    while let Some(linked) = pending.pop() {
+        if !visited.insert(linked.clone()) {
+            continue;
+        }
+
+        match linked {
+            Linked::LocalVariable {
+                definition_location,
+                name,
+                kind,
+            } => {
+                let references = FindVariableReferences::new(definition_location, name)
+                    .find_in_module(&module.ast)
+                    .into_iter()
+                    .map(|reference| (reference.location, reference.kind));
+
+                for (location, kind) in references.chain([(definition_location, kind)]) {
+                    match kind {
+                        VariableReferenceKind::Variable => _ = spans.insert(location),
+                        VariableReferenceKind::LabelShorthand => {
+                            _ = spans.insert(shorthand_label(location));
+                            pending.push(Linked::Label(label_at(interface, location)?));
+                        }
+                    }
+                }
+            }
+
+            Linked::Label(label) => {
+                // If the label is used in other modules, editing it only here
+                // would break those.
+                let used_elsewhere = modules.values().any(|other| {
+                    other.name != module.name
+                        && (other.references.label_references.contains_key(&label)
+                            || other.references.label_definitions.contains_key(&label))
+                });
+                if used_elsewhere {
+                    return None;
+                }
+
+                let definitions = interface.references.label_definitions.get(&label);
+                for definition in definitions.into_iter().flatten() {
+                    _ = spans.insert(definition.location);
+                }
+
+                let references = interface.references.label_references.get(&label);
+                for reference in references.into_iter().flatten() {
+                    match reference.syntax {
+                        LabelSyntax::Longhand => _ = spans.insert(reference.location),
+                        LabelSyntax::Shorthand => {
+                            _ = spans.insert(shorthand_label(reference.location));
+                            pending.push(variable_at(module, reference.location.start)?);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // There's nothing to keep in sync if something is only written once.
+    if spans.len() < 2 {
+        return None;
+    }
+
+    Some(LinkedEditingRanges {
+        ranges: spans
+            .into_iter()
+            .sorted_by_key(|span| span.start)
+            .map(|span| src_span_to_lsp_range(span, line_numbers))
+            .collect(),
+        word_pattern: Some("[a-z_][a-z0-9_]*".into()),
+    })
+}
+
+fn linked(referenced: Referenced) -> Option<Linked> {
+    match referenced {
+        Referenced::LocalVariable {
+            definition_location,
+            origin,
+            name,
+            ..
+        } => {
+            let kind = match origin.map(|origin| origin.syntax) {
+                Some(VariableSyntax::Generated) => return None,
+                Some(VariableSyntax::LabelShorthand(_)) => VariableReferenceKind::LabelShorthand,
+                Some(VariableSyntax::AssignmentPattern(..) | VariableSyntax::Variable(_))
+                | None => VariableReferenceKind::Variable,
+            };
+            Some(Linked::LocalVariable {
+                definition_location,
+                name,
+                kind,
+            })
+        }
+        Referenced::Label {
+            type_module,
+            type_name,
+            label,
+            ..
+        } => Some(Linked::Label(RecordLabel {
+            type_module,
+            type_name,
+            label,
+        })),
+        Referenced::ModuleName { .. }
+        | Referenced::ModuleValue { .. }
+        | Referenced::ModuleType { .. }
+        | Referenced::TypeVariable { .. } => None,
+    }
+}
+
+/// The location of a label shorthand spans the entire `label:`, this returns
+/// the location of just the label.
+///
+fn shorthand_label(location: SrcSpan) -> SrcSpan {
+    SrcSpan::new(location.start, location.end - 1)
+}
+
+/// The label written with the shorthand syntax at the given location.
+///
+fn label_at(interface: &ModuleInterface, location: SrcSpan) -> Option<RecordLabel> {
+    interface
+        .references
+        .label_references
+        .iter()
+        .find(|(_, references)| {
+            references.iter().any(|reference| {
+                reference.syntax == LabelSyntax::Shorthand && reference.location == location
+            })
+        })
+        .map(|(label, _)| label.clone())
+}
+
+/// The variable a label shorthand starting at the given position stands for.
+///
+fn variable_at(module: &Module, byte_index: u32) -> Option<Linked> {
+    let node = module.find_node(byte_index)?;
+    match reference_for_ast_node(node, &module.name)? {
+        referenced @ Referenced::LocalVariable { .. } => linked(referenced),
+        Referenced::ModuleName { .. }
+        | Referenced::ModuleValue { .. }
+        | Referenced::ModuleType { .. }
+        | Referenced::TypeVariable { .. }
+        | Referenced::Label { .. } => None,
+    }
+}
@@ -1,16 +1,26 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2024 The Gleam contributors
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use lsp::{DefinitionRequest, DidChangeWatchedFilesNotification, DidOpenTextDocumentNotification};
 use lsp_types::{
-    self as lsp, CodeActionRequest, CompletionRequest, DidChangeTextDocumentNotification,
-    DidCloseTextDocumentNotification, DidSaveTextDocumentNotification, DocumentFormattingRequest,
-    DocumentHighlightRequest, DocumentSymbolRequest, FoldingRangeRequest, HoverRequest,
-    PrepareRenameRequest, ReferencesRequest, RenameRequest, SignatureHelpRequest,
+    self as lsp, CallHierarchyIncomingCallsRequest, CallHierarchyOutgoingCallsRequest,
+    CallHierarchyPrepareRequest, CancelNotification, CodeActionRequest, CodeLensRequest,
+    CodeLensResolveRequest, CompletionRequest, DidChangeTextDocumentNotification,
+    DidChangeWorkspaceFoldersNotification, DidCloseTextDocumentNotification,
+    DidSaveTextDocumentNotification, DocumentDiagnosticRequest, DocumentFormattingRequest,
+    DocumentHighlightRequest, DocumentLinkRequest, DocumentOnTypeFormattingRequest,
+    DocumentRangeFormattingRequest, DocumentSymbolRequest, ExecuteCommandRequest,
+    FoldingRangeRequest, HoverRequest, ImplementationRequest, InlayHintRequest,
+    LinkedEditingRangeRequest, PrepareRenameRequest, ReferencesRequest, RenameRequest,
+    SelectionRangeRequest, SemanticTokensRangeRequest, SemanticTokensRequest, SignatureHelpRequest,
     TextDocumentContentChangeEvent, TypeDefinitionRequest, WillRenameFilesRequest,
+    WorkspaceDiagnosticRequest, WorkspaceSymbolRequest,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Duration,
 };
-use std::time::Duration;
 
 #[derive(Debug)]
 pub enum Message {
@@ -21,19 +31,47 @@ pub enum Message {
 #[derive(Debug)]
 pub enum Request {
     Format(lsp::DocumentFormattingParams),
+    RangeFormat(lsp::DocumentRangeFormattingParams),
+    OnTypeFormat(lsp::DocumentOnTypeFormattingParams),
+    CodeAction(lsp::CodeActionParams),
+    PrepareRename(lsp::PrepareRenameParams),
+    Rename(lsp::RenameParams),
+    RenameFiles(lsp::RenameFilesParams),
+    WorkspaceSymbol(lsp::WorkspaceSymbolParams),
+    CodeLensResolve(lsp::CodeLens),
+    ExecuteCommand(lsp::ExecuteCommandParams),
+    DocumentDiagnostic(lsp::DocumentDiagnosticParams),
+    WorkspaceDiagnostic(lsp::WorkspaceDiagnosticParams),
+    Analysis(AnalysisRequest),
+}
+
+/// A request which only reads the analysis of a module, so it can be
+/// answered using the last analysis of the project.
+///
+#[derive(Debug)]
+pub enum AnalysisRequest {
     Hover(lsp::HoverParams),
     GoToDefinition(lsp::DefinitionParams),
     GoToTypeDefinition(lsp::TypeDefinitionParams),
+    GoToImplementation(lsp::ImplementationParams),
     Completion(lsp::CompletionParams),
-    CodeAction(lsp::CodeActionParams),
     SignatureHelp(lsp::SignatureHelpParams),
     DocumentSymbol(lsp::DocumentSymbolParams),
     FoldingRange(lsp::FoldingRangeParams),
-    PrepareRename(lsp::PrepareRenameParams),
-    Rename(lsp::RenameParams),
     FindReferences(lsp::ReferenceParams),
     DocumentHighlight(lsp::DocumentHighlightParams),
-    RenameFiles(lsp::RenameFilesParams),
+    SemanticTokensFull(lsp::SemanticTokensParams),
+    SemanticTokensRange(lsp::SemanticTokensRangeParams),
+    InlayHint(lsp::InlayHintParams),
+    PrepareCallHierarchy(lsp::CallHierarchyPrepareParams),
+    // Call hierarchy items are quite big, so these are boxed to keep the size
+    // of all the other requests down.
+    IncomingCalls(Box<lsp::CallHierarchyIncomingCallsParams>),
+    OutgoingCalls(Box<lsp::CallHierarchyOutgoingCallsParams>),
+    CodeLens(lsp::CodeLensParams),
+    DocumentLink(lsp::DocumentLinkParams),
+    SelectionRange(lsp::SelectionRangeParams),
+    LinkedEditingRange(lsp::LinkedEditingRangeParams),
 }
 
 impl Request {
@@ -44,17 +82,34 @@ impl Request {
                 let params = cast_request::<DocumentFormattingRequest>(request);
                 Some(Message::Request(id, Request::Format(params)))
             }
+            "textDocument/rangeFormatting" => {
+                let params = cast_request::<DocumentRangeFormattingRequest>(request);
+                Some(Message::Request(id, Request::RangeFormat(params)))
+            }
+            "textDocument/onTypeFormatting" => {
+                let params = cast_request::<DocumentOnTypeFormattingRequest>(request);
+                Some(Message::Request(id, Request::OnTypeFormat(params)))
+            }
             "textDocument/hover" => {
                 let params = cast_request::<HoverRequest>(request);
-                Some(Message::Request(id, Request::Hover(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::Hover(params)),
+                ))
             }
             "textDocument/definition" => {
                 let params = cast_request::<DefinitionRequest>(request);
-                Some(Message::Request(id, Request::GoToDefinition(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::GoToDefinition(params)),
+                ))
             }
             "textDocument/completion" => {
                 let params = cast_request::<CompletionRequest>(request);
-                Some(Message::Request(id, Request::Completion(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::Completion(params)),
+                ))
             }
             "textDocument/codeAction" => {
                 let params = cast_request::<CodeActionRequest>(request);
@@ -62,15 +117,24 @@ impl Request {
             }
             "textDocument/signatureHelp" => {
                 let params = cast_request::<SignatureHelpRequest>(request);
-                Some(Message::Request(id, Request::SignatureHelp(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::SignatureHelp(params)),
+                ))
             }
             "textDocument/documentSymbol" => {
                 let params = cast_request::<DocumentSymbolRequest>(request);
-                Some(Message::Request(id, Request::DocumentSymbol(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::DocumentSymbol(params)),
+                ))
             }
             "textDocument/foldingRange" => {
                 let params = cast_request::<FoldingRangeRequest>(request);
-                Some(Message::Request(id, Request::FoldingRange(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::FoldingRange(params)),
+                ))
             }
             "textDocument/rename" => {
                 let params = cast_request::<RenameRequest>(request);
@@ -82,29 +146,213 @@ impl Request {
             }
             "textDocument/typeDefinition" => {
                 let params = cast_request::<TypeDefinitionRequest>(request);
-                Some(Message::Request(id, Request::GoToTypeDefinition(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::GoToTypeDefinition(params)),
+                ))
+            }
+            "textDocument/implementation" => {
+                let params = cast_request::<ImplementationRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::GoToImplementation(params)),
+                ))
             }
             "textDocument/references" => {
                 let params = cast_request::<ReferencesRequest>(request);
-                Some(Message::Request(id, Request::FindReferences(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::FindReferences(params)),
+                ))
             }
             "textDocument/documentHighlight" => {
                 let params = cast_request::<DocumentHighlightRequest>(request);
-                Some(Message::Request(id, Request::DocumentHighlight(params)))
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::DocumentHighlight(params)),
+                ))
             }
             "workspace/willRenameFiles" => {
                 let params = cast_request::<WillRenameFilesRequest>(request);
                 Some(Message::Request(id, Request::RenameFiles(params)))
             }
+            "workspace/symbol" => {
+                let params = cast_request::<WorkspaceSymbolRequest>(request);
+                Some(Message::Request(id, Request::WorkspaceSymbol(params)))
+            }
+            "textDocument/semanticTokens/full" => {
+                let params = cast_request::<SemanticTokensRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::SemanticTokensFull(params)),
+                ))
+            }
+            "textDocument/semanticTokens/range" => {
+                let params = cast_request::<SemanticTokensRangeRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::SemanticTokensRange(params)),
+                ))
+            }
+            "textDocument/inlayHint" => {
+                let params = cast_request::<InlayHintRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::InlayHint(params)),
+                ))
+            }
+            "textDocument/prepareCallHierarchy" => {
+                let params = cast_request::<CallHierarchyPrepareRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::PrepareCallHierarchy(params)),
+                ))
+            }
+            "callHierarchy/incomingCalls" => {
+                let params = cast_request::<CallHierarchyIncomingCallsRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::IncomingCalls(Box::new(params))),
+                ))
+            }
+            "callHierarchy/outgoingCalls" => {
+                let params = cast_request::<CallHierarchyOutgoingCallsRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::OutgoingCalls(Box::new(params))),
+                ))
+            }
+            "textDocument/codeLens" => {
+                let params = cast_request::<CodeLensRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::CodeLens(params)),
+                ))
+            }
+            "codeLens/resolve" => {
+                let params = cast_request::<CodeLensResolveRequest>(request);
+                Some(Message::Request(id, Request::CodeLensResolve(params)))
+            }
+            "textDocument/documentLink" => {
+                let params = cast_request::<DocumentLinkRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::DocumentLink(params)),
+                ))
+            }
+            "workspace/executeCommand" => {
+                let params = cast_request::<ExecuteCommandRequest>(request);
+                Some(Message::Request(id, Request::ExecuteCommand(params)))
+            }
+            "textDocument/selectionRange" => {
+                let params = cast_request::<SelectionRangeRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::SelectionRange(params)),
+                ))
+            }
+            "textDocument/linkedEditingRange" => {
+                let params = cast_request::<LinkedEditingRangeRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::Analysis(AnalysisRequest::LinkedEditingRange(params)),
+                ))
+            }
+            "textDocument/diagnostic" => {
+                let params = cast_request::<DocumentDiagnosticRequest>(request);
+                Some(Message::Request(id, Request::DocumentDiagnostic(params)))
+            }
+            "workspace/diagnostic" => {
+                let params = cast_request::<WorkspaceDiagnosticRequest>(request);
+                Some(Message::Request(id, Request::WorkspaceDiagnostic(params)))
+            }
             _ => None,
         }
     }
+
+    /// The file a request is about, if the request only reads the code or
+    /// its analysis and so can be answered using the last analysis of the
+    /// project. Other requests need any pending changes to be compiled first.
+    ///
+    fn read_only_document(&self) -> Option<&lsp::Uri> {
+        match self {
+            Request::Format(params) => Some(&params.text_document.uri),
+            Request::RangeFormat(params) => Some(&params.text_document.uri),
+            Request::OnTypeFormat(params) => Some(&params.text_document.uri),
+            Request::Analysis(request) => Some(request.document()),
+
+            // Code actions, renames and commands change the code, and
+            // diagnostics have to be up to date, so these always work with
+            // the latest code.
+            Request::CodeAction(_)
+            | Request::PrepareRename(_)
+            | Request::Rename(_)
+            | Request::RenameFiles(_)
+            | Request::WorkspaceSymbol(_)
+            | Request::CodeLensResolve(_)
+            | Request::ExecuteCommand(_)
+            | Request::DocumentDiagnostic(_)
+            | Request::WorkspaceDiagnostic(_) => None,
+        }
+    }
+}
+
+impl AnalysisRequest {
+    /// The file the request is about.
+    pub fn document(&self) -> &lsp::Uri {
+        match self {
+            AnalysisRequest::Hover(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::GoToDefinition(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::GoToTypeDefinition(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::GoToImplementation(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::Completion(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::SignatureHelp(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::DocumentSymbol(params) => &params.text_document.uri,
+            AnalysisRequest::FoldingRange(params) => &params.text_document.uri,
+            AnalysisRequest::FindReferences(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::DocumentHighlight(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::SemanticTokensFull(params) => &params.text_document.uri,
+            AnalysisRequest::SemanticTokensRange(params) => &params.text_document.uri,
+            AnalysisRequest::InlayHint(params) => &params.text_document.uri,
+            AnalysisRequest::PrepareCallHierarchy(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+            AnalysisRequest::IncomingCalls(params) => &params.item.uri,
+            AnalysisRequest::OutgoingCalls(params) => &params.item.uri,
+            AnalysisRequest::CodeLens(params) => &params.text_document.uri,
+            AnalysisRequest::DocumentLink(params) => &params.text_document.uri,
+            AnalysisRequest::SelectionRange(params) => &params.text_document.uri,
+            AnalysisRequest::LinkedEditingRange(params) => {
+                &params.text_document_position_params.text_document.uri
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Notification {
-    /// A Gleam file has been modified in memory, and the new text is provided.
-    SourceFileChangedInMemory { path: Utf8PathBuf, text: String },
+    /// A Gleam file has been modified in memory, and the changes made to its
+    /// text are provided.
+    SourceFileChangedInMemory {
+        path: Utf8PathBuf,
+        changes: Vec<TextDocumentContentChangeEvent>,
+    },
     /// A Gleam file has been opened in the editor.
     SourceFileOpened { path: Utf8PathBuf, text: String },
     /// A Gleam file has been closed in the editor.
@@ -113,6 +361,11 @@ pub enum Notification {
     SourceFileSaved { path: Utf8PathBuf },
     /// gleam.toml has changed.
     ConfigFileChanged { path: Utf8PathBuf },
+    /// Workspace folders have been opened or closed in the editor.
+    WorkspaceFoldersChanged {
+        added: Vec<Utf8PathBuf>,
+        removed: Vec<Utf8PathBuf>,
+    },
     /// It's time to compile all open projects.
     CompilePlease,
 }
@@ -130,14 +383,9 @@ impl Notification {
             }
             "textDocument/didChange" => {
                 let params = cast_notification::<DidChangeTextDocumentNotification>(notification);
-                let TextDocumentContentChangeEvent::TextDocumentContentChangeWholeDocument(change) =
-                    params.content_changes.into_iter().next_back()?
-                else {
-                    return None;
-                };
                 let notification = Notification::SourceFileChangedInMemory {
                     path: super::path(&params.text_document.text_document_identifier.uri),
-                    text: change.text,
+                    changes: params.content_changes,
                 };
                 Some(Message::Notification(notification))
             }
@@ -162,14 +410,44 @@ impl Notification {
                 };
                 Some(Message::Notification(notification))
             }
+            "workspace/didChangeWorkspaceFolders" => {
+                let params =
+                    cast_notification::<DidChangeWorkspaceFoldersNotification>(notification);
+                let paths = |folders: Vec<lsp::WorkspaceFolder>| {
+                    folders
+                        .iter()
+                        .map(|folder| super::path(&folder.uri))
+                        .collect()
+                };
+                let notification = Notification::WorkspaceFoldersChanged {
+                    added: paths(params.event.added),
+                    removed: paths(params.event.removed),
+                };
+                Some(Message::Notification(notification))
+            }
             _ => None,
         }
     }
+
+    /// The file changed by this notification. `None` if the notification
+    /// could change the analysis of any file, such as a change to `gleam.toml`.
+    ///
+    fn changed_file(&self) -> Option<&Utf8Path> {
+        match self {
+            Notification::SourceFileChangedInMemory { path, .. }
+            | Notification::SourceFileOpened { path, .. }
+            | Notification::SourceFileClosed { path }
+            | Notification::SourceFileSaved { path } => Some(path),
+            Notification::ConfigFileChanged { .. }
+            | Notification::WorkspaceFoldersChanged { .. }
+            | Notification::CompilePlease => None,
+        }
+    }
 }
 
 pub enum Next {
     MorePlease,
-    Handle(Vec<Message>),
+    Handle,
     Stop,
 }
 
@@ -179,15 +457,28 @@ pub enum Next {
 /// - A short pause in messages is detected, indicating the programmer has
 ///   stopped typing for a moment and would benefit from feedback.
 /// - A request type message is received, which requires an immediate response.
+///   Any messages the client has already sent are taken too, so requests the
+///   client has cancelled in the meantime can be dropped without doing any work.
+///
+/// The messages are then handed to the server one at a time. While it is busy
+/// compiling a project it can take any messages that have since arrived, so
+/// that requests are cancelled or answered without waiting for it to finish.
 ///
 pub struct MessageBuffer {
     messages: Vec<Message>,
+    /// The messages given to the server to handle, which it has not got to yet.
+    handling: VecDeque<Message>,
+    /// A shutdown request received while the server was busy, which is
+    /// handled once it is done.
+    shutdown_request: Option<lsp_server::Request>,
 }
 
 impl MessageBuffer {
     pub fn new() -> Self {
         Self {
             messages: Vec::new(),
+            handling: VecDeque::new(),
+            shutdown_request: None,
         }
     }
 
@@ -195,46 +486,124 @@ impl MessageBuffer {
         let pause = Duration::from_millis(100);
 
         // If the buffer is empty, wait indefinitely for the first message.
-        // If the buffer is not empty, wait for a short time to see if more messages are
-        // coming before processing the ones we have.
-        let message = if self.messages.is_empty() {
+        // If there's a request waiting for a response only take the messages
+        // that have already arrived.
+        // Otherwise wait for a short time to see if more messages are coming
+        // before processing the ones we have.
+        let message = if let Some(request) = self.shutdown_request.take() {
+            Some(lsp_server::Message::Request(request))
+        } else if self.messages.is_empty() {
             Some(conn.receiver.recv().expect("Receiving LSP message"))
+        } else if self.has_request() {
+            conn.receiver.try_recv().ok()
         } else {
             conn.receiver.recv_timeout(pause).ok()
         };
 
         // If have have not received a message then it means there is a pause in the
-        // messages from the client, implying the programmer has stopped typing. Process
-        // the currently enqueued messages.
+        // messages from the client, implying the programmer has stopped typing, or
+        // that we have all the messages sent before a request. Process the currently
+        // enqueued messages.
         let message = match message {
             Some(message) => message,
             None => {
-                // A compile please message it added in the instance of this
-                // pause of activity so that the client gets feedback on the
-                // state of the code as it is now.
-                self.push_compile_please_message();
-                return Next::Handle(self.take_messages());
+                let messages = self.take_messages();
+                self.handling.extend(messages);
+                return Next::Handle;
             }
         };
 
         match message {
             lsp_server::Message::Request(r) if self.shutdown(conn, &r) => Next::Stop,
+            message => self.buffer(conn, message),
+        }
+    }
+
+    /// The next message for the server to handle, after `receive` has told it
+    /// to handle the messages.
+    ///
+    pub fn next_message(&mut self) -> Option<Message> {
+        self.handling.pop_front()
+    }
+
+    /// Takes the messages the client has sent while the server was busy,
+    /// without waiting for any more. Cancelled requests are dropped straight
+    /// away, even if they have already been given to the server to handle.
+    ///
+    pub fn receive_available(&mut self, conn: &lsp_server::Connection) {
+        while self.shutdown_request.is_none()
+            && let Ok(message) = conn.receiver.try_recv()
+        {
+            match message {
+                lsp_server::Message::Request(r) if r.method == "shutdown" => {
+                    self.shutdown_request = Some(r);
+                }
+                message => _ = self.buffer(conn, message),
+            }
+        }
+    }
+
+    /// Takes the requests which only read the analysis of a file that has no
+    /// pending changes before them, and for which `can_answer` returns true.
+    /// These can be answered straight away, while the server is busy.
+    ///
+    pub fn take_analysis_requests(
+        &mut self,
+        mut can_answer: impl FnMut(&lsp::Uri) -> bool,
+    ) -> Vec<(lsp_server::RequestId, AnalysisRequest)> {
+        let mut requests = Vec::new();
+        let mut changed_files = HashSet::new();
+        let mut everything_changed = false;
+
+        let mut keep = |message: Message| match message {
+            Message::Notification(Notification::CompilePlease) => Some(message),
+
+            Message::Notification(notification) => {
+                match notification.changed_file() {
+                    Some(path) => _ = changed_files.insert(path.to_path_buf()),
+                    None => everything_changed = true,
+                }
+                Some(Message::Notification(notification))
+            }
+
+            Message::Request(id, Request::Analysis(request))
+                if !everything_changed
+                    && !changed_files.contains(&super::path(request.document()))
+                    && can_answer(request.document()) =>
+            {
+                requests.push((id, request));
+                None
+            }
+
+            Message::Request(..) => Some(message),
+        };
+
+        // The messages being handled were received first, so they are
+        // checked for changes first.
+        let handling = std::mem::take(&mut self.handling);
+        self.handling = handling.into_iter().filter_map(&mut keep).collect();
+        let messages = std::mem::take(&mut self.messages);
+        self.messages = messages.into_iter().filter_map(&mut keep).collect();
+
+        requests
+    }
+
+    fn buffer(&mut self, conn: &lsp_server::Connection, message: lsp_server::Message) -> Next {
+        match message {
             lsp_server::Message::Request(r) => self.request(r),
             lsp_server::Message::Response(r) => self.response(r),
+            lsp_server::Message::Notification(n) if n.method == "$/cancelRequest" => {
+                self.cancel(conn, n)
+            }
             lsp_server::Message::Notification(n) => self.notification(n),
         }
     }
 
     fn request(&mut self, r: lsp_server::Request) -> Next {
-        let Some(message) = Request::extract(r) else {
-            return Next::MorePlease;
-        };
-
-        // Compile the code prior to attempting to process the response, to
-        // ensure that the response is based on the latest code.
-        self.push_compile_please_message();
-        self.messages.push(message);
-        Next::Handle(self.take_messages())
+        if let Some(message) = Request::extract(r) {
+            self.messages.push(message);
+        }
+        Next::MorePlease
     }
 
     fn notification(&mut self, n: lsp_server::Notification) -> Next {
@@ -247,22 +616,101 @@ impl MessageBuffer {
         Next::MorePlease
     }
 
+    /// The client no longer needs the response to a request. If the server
+    /// has not answered it yet it is dropped, and the client is told that it
+    /// has been cancelled.
+    ///
+    fn cancel(&mut self, conn: &lsp_server::Connection, n: lsp_server::Notification) -> Next {
+        let params = cast_notification::<CancelNotification>(n);
+        let id = match params.id {
+            lsp::Id::Int(id) => lsp_server::RequestId::from(id),
+            lsp::Id::String(id) => lsp_server::RequestId::from(id),
+        };
+
+        let is_cancelled = |message: &Message| match message {
+            Message::Request(request_id, _) => *request_id == id,
+            Message::Notification(_) => false,
+        };
+        let cancelled = if let Some(position) = self.messages.iter().position(is_cancelled) {
+            Some(self.messages.remove(position))
+        } else if let Some(position) = self.handling.iter().position(is_cancelled) {
+            self.handling.remove(position)
+        } else {
+            None
+        };
+
+        if cancelled.is_some() {
+            let response = lsp_server::Response::new_err(
+                id,
+                lsp_server::ErrorCode::RequestCanceled as i32,
+                "Request cancelled".into(),
+            );
+            conn.sender
+                .send(lsp_server::Message::Response(response))
+                .expect("channel send LSP response");
+        }
+        Next::MorePlease
+    }
+
     fn response(&mut self, _: lsp_server::Response) -> Next {
         // We do not use or expect responses from the client currently.
         Next::MorePlease
     }
 
-    /// Add a `CompilePlease` message which will prompt the engine to compile
-    /// the projects.
-    ///
-    fn push_compile_please_message(&mut self) {
-        let message = Notification::CompilePlease;
-        let value = Message::Notification(message);
-        self.messages.push(value);
+    fn has_request(&self) -> bool {
+        self.messages
+            .iter()
+            .any(|message| matches!(message, Message::Request(..)))
     }
 
+    /// Takes the enqueued messages, adding `CompilePlease` messages which will
+    /// prompt the engine to compile the projects.
+    ///
+    /// The code is compiled before each request so that the response is based
+    /// on the latest code, unless the request only reads the analysis of a
+    /// file which has not changed. Those are answered using the last analysis
+    /// of the project, so they are handled before the pending changes to other
+    /// modules are compiled.
+    ///
+    /// The code is always compiled at the end so that the client gets feedback
+    /// on the state of the code as it is now.
+    ///
     fn take_messages(&mut self) -> Vec<Message> {
-        std::mem::take(&mut self.messages)
+        let mut messages = Vec::with_capacity(self.messages.len() + 1);
+        let mut changed_files = HashSet::new();
+        let mut everything_changed = false;
+
+        for message in std::mem::take(&mut self.messages) {
+            match &message {
+                Message::Notification(Notification::CompilePlease) => {
+                    changed_files.clear();
+                    everything_changed = false;
+                }
+
+                Message::Notification(notification) => match notification.changed_file() {
+                    Some(path) => _ = changed_files.insert(path.to_path_buf()),
+                    None => everything_changed = true,
+                },
+
+                Message::Request(_, request) => {
+                    let up_to_date = match request.read_only_document() {
+                        Some(uri) => {
+                            !everything_changed && !changed_files.contains(&super::path(uri))
+                        }
+                        None => false,
+                    };
+                    if !up_to_date {
+                        messages.push(Message::Notification(Notification::CompilePlease));
+                        changed_files.clear();
+                        everything_changed = false;
+                    }
+                }
+            }
+            messages.push(message);
+        }
+
+        messages.push(Message::Notification(Notification::CompilePlease));
+        messages
     }
 
     fn shutdown(
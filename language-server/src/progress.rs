@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2023 The Gleam contributors
 
+use std::{cell::Cell, rc::Rc};
+
 use debug_ignore::DebugIgnore;
 use lsp_types::{
     InitializeParams, LspAny, ProgressParams, ProgressToken, WorkDoneProgressBegin,
@@ -8,6 +10,7 @@ use lsp_types::{
 };
 
 const DOWNLOADING_TOKEN: &str = "downloading-dependencies";
+const COMPILING_TOKEN: &str = "compiling-gleam";
 
 pub trait ProgressReporter {
     fn compilation_started(&self);
@@ -21,6 +24,10 @@ pub trait ProgressReporter {
 #[derive(Debug, Clone)]
 pub struct ConnectionProgressReporter<'a> {
     connection: DebugIgnore<&'a lsp_server::Connection>,
+    /// The number of compilations started, shared by every engine's copy of
+    /// the reporter. Clients reject a progress token that has already been
+    /// ended, so each compilation creates its own token numbered by this.
+    compilations: Rc<Cell<u32>>,
 }
 
 impl<'a> ConnectionProgressReporter<'a> {
@@ -35,9 +42,14 @@ impl<'a> ConnectionProgressReporter<'a> {
         create_token(DOWNLOADING_TOKEN, connection);
         Self {
             connection: connection.into(),
+            compilations: Rc::new(Cell::new(0)),
         }
     }
 
+    fn compiling_token(&self) -> String {
+        format!("{COMPILING_TOKEN}-{}", self.compilations.get())
+    }
+
     fn send_notification(&self, token: &str, work_done: LspAny) {
         let params = ProgressParams {
             token: ProgressToken::String(token.to_string()),
@@ -56,13 +68,15 @@ impl<'a> ConnectionProgressReporter<'a> {
 
 impl ProgressReporter for ConnectionProgressReporter<'_> {
     fn compilation_started(&self) {
-        // Do nothing. This is only used for tests currently.
-        // In future we could make this emit a message to the client if compilation is taking a
-        // long time.
+        self.compilations.set(self.compilations.get() + 1);
+        let token = self.compiling_token();
+        create_token(&token, &self.connection);
+        let title = "Compiling Gleam";
+        self.send_notification(&token, begin_message(title));
     }
 
     fn compilation_finished(&self) {
-        // Do nothing. This is only used for tests currently.
+        self.send_notification(&self.compiling_token(), end_message());
     }
 
     fn dependency_downloading_started(&self) {
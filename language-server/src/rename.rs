@@ -47,7 +47,7 @@ pub enum RenameOutcome {
 /// Error code for when a request has invalid params as described in:
 /// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#errorCodes
 ///
-const INVALID_PARAMS: i32 = -32602;
+pub const INVALID_PARAMS: i32 = -32602;
 
 impl RenameOutcome {
     /// Turns the outcome of renaming into a value that's suitable to be used as
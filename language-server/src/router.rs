@@ -3,17 +3,19 @@
 
 use gleam_core::{
     Error, Result,
-    build::SourceFingerprint,
+    build::{DependencyCache, SourceFingerprint},
+    config::Workspace,
     error::{FileIoAction, FileKind},
     io::{BeamCompilerIO, CommandExecutor, FileSystemReader, FileSystemWriter},
     paths::ProjectPaths,
 };
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    collections::{HashMap, HashSet, hash_map::Entry},
     time::SystemTime,
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
 
 use super::{
     DownloadDependencies, MakeLocker, engine::LanguageServerEngine, feedback::FeedbackBookKeeper,
@@ -25,13 +27,21 @@ use super::{
 /// an instance of the language server engine for each project.
 ///
 /// This router is responsible for finding or creating an engine for a given
-/// file using the nearest parent `gleam.toml` file.
+/// file using the nearest parent `gleam.toml` file. A project that is a member
+/// of a workspace shares the manifest and build directory of the workspace.
+///
+/// The projects of the workspace folders open in the editor get an engine as
+/// soon as their folder is opened, and lose it once it is closed.
 ///
 #[derive(Debug)]
 pub(crate) struct Router<IO, Reporter> {
     io: FileSystemProxy<IO>,
     engines: HashMap<Utf8PathBuf, Project<IO, Reporter>>,
     progress_reporter: Reporter,
+    workspace_folders: HashSet<Utf8PathBuf>,
+    /// Shared by the compilers of all the engines, so that the modules of a
+    /// Hex package used by several projects are only loaded once.
+    dependency_cache: DependencyCache,
 }
 
 impl<IO, Reporter> Router<IO, Reporter>
@@ -52,6 +62,8 @@ where
             io,
             engines: HashMap::new(),
             progress_reporter,
+            workspace_folders: HashSet::new(),
+            dependency_cache: DependencyCache::new(),
         }
     }
 
@@ -59,6 +71,14 @@ where
         find_gleam_project_parent(&self.io, path)
     }
 
+    /// The paths of the project the given file is in, if any.
+    pub fn project_paths(&self, path: &Utf8Path) -> Result<Option<ProjectPaths>> {
+        match find_gleam_project_parent(&self.io, path) {
+            Some(root) => project_paths(&self.io, root).map(Some),
+            None => Ok(None),
+        }
+    }
+
     pub fn project_for_path(
         &mut self,
         path: Utf8PathBuf,
@@ -74,15 +94,16 @@ where
             path
         };
 
-        // If the gleam.toml has changed or the build directory is missing
-        // (e.g. `gleam clean`), then discard the project as the target,
-        // deps, etc may have changed and we need to rebuild taking them into
-        // account.
+        // If the gleam.toml or the workspace config has changed or the build
+        // directory is missing (e.g. `gleam clean`), then discard the project
+        // as the target, deps, etc may have changed and we need to rebuild
+        // taking them into account.
         if let Some(project) = self.engines.get(&path) {
-            let paths = ProjectPaths::new(path.clone());
+            let paths = &project.engine.paths;
 
             if !self.io.exists(&paths.build_directory())
-                || Self::gleam_toml_changed(&paths, project, &self.io)?
+                || Self::gleam_toml_changed(paths, project, &self.io)?
+                || Self::workspace_config_changed(paths, project, &self.io)?
             {
                 let _ = self.engines.remove(&path);
             }
@@ -92,13 +113,37 @@ where
         Ok(Some(match self.engines.entry(path.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
-                let project =
+                let mut project =
                     Self::new_project(path, self.io.clone(), self.progress_reporter.clone())?;
+                project.engine.compiler.project_compiler.dependency_cache =
+                    Some(self.dependency_cache.clone());
                 entry.insert(project)
             }
         }))
     }
 
+    /// The root of the project the given file is in, if that project doesn't
+    /// have an engine yet and so has never been compiled.
+    pub fn unloaded_project_path(&self, path: &Utf8Path) -> Option<Utf8PathBuf> {
+        find_gleam_project_parent(&self.io, path).filter(|root| !self.engines.contains_key(root))
+    }
+
+    /// The roots of the projects of the open workspace folders that don't have
+    /// an engine yet.
+    pub fn unloaded_workspace_projects(&self) -> Vec<Utf8PathBuf> {
+        self.workspace_folders
+            .iter()
+            .filter_map(|folder| self.unloaded_project_path(folder))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// All the projects that currently have an engine running.
+    pub fn projects(&mut self) -> impl Iterator<Item = &mut Project<IO, Reporter>> {
+        self.engines.values_mut()
+    }
+
     /// Has gleam.toml changed since the last time we saw this project?
     fn gleam_toml_changed(
         paths: &ProjectPaths,
@@ -120,6 +165,73 @@ where
         Ok(gleam_toml_changed)
     }
 
+    /// Has the config of the workspace this project is a member of changed
+    /// since the last time we saw this project? Its members could have
+    /// changed, and with them the dependencies shared by the workspace.
+    fn workspace_config_changed(
+        paths: &ProjectPaths,
+        project: &Project<IO, Reporter>,
+        io: &FileSystemProxy<IO>,
+    ) -> Result<bool, Error> {
+        let (Some(config_path), Some((modification_time, fingerprint))) =
+            (paths.workspace_config(), &project.workspace_config)
+        else {
+            return Ok(false);
+        };
+
+        // The workspace could have been deleted.
+        if !io.is_file(&config_path) {
+            return Ok(true);
+        }
+        if io.modification_time(&config_path)? == *modification_time {
+            return Ok(false);
+        }
+        let toml = io.read(&config_path)?;
+        Ok(*fingerprint != SourceFingerprint::new(&toml))
+    }
+
+    /// Opens a workspace folder, starting an engine for the Gleam project it
+    /// is or is inside of, if any. The root of that project is returned.
+    pub fn add_workspace_folder(&mut self, folder: Utf8PathBuf) -> Result<Option<Utf8PathBuf>> {
+        let root = find_gleam_project_parent(&self.io, &folder);
+        _ = self.workspace_folders.insert(folder);
+        let Some(root) = root else {
+            return Ok(None);
+        };
+        _ = self.project_for_path(root.clone())?;
+        Ok(Some(root))
+    }
+
+    /// Closes a workspace folder, shutting down the engines of the projects
+    /// in it, unless they are also in another folder that is still open. The
+    /// projects that have been shut down are returned.
+    pub fn remove_workspace_folder(&mut self, folder: &Utf8Path) -> Vec<Project<IO, Reporter>> {
+        if !self.workspace_folders.remove(folder) {
+            return vec![];
+        }
+
+        let folder_project = find_gleam_project_parent(&self.io, folder);
+        let roots = self
+            .engines
+            .keys()
+            .filter(|root| root.starts_with(folder) || folder_project.as_ref() == Some(*root))
+            .filter(|root| !self.in_workspace_folder(root))
+            .cloned()
+            .collect_vec();
+
+        roots
+            .iter()
+            .filter_map(|root| self.engines.remove(root))
+            .collect()
+    }
+
+    fn in_workspace_folder(&self, root: &Utf8Path) -> bool {
+        self.workspace_folders.iter().any(|folder| {
+            root.starts_with(folder)
+                || find_gleam_project_parent(&self.io, folder).as_deref() == Some(root)
+        })
+    }
+
     pub fn delete_engine_for_path(&mut self, path: &Utf8Path) {
         if let Some(path) = find_gleam_project_parent(&self.io, path) {
             _ = self.engines.remove(&path);
@@ -132,7 +244,15 @@ where
         progress_reporter: Reporter,
     ) -> Result<Project<IO, Reporter>, Error> {
         tracing::info!(?path, "creating_new_language_server_engine");
-        let paths = ProjectPaths::new(path);
+        let paths = project_paths(&io, path)?;
+        let workspace_config = match paths.workspace_config() {
+            Some(path) => {
+                let modification_time = io.modification_time(&path)?;
+                let toml = io.read(&path)?;
+                Some((modification_time, SourceFingerprint::new(&toml)))
+            }
+            None => None,
+        };
         let config_path = paths.root_config();
         let modification_time = io.modification_time(&config_path)?;
         let toml = io.read(&config_path)?;
@@ -148,6 +268,7 @@ where
             feedback: FeedbackBookKeeper::default(),
             gleam_toml_modification_time: modification_time,
             gleam_toml_fingerprint: SourceFingerprint::new(&toml),
+            workspace_config,
         };
         Ok(project)
     }
@@ -199,6 +320,21 @@ pub(crate) struct Project<A, B> {
     pub feedback: FeedbackBookKeeper,
     pub gleam_toml_modification_time: SystemTime,
     pub gleam_toml_fingerprint: SourceFingerprint,
+    /// The modification time and fingerprint of the config of the workspace
+    /// the project is a member of, if any.
+    pub workspace_config: Option<(SystemTime, SourceFingerprint)>,
+}
+
+/// The paths of the project with the given root directory, which share the
+/// manifest and build directory of its workspace if it is a member of one.
+fn project_paths<IO>(io: &IO, root: Utf8PathBuf) -> Result<ProjectPaths>
+where
+    IO: FileSystemReader,
+{
+    match Workspace::containing_package(&root, io)? {
+        Some(workspace) => Ok(ProjectPaths::new_workspace_member(root, workspace.root)),
+        None => Ok(ProjectPaths::new(root)),
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::sync::Arc;
+
+use gleam_core::{
+    ast::{
+        SrcSpan, TypeAst, TypedClause, TypedCustomType, TypedExpr, TypedFunction, TypedModule,
+        TypedModuleConstant, TypedPattern, TypedPipelineAssignment, TypedStatement, TypedTypeAlias,
+        visit::{self, Visit},
+    },
+    line_numbers::LineNumbers,
+    type_::{Type, TypedCallArg},
+};
+use lsp_types::{Position, Range, SelectionRange};
+
+use crate::src_span_to_lsp_range;
+
+/// Returns the ranges an editor can expand the selection to, starting from
+/// the given position: each range is the one of a node of the AST containing
+/// the position, and its parent is the range of the node containing it.
+///
+/// For example, expanding the selection from a variable passed to a function
+/// in a pipeline would go through the argument, the call, the pipeline, the
+/// statement it is in, the body of the function and finally the function
+/// itself.
+///
+pub fn selection_range(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    position: Position,
+) -> SelectionRange {
+    let mut collector = SelectionRangeCollector {
+        byte_index: line_numbers.byte_index(position),
+        spans: vec![],
+    };
+    collector.visit_typed_module(module);
+
+    let mut selection_range = None;
+    for span in collector.spans {
+        selection_range = Some(SelectionRange {
+            range: src_span_to_lsp_range(span, line_numbers),
+            parent: selection_range.map(Box::new),
+        });
+    }
+
+    // If the position is not inside any node, the only thing that can be
+    // selected is the position itself.
+    selection_range.unwrap_or(SelectionRange {
+        range: Range::new(position, position),
+        parent: None,
+    })
+}
+
+struct SelectionRangeCollector {
+    byte_index: u32,
+    /// The spans of the nodes containing the position, from the outermost to
+    /// the innermost.
+    spans: Vec<SrcSpan>,
+}
+
+impl SelectionRangeCollector {
+    fn push(&mut self, span: SrcSpan) {
+        if !span.contains(self.byte_index) {
+            return;
+        }
+
+        match self.spans.last() {
+            // Different nodes can have the same location, for example a
+            // statement and the expression it is made of: there's no point in
+            // selecting the same range twice.
+            Some(last) if *last == span => (),
+            // Nodes are visited from the outermost to the innermost, so each
+            // node we find must be inside the previous one. Nodes generated by
+            // the compiler, or adjacent nodes sharing a boundary with the
+            // position, might not be and are skipped.
+            Some(last) if !last.contains_span(span) => (),
+            Some(_) | None => self.spans.push(span),
+        }
+    }
+
+    fn push_body(&mut self, body: &[TypedStatement]) {
+        if let (Some(first), Some(last)) = (body.first(), body.last()) {
+            self.push(first.location().merge(&last.location()));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for SelectionRangeCollector {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.push(function.full_location());
+        for argument in &function.arguments {
+            self.push(argument.location);
+        }
+        self.push_body(&function.body);
+        visit::visit_typed_function(self, function);
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        self.push(constant.location.merge(&constant.value.location()));
+        visit::visit_typed_module_constant(self, constant);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        self.push(custom_type.full_location());
+        for constructor in &custom_type.constructors {
+            self.push(constructor.location);
+            for argument in &constructor.arguments {
+                self.push(argument.location);
+            }
+        }
+        visit::visit_typed_custom_type(self, custom_type);
+    }
+
+    fn visit_typed_type_alias(&mut self, type_alias: &'ast TypedTypeAlias) {
+        self.push(type_alias.location.merge(&type_alias.type_ast.location()));
+        visit::visit_typed_type_alias(self, type_alias);
+    }
+
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        self.push(statement.location());
+        visit::visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        self.push(expression.location());
+        if let TypedExpr::Fn {
+            arguments, body, ..
+        } = expression
+        {
+            for argument in arguments {
+                self.push(argument.location);
+            }
+            self.push_body(body);
+        }
+        visit::visit_typed_expr(self, expression);
+    }
+
+    fn visit_typed_pipeline_assignment(&mut self, assignment: &'ast TypedPipelineAssignment) {
+        self.push(assignment.location);
+        visit::visit_typed_pipeline_assignment(self, assignment);
+    }
+
+    fn visit_typed_call_arg(&mut self, argument: &'ast TypedCallArg) {
+        self.push(argument.location);
+        visit::visit_typed_call_arg(self, argument);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.push(clause.location);
+        visit::visit_typed_clause(self, clause);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        self.push(pattern.location());
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_type_ast(&mut self, node: &'ast TypeAst, type_: Option<Arc<Type>>) {
+        self.push(node.location());
+        visit::visit_type_ast(self, node, type_);
+    }
+}
@@ -0,0 +1,784 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+use gleam_core::{
+    analyse::Inferred,
+    ast::{
+        self, ArgNames, AssignName, CallArg, Publicity, SrcSpan, TypeAst, TypeAstConstructor,
+        TypeAstConstructorName, TypedArg, TypedConstant, TypedExpr, TypedModule, TypedPattern,
+        visit::Visit,
+    },
+    line_numbers::LineNumbers,
+    type_::{
+        Deprecation, ModuleInterface, ModuleValueConstructor, PatternConstructor, Type,
+        TypedCallArg, ValueConstructor, ValueConstructorVariant,
+        error::{VariableDeclaration, VariableOrigin},
+    },
+};
+use lsp_types::{
+    SemanticToken, SemanticTokenModifiers, SemanticTokenTypes, SemanticTokens, SemanticTokensLegend,
+};
+
+/// The kinds of token the language server can highlight. The order of the
+/// variants must match the order of the token types in the legend returned by
+/// `legend`, as clients refer to the token types by their index.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    Namespace,
+    Type,
+    TypeParameter,
+    Parameter,
+    Variable,
+    Property,
+    EnumMember,
+    Function,
+}
+
+const TOKEN_TYPES: [SemanticTokenTypes; 8] = [
+    SemanticTokenTypes::Namespace,
+    SemanticTokenTypes::Type,
+    SemanticTokenTypes::TypeParameter,
+    SemanticTokenTypes::Parameter,
+    SemanticTokenTypes::Variable,
+    SemanticTokenTypes::Property,
+    SemanticTokenTypes::EnumMember,
+    SemanticTokenTypes::Function,
+];
+
+/// The modifiers that can be applied to a token, each one is a bit in the
+/// token's modifiers bitset. The position of each bit must match the position
+/// of the corresponding modifier in the legend returned by `legend`.
+///
+pub const DECLARATION: u32 = 1 << 0;
+pub const READONLY: u32 = 1 << 1;
+pub const DEPRECATED: u32 = 1 << 2;
+/// A public definition, or a reference to one.
+pub const PUBLIC: u32 = 1 << 3;
+/// The label of a labelled argument, both in a function's definition and when
+/// it is called.
+pub const LABELLED: u32 = 1 << 4;
+/// A function implemented with `@external`, or a reference to one.
+pub const EXTERNAL: u32 = 1 << 5;
+
+const TOKEN_MODIFIERS: [SemanticTokenModifiers; 6] = [
+    SemanticTokenModifiers::Declaration,
+    SemanticTokenModifiers::Readonly,
+    SemanticTokenModifiers::Deprecated,
+    SemanticTokenModifiers::Custom(std::borrow::Cow::Borrowed("public")),
+    SemanticTokenModifiers::Custom(std::borrow::Cow::Borrowed("labelled")),
+    SemanticTokenModifiers::Custom(std::borrow::Cow::Borrowed("external")),
+];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.into_iter().map(Into::into).collect(),
+        token_modifiers: TOKEN_MODIFIERS.into_iter().map(Into::into).collect(),
+    }
+}
+
+/// Returns the names of the token types and modifiers, in the same order as
+/// they appear in the legend. Used to make tests output readable.
+///
+#[cfg(test)]
+pub fn token_names(token: &SemanticToken) -> (String, Vec<String>) {
+    let type_ = TOKEN_TYPES
+        .get(token.token_type as usize)
+        .map(|type_| type_.to_string())
+        .unwrap_or_default();
+    let modifiers = TOKEN_MODIFIERS
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| token.token_modifiers_bitset & (1 << index) != 0)
+        .map(|(_, modifier)| modifier.to_string())
+        .collect();
+    (type_, modifiers)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    location: SrcSpan,
+    type_: TokenType,
+    modifiers: u32,
+}
+
+/// Computes the semantic tokens for a module. If a `range` is given, only the
+/// tokens overlapping with it are returned.
+///
+pub fn semantic_tokens(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+    range: Option<SrcSpan>,
+) -> SemanticTokens {
+    let mut collector = TokenCollector {
+        modules,
+        tokens: vec![],
+    };
+    collector.visit_typed_module(module);
+
+    let mut tokens = collector.tokens;
+    tokens.sort_by_key(|token| (token.location.start, token.location.end));
+
+    let mut data = Vec::with_capacity(tokens.len());
+    let mut previous_end = 0;
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+    for token in tokens {
+        // Some nodes share the same location, for example a label using the
+        // shorthand syntax `name:` is both a label and a variable. The first
+        // token wins and any other overlapping token is discarded, as clients
+        // do not support overlapping tokens.
+        if token.location.start < previous_end || token.location.start == token.location.end {
+            continue;
+        }
+        previous_end = token.location.end;
+
+        if let Some(range) = range
+            && (token.location.end <= range.start || token.location.start >= range.end)
+        {
+            continue;
+        }
+
+        let start = line_numbers.line_and_column_number(token.location.start);
+        let end = line_numbers.line_and_column_number(token.location.end);
+        // Tokens cannot span multiple lines.
+        if start.line != end.line {
+            continue;
+        }
+
+        let line = start.line - 1;
+        let column = start.column - 1;
+        let delta_start = if line == previous_line {
+            column - previous_start
+        } else {
+            column
+        };
+
+        data.push(SemanticToken {
+            delta_line: line - previous_line,
+            delta_start,
+            length: end.column - start.column,
+            token_type: token.type_ as u32,
+            token_modifiers_bitset: token.modifiers,
+        });
+        previous_line = line;
+        previous_start = column;
+    }
+
+    SemanticTokens {
+        result_id: None,
+        data,
+    }
+}
+
+struct TokenCollector<'a> {
+    modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    tokens: Vec<Token>,
+}
+
+impl TokenCollector<'_> {
+    fn push(&mut self, location: SrcSpan, type_: TokenType, modifiers: u32) {
+        self.tokens.push(Token {
+            location,
+            type_,
+            modifiers,
+        });
+    }
+
+    /// Pushes a token for a name ending where the given location ends. This is
+    /// useful for nodes whose location also includes a module qualifier or an
+    /// `as` keyword before the name.
+    ///
+    fn push_name_at_end(
+        &mut self,
+        location: SrcSpan,
+        name: &str,
+        type_: TokenType,
+        modifiers: u32,
+    ) {
+        let start = location.end.saturating_sub(name.len() as u32);
+        self.push(SrcSpan::new(start, location.end), type_, modifiers);
+    }
+
+    fn push_arguments(&mut self, arguments: &[TypedArg]) {
+        for argument in arguments {
+            match &argument.names {
+                ArgNames::Discard { .. } => {}
+                ArgNames::LabelledDiscard { label_location, .. } => {
+                    self.push(
+                        *label_location,
+                        TokenType::Parameter,
+                        DECLARATION | LABELLED,
+                    );
+                }
+                ArgNames::Named { location, .. } => {
+                    self.push(*location, TokenType::Parameter, DECLARATION);
+                }
+                ArgNames::NamedLabelled {
+                    label_location,
+                    name_location,
+                    ..
+                } => {
+                    self.push(
+                        *label_location,
+                        TokenType::Parameter,
+                        DECLARATION | LABELLED,
+                    );
+                    self.push(*name_location, TokenType::Parameter, DECLARATION);
+                }
+            }
+        }
+    }
+
+    fn push_call_arg_label<A>(&mut self, argument: &CallArg<A>) {
+        if argument.implicit.is_some() {
+            return;
+        }
+        if let Some(label) = &argument.label {
+            let start = argument.location.start;
+            let location = SrcSpan::new(start, start + label.len() as u32);
+            self.push(location, TokenType::Parameter, LABELLED);
+        }
+    }
+
+    /// Pushes a token for a reference to a value defined in a module.
+    fn push_module_value(&mut self, location: SrcSpan, module: &EcoString, name: &EcoString) {
+        let Some(value) = self
+            .modules
+            .get(module)
+            .and_then(|module| module.values.get(name))
+        else {
+            return;
+        };
+        let (type_, modifiers) = value_token(value);
+        self.push(location, type_, modifiers);
+    }
+
+    /// Returns the modifiers for a reference to a type. The type being
+    /// referenced is looked up using the type inferred for the annotation,
+    /// so modifiers are only added if the annotation uses the type's name
+    /// rather than an alias.
+    ///
+    fn type_modifiers(&self, name: &EcoString, type_: Option<&Arc<Type>>) -> u32 {
+        let Some(Type::Named {
+            module,
+            name: type_name,
+            ..
+        }) = type_.map(|type_| type_.as_ref())
+        else {
+            return 0;
+        };
+        if name != type_name {
+            return 0;
+        }
+
+        self.modules
+            .get(module)
+            .and_then(|module| module.types.get(type_name))
+            .map(|type_| {
+                publicity_modifier(&type_.publicity) | deprecation_modifier(&type_.deprecation)
+            })
+            .unwrap_or(0)
+    }
+}
+
+fn value_token(value: &ValueConstructor) -> (TokenType, u32) {
+    let modifiers = publicity_modifier(&value.publicity) | deprecation_modifier(&value.deprecation);
+    match &value.variant {
+        ValueConstructorVariant::LocalVariable { origin, .. } => match origin.declaration {
+            VariableDeclaration::FunctionParameter { .. } => (TokenType::Parameter, 0),
+            VariableDeclaration::LetPattern
+            | VariableDeclaration::UsePattern
+            | VariableDeclaration::ClausePattern
+            | VariableDeclaration::Generated => (TokenType::Variable, 0),
+        },
+        ValueConstructorVariant::ModuleConstant { .. } => {
+            (TokenType::Variable, modifiers | READONLY)
+        }
+        ValueConstructorVariant::ModuleFn {
+            external_erlang,
+            external_javascript,
+            ..
+        } => {
+            let external = if external_erlang.is_some() || external_javascript.is_some() {
+                EXTERNAL
+            } else {
+                0
+            };
+            (TokenType::Function, modifiers | external)
+        }
+        ValueConstructorVariant::Record { .. } => (TokenType::EnumMember, modifiers),
+    }
+}
+
+/// Variables introduced by the compiler when desugaring pipelines, function
+/// captures, `use` expressions and so on do not appear in the source code, so
+/// they are not highlighted. Their names all start with an underscore, which
+/// is something a variable written by a programmer can never be referenced
+/// by, as it would be a discard.
+///
+fn is_generated(name: &str) -> bool {
+    name.starts_with('_')
+}
+
+fn publicity_modifier(publicity: &Publicity) -> u32 {
+    if publicity.is_public() { PUBLIC } else { 0 }
+}
+
+fn deprecation_modifier(deprecation: &Deprecation) -> u32 {
+    if deprecation.is_deprecated() {
+        DEPRECATED
+    } else {
+        0
+    }
+}
+
+impl<'ast> Visit<'ast> for TokenCollector<'_> {
+    fn visit_typed_import(&mut self, import: &'ast ast::TypedImport) {
+        self.push(import.module_location, TokenType::Namespace, 0);
+        if let Some((AssignName::Variable(name), location)) = &import.as_name {
+            self.push_name_at_end(*location, name, TokenType::Namespace, DECLARATION);
+        }
+
+        let module = self.modules.get(&import.module);
+        for value in &import.unqualified_values {
+            let Some(constructor) = module.and_then(|module| module.values.get(&value.name)) else {
+                continue;
+            };
+            let (type_, modifiers) = value_token(constructor);
+            self.push(value.imported_name_location, type_, modifiers);
+            if let Some(as_name) = &value.as_name {
+                self.push_name_at_end(value.location, as_name, type_, modifiers | DECLARATION);
+            }
+        }
+
+        for type_ in &import.unqualified_types {
+            let modifiers = module
+                .and_then(|module| module.types.get(&type_.name))
+                .map(|type_| {
+                    publicity_modifier(&type_.publicity) | deprecation_modifier(&type_.deprecation)
+                })
+                .unwrap_or(0);
+            self.push(type_.imported_name_location, TokenType::Type, modifiers);
+            if let Some(as_name) = &type_.as_name {
+                self.push_name_at_end(
+                    type_.location,
+                    as_name,
+                    TokenType::Type,
+                    modifiers | DECLARATION,
+                );
+            }
+        }
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast ast::TypedModuleConstant) {
+        let modifiers = DECLARATION
+            | READONLY
+            | publicity_modifier(&constant.publicity)
+            | deprecation_modifier(&constant.deprecation);
+        self.push(constant.name_location, TokenType::Variable, modifiers);
+        ast::visit::visit_typed_module_constant(self, constant);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast ast::TypedCustomType) {
+        let publicity = publicity_modifier(&custom_type.publicity);
+        let modifiers = DECLARATION | publicity | deprecation_modifier(&custom_type.deprecation);
+        self.push(custom_type.name_location, TokenType::Type, modifiers);
+
+        for (location, _) in &custom_type.parameters {
+            self.push(*location, TokenType::TypeParameter, DECLARATION);
+        }
+
+        for constructor in &custom_type.constructors {
+            let modifiers =
+                DECLARATION | publicity | deprecation_modifier(&constructor.deprecation);
+            self.push(constructor.name_location, TokenType::EnumMember, modifiers);
+            for argument in &constructor.arguments {
+                if let Some((location, _)) = &argument.label {
+                    self.push(*location, TokenType::Property, DECLARATION | publicity);
+                }
+            }
+        }
+
+        ast::visit::visit_typed_custom_type(self, custom_type);
+    }
+
+    fn visit_typed_type_alias(&mut self, type_alias: &'ast ast::TypedTypeAlias) {
+        let modifiers = DECLARATION
+            | publicity_modifier(&type_alias.publicity)
+            | deprecation_modifier(&type_alias.deprecation);
+        self.push(type_alias.name_location, TokenType::Type, modifiers);
+
+        for (location, _) in &type_alias.parameters {
+            self.push(*location, TokenType::TypeParameter, DECLARATION);
+        }
+
+        ast::visit::visit_typed_type_alias(self, type_alias);
+    }
+
+    fn visit_typed_function(&mut self, fun: &'ast ast::TypedFunction) {
+        if let Some((location, _)) = &fun.name {
+            let external = if fun.external_erlang.is_some() || fun.external_javascript.is_some() {
+                EXTERNAL
+            } else {
+                0
+            };
+            let modifiers = DECLARATION
+                | external
+                | publicity_modifier(&fun.publicity)
+                | deprecation_modifier(&fun.deprecation);
+            self.push(*location, TokenType::Function, modifiers);
+        }
+        self.push_arguments(&fun.arguments);
+
+        ast::visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast ast::FunctionLiteralKind,
+        arguments: &'ast [TypedArg],
+        body: &'ast vec1::Vec1<ast::TypedStatement>,
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        match kind {
+            ast::FunctionLiteralKind::Anonymous { .. } => self.push_arguments(arguments),
+            // The arguments of a function capture are generated by the compiler
+            // and have no name in the source code.
+            ast::FunctionLiteralKind::Capture { .. } => {}
+            // The arguments of a `use` callback are the variables on the left
+            // hand side of the arrow, unless the compiler had to generate an
+            // argument to destructure a more complex pattern.
+            ast::FunctionLiteralKind::Use { .. } => {
+                for argument in arguments {
+                    if let ArgNames::Named { name, location } = &argument.names
+                        && !is_generated(name)
+                    {
+                        let end = location.start + name.len() as u32;
+                        self.push(
+                            SrcSpan::new(location.start, end),
+                            TokenType::Variable,
+                            DECLARATION,
+                        );
+                    }
+                }
+            }
+        }
+        ast::visit::visit_typed_expr_fn(
+            self,
+            location,
+            type_,
+            kind,
+            arguments,
+            body,
+            return_annotation,
+        );
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        if is_generated(name) {
+            return;
+        }
+        let (type_, modifiers) = value_token(constructor);
+        self.push(*location, type_, modifiers);
+    }
+
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        field_start: &'ast u32,
+        _type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        let alias_end = location.start + module_alias.len() as u32;
+        self.push(
+            SrcSpan::new(location.start, alias_end),
+            TokenType::Namespace,
+            0,
+        );
+
+        let label_location = SrcSpan::new(*field_start, location.end);
+        match constructor {
+            ModuleValueConstructor::Record { .. }
+            | ModuleValueConstructor::Fn { .. }
+            | ModuleValueConstructor::Constant { .. } => {
+                self.push_module_value(label_location, module_name, label)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_expr_record_access(
+        &mut self,
+        location: &'ast SrcSpan,
+        field_start: &'ast u32,
+        type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        index: &'ast u64,
+        record: &'ast TypedExpr,
+        documentation: &'ast Option<EcoString>,
+    ) {
+        self.push(
+            SrcSpan::new(*field_start, location.end),
+            TokenType::Property,
+            0,
+        );
+        ast::visit::visit_typed_expr_record_access(
+            self,
+            location,
+            field_start,
+            type_,
+            label,
+            index,
+            record,
+            documentation,
+        );
+    }
+
+    fn visit_typed_call_arg(&mut self, arg: &'ast TypedCallArg) {
+        self.push_call_arg_label(arg);
+        ast::visit::visit_typed_call_arg(self, arg);
+    }
+
+    fn visit_typed_clause_guard_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        _name: &'ast EcoString,
+        _type_: &'ast Arc<Type>,
+        _definition_location: &'ast SrcSpan,
+        _origin: &'ast VariableOrigin,
+    ) {
+        self.push(*location, TokenType::Variable, 0);
+    }
+
+    fn visit_typed_clause_guard_field_access(
+        &mut self,
+        label_location: &'ast SrcSpan,
+        index: &'ast Option<u64>,
+        label: &'ast EcoString,
+        type_: &'ast Arc<Type>,
+        container: &'ast ast::TypedClauseGuard,
+    ) {
+        self.push(*label_location, TokenType::Property, 0);
+        ast::visit::visit_typed_clause_guard_field_access(
+            self,
+            label_location,
+            index,
+            label,
+            type_,
+            container,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_clause_guard_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        field_start: &'ast u32,
+        _definition_location: &'ast SrcSpan,
+        _type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        _literal: &'ast TypedConstant,
+    ) {
+        let alias_end = location.start + module_alias.len() as u32;
+        self.push(
+            SrcSpan::new(location.start, alias_end),
+            TokenType::Namespace,
+            0,
+        );
+        self.push_module_value(SrcSpan::new(*field_start, location.end), module_name, label);
+    }
+
+    fn visit_typed_pattern_variable(
+        &mut self,
+        location: &'ast SrcSpan,
+        _name: &'ast EcoString,
+        _type_: &'ast Arc<Type>,
+        _origin: &'ast VariableOrigin,
+    ) {
+        self.push(*location, TokenType::Variable, DECLARATION);
+    }
+
+    fn visit_typed_pattern_assign(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        pattern: &'ast TypedPattern,
+    ) {
+        self.push(*location, TokenType::Variable, DECLARATION);
+        ast::visit::visit_typed_pattern_assign(self, location, name, pattern);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_pattern_constructor(
+        &mut self,
+        location: &'ast SrcSpan,
+        name_location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        arguments: &'ast Vec<CallArg<TypedPattern>>,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        constructor: &'ast Inferred<PatternConstructor>,
+        spread: &'ast Option<SrcSpan>,
+        type_: &'ast Arc<Type>,
+    ) {
+        if let Some((_, module_location)) = module {
+            self.push(*module_location, TokenType::Namespace, 0);
+        }
+        match constructor {
+            Inferred::Known(constructor) => {
+                self.push_module_value(*name_location, &constructor.module, &constructor.name)
+            }
+            Inferred::Unknown => self.push(*name_location, TokenType::EnumMember, 0),
+        }
+
+        ast::visit::visit_typed_pattern_constructor(
+            self,
+            location,
+            name_location,
+            name,
+            arguments,
+            module,
+            constructor,
+            spread,
+            type_,
+        );
+    }
+
+    fn visit_typed_pattern_call_arg(&mut self, arg: &'ast CallArg<TypedPattern>) {
+        self.push_call_arg_label(arg);
+        ast::visit::visit_typed_pattern_call_arg(self, arg);
+    }
+
+    fn visit_typed_pattern_string_prefix(
+        &mut self,
+        location: &'ast SrcSpan,
+        left_location: &'ast SrcSpan,
+        left_side_assignment: &'ast Option<(EcoString, SrcSpan)>,
+        right_location: &'ast SrcSpan,
+        left_side_string: &'ast EcoString,
+        right_side_assignment: &'ast AssignName,
+    ) {
+        if let Some((_, location)) = left_side_assignment {
+            self.push(*location, TokenType::Variable, DECLARATION);
+        }
+        if let AssignName::Variable(_) = right_side_assignment {
+            self.push(*right_location, TokenType::Variable, DECLARATION);
+        }
+        ast::visit::visit_typed_pattern_string_prefix(
+            self,
+            location,
+            left_location,
+            left_side_assignment,
+            right_location,
+            left_side_string,
+            right_side_assignment,
+        );
+    }
+
+    fn visit_type_ast(&mut self, node: &'ast TypeAst, inferred_type: Option<Arc<Type>>) {
+        if let TypeAst::Constructor(TypeAstConstructor { name, .. }) = node {
+            match name {
+                TypeAstConstructorName::Unqualified { name, location } => {
+                    let modifiers = self.type_modifiers(name, inferred_type.as_ref());
+                    self.push(*location, TokenType::Type, modifiers);
+                }
+                TypeAstConstructorName::Qualified {
+                    module_location,
+                    name,
+                    ..
+                } => {
+                    self.push(*module_location, TokenType::Namespace, 0);
+                    if let Some((name, location)) = name {
+                        let modifiers = self.type_modifiers(name, inferred_type.as_ref());
+                        self.push(*location, TokenType::Type, modifiers);
+                    }
+                }
+            }
+        }
+        ast::visit::visit_type_ast(self, node, inferred_type);
+    }
+
+    fn visit_type_ast_var(&mut self, location: &'ast SrcSpan, _name: &'ast EcoString) {
+        self.push(*location, TokenType::TypeParameter, 0);
+    }
+
+    fn visit_typed_constant_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        name: &'ast EcoString,
+        constructor: &'ast Option<Box<ValueConstructor>>,
+        _type_: &'ast Arc<Type>,
+    ) {
+        if let Some((_, module_location)) = module {
+            self.push(*module_location, TokenType::Namespace, 0);
+        }
+        let (type_, modifiers) = match constructor {
+            Some(constructor) => value_token(constructor),
+            None => (TokenType::Variable, 0),
+        };
+        self.push_name_at_end(*location, name, type_, modifiers);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_constant_record(
+        &mut self,
+        location: &'ast SrcSpan,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        name: &'ast EcoString,
+        arguments: &'ast Option<Vec<CallArg<TypedConstant>>>,
+        type_: &'ast Arc<Type>,
+        field_map: &'ast Inferred<gleam_core::type_::FieldMap>,
+        record_constructor: &'ast Option<Box<ValueConstructor>>,
+    ) {
+        // The name comes right after the module qualifier and its `.`, if
+        // there is one.
+        let start = match module {
+            Some((_, module_location)) => {
+                self.push(*module_location, TokenType::Namespace, 0);
+                module_location.end + 1
+            }
+            None => location.start,
+        };
+        let modifiers = match record_constructor {
+            Some(constructor) => value_token(constructor).1,
+            None => 0,
+        };
+        self.push(
+            SrcSpan::new(start, start + name.len() as u32),
+            TokenType::EnumMember,
+            modifiers,
+        );
+
+        for argument in arguments.iter().flatten() {
+            self.push_call_arg_label(argument);
+        }
+
+        ast::visit::visit_typed_constant_record(
+            self,
+            location,
+            module,
+            name,
+            arguments,
+            type_,
+            field_map,
+            record_constructor,
+        );
+    }
+}
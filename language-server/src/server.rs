@@ -2,14 +2,18 @@
 // SPDX-FileCopyrightText: 2023 The Gleam contributors
 
 use super::{
-    DownloadDependencies, MakeLocker,
+    DownloadDependencies, MakeLocker, RunProgram,
+    code_lens::{self, RunArguments},
+    configuration::Configuration,
     engine::{self, LanguageServerEngine},
-    feedback::{Feedback, FeedbackBookKeeper},
+    feedback::{DiagnosticsReport, DiagnosticsStore, Feedback, FeedbackBookKeeper},
     files::FileSystemProxy,
-    messages::{Message, MessageBuffer, Next, Notification, Request},
+    formatting,
+    messages::{AnalysisRequest, Message, MessageBuffer, Next, Notification, Request},
     progress::ConnectionProgressReporter,
+    rename::INVALID_PARAMS,
     router::Router,
-    src_span_to_lsp_range,
+    semantic_tokens, src_span_to_lsp_range, workspace_symbol,
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use debug_ignore::DebugIgnore;
@@ -43,11 +47,15 @@ use std::collections::{HashMap, HashSet};
 #[derive(Debug)]
 pub struct LanguageServer<'a, IO> {
     initialise_params: InitializeParams,
+    configuration: Configuration,
     connection: DebugIgnore<&'a lsp_server::Connection>,
     outside_of_project_feedback: FeedbackBookKeeper,
+    diagnostics: DiagnosticsStore,
+    diagnostics_refresh_requests: u64,
     router: Router<IO, ConnectionProgressReporter<'a>>,
     changed_projects: HashSet<Utf8PathBuf>,
     io: FileSystemProxy<IO>,
+    messages: DebugIgnore<MessageBuffer>,
 }
 
 impl<'a, IO> LanguageServer<'a, IO>
@@ -58,36 +66,49 @@ where
         + CommandExecutor
         + DownloadDependencies
         + MakeLocker
+        + RunProgram
         + Clone,
 {
     pub fn new(connection: &'a lsp_server::Connection, io: IO) -> Result<Self> {
         let initialise_params = initialisation_handshake(connection);
+        let configuration = Configuration::from_initialization_options(
+            initialise_params.initialization_options.as_ref(),
+        );
         let reporter = ConnectionProgressReporter::new(connection, &initialise_params);
         let io = FileSystemProxy::new(io);
         let router = Router::new(reporter, io.clone());
         Ok(Self {
             connection: connection.into(),
             initialise_params,
+            configuration,
             changed_projects: HashSet::new(),
             outside_of_project_feedback: FeedbackBookKeeper::default(),
+            diagnostics: DiagnosticsStore::default(),
+            diagnostics_refresh_requests: 0,
             router,
             io,
+            messages: MessageBuffer::new().into(),
         })
     }
 
     pub fn run(&mut self) -> Result<()> {
         self.start_watching_gleam_toml();
-        let mut buffer = MessageBuffer::new();
+        self.open_initial_workspace_folders();
 
         loop {
-            match buffer.receive(*self.connection) {
+            match self.messages.receive(*self.connection) {
                 Next::Stop => break,
                 Next::MorePlease => (),
-                Next::Handle(messages) => {
-                    for message in messages {
-                        self.handle_message(message);
-                    }
-                }
+                Next::Handle => loop {
+                    // Messages sent while the previous one was being handled
+                    // are taken first, so the client can cancel the requests
+                    // that are still to be handled.
+                    self.messages.receive_available(*self.connection);
+                    let Some(message) = self.messages.next_message() else {
+                        break;
+                    };
+                    self.handle_message(message);
+                },
             }
         }
 
@@ -104,40 +125,125 @@ where
     fn handle_request(&mut self, id: lsp_server::RequestId, request: Request) {
         let (outcome, feedback) = match request {
             Request::Format(param) => self.format(param),
-            Request::Hover(param) => self.hover(param),
-            Request::GoToDefinition(param) => self.goto_definition(param),
-            Request::Completion(param) => self.completion(param),
+            Request::RangeFormat(param) => self.range_format(param),
+            Request::OnTypeFormat(param) => self.on_type_format(param),
             Request::CodeAction(param) => self.code_action(param),
-            Request::SignatureHelp(param) => self.signature_help(param),
-            Request::DocumentSymbol(param) => self.document_symbol(param),
-            Request::FoldingRange(param) => self.folding_range(param),
             Request::PrepareRename(param) => self.prepare_rename(param),
             Request::Rename(param) => self.rename(param),
-            Request::GoToTypeDefinition(param) => self.goto_type_definition(param),
-            Request::FindReferences(param) => self.find_references(param),
-            Request::DocumentHighlight(param) => self.document_highlight(param),
             Request::RenameFiles(param) => self.rename_files(param),
+            Request::WorkspaceSymbol(param) => self.workspace_symbol(param),
+            Request::CodeLensResolve(param) => self.resolve_code_lens(param),
+            Request::ExecuteCommand(param) => self.execute_command(param),
+            Request::DocumentDiagnostic(param) => self.document_diagnostic(param),
+            Request::WorkspaceDiagnostic(param) => self.workspace_diagnostic(param),
+            Request::Analysis(request) => {
+                let path = super::path(request.document());
+                let configuration = self.configuration.clone();
+                let io = self.io.clone();
+                self.respond_with_engine(path, |engine| {
+                    Self::analysis_response(engine, request, &configuration, &io)
+                })
+            }
         };
 
         self.publish_feedback(feedback);
+        send_response(&self.connection, id, outcome);
+    }
 
-        let response = match outcome {
-            Ok(payload) => lsp_server::Response {
-                id,
-                error: None,
-                result: Some(payload),
-            },
-            Err(error) => lsp_server::Response {
-                id,
-                error: Some(error),
-                result: None,
-            },
-        };
+    /// Answers a request which only reads the analysis of a module, using the
+    /// last analysis of its project.
+    ///
+    fn analysis_response(
+        engine: &mut LanguageServerEngine<IO, ConnectionProgressReporter<'a>>,
+        request: AnalysisRequest,
+        configuration: &Configuration,
+        io: &FileSystemProxy<IO>,
+    ) -> engine::Response<Json> {
+        match request {
+            AnalysisRequest::Hover(params) => json_response(engine.hover(params)),
+            AnalysisRequest::GoToDefinition(params) => {
+                json_response(engine.goto_definition(params))
+            }
+            AnalysisRequest::GoToTypeDefinition(params) => {
+                json_response(engine.goto_type_definition(params))
+            }
+            AnalysisRequest::GoToImplementation(params) => {
+                json_response(engine.goto_implementation(params))
+            }
+            AnalysisRequest::Completion(params) => {
+                let path = super::path(&params.text_document_position_params.text_document.uri);
+                match io.read(&path) {
+                    Ok(src) => json_response(
+                        engine.completion(params.text_document_position_params, src.into()),
+                    ),
+                    Err(error) => engine::Response {
+                        result: Err(error),
+                        warnings: vec![],
+                        compilation: engine::Compilation::No,
+                    },
+                }
+            }
+            AnalysisRequest::SignatureHelp(params) => json_response(engine.signature_help(params)),
+            AnalysisRequest::DocumentSymbol(params) => {
+                json_response(engine.document_symbol(params))
+            }
+            AnalysisRequest::FoldingRange(params) => json_response(engine.folding_range(params)),
+            AnalysisRequest::FindReferences(params) => {
+                json_response(engine.find_references(params))
+            }
+            AnalysisRequest::DocumentHighlight(params) => {
+                json_response(engine.document_highlight(params))
+            }
+            AnalysisRequest::SemanticTokensFull(params) => {
+                json_response(engine.semantic_tokens_full(params))
+            }
+            AnalysisRequest::SemanticTokensRange(params) => {
+                json_response(engine.semantic_tokens_range(params))
+            }
+            AnalysisRequest::InlayHint(params) => {
+                json_response(engine.inlay_hints(params, configuration.inlay_hints))
+            }
+            AnalysisRequest::PrepareCallHierarchy(params) => {
+                json_response(engine.prepare_call_hierarchy(params))
+            }
+            AnalysisRequest::IncomingCalls(params) => json_response(engine.incoming_calls(*params)),
+            AnalysisRequest::OutgoingCalls(params) => json_response(engine.outgoing_calls(*params)),
+            AnalysisRequest::CodeLens(params) => json_response(engine.code_lens(params)),
+            AnalysisRequest::DocumentLink(params) => json_response(engine.document_link(params)),
+            AnalysisRequest::SelectionRange(params) => {
+                json_response(engine.selection_range(params))
+            }
+            AnalysisRequest::LinkedEditingRange(params) => {
+                json_response(engine.linked_editing_range(params))
+            }
+        }
+    }
 
-        self.connection
-            .sender
-            .send(lsp_server::Message::Response(response))
-            .expect("channel send LSP response")
+    /// Called while a project is being compiled. Requests the client has sent
+    /// in the meantime are answered using the last analysis of the project,
+    /// if they are about one of its modules whose code has not changed since.
+    /// Errors are collected to be reported once the compilation has finished.
+    ///
+    fn answer_while_compiling(
+        engine: &mut LanguageServerEngine<IO, ConnectionProgressReporter<'a>>,
+        messages: &mut MessageBuffer,
+        connection: &lsp_server::Connection,
+        configuration: &Configuration,
+        io: &FileSystemProxy<IO>,
+        errors: &mut Vec<gleam_core::Error>,
+    ) {
+        messages.receive_available(connection);
+        let requests = messages.take_analysis_requests(|uri| engine.has_current_analysis(uri));
+        for (id, request) in requests {
+            let outcome = match Self::analysis_response(engine, request, configuration, io).result {
+                Ok(json) => Ok(json),
+                Err(error) => {
+                    errors.push(error);
+                    Ok(Json::Null)
+                }
+            };
+            send_response(connection, id, outcome);
+        }
     }
 
     fn handle_notification(&mut self, notification: Notification) {
@@ -145,20 +251,57 @@ where
             Notification::CompilePlease => self.compile_please(),
             Notification::SourceFileOpened { path, text } => self.source_file_opened(path, text),
             Notification::SourceFileClosed { path } => self.source_file_closed(path),
-            Notification::SourceFileSaved { path } => self.discard_in_memory_cache(path),
-            Notification::SourceFileChangedInMemory { path, text } => {
-                self.cache_file_in_memory(path, text)
+            Notification::SourceFileSaved { path } => self.source_file_saved(path),
+            Notification::SourceFileChangedInMemory { path, changes } => {
+                self.apply_changes_in_memory(path, changes)
             }
             Notification::ConfigFileChanged { path } => self.watched_files_changed(path),
+            Notification::WorkspaceFoldersChanged { added, removed } => {
+                self.workspace_folders_changed(added, removed)
+            }
         };
         self.publish_feedback(feedback);
     }
 
-    fn publish_feedback(&self, feedback: Feedback) {
-        self.publish_diagnostics(feedback.diagnostics);
+    fn publish_feedback(&mut self, feedback: Feedback) {
+        let diagnostics_changed = self.diagnostics.update(&feedback);
+        if supports_pull_diagnostics(&self.initialise_params) {
+            // Clients pulling diagnostics would show them twice if they were
+            // also pushed, so instead they're told to pull them again.
+            if diagnostics_changed {
+                self.refresh_diagnostics();
+            }
+        } else {
+            self.publish_diagnostics(feedback.diagnostics);
+        }
         self.publish_messages(feedback.messages);
     }
 
+    fn refresh_diagnostics(&mut self) {
+        let supports_refresh = self
+            .initialise_params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.diagnostics)
+            .and_then(|diagnostics| diagnostics.refresh_support)
+            .unwrap_or(false);
+        if !supports_refresh {
+            return;
+        }
+
+        self.diagnostics_refresh_requests += 1;
+        let request = lsp_server::Request {
+            id: format!("diagnostic-refresh-{}", self.diagnostics_refresh_requests).into(),
+            method: "workspace/diagnostic/refresh".into(),
+            params: Json::Null,
+        };
+        self.connection
+            .sender
+            .send(lsp_server::Message::Request(request))
+            .expect("send workspace/diagnostic/refresh");
+    }
+
     fn publish_diagnostics(&self, diagnostics: HashMap<Utf8PathBuf, Vec<Diagnostic>>) {
         for (path, diagnostics) in diagnostics {
             let diagnostics = diagnostics
@@ -353,48 +496,41 @@ where
         (Ok(json), Feedback::default())
     }
 
-    fn hover(&mut self, params: lsp::HoverParams) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.hover(params))
-    }
-
-    fn goto_definition(
+    fn range_format(
         &mut self,
-        params: lsp::DefinitionParams,
+        params: lsp::DocumentRangeFormattingParams,
     ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.goto_definition(params))
-    }
+        let path = super::path(&params.text_document.uri);
 
-    fn goto_type_definition(
-        &mut self,
-        params: lsp_types::TypeDefinitionParams,
-    ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.goto_type_definition(params))
+        let src = match self.io.read(&path) {
+            Ok(src) => src.into(),
+            Err(error) => return self.path_error_response(path, error),
+        };
+
+        let edits = match formatting::range_formatting(&src, &path, params.range) {
+            Ok(edits) => edits,
+            Err(error) => return self.path_error_response(path, error),
+        };
+        let json = serde_json::to_value(edits).expect("to JSON value");
+
+        (Ok(json), Feedback::default())
     }
 
-    fn completion(
+    fn on_type_format(
         &mut self,
-        params: lsp::CompletionParams,
+        params: lsp::DocumentOnTypeFormattingParams,
     ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
+        let path = super::path(&params.text_document.uri);
 
         let src = match self.io.read(&path) {
             Ok(src) => src.into(),
             Err(error) => return self.path_error_response(path, error),
         };
-        self.respond_with_engine(path, |engine| {
-            engine.completion(params.text_document_position_params, src)
-        })
-    }
 
-    fn signature_help(
-        &mut self,
-        params: lsp_types::SignatureHelpParams,
-    ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.signature_help(params))
+        let edits = formatting::on_type_formatting(&src, &path, params.position, &params.ch);
+        let json = serde_json::to_value(edits).expect("to JSON value");
+
+        (Ok(json), Feedback::default())
     }
 
     fn code_action(
@@ -405,20 +541,111 @@ where
         self.respond_with_engine(path, |engine| engine.code_actions(params))
     }
 
-    fn document_symbol(
+    fn resolve_code_lens(
         &mut self,
-        params: lsp::DocumentSymbolParams,
+        lens: lsp::CodeLens,
     ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.document_symbol(params))
+        let Some(data) = code_lens::ReferencesData::from_lens(&lens) else {
+            let json = serde_json::to_value(lens).expect("to JSON value");
+            return (Ok(json), Feedback::default());
+        };
+        let path = super::path(&data.uri);
+        self.respond_with_engine(path, |engine| engine.resolve_code_lens(lens))
+    }
+
+    fn execute_command(
+        &mut self,
+        params: lsp::ExecuteCommandParams,
+    ) -> (Result<Json, ResponseError>, Feedback) {
+        if params.command != code_lens::RUN_COMMAND {
+            let error = ResponseError {
+                code: INVALID_PARAMS,
+                message: format!("Unknown command {}", params.command),
+                data: None,
+            };
+            return (Err(error), Feedback::default());
+        }
+
+        let arguments = params
+            .arguments
+            .into_iter()
+            .flatten()
+            .next()
+            .and_then(|argument| serde_json::from_value::<RunArguments>(argument).ok());
+        let Some(RunArguments { uri, runnable }) = arguments else {
+            let error = ResponseError {
+                code: INVALID_PARAMS,
+                message: "Invalid arguments for the run command".into(),
+                data: None,
+            };
+            return (Err(error), Feedback::default());
+        };
+
+        let path = super::path(&uri);
+        let paths = match self.router.project_paths(&path) {
+            Ok(Some(paths)) => paths,
+            Ok(None) => return (Ok(Json::Null), Feedback::default()),
+            Err(error) => return self.path_error_response(path, error),
+        };
+        match self.io.run_program(&paths, &runnable) {
+            Ok(()) => (Ok(Json::Null), Feedback::default()),
+            Err(error) => self.path_error_response(path, error),
+        }
     }
 
-    fn folding_range(
+    fn document_diagnostic(
         &mut self,
-        params: lsp::FoldingRangeParams,
+        params: lsp::DocumentDiagnosticParams,
     ) -> (Result<Json, ResponseError>, Feedback) {
+        // Any pending changes are compiled first so that the diagnostics are
+        // not out of date. If the file's project has never been loaded it is
+        // compiled now, otherwise there would be no diagnostics to report.
         let path = super::path(&params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.folding_range(params))
+        self.changed_projects
+            .extend(self.router.unloaded_project_path(&path));
+        let feedback = self.compile_please();
+        _ = self.diagnostics.update(&feedback);
+
+        let source = self.io.read(&path).unwrap_or_default();
+        let previous_result_id = params.previous_result_id.as_deref();
+        let report = self.diagnostics.report(&path, &source, previous_result_id);
+        let report = document_diagnostic_report(report);
+        let json = serde_json::to_value(report).expect("response to json");
+        (Ok(json), feedback)
+    }
+
+    /// Workspace diagnostics include the diagnostics of all the files of the
+    /// projects opened in the editor, even the ones that are not open.
+    fn workspace_diagnostic(
+        &mut self,
+        params: lsp::WorkspaceDiagnosticParams,
+    ) -> (Result<Json, ResponseError>, Feedback) {
+        self.changed_projects
+            .extend(self.router.unloaded_workspace_projects());
+        let feedback = self.compile_please();
+        _ = self.diagnostics.update(&feedback);
+
+        let previous_result_ids: HashMap<_, _> = params
+            .previous_result_ids
+            .into_iter()
+            .map(|previous| (super::path(&previous.uri), previous.value))
+            .collect();
+
+        let items = self
+            .diagnostics
+            .paths()
+            .sorted()
+            .map(|path| {
+                let source = self.io.read(path).unwrap_or_default();
+                let previous_result_id = previous_result_ids.get(path).map(String::as_str);
+                let report = self.diagnostics.report(path, &source, previous_result_id);
+                workspace_diagnostic_report(path_to_uri(path.clone()), report)
+            })
+            .collect();
+
+        let report = lsp::WorkspaceDiagnosticReport { items };
+        let json = serde_json::to_value(report).expect("response to json");
+        (Ok(json), feedback)
     }
 
     fn prepare_rename(
@@ -463,20 +690,41 @@ where
         })
     }
 
-    fn find_references(
+    /// Workspace symbols are not tied to any file, so all the projects opened
+    /// in the editor are searched.
+    fn workspace_symbol(
         &mut self,
-        params: lsp_types::ReferenceParams,
+        params: lsp::WorkspaceSymbolParams,
     ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.find_references(params))
-    }
+        let mut symbols = vec![];
+        let mut feedback = Feedback::none();
+
+        for project in self.router.projects() {
+            let engine::Response {
+                result,
+                warnings,
+                compilation,
+            } = project.engine.workspace_symbol(&params);
+            match result {
+                Ok(project_symbols) => {
+                    symbols.extend(project_symbols);
+                    feedback.append_feedback(project.feedback.response(compilation, warnings));
+                }
+                Err(error) => feedback.append_feedback(project.feedback.build_with_error(
+                    error,
+                    compilation,
+                    warnings,
+                )),
+            }
+        }
 
-    fn document_highlight(
-        &mut self,
-        params: lsp_types::DocumentHighlightParams,
-    ) -> (Result<Json, ResponseError>, Feedback) {
-        let path = super::path(&params.text_document_position_params.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.document_highlight(params))
+        workspace_symbol::sort_symbols(&mut symbols);
+        let symbols = symbols
+            .into_iter()
+            .map(|scored| scored.symbol)
+            .collect_vec();
+        let json = serde_json::to_value(symbols).expect("response to json");
+        (Ok(json), feedback)
     }
 
     fn cache_file_in_memory(&mut self, path: Utf8PathBuf, text: String) -> Feedback {
@@ -487,6 +735,27 @@ where
         Feedback::none()
     }
 
+    fn apply_changes_in_memory(
+        &mut self,
+        path: Utf8PathBuf,
+        changes: Vec<lsp::TextDocumentContentChangeEvent>,
+    ) -> Feedback {
+        self.project_changed(&path);
+        if let Err(error) = self.io.apply_mem_cache_changes(&path, &changes) {
+            return self.outside_of_project_feedback.error(error);
+        }
+        Feedback::none()
+    }
+
+    /// The in-memory copy of the file is kept until it is closed, as the
+    /// editor's buffer can differ from what was saved to disk, for example if
+    /// it was formatted on save, and further changes are applied to it.
+    ///
+    fn source_file_saved(&mut self, path: Utf8PathBuf) -> Feedback {
+        self.project_changed(&path);
+        Feedback::none()
+    }
+
     fn discard_in_memory_cache(&mut self, path: Utf8PathBuf) -> Feedback {
         self.project_changed(&path);
         if let Err(error) = self.io.delete_mem_cache(&path) {
@@ -500,13 +769,86 @@ where
         Feedback::none()
     }
 
+    fn open_initial_workspace_folders(&mut self) {
+        let Some(lsp::WorkspaceFolders::WorkspaceFolderList(folders)) = &self
+            .initialise_params
+            .workspace_folders_initialize_params
+            .workspace_folders
+        else {
+            return;
+        };
+        let added = folders
+            .iter()
+            .map(|folder| super::path(&folder.uri))
+            .collect();
+        let feedback = self.workspace_folders_changed(added, vec![]);
+        self.publish_feedback(feedback);
+    }
+
+    /// Each Gleam project in an opened folder gets an engine, which compiles
+    /// it with the next batch of changes so that its diagnostics are shown
+    /// without having to open one of its files. The diagnostics of the
+    /// projects in a closed folder are cleared.
+    fn workspace_folders_changed(
+        &mut self,
+        added: Vec<Utf8PathBuf>,
+        removed: Vec<Utf8PathBuf>,
+    ) -> Feedback {
+        let mut feedback = Feedback::none();
+
+        for folder in removed {
+            for mut project in self.router.remove_workspace_folder(&folder) {
+                _ = self.changed_projects.remove(project.engine.paths.root());
+                feedback.append_feedback(project.feedback.clear());
+            }
+        }
+
+        for folder in added {
+            match self.router.add_workspace_folder(folder) {
+                Ok(Some(root)) => _ = self.changed_projects.insert(root),
+                Ok(None) => (),
+                Err(error) => {
+                    feedback.append_feedback(self.outside_of_project_feedback.error(error))
+                }
+            }
+        }
+
+        feedback
+    }
+
     fn compile_please(&mut self) -> Feedback {
         let mut accumulator = Feedback::none();
         let projects = std::mem::take(&mut self.changed_projects);
+
+        // Requests the client sends while a project is being compiled are
+        // answered in the meantime if they only need its last analysis.
+        let connection = *self.connection;
+        let configuration = self.configuration.clone();
+        let io = self.io.clone();
+        let mut messages = std::mem::replace(&mut *self.messages, MessageBuffer::new());
+        let mut errors = vec![];
+
         for path in projects {
-            let (_, feedback) = self.respond_with_engine(path, |this| this.compile_please());
+            let (_, feedback) = self.respond_with_engine(path.clone(), |engine| {
+                engine.compile_please_with_checkpoint(&mut |engine| {
+                    Self::answer_while_compiling(
+                        engine,
+                        &mut messages,
+                        connection,
+                        &configuration,
+                        &io,
+                        &mut errors,
+                    )
+                })
+            });
             accumulator.append_feedback(feedback);
+            for error in errors.drain(..) {
+                let (_, feedback) = self.path_error_response(path.clone(), error);
+                accumulator.append_feedback(feedback);
+            }
         }
+
+        *self.messages = messages;
         accumulator
     }
 
@@ -535,11 +877,28 @@ where
 }
 
 fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializeParams {
+    let (initialise_id, initialise_params_json) =
+        connection.initialize_start().expect("LSP initialize");
+    let initialise_params: InitializeParams =
+        serde_json::from_value(initialise_params_json).expect("LSP InitializeParams from json");
+
+    // Diagnostics are only provided to clients that can pull them, all the
+    // others have them pushed after each compilation.
+    let diagnostic_provider = supports_pull_diagnostics(&initialise_params).then(|| {
+        lsp::DiagnosticOptions {
+            identifier: Some("gleam".into()),
+            inter_file_dependencies: true,
+            workspace_diagnostics: true,
+            work_done_progress_options: Default::default(),
+        }
+        .into()
+    });
+
     let server_capabilities = lsp::ServerCapabilities {
         text_document_sync: Some(
             lsp::TextDocumentSyncOptions {
                 open_close: Some(true),
-                change: Some(lsp::TextDocumentSyncKind::Full),
+                change: Some(lsp::TextDocumentSyncKind::Incremental),
                 will_save: None,
                 will_save_wait_until: None,
                 save: Some(
@@ -551,7 +910,8 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
             }
             .into(),
         ),
-        selection_range_provider: None,
+        selection_range_provider: Some(true.into()),
+        linked_editing_range_provider: Some(true.into()),
         hover_provider: Some(true.into()),
         completion_provider: Some(lsp::CompletionOptions {
             resolve_provider: None,
@@ -571,16 +931,22 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
         }),
         definition_provider: Some(true.into()),
         type_definition_provider: Some(true.into()),
-        implementation_provider: None,
+        implementation_provider: Some(true.into()),
         references_provider: Some(true.into()),
         document_highlight_provider: Some(true.into()),
         document_symbol_provider: Some(true.into()),
-        workspace_symbol_provider: None,
+        workspace_symbol_provider: Some(true.into()),
         code_action_provider: Some(true.into()),
-        code_lens_provider: None,
+        code_lens_provider: Some(lsp::CodeLensOptions {
+            resolve_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        }),
         document_formatting_provider: Some(true.into()),
-        document_range_formatting_provider: None,
-        document_on_type_formatting_provider: None,
+        document_range_formatting_provider: Some(true.into()),
+        document_on_type_formatting_provider: Some(lsp::DocumentOnTypeFormattingOptions {
+            first_trigger_character: "}".into(),
+            more_trigger_character: Some(vec!["\n".into()]),
+        }),
         rename_provider: Some(
             RenameOptions {
                 prepare_provider: Some(true),
@@ -590,13 +956,22 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
             }
             .into(),
         ),
-        document_link_provider: None,
+        document_link_provider: Some(lsp::DocumentLinkOptions {
+            resolve_provider: Some(false),
+            work_done_progress_options: Default::default(),
+        }),
         color_provider: None,
         folding_range_provider: Some(true.into()),
         declaration_provider: None,
-        execute_command_provider: None,
+        execute_command_provider: Some(lsp::ExecuteCommandOptions {
+            commands: vec![code_lens::RUN_COMMAND.into()],
+            work_done_progress_options: Default::default(),
+        }),
         workspace: Some(WorkspaceOptions {
-            workspace_folders: None,
+            workspace_folders: Some(lsp::WorkspaceFoldersServerCapabilities {
+                supported: Some(true),
+                change_notifications: Some(lsp::ChangeNotifications::Bool(true)),
+            }),
             file_operations: Some(FileOperationOptions {
                 did_create: None,
                 will_create: None,
@@ -616,29 +991,148 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
             }),
             text_document_content: None,
         }),
-        call_hierarchy_provider: None,
-        semantic_tokens_provider: None,
+        call_hierarchy_provider: Some(true.into()),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensOptions {
+                legend: semantic_tokens::legend(),
+                range: Some(true.into()),
+                full: Some(true.into()),
+                work_done_progress_options: Default::default(),
+            }
+            .into(),
+        ),
         moniker_provider: None,
-        linked_editing_range_provider: None,
         experimental: None,
         position_encoding: None,
         inline_value_provider: None,
-        inlay_hint_provider: None,
-        diagnostic_provider: None,
+        inlay_hint_provider: Some(true.into()),
+        diagnostic_provider,
         type_hierarchy_provider: None,
         notebook_document_sync: None,
         inline_completion_provider: None,
     };
-    let server_capabilities_json =
-        serde_json::to_value(server_capabilities).expect("server_capabilities_serde");
-    let initialise_params_json = connection
-        .initialize(server_capabilities_json)
+    let initialise_result = lsp::InitializeResult {
+        capabilities: server_capabilities,
+        server_info: None,
+    };
+    let initialise_result_json =
+        serde_json::to_value(initialise_result).expect("server_capabilities_serde");
+    connection
+        .initialize_finish(initialise_id, initialise_result_json)
         .expect("LSP initialize");
-    let initialise_params: InitializeParams =
-        serde_json::from_value(initialise_params_json).expect("LSP InitializeParams from json");
     initialise_params
 }
 
+fn supports_pull_diagnostics(params: &InitializeParams) -> bool {
+    params
+        .capabilities
+        .text_document
+        .as_ref()
+        .is_some_and(|text_document| text_document.diagnostic.is_some())
+}
+
+fn json_response<T: serde::Serialize>(response: engine::Response<T>) -> engine::Response<Json> {
+    engine::Response {
+        result: response
+            .result
+            .map(|value| serde_json::to_value(value).expect("response to json")),
+        warnings: response.warnings,
+        compilation: response.compilation,
+    }
+}
+
+fn send_response(
+    connection: &lsp_server::Connection,
+    id: lsp_server::RequestId,
+    outcome: Result<Json, ResponseError>,
+) {
+    let response = match outcome {
+        Ok(payload) => lsp_server::Response {
+            id,
+            error: None,
+            result: Some(payload),
+        },
+        Err(error) => lsp_server::Response {
+            id,
+            error: Some(error),
+            result: None,
+        },
+    };
+
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .expect("channel send LSP response")
+}
+
+fn document_diagnostic_report(report: DiagnosticsReport) -> lsp::DocumentDiagnosticReport {
+    use lsp::DocumentDiagnosticReport as Report;
+    match report {
+        DiagnosticsReport::Unchanged { result_id } => {
+            Report::RelatedUnchangedDocumentDiagnosticReport(
+                lsp::RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: lsp::UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    },
+                },
+            )
+        }
+        DiagnosticsReport::Full {
+            result_id,
+            diagnostics,
+        } => {
+            Report::RelatedFullDocumentDiagnosticReport(lsp::RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: full_diagnostic_report(result_id, diagnostics),
+            })
+        }
+    }
+}
+
+fn workspace_diagnostic_report(
+    uri: Url,
+    report: DiagnosticsReport,
+) -> lsp::WorkspaceDocumentDiagnosticReport {
+    use lsp::WorkspaceDocumentDiagnosticReport as Report;
+    match report {
+        DiagnosticsReport::Unchanged { result_id } => {
+            Report::WorkspaceUnchangedDocumentDiagnosticReport(
+                lsp::WorkspaceUnchangedDocumentDiagnosticReport {
+                    uri,
+                    version: None,
+                    unchanged_document_diagnostic_report: lsp::UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    },
+                },
+            )
+        }
+        DiagnosticsReport::Full {
+            result_id,
+            diagnostics,
+        } => Report::WorkspaceFullDocumentDiagnosticReport(
+            lsp::WorkspaceFullDocumentDiagnosticReport {
+                uri,
+                version: None,
+                full_document_diagnostic_report: full_diagnostic_report(result_id, diagnostics),
+            },
+        ),
+    }
+}
+
+fn full_diagnostic_report(
+    result_id: String,
+    diagnostics: Vec<Diagnostic>,
+) -> lsp::FullDocumentDiagnosticReport {
+    lsp::FullDocumentDiagnosticReport {
+        result_id: Some(result_id),
+        items: diagnostics
+            .into_iter()
+            .flat_map(diagnostic_to_lsp)
+            .collect(),
+    }
+}
+
 fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
     let severity = match diagnostic.level {
         Level::Error => lsp::DiagnosticSeverity::Error,
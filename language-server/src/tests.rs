@@ -2,17 +2,29 @@
 // SPDX-FileCopyrightText: 2023 The Gleam contributors
 
 mod action;
+mod call_hierarchy;
+mod code_lens;
 mod compilation;
 mod completion;
 mod definition;
 mod document_highlight;
+mod document_link;
 mod document_symbols;
+mod files;
 mod folding_range;
+mod formatting;
 mod hover;
+mod implementation;
+mod inlay_hints;
+mod linked_editing;
+mod messages;
 mod reference;
 mod rename;
 mod router;
+mod selection_range;
+mod semantic_tokens;
 mod signature_help;
+mod workspace_symbol;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -76,6 +88,7 @@ fn default_manifest_package() -> ManifestPackage {
         version: Version::new(1, 0, 0),
         source: ManifestPackageSource::Hex {
             outer_checksum: Base16Checksum(vec![]),
+            repository: None,
         },
     }
 }
@@ -89,6 +102,7 @@ impl LanguageServerTestIO {
             manifest: Manifest {
                 requirements: HashMap::new(),
                 packages: vec![],
+                vendor: None,
             },
         }
     }
@@ -126,6 +140,14 @@ impl LanguageServerTestIO {
         path
     }
 
+    /// Writes a file that isn't a Gleam module, such as an Erlang or
+    /// JavaScript file implementing external functions.
+    pub fn native_file(&self, path: &str, code: &str) -> Utf8PathBuf {
+        let path = self.paths.root().join(path);
+        self.module(&path, code);
+        path
+    }
+
     pub fn hex_dep_module(&self, dep: &str, name: &str, code: &str) -> Utf8PathBuf {
         let dep_dir = self.paths.build_packages_package(dep).join("src");
         let path = dep_dir.join(name).with_extension("gleam");
@@ -138,6 +160,7 @@ impl LanguageServerTestIO {
             name: name.into(),
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
             build_tools: vec!["gleam".into()],
             ..default_manifest_package()
@@ -335,6 +358,7 @@ fn add_package_from_manifest<B>(
         match package.source {
             ManifestPackageSource::Hex { .. } => Requirement::Hex {
                 version: Range::new("1.0.0".into()).unwrap(),
+                repository: None,
             },
             ManifestPackageSource::Local { ref path } => Requirement::Path { path: path.into() },
             ManifestPackageSource::Git {
@@ -360,6 +384,7 @@ fn add_dev_package_from_manifest<B>(
         match package.source {
             ManifestPackageSource::Hex { .. } => Requirement::Hex {
                 version: Range::new("1.0.0".into()).unwrap(),
+                repository: None,
             },
             ManifestPackageSource::Local { ref path } => Requirement::Path { path: path.into() },
             ManifestPackageSource::Git {
@@ -430,6 +455,7 @@ struct TestProject<'a> {
     dev_hex_modules: Vec<(&'a str, &'a str)>,
     indirect_hex_modules: Vec<(&'a str, &'a str)>,
     package_modules: HashMap<&'a str, Vec<(&'a str, &'a str)>>,
+    native_files: Vec<(&'a str, &'a str)>,
 }
 
 impl<'a> TestProject<'a> {
@@ -444,6 +470,7 @@ impl<'a> TestProject<'a> {
             dev_hex_modules: vec![],
             indirect_hex_modules: vec![],
             package_modules: HashMap::new(),
+            native_files: vec![],
         }
     }
 
@@ -548,6 +575,11 @@ impl<'a> TestProject<'a> {
         self
     }
 
+    pub fn add_native_file(mut self, path: &'a str, src: &'a str) -> Self {
+        self.native_files.push((path, src));
+        self
+    }
+
     pub fn build_engine(
         &self,
         io: &mut LanguageServerTestIO,
@@ -595,6 +627,10 @@ impl<'a> TestProject<'a> {
             let _ = io.dev_module(name, code);
         });
 
+        for (path, code) in &self.native_files {
+            let _ = io.native_file(path, code);
+        }
+
         for package in &io.manifest.packages {
             let toml_path = engine.paths.build_packages_package_config(&package.name);
             add_package_from_manifest(&mut engine, toml_path, package.clone());
@@ -609,6 +645,7 @@ impl<'a> TestProject<'a> {
                 name: "indirect_hex".into(),
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![]),
+                    repository: None,
                 },
                 build_tools: vec!["gleam".into()],
                 ..default_manifest_package()
@@ -624,6 +661,7 @@ impl<'a> TestProject<'a> {
                 name: "dev_hex".into(),
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![]),
+                    repository: None,
                 },
                 build_tools: vec!["gleam".into()],
                 ..default_manifest_package()
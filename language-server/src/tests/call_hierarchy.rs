@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{
+    CallHierarchyIncomingCallsParams, CallHierarchyItem, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, Range,
+};
+
+use super::*;
+
+fn prepare_call_hierarchy(
+    tester: &TestProject<'_>,
+    position: Position,
+) -> Option<CallHierarchyItem> {
+    tester.at(position, |engine, params, _| {
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: params,
+            work_done_progress_params: Default::default(),
+        };
+        engine
+            .prepare_call_hierarchy(params)
+            .result
+            .expect("prepare call hierarchy should not fail")
+            .and_then(|items| items.into_iter().next())
+    })
+}
+
+fn incoming_calls(tester: TestProject<'_>, position: PositionFinder) -> String {
+    let position = position.find_position(tester.src);
+    let item = prepare_call_hierarchy(&tester, position).expect("no call hierarchy item");
+
+    let calls = tester.at(position, |engine, _, _| {
+        let params = CallHierarchyIncomingCallsParams {
+            item: item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .incoming_calls(params)
+            .result
+            .expect("incoming calls should not fail")
+            .unwrap_or_default()
+    });
+
+    let mut output = format!("Incoming calls to {}\n", show_item(&item));
+    for call in calls {
+        let src = tester
+            .src_from_module_url(&call.from.uri)
+            .expect("caller module source");
+        output.push_str(&format!("\nFrom {}\n", show_item(&call.from)));
+        output.push_str(&show_ranges(src, &call.from_ranges));
+    }
+    output
+}
+
+fn outgoing_calls(tester: TestProject<'_>, position: PositionFinder) -> String {
+    let position = position.find_position(tester.src);
+    let item = prepare_call_hierarchy(&tester, position).expect("no call hierarchy item");
+
+    let calls = tester.at(position, |engine, _, _| {
+        let params = CallHierarchyOutgoingCallsParams {
+            item: item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .outgoing_calls(params)
+            .result
+            .expect("outgoing calls should not fail")
+            .unwrap_or_default()
+    });
+
+    let src = tester
+        .src_from_module_url(&item.uri)
+        .expect("caller module source");
+    let mut output = format!("Outgoing calls from {}\n", show_item(&item));
+    for call in calls {
+        output.push_str(&format!("\nTo {}\n", show_item(&call.to)));
+        output.push_str(&show_ranges(src, &call.from_ranges));
+    }
+    output
+}
+
+fn show_item(item: &CallHierarchyItem) -> String {
+    let detail = item.detail.as_deref().unwrap_or_default();
+    format!(
+        "{detail}.{name} (lines {start}-{end})",
+        name = item.name,
+        start = item.range.start.line,
+        end = item.range.end.line
+    )
+}
+
+/// Prints the lines of code each range appears in, with the range underlined.
+///
+fn show_ranges(src: &str, ranges: &[Range]) -> String {
+    let lines = src.lines().collect_vec();
+    let mut output = String::new();
+    for range in ranges {
+        let line = lines
+            .get(range.start.line as usize)
+            .expect("range on a line of the source");
+        let start = range.start.character as usize;
+        let end = range.end.character as usize;
+        output.push_str(&format!("{line}\n"));
+        output.push_str(&format!(
+            "{}{}\n",
+            " ".repeat(start),
+            "▔".repeat(end - start)
+        ));
+    }
+    output
+}
+
+#[test]
+fn prepare_call_hierarchy_on_function_definition() {
+    let code = r#"
+pub fn main() {
+  wibble()
+}
+
+fn wibble() { Nil }
+"#;
+
+    let tester = TestProject::for_source(code);
+    let position = find_position_of("wibble() {").find_position(code);
+    let item = prepare_call_hierarchy(&tester, position).expect("no call hierarchy item");
+
+    assert_eq!(item.name, "wibble");
+    assert_eq!(item.detail.as_deref(), Some("app"));
+    assert_eq!(
+        item.range,
+        Range::new(Position::new(5, 0), Position::new(5, 19))
+    );
+    assert_eq!(
+        item.selection_range,
+        Range::new(Position::new(5, 3), Position::new(5, 9))
+    );
+}
+
+#[test]
+fn prepare_call_hierarchy_on_constant_returns_nothing() {
+    let code = r#"
+const wibble = 1
+
+pub fn main() {
+  wibble
+}
+"#;
+
+    let tester = TestProject::for_source(code);
+    let position = find_position_of("wibble")
+        .nth_occurrence(2)
+        .find_position(code);
+    assert_eq!(prepare_call_hierarchy(&tester, position), None);
+}
+
+#[test]
+fn incoming_calls_in_same_module() {
+    let code = r#"
+pub fn main() {
+  wibble(1)
+  wobble()
+}
+
+fn wobble() {
+  wibble(2) + wibble(3)
+}
+
+fn wibble(x: Int) -> Int { x }
+"#;
+
+    assert_snapshot!(incoming_calls(
+        TestProject::for_source(code),
+        find_position_of("wibble(x")
+    ));
+}
+
+#[test]
+fn incoming_calls_through_pipelines_and_captures() {
+    let code = r#"
+pub fn main() {
+  1
+  |> add(2)
+  |> add(3, _)
+}
+
+pub fn apply() {
+  let f = add
+  f(1, 2)
+}
+
+fn add(a: Int, b: Int) -> Int { a + b }
+"#;
+
+    assert_snapshot!(incoming_calls(
+        TestProject::for_source(code),
+        find_position_of("add(a")
+    ));
+}
+
+#[test]
+fn incoming_calls_from_other_modules() {
+    let code = r#"
+pub fn wibble() { Nil }
+"#;
+
+    let caller = r#"
+import app.{wibble as wobble}
+
+pub fn main() {
+  app.wibble()
+  wobble()
+}
+
+const not_a_function = app.wibble
+"#;
+
+    assert_snapshot!(incoming_calls(
+        TestProject::for_source(code).add_module("caller", caller),
+        find_position_of("wibble")
+    ));
+}
+
+#[test]
+fn outgoing_calls_from_function() {
+    let code = r#"
+import other
+
+pub fn main() {
+  let x = helper(1)
+  x
+  |> other.double
+  |> helper
+  |> fn(y) { other.double(y) }
+  Wibble(x)
+}
+
+type Wibble {
+  Wibble(Int)
+}
+
+fn helper(x: Int) -> Int { x }
+"#;
+
+    let other = r#"
+pub fn double(x: Int) -> Int { x * 2 }
+"#;
+
+    assert_snapshot!(outgoing_calls(
+        TestProject::for_source(code).add_module("other", other),
+        find_position_of("main")
+    ));
+}
+
+#[test]
+fn outgoing_calls_from_call_site() {
+    let code = r#"
+pub fn main() {
+  wibble()
+}
+
+fn wibble() {
+  wobble()
+}
+
+fn wobble() { Nil }
+"#;
+
+    assert_snapshot!(outgoing_calls(
+        TestProject::for_source(code),
+        find_position_of("wibble()")
+    ));
+}
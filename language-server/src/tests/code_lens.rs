@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{CodeLens, CodeLensParams};
+
+use crate::code_lens::{RUN_COMMAND, RunArguments};
+
+use super::*;
+
+fn code_lenses(tester: TestProject<'_>, origin: Origin, module: &str) -> String {
+    tester.in_module_at(
+        origin,
+        module,
+        Position::default(),
+        |engine, params, src| {
+            let params = CodeLensParams {
+                text_document: params.text_document,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            };
+            let lenses = engine
+                .code_lens(params)
+                .result
+                .expect("code lenses should not fail");
+            let lenses = lenses
+                .into_iter()
+                .map(|lens| {
+                    engine
+                        .resolve_code_lens(lens)
+                        .result
+                        .expect("resolving code lens should not fail")
+                })
+                .collect_vec();
+            show_lenses(&src, lenses)
+        },
+    )
+}
+
+fn show_lenses(src: &str, lenses: Vec<CodeLens>) -> String {
+    let lines = src.lines().collect_vec();
+    let mut output = String::new();
+    for lens in lenses {
+        let command = lens.command.expect("resolved code lens");
+        let line = lines
+            .get(lens.range.start.line as usize)
+            .expect("code lens line");
+        output.push_str(&format!("{line}\n  {}", command.title));
+
+        if command.command == RUN_COMMAND {
+            let argument = command
+                .arguments
+                .into_iter()
+                .flatten()
+                .next()
+                .expect("run command argument");
+            let arguments: RunArguments =
+                serde_json::from_value(argument).expect("run command arguments");
+            output.push_str(&format!(" {:?}", arguments.runnable));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[test]
+fn run_main_function() {
+    let src = "
+pub fn main() {
+  Nil
+}
+";
+
+    assert_snapshot!(code_lenses(
+        TestProject::for_source(src),
+        Origin::Src,
+        LSP_TEST_ROOT_PACKAGE_NAME
+    ));
+}
+
+#[test]
+fn run_main_function_erlang_only() {
+    let src = r#"
+@external(erlang, "wibble", "wobble")
+pub fn main() -> Nil
+"#;
+
+    assert_snapshot!(code_lenses(
+        TestProject::for_source(src),
+        Origin::Src,
+        LSP_TEST_ROOT_PACKAGE_NAME
+    ));
+}
+
+#[test]
+fn no_run_lens_for_private_main_or_main_with_arguments() {
+    let src = "
+fn main() {
+  Nil
+}
+
+pub fn wibble() {
+  main()
+}
+";
+    let wobble = "
+pub fn main(argument) {
+  argument
+}
+";
+
+    assert_snapshot!(code_lenses(
+        TestProject::for_source(src).add_module("wobble", wobble),
+        Origin::Src,
+        "wobble"
+    ));
+}
+
+#[test]
+fn run_tests() {
+    let test = "
+import app
+
+pub fn main() {
+  Nil
+}
+
+pub fn wibble_test() {
+  app.wibble()
+}
+
+pub fn wobble_test() {
+  helper()
+}
+
+fn helper() {
+  Nil
+}
+
+fn private_test() {
+  Nil
+}
+";
+    let src = "
+pub fn wibble() {
+  Nil
+}
+";
+
+    assert_snapshot!(code_lenses(
+        TestProject::for_source(src).add_test_module("app_test", test),
+        Origin::Test,
+        "app_test"
+    ));
+}
+
+#[test]
+fn test_functions_outside_of_test_directory_cannot_be_run() {
+    let src = "
+pub fn wibble_test() {
+  Nil
+}
+";
+
+    assert_snapshot!(code_lenses(
+        TestProject::for_source(src),
+        Origin::Src,
+        LSP_TEST_ROOT_PACKAGE_NAME
+    ));
+}
+
+#[test]
+fn reference_counts() {
+    let src = "
+pub fn wibble() {
+  wobble() + wobble()
+}
+
+pub fn wobble() {
+  1
+}
+
+pub fn wubble() {
+  wibble
+}
+
+fn private() {
+  wubble()
+}
+";
+    let other = "
+import app.{wobble}
+
+pub fn other() {
+  app.wubble()
+  wobble()
+}
+";
+
+    assert_snapshot!(code_lenses(
+        TestProject::for_source(src).add_module("other", other),
+        Origin::Src,
+        LSP_TEST_ROOT_PACKAGE_NAME
+    ));
+}
@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: 2023 The Gleam contributors
 
-use crate::engine::Compilation;
+use lsp_types::{HoverParams, Position, TextDocumentIdentifier, TextDocumentPositionParams};
+
+use crate::{engine::Compilation, url_from_path};
 
 use super::*;
 
@@ -247,3 +249,48 @@ fn dep_compile_recompile() {
         ]
     )
 }
+
+#[test]
+fn requests_are_answered_with_the_last_analysis_while_compiling() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    let app = io.src_module("app", "pub fn main() { 0 }");
+    _ = io.src_module("other", "pub fn wibble() { 0 }");
+    _ = io.src_module("user", "pub fn main() { 0 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+
+    // `app` has not changed, so it can still be asked about while the
+    // project is being compiled.
+    let other = io.src_module("other", "pub fn wibble() { let unused = 1 0 }");
+    let user = io.src_module("user", "import other pub fn main() { other.wibble() }");
+    let app_uri = url_from_path(app.as_str()).expect("app URL");
+    let other_uri = url_from_path(other.as_str()).expect("other URL");
+
+    let mut hovers = vec![];
+    let response = engine.compile_please_with_checkpoint(&mut |engine| {
+        assert!(engine.has_current_analysis(&app_uri));
+        assert!(!engine.has_current_analysis(&other_uri));
+        hovers.push(engine.hover(HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier::new(app_uri.clone()),
+                position: Position::new(0, 8),
+            },
+            work_done_progress_params: Default::default(),
+        }));
+    });
+
+    // The warning in `other` is reported with the compilation, rather than
+    // with the hover answered before `user` was analysed.
+    assert!(response.result.is_ok());
+    assert_eq!(response.warnings.len(), 1);
+    assert_eq!(response.compilation, Compilation::Yes(vec![other, user]));
+
+    assert_eq!(hovers.len(), 2);
+    for hover in hovers {
+        assert!(hover.result.is_ok_and(|hover| hover.is_some()));
+        assert!(hover.warnings.is_empty());
+        assert_eq!(hover.compilation, Compilation::No);
+    }
+}
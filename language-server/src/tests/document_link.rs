@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{DocumentLink, DocumentLinkParams};
+
+use super::*;
+
+fn document_links(tester: &TestProject<'_>) -> Vec<DocumentLink> {
+    tester.at(Position::default(), |engine, param, _| {
+        let params = DocumentLinkParams {
+            text_document: param.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .document_link(params)
+            .result
+            .expect("document links should not fail")
+    })
+}
+
+/// Shows the linked code of each link, followed by where it points to. Files
+/// are shown as their path so that the output is the same on all platforms.
+///
+fn pretty_document_links(tester: TestProject<'_>) -> String {
+    let links = document_links(&tester);
+    let line_numbers = LineNumbers::new(tester.src);
+    let mut output = format!("----- Code -----\n{}\n----- Links -----\n", tester.src);
+
+    if links.is_empty() {
+        output.push_str("(none)\n");
+    }
+
+    for link in links {
+        let start = line_numbers.byte_index(link.range.start) as usize;
+        let end = line_numbers.byte_index(link.range.end) as usize;
+        let target = link.target.expect("a link target");
+        let target = if target.scheme() == "file" {
+            target
+                .path_segments()
+                .expect("a file path")
+                .skip_while(|segment| *segment == "C:")
+                .join("/")
+        } else {
+            target.to_string()
+        };
+        output.push_str(&format!("`{}` -> {target}\n", &tester.src[start..end]));
+    }
+
+    output
+}
+
+#[test]
+fn document_links_for_imports() {
+    let src = "
+import wibble
+import wibble/wobble.{type Wobble, wobble} as wob
+
+pub fn main() -> Wobble {
+  wibble.wibble()
+  wob.wobble()
+}
+";
+
+    assert_snapshot!(pretty_document_links(
+        TestProject::for_source(src)
+            .add_module("wibble", "pub fn wibble() { Nil }")
+            .add_module(
+                "wibble/wobble",
+                "pub type Wobble { Wobble }\npub fn wobble() { Wobble }"
+            )
+    ));
+}
+
+#[test]
+fn document_links_for_dependency_imports() {
+    let src = "
+import hex_module
+
+pub fn main() {
+  hex_module.wibble()
+}
+";
+
+    assert_snapshot!(pretty_document_links(
+        TestProject::for_source(src).add_hex_module("hex_module", "pub fn wibble() { Nil }")
+    ));
+}
+
+#[test]
+fn document_links_for_javascript_externals() {
+    let src = r#"
+@external(javascript, "./wibble_ffi.mjs", "doThing")
+pub fn do_thing(x: Int) -> Int
+
+@external(javascript, "./wibble_ffi.mjs", "Wibble")
+pub type Wibble
+"#;
+
+    assert_snapshot!(pretty_document_links(
+        TestProject::for_source(src).add_native_file(
+            "src/wibble_ffi.mjs",
+            "export function doThing(x) { return x; }"
+        )
+    ));
+}
+
+#[test]
+fn no_document_links_for_missing_or_package_javascript_modules() {
+    let src = r#"
+@external(javascript, "./missing.mjs", "doThing")
+pub fn do_thing(x: Int) -> Int
+
+@external(javascript, "node:fs", "readFileSync")
+pub fn read(path: String) -> String
+
+@external(erlang, "wibble_ffi", "do_thing")
+pub fn do_other_thing(x: Int) -> Int
+"#;
+
+    assert_snapshot!(pretty_document_links(TestProject::for_source(src)));
+}
+
+#[test]
+fn document_links_in_doc_comments() {
+    let src = "
+//// See [the Gleam website](https://gleam.run) for more.
+
+/// Wibbles, as described in [the paper](https://example.com/wibble.pdf).
+/// [Relative links](wobble.html) and [text] (not links) are not linked.
+pub fn wibble() {
+  // [Regular comments](https://example.com) are not linked either.
+  Nil
+}
+";
+
+    assert_snapshot!(pretty_document_links(TestProject::for_source(src)));
+}
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use gleam_core::io::FileSystemReader;
+use lsp_types::{
+    Position, Range, TextDocumentContentChangeEvent, TextDocumentContentChangePartial,
+    TextDocumentContentChangeWholeDocument,
+};
+
+use crate::files::{FileSystemProxy, apply_changes};
+
+use super::LanguageServerTestIO;
+
+fn edit(
+    (start_line, start_character): (u32, u32),
+    (end_line, end_character): (u32, u32),
+    text: &str,
+) -> TextDocumentContentChangeEvent {
+    let range = Range::new(
+        Position::new(start_line, start_character),
+        Position::new(end_line, end_character),
+    );
+    TextDocumentContentChangePartial::new(range, None, text.into()).into()
+}
+
+fn whole_document(text: &str) -> TextDocumentContentChangeEvent {
+    TextDocumentContentChangeWholeDocument { text: text.into() }.into()
+}
+
+fn apply(text: &str, changes: &[TextDocumentContentChangeEvent]) -> String {
+    let mut text = text.to_string();
+    apply_changes(&mut text, changes);
+    text
+}
+
+#[test]
+fn incremental_changes_give_the_same_text_as_a_full_replacement() {
+    let original = "pub fn main() {
+  let x = 1
+  x
+}
+";
+    let expected = "pub fn main() -> Int {
+  let wibble = 1
+  let wobble = 2
+  wibble + wobble
+}
+";
+
+    let changes = [
+        // Renaming `x` to `wibble`
+        edit((1, 6), (1, 7), "wibble"),
+        edit((2, 2), (2, 3), "wibble"),
+        // Adding the return annotation
+        edit((0, 13), (0, 13), " -> Int"),
+        // Adding a new line in the middle of the function
+        edit((1, 16), (1, 16), "\n  let wobble = 2"),
+        // Typing at the end of a line, one character at a time
+        edit((3, 8), (3, 8), " "),
+        edit((3, 9), (3, 9), "+"),
+        edit((3, 10), (3, 10), " wobble"),
+    ];
+
+    assert_eq!(apply(original, &changes), expected);
+    assert_eq!(apply(original, &[whole_document(expected)]), expected);
+}
+
+#[test]
+fn incremental_changes_spanning_multiple_lines() {
+    let original = "pub fn main() {
+  wibble()
+  wobble()
+  wubble()
+}
+";
+
+    let changes = [
+        // Deleting the middle line
+        edit((2, 0), (3, 0), ""),
+        // Replacing the two remaining calls with a single one
+        edit((1, 2), (2, 10), "wibble() |> wobble"),
+    ];
+
+    assert_eq!(
+        apply(original, &changes),
+        "pub fn main() {
+  wibble() |> wobble
+}
+"
+    );
+}
+
+#[test]
+fn incremental_changes_use_utf16_positions() {
+    // `🦀` takes up two UTF-16 code units and four bytes, `é` takes up one
+    // UTF-16 code unit and two bytes.
+    let original = "const crab = \"🦀 é\"\nconst x = 1\n";
+
+    let changes = [
+        // Replacing the `é` right after the crab and the space.
+        edit((0, 17), (0, 18), "e"),
+        // Inserting right after the crab.
+        edit((0, 16), (0, 16), "🦀"),
+        // Positions on following lines are not affected.
+        edit((1, 6), (1, 7), "y"),
+    ];
+
+    assert_eq!(
+        apply(original, &changes),
+        "const crab = \"🦀🦀 e\"\nconst y = 1\n"
+    );
+}
+
+#[test]
+fn incremental_changes_past_the_end_of_a_line_are_clamped() {
+    let original = "wibble\nwobble\n";
+
+    // A character offset past the end of the line refers to the end of the
+    // line itself, rather than to the following line.
+    let changes = [edit((0, 100), (0, 200), "!")];
+
+    assert_eq!(apply(original, &changes), "wibble!\nwobble\n");
+}
+
+#[test]
+fn incremental_changes_past_the_end_of_the_document() {
+    let original = "wibble\n";
+    let changes = [edit((5, 0), (5, 0), "wobble\n")];
+
+    assert_eq!(apply(original, &changes), "wibble\nwobble\n");
+}
+
+#[test]
+fn whole_document_change_replaces_previous_changes() {
+    let changes = [
+        edit((0, 0), (0, 0), "wibble"),
+        whole_document("wobble"),
+        edit((0, 6), (0, 6), "!"),
+    ];
+
+    assert_eq!(apply("", &changes), "wobble!");
+}
+
+#[test]
+fn changes_are_applied_to_the_file_on_disk_and_then_to_the_cache() {
+    let io = LanguageServerTestIO::new();
+    let path = io.src_module("app", "pub fn main() { 1 }\n");
+    let mut proxy = FileSystemProxy::new(io.clone());
+
+    proxy
+        .apply_mem_cache_changes(&path, &[edit((0, 16), (0, 17), "2")])
+        .expect("changes should apply");
+    proxy
+        .apply_mem_cache_changes(&path, &[edit((0, 17), (0, 17), " + 3")])
+        .expect("changes should apply");
+
+    assert_eq!(
+        proxy.read(&path).expect("cached file"),
+        "pub fn main() { 2 + 3 }\n"
+    );
+    // The file on disk is left untouched.
+    assert_eq!(
+        io.read(&path).expect("file on disk"),
+        "pub fn main() { 1 }\n"
+    );
+}
+
+#[test]
+fn incremental_changes_with_crlf_line_endings() {
+    let original = "pub fn main() {\r\n  wibble()\r\n}\r\n";
+
+    let changes = [
+        // A character offset past the end of the line is clamped to before
+        // the `\r\n`, rather than between the `\r` and the `\n`.
+        edit((1, 100), (1, 100), " |> wobble"),
+        // Replacing the line ending of the first line.
+        edit((0, 15), (1, 0), "\r\n\r\n"),
+    ];
+
+    assert_eq!(
+        apply(original, &changes),
+        "pub fn main() {\r\n\r\n  wibble() |> wobble\r\n}\r\n"
+    );
+}
+
+#[test]
+fn several_incremental_changes_in_one_notification() {
+    let original = "pub fn main() {\n  1\n}\n";
+
+    // Each change is applied to the text resulting from the previous ones, so
+    // the second change refers to the line added by the first one.
+    let changes = [
+        edit((1, 3), (1, 3), "\n  2"),
+        edit((2, 3), (2, 3), " + 3"),
+        edit((0, 7), (0, 11), "wibble"),
+    ];
+
+    assert_eq!(
+        apply(original, &changes),
+        "pub fn wibble() {\n  1\n  2 + 3\n}\n"
+    );
+}
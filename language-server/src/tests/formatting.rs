@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use camino::Utf8Path;
+use ecow::EcoString;
+use gleam_core::line_numbers::LineNumbers;
+use lsp_types::{Position, Range, TextEdit};
+
+use crate::formatting::{on_type_formatting, range_formatting};
+
+fn apply(src: &str, edits: &[TextEdit]) -> String {
+    let line_numbers = LineNumbers::new(src);
+    let mut src = src.to_string();
+    let mut edits = edits.to_vec();
+    edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+    for edit in edits.iter().rev() {
+        let start = line_numbers.byte_index(edit.range.start) as usize;
+        let end = line_numbers.byte_index(edit.range.end) as usize;
+        src.replace_range(start..end, &edit.new_text);
+    }
+    src
+}
+
+fn format_range(src: &str, range: Range) -> String {
+    let edits = range_formatting(
+        &EcoString::from(src),
+        Utf8Path::new("/src/app.gleam"),
+        range,
+    )
+    .expect("valid module");
+    apply(src, &edits)
+}
+
+fn format_on_type(src: &str, position: Position, character: &str) -> String {
+    let edits = on_type_formatting(
+        &EcoString::from(src),
+        Utf8Path::new("/src/app.gleam"),
+        position,
+        character,
+    );
+    apply(src, &edits)
+}
+
+const UNFORMATTED: &str = "pub fn one() {
+    1+1
+}
+
+pub fn two() {
+    case 2 {
+      2 ->    \"two\"
+      _ ->    \"other\"
+    }
+}
+";
+
+#[test]
+fn range_formatting_only_formats_the_selected_function() {
+    let range = Range::new(Position::new(4, 0), Position::new(9, 1));
+
+    assert_eq!(
+        format_range(UNFORMATTED, range),
+        "pub fn one() {
+    1+1
+}
+
+pub fn two() {
+  case 2 {
+    2 -> \"two\"
+    _ -> \"other\"
+  }
+}
+"
+    );
+}
+
+#[test]
+fn range_formatting_only_formats_the_selected_case_clause() {
+    let range = Range::new(Position::new(6, 0), Position::new(6, 20));
+
+    assert_eq!(
+        format_range(UNFORMATTED, range),
+        "pub fn one() {
+    1+1
+}
+
+pub fn two() {
+    case 2 {
+    2 -> \"two\"
+      _ ->    \"other\"
+    }
+}
+"
+    );
+}
+
+#[test]
+fn range_formatting_whole_document() {
+    let range = Range::new(Position::new(0, 0), Position::new(11, 0));
+
+    assert_eq!(
+        format_range(UNFORMATTED, range),
+        "pub fn one() {
+  1 + 1
+}
+
+pub fn two() {
+  case 2 {
+    2 -> \"two\"
+    _ -> \"other\"
+  }
+}
+"
+    );
+}
+
+#[test]
+fn range_formatting_invalid_module_is_an_error() {
+    let src = "pub fn main( {";
+    let range = Range::new(Position::new(0, 0), Position::new(0, 5));
+
+    assert!(
+        range_formatting(
+            &EcoString::from(src),
+            Utf8Path::new("/src/app.gleam"),
+            range
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn range_formatting_does_not_change_code_outside_the_selection() {
+    // The formatter puts the call on a single line, which changes both lines
+    // at once. Only the selected first line is changed.
+    let src = "pub fn main() {
+  let x =    wibble(1,
+    2)
+  x
+}
+";
+    let range = Range::new(Position::new(1, 0), Position::new(1, 22));
+
+    assert_eq!(
+        format_range(src, range),
+        "pub fn main() {
+  let x = wibble(1,
+    2)
+  x
+}
+"
+    );
+}
+
+#[test]
+fn range_formatting_with_errors_in_other_definitions() {
+    let src = "pub fn one() {
+    1+1
+}
+
+pub fn two( {
+";
+    let range = Range::new(Position::new(0, 0), Position::new(2, 1));
+
+    assert_eq!(
+        format_range(src, range),
+        "pub fn one() {
+  1 + 1
+}
+
+pub fn two( {
+"
+    );
+}
+
+#[test]
+fn range_formatting_keeps_doc_comments_and_attributes_with_their_definition() {
+    let src = "/// Wibble
+@deprecated(\"Use wobble\")
+pub fn wibble() {
+    1+1
+}
+";
+    let range = Range::new(Position::new(3, 0), Position::new(3, 7));
+
+    assert_eq!(
+        format_range(src, range),
+        "/// Wibble
+@deprecated(\"Use wobble\")
+pub fn wibble() {
+  1 + 1
+}
+"
+    );
+}
+
+#[test]
+fn on_type_formatting_after_closing_brace() {
+    // The brace closing `one` was just typed.
+    let position = Position::new(2, 1);
+
+    assert_eq!(
+        format_on_type(UNFORMATTED, position, "}"),
+        "pub fn one() {
+  1 + 1
+}
+
+pub fn two() {
+    case 2 {
+      2 ->    \"two\"
+      _ ->    \"other\"
+    }
+}
+"
+    );
+}
+
+#[test]
+fn on_type_formatting_after_new_line() {
+    let src = "pub fn main() {
+  let x =   1+1
+  
+  x
+}
+";
+    // A new line was just typed at the end of the `let`, the line the cursor
+    // is on now is left alone.
+    let position = Position::new(2, 2);
+
+    assert_eq!(
+        format_on_type(src, position, "\n"),
+        "pub fn main() {
+  let x = 1 + 1
+  
+  x
+}
+"
+    );
+}
+
+#[test]
+fn on_type_formatting_invalid_module_does_nothing() {
+    let src = "pub fn main() {
+  let x =   1+1
+  case x {
+}
+";
+    let position = Position::new(3, 1);
+
+    assert_eq!(format_on_type(src, position, "}"), src);
+}
+
+#[test]
+fn on_type_formatting_with_errors_in_other_definitions() {
+    let src = "pub fn one() {
+    1+1
+}
+
+pub fn two( {
+";
+    let position = Position::new(2, 1);
+
+    assert_eq!(
+        format_on_type(src, position, "}"),
+        "pub fn one() {
+  1 + 1
+}
+
+pub fn two( {
+"
+    );
+}
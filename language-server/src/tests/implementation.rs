@@ -0,0 +1,237 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use lsp_types::{ImplementationParams, Location, Position, Range};
+
+use super::*;
+
+fn implementation(tester: &TestProject<'_>, position: Position) -> Vec<Location> {
+    tester.at(position, |engine, param, _| {
+        let params = ImplementationParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.goto_implementation(params).result.unwrap()
+    })
+}
+
+fn pretty_implementation(project: TestProject<'_>, position_finder: PositionFinder) -> String {
+    let position = position_finder.find_position(project.src);
+    let locations = implementation(&project, position);
+
+    let src = hover::show_hover(
+        project.src,
+        Range {
+            start: position,
+            end: position,
+        },
+        position,
+    );
+
+    let destinations = locations
+        .iter()
+        .map(|location| {
+            let path = location
+                .uri
+                .path_segments()
+                .expect("a location to jump to")
+                .skip_while(|segment| *segment == "C:")
+                .join("/");
+            let native_src = project
+                .native_files
+                .iter()
+                .find(|(native_path, _)| path.ends_with(native_path))
+                .map(|(_, src)| *src)
+                .expect("a native file to jump to");
+            let destination_code =
+                hover::show_hover(native_src, location.range, location.range.start);
+            format!("----- Jumped to `{path}`\n{destination_code}")
+        })
+        .join("\n\n");
+
+    format!("----- Jumping from `src/app.gleam`\n{src}\n{destinations}")
+}
+
+macro_rules! assert_goto_implementation {
+    ($project:expr, $position:expr) => {
+        let output = pretty_implementation($project, $position);
+        insta::assert_snapshot!(insta::internals::AutoName, output);
+    };
+}
+
+const ERLANG_FFI: &str = "-module(wibble_ffi).
+-export([do_thing/1, do_thing_else/1]).
+
+do_thing_else(X) ->
+    X.
+
+do_thing(X) ->
+    X.
+";
+
+const JAVASCRIPT_FFI: &str = "import { Ok } from \"./gleam.mjs\";
+
+export function doThingElse(x) {
+  return x;
+}
+
+export function doThing(x) {
+  return new Ok(x);
+}
+";
+
+#[test]
+fn goto_implementation_from_external_declaration() {
+    let src = r#"
+@external(erlang, "wibble_ffi", "do_thing")
+pub fn do_thing(x: Int) -> Int
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src).add_native_file("src/wibble_ffi.erl", ERLANG_FFI),
+        find_position_of("do_thing(x")
+    );
+}
+
+#[test]
+fn goto_implementation_from_call() {
+    let src = r#"
+@external(erlang, "wibble_ffi", "do_thing")
+fn do_thing(x: Int) -> Int
+
+pub fn main() {
+  do_thing(1)
+}
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src).add_native_file("src/wibble_ffi.erl", ERLANG_FFI),
+        find_position_of("do_thing(1)")
+    );
+}
+
+#[test]
+fn goto_implementation_in_nested_erlang_file() {
+    let src = r#"
+@external(erlang, "wibble_ffi", "do_thing")
+pub fn do_thing(x: Int) -> Int
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src).add_native_file("src/ffi/wibble_ffi.erl", ERLANG_FFI),
+        find_position_of("do_thing(x")
+    );
+}
+
+#[test]
+fn goto_implementation_javascript() {
+    let src = r#"
+@external(javascript, "./wibble_ffi.mjs", "doThing")
+pub fn do_thing(x: Int) -> Result(Int, Nil)
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src).add_native_file("src/wibble_ffi.mjs", JAVASCRIPT_FFI),
+        find_position_of("do_thing(x")
+    );
+}
+
+#[test]
+fn goto_implementation_both_targets() {
+    let src = r#"
+@external(erlang, "wibble_ffi", "do_thing")
+@external(javascript, "./wibble_ffi.mjs", "doThing")
+pub fn do_thing(x: Int) -> Int
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src)
+            .add_native_file("src/wibble_ffi.erl", ERLANG_FFI)
+            .add_native_file("src/wibble_ffi.mjs", JAVASCRIPT_FFI),
+        find_position_of("do_thing(x")
+    );
+}
+
+#[test]
+fn goto_implementation_elixir() {
+    let src = r#"
+@external(erlang, "Elixir.Wibble.Ffi", "do_thing")
+pub fn do_thing(x: Int) -> Int
+"#;
+
+    let elixir = "defmodule Wibble.Ffi do
+  def do_thing_else(x), do: x
+
+  def do_thing(x) do
+    x
+  end
+end
+";
+
+    assert_goto_implementation!(
+        TestProject::for_source(src).add_native_file("src/wibble_ffi.ex", elixir),
+        find_position_of("do_thing(x")
+    );
+}
+
+#[test]
+fn goto_implementation_function_not_found_jumps_to_start_of_file() {
+    let src = r#"
+@external(erlang, "wibble_ffi", "missing")
+pub fn do_thing(x: Int) -> Int
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src).add_native_file("src/wibble_ffi.erl", ERLANG_FFI),
+        find_position_of("do_thing(x")
+    );
+}
+
+#[test]
+fn goto_implementation_from_other_module() {
+    let src = r#"
+import wibble
+
+pub fn main() {
+  wibble.do_thing(1)
+}
+"#;
+
+    let wibble = r#"
+@external(javascript, "./wibble_ffi.mjs", "doThing")
+pub fn do_thing(x: Int) -> Result(Int, Nil)
+"#;
+
+    assert_goto_implementation!(
+        TestProject::for_source(src)
+            .add_module("wibble", wibble)
+            .add_native_file("src/wibble_ffi.mjs", JAVASCRIPT_FFI),
+        find_position_of("do_thing(1)")
+    );
+}
+
+#[test]
+fn no_implementation_for_gleam_function() {
+    let src = r#"
+pub fn do_thing(x: Int) -> Int {
+  x
+}
+"#;
+
+    let project = TestProject::for_source(src).add_native_file("src/wibble_ffi.erl", ERLANG_FFI);
+    let position = find_position_of("do_thing(x").find_position(src);
+    assert_eq!(implementation(&project, position), vec![]);
+}
+
+#[test]
+fn no_implementation_for_javascript_package() {
+    let src = r#"
+@external(javascript, "node:fs", "readFileSync")
+pub fn read(path: String) -> String
+"#;
+
+    let project = TestProject::for_source(src);
+    let position = find_position_of("read(path").find_position(src);
+    assert_eq!(implementation(&project, position), vec![]);
+}
@@ -0,0 +1,237 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{InlayHint, InlayHintParams, Label, Range};
+
+use crate::configuration::{Configuration, InlayHintsConfig};
+
+use super::*;
+
+fn inlay_hints(tester: TestProject<'_>) -> String {
+    inlay_hints_with_config(tester, InlayHintsConfig::default())
+}
+
+fn inlay_hints_with_config(tester: TestProject<'_>, config: InlayHintsConfig) -> String {
+    tester.at(Position::default(), |engine, param, src| {
+        let params = InlayHintParams {
+            text_document: param.text_document,
+            range: Range::new(Position::new(0, 0), Position::new(u32::MAX, 0)),
+            work_done_progress_params: Default::default(),
+        };
+        let hints = engine
+            .inlay_hints(params, config)
+            .result
+            .expect("inlay hints should not fail");
+        show_hints(&src, hints)
+    })
+}
+
+/// Prints the code with each hint inserted where the editor would display it,
+/// surrounded by `[` and `]`.
+///
+fn show_hints(code: &str, mut hints: Vec<InlayHint>) -> String {
+    hints.sort_by_key(|hint| (hint.position.line, hint.position.character));
+
+    let mut output = String::new();
+    for (line_number, line) in code.lines().enumerate() {
+        let mut last = 0;
+        for hint in hints
+            .iter()
+            .filter(|hint| hint.position.line as usize == line_number)
+        {
+            let column = hint.position.character as usize;
+            let label = match &hint.label {
+                Label::String(label) => label.clone(),
+                Label::InlayHintLabelPartList(parts) => {
+                    parts.iter().map(|part| part.value.as_str()).collect()
+                }
+            };
+            output.push_str(line.get(last..column).unwrap_or_default());
+            output.push_str(&format!("[{label}]"));
+            last = column;
+        }
+        output.push_str(line.get(last..).unwrap_or_default());
+        output.push('\n');
+    }
+    output
+}
+
+#[test]
+fn inlay_hints_for_let_bindings() {
+    let code = r#"
+pub fn main() {
+  let number = 1
+  let annotated: Int = 2
+  let #(first, second) = #(number, "two")
+  let _ = first
+  let assert [head, ..] = [second]
+  head
+}
+"#;
+
+    assert_snapshot!(inlay_hints(TestProject::for_source(code)));
+}
+
+#[test]
+fn inlay_hints_for_generic_let_bindings() {
+    let code = r#"
+pub fn identity(value: a) -> a {
+  let result = value
+  result
+}
+
+pub fn main() {
+  let empty = []
+  let function = identity
+  function(empty)
+}
+"#;
+
+    assert_snapshot!(inlay_hints(TestProject::for_source(code)));
+}
+
+#[test]
+fn inlay_hints_for_use_bindings() {
+    let code = r#"
+pub fn main() {
+  use number <- apply(1)
+  use #(a, b) <- apply(#(number, "two"))
+  use annotated: Int <- apply(number)
+  use _ <- apply(a)
+  b
+}
+
+fn apply(value: a, f: fn(a) -> b) -> b {
+  f(value)
+}
+"#;
+
+    assert_snapshot!(inlay_hints(TestProject::for_source(code)));
+}
+
+#[test]
+fn inlay_hints_for_multiline_pipelines() {
+    let code = r#"
+pub fn main() {
+  [1, 2, 3]
+  |> length
+  |> to_string
+}
+
+pub fn single_line() {
+  [1, 2, 3] |> length |> to_string
+}
+
+fn length(list: List(a)) -> Int { todo }
+
+fn to_string(int: Int) -> String { todo }
+"#;
+
+    assert_snapshot!(inlay_hints(TestProject::for_source(code)));
+}
+
+#[test]
+fn inlay_hints_for_positional_arguments() {
+    let code = r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  let age = 10
+  Person("Lucy", age)
+  Person(age: 11, name: "Nubi")
+  greet("Hello", name: "Lucy")
+  unlabelled(1)
+}
+
+fn greet(greeting greeting: String, name name: String) -> String {
+  greeting <> name
+}
+
+fn unlabelled(value: Int) -> Int { value }
+"#;
+
+    assert_snapshot!(inlay_hints(TestProject::for_source(code)));
+}
+
+#[test]
+fn inlay_hints_for_positional_arguments_ignore_pipes_and_captures() {
+    let code = r#"
+pub fn main() {
+  1
+  |> add(to: 2)
+  |> add(3, _)
+}
+
+fn add(value value: Int, to other: Int) -> Int {
+  value + other
+}
+"#;
+
+    assert_snapshot!(inlay_hints_with_config(
+        TestProject::for_source(code),
+        InlayHintsConfig {
+            variable_types: false,
+            pipelines: false,
+            parameter_labels: true,
+        }
+    ));
+}
+
+#[test]
+fn inlay_hints_can_be_disabled() {
+    let code = r#"
+pub fn main() {
+  let number = add(1, 2)
+  number
+  |> add(3)
+}
+
+fn add(value value: Int, to other: Int) -> Int {
+  value + other
+}
+"#;
+
+    assert_snapshot!(inlay_hints_with_config(
+        TestProject::for_source(code),
+        InlayHintsConfig {
+            variable_types: false,
+            pipelines: false,
+            parameter_labels: false,
+        }
+    ));
+}
+
+#[test]
+fn inlay_hints_configuration_from_initialization_options() {
+    let options = serde_json::json!({
+        "inlayHints": { "pipelines": false, "parameterLabels": false }
+    });
+
+    assert_eq!(
+        Configuration::from_initialization_options(Some(&options)),
+        Configuration {
+            inlay_hints: InlayHintsConfig {
+                variable_types: true,
+                pipelines: false,
+                parameter_labels: false,
+            }
+        }
+    );
+}
+
+#[test]
+fn inlay_hints_configuration_defaults() {
+    let invalid = serde_json::json!({ "inlayHints": { "pipelines": "yes" } });
+
+    assert_eq!(
+        Configuration::from_initialization_options(None),
+        Configuration::default()
+    );
+    assert_eq!(
+        Configuration::from_initialization_options(Some(&invalid)),
+        Configuration::default()
+    );
+}
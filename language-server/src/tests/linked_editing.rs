@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{LinkedEditingRangeParams, Range};
+
+use super::*;
+
+fn linked_editing_ranges(tester: TestProject<'_>, position: PositionFinder) -> Option<Vec<Range>> {
+    let position = position.find_position(tester.src);
+    tester.at(position, |engine, params, _| {
+        let params = LinkedEditingRangeParams {
+            work_done_progress_params: Default::default(),
+            text_document_position_params: params,
+        };
+        engine
+            .linked_editing_range(params)
+            .result
+            .expect("linked editing range should not fail")
+            .map(|ranges| ranges.ranges)
+    })
+}
+
+/// Underlines the linked ranges on the line they appear in.
+///
+fn show_linked_ranges(src: &str, ranges: Vec<Range>) -> String {
+    let mut output = String::new();
+    for (line_number, line) in src.lines().enumerate() {
+        output.push_str(line);
+        output.push('\n');
+
+        let line_ranges = ranges
+            .iter()
+            .filter(|range| range.start.line as usize == line_number)
+            .collect_vec();
+        if line_ranges.is_empty() {
+            continue;
+        }
+
+        let mut underline = " ".repeat(line.len());
+        for range in line_ranges {
+            let start = range.start.character as usize;
+            let end = range.end.character as usize;
+            underline.replace_range(start..end, &"^".repeat(end - start));
+        }
+        output.push_str(underline.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+fn linked_ranges(tester: TestProject<'_>, position: PositionFinder) -> String {
+    let src = tester.src;
+    let ranges = linked_editing_ranges(tester, position).expect("linked editing ranges");
+    show_linked_ranges(src, ranges)
+}
+
+#[test]
+fn linked_editing_local_variable() {
+    let src = "
+pub fn main() {
+  let wibble = 10
+  wibble + wibble
+}
+";
+
+    assert_snapshot!(linked_ranges(
+        TestProject::for_source(src),
+        find_position_of("wibble").nth_occurrence(2)
+    ));
+}
+
+#[test]
+fn linked_editing_alternative_patterns() {
+    let src = "
+pub type Wibble {
+  Wibble(Int)
+  Wobble(Int)
+}
+
+pub fn main(x) {
+  case x {
+    Wibble(value) | Wobble(value) -> value
+  }
+}
+";
+
+    assert_snapshot!(linked_ranges(
+        TestProject::for_source(src),
+        find_position_of("value")
+    ));
+}
+
+#[test]
+fn linked_editing_label_and_pattern_shorthand() {
+    let src = "
+type Wibble {
+  Wibble(wobble: Int)
+}
+
+pub fn main() {
+  let Wibble(wobble:) = Wibble(wobble: 1)
+  wobble + 1
+}
+";
+
+    assert_snapshot!(linked_ranges(
+        TestProject::for_source(src),
+        find_position_of("wobble").nth_occurrence(4)
+    ));
+}
+
+#[test]
+fn linked_editing_label_and_argument_shorthand() {
+    let src = "
+type Wibble {
+  Wibble(wobble: Int)
+}
+
+pub fn main() {
+  let wobble = 1
+  Wibble(wobble:)
+}
+";
+
+    assert_snapshot!(linked_ranges(
+        TestProject::for_source(src),
+        find_position_of("wobble")
+    ));
+}
+
+#[test]
+fn no_linked_editing_for_label_used_in_other_module() {
+    let src = "
+pub type Wibble {
+  Wibble(wobble: Int)
+}
+";
+    let other = "
+import app
+
+pub fn main() {
+  app.Wibble(wobble: 1)
+}
+";
+
+    assert_eq!(
+        linked_editing_ranges(
+            TestProject::for_source(src).add_module("other", other),
+            find_position_of("wobble")
+        ),
+        None
+    );
+}
+
+#[test]
+fn no_linked_editing_for_single_occurrence() {
+    let src = "
+pub fn main() {
+  let wibble = 10
+  Nil
+}
+";
+
+    assert_eq!(
+        linked_editing_ranges(TestProject::for_source(src), find_position_of("wibble")),
+        None
+    );
+}
+
+#[test]
+fn no_linked_editing_for_function() {
+    let src = "
+pub fn main() {
+  wibble() + wibble()
+}
+
+fn wibble() {
+  1
+}
+";
+
+    assert_eq!(
+        linked_editing_ranges(TestProject::for_source(src), find_position_of("wibble")),
+        None
+    );
+}
@@ -0,0 +1,357 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use lsp_server::{Connection, ErrorCode};
+use serde_json::json;
+
+use crate::messages::{AnalysisRequest, Message, MessageBuffer, Next, Notification, Request};
+
+fn hover(id: i32, file: &str) -> lsp_server::Message {
+    lsp_server::Message::Request(lsp_server::Request {
+        id: id.into(),
+        method: "textDocument/hover".into(),
+        params: json!({
+            "textDocument": { "uri": format!("file:///app/src/{file}") },
+            "position": { "line": 0, "character": 0 },
+        }),
+    })
+}
+
+fn rename(id: i32, file: &str) -> lsp_server::Message {
+    lsp_server::Message::Request(lsp_server::Request {
+        id: id.into(),
+        method: "textDocument/rename".into(),
+        params: json!({
+            "textDocument": { "uri": format!("file:///app/src/{file}") },
+            "position": { "line": 0, "character": 0 },
+            "newName": "wobble",
+        }),
+    })
+}
+
+fn change(file: &str) -> lsp_server::Message {
+    lsp_server::Message::Notification(lsp_server::Notification {
+        method: "textDocument/didChange".into(),
+        params: json!({
+            "textDocument": { "uri": format!("file:///app/src/{file}"), "version": 1 },
+            "contentChanges": [{ "text": "pub fn wibble() { Nil }" }],
+        }),
+    })
+}
+
+fn cancel(id: i32) -> lsp_server::Message {
+    lsp_server::Message::Notification(lsp_server::Notification {
+        method: "$/cancelRequest".into(),
+        params: json!({ "id": id }),
+    })
+}
+
+/// Sends the messages from the client, and returns a description of the
+/// first batch of messages the buffer gives to the server to handle.
+///
+fn handled_messages(
+    client: &Connection,
+    server: &Connection,
+    messages: Vec<lsp_server::Message>,
+) -> Vec<String> {
+    let mut buffer = MessageBuffer::new();
+    receive_messages(&mut buffer, client, server, messages);
+    std::iter::from_fn(|| buffer.next_message())
+        .map(|message| describe(&message))
+        .collect()
+}
+
+/// Sends the messages from the client, and has the buffer receive them until
+/// it has a batch for the server to handle.
+///
+fn receive_messages(
+    buffer: &mut MessageBuffer,
+    client: &Connection,
+    server: &Connection,
+    messages: Vec<lsp_server::Message>,
+) {
+    for message in messages {
+        client.sender.send(message).expect("send message");
+    }
+
+    loop {
+        match buffer.receive(server) {
+            Next::MorePlease => (),
+            Next::Stop => panic!("The buffer should not stop"),
+            Next::Handle => return,
+        }
+    }
+}
+
+/// Sends the messages from the client while the server is busy, and has the
+/// buffer take them.
+///
+fn send_while_busy(
+    buffer: &mut MessageBuffer,
+    client: &Connection,
+    server: &Connection,
+    messages: Vec<lsp_server::Message>,
+) {
+    for message in messages {
+        client.sender.send(message).expect("send message");
+    }
+    buffer.receive_available(server);
+}
+
+fn next_message(buffer: &mut MessageBuffer) -> Option<String> {
+    buffer.next_message().map(|message| describe(&message))
+}
+
+fn describe(message: &Message) -> String {
+    match message {
+        Message::Request(id, Request::Analysis(AnalysisRequest::Hover(_))) => format!("hover {id}"),
+        Message::Request(id, Request::Rename(_)) => format!("rename {id}"),
+        Message::Request(id, request) => format!("request {id}: {request:?}"),
+        Message::Notification(Notification::CompilePlease) => "compile".into(),
+        Message::Notification(Notification::SourceFileChangedInMemory { path, .. }) => {
+            format!("change {}", path.file_name().unwrap_or_default())
+        }
+        Message::Notification(notification) => format!("{notification:?}"),
+    }
+}
+
+#[test]
+fn changes_are_compiled_after_a_pause() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(&client, &server, vec![change("wibble.gleam")]);
+
+    assert_eq!(messages, vec!["change wibble.gleam", "compile"]);
+}
+
+#[test]
+fn read_only_request_for_unchanged_file_does_not_wait_for_compilation() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(
+        &client,
+        &server,
+        vec![change("wibble.gleam"), hover(1, "wobble.gleam")],
+    );
+
+    assert_eq!(messages, vec!["change wibble.gleam", "hover 1", "compile"]);
+}
+
+#[test]
+fn read_only_request_for_changed_file_is_compiled_first() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(
+        &client,
+        &server,
+        vec![change("wibble.gleam"), hover(1, "wibble.gleam")],
+    );
+
+    assert_eq!(
+        messages,
+        vec!["change wibble.gleam", "compile", "hover 1", "compile"]
+    );
+}
+
+#[test]
+fn other_requests_are_compiled_first() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(
+        &client,
+        &server,
+        vec![change("wibble.gleam"), rename(1, "wobble.gleam")],
+    );
+
+    assert_eq!(
+        messages,
+        vec!["change wibble.gleam", "compile", "rename 1", "compile"]
+    );
+}
+
+#[test]
+fn messages_sent_after_a_request_are_taken_with_it() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(
+        &client,
+        &server,
+        vec![
+            hover(1, "wibble.gleam"),
+            change("wibble.gleam"),
+            hover(2, "wibble.gleam"),
+        ],
+    );
+
+    assert_eq!(
+        messages,
+        vec![
+            "hover 1",
+            "change wibble.gleam",
+            "compile",
+            "hover 2",
+            "compile"
+        ]
+    );
+}
+
+#[test]
+fn cancelled_request_is_not_handled() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(
+        &client,
+        &server,
+        vec![
+            hover(1, "wibble.gleam"),
+            cancel(1),
+            hover(2, "wibble.gleam"),
+        ],
+    );
+
+    assert_eq!(messages, vec!["hover 2", "compile"]);
+
+    let Ok(lsp_server::Message::Response(response)) = client.receiver.try_recv() else {
+        panic!("The cancelled request should be responded to");
+    };
+    assert_eq!(response.id, 1.into());
+    assert_eq!(
+        response.error.map(|error| error.code),
+        Some(ErrorCode::RequestCanceled as i32)
+    );
+}
+
+#[test]
+fn cancelling_an_unknown_request_does_nothing() {
+    let (server, client) = Connection::memory();
+    let messages = handled_messages(&client, &server, vec![cancel(1), hover(2, "wibble.gleam")]);
+
+    assert_eq!(messages, vec!["hover 2", "compile"]);
+    assert!(client.receiver.try_recv().is_err());
+}
+
+#[test]
+fn cancelled_request_is_dropped_after_being_given_to_the_server() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+    receive_messages(
+        &mut buffer,
+        &client,
+        &server,
+        vec![hover(1, "wibble.gleam"), hover(2, "wibble.gleam")],
+    );
+
+    assert_eq!(next_message(&mut buffer), Some("hover 1".into()));
+    send_while_busy(&mut buffer, &client, &server, vec![cancel(2)]);
+    assert_eq!(next_message(&mut buffer), Some("compile".into()));
+    assert_eq!(next_message(&mut buffer), None);
+
+    let Ok(lsp_server::Message::Response(response)) = client.receiver.try_recv() else {
+        panic!("The cancelled request should be responded to");
+    };
+    assert_eq!(response.id, 2.into());
+    assert_eq!(
+        response.error.map(|error| error.code),
+        Some(ErrorCode::RequestCanceled as i32)
+    );
+}
+
+#[test]
+fn analysis_requests_sent_while_busy_can_be_answered_straight_away() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+    receive_messages(&mut buffer, &client, &server, vec![change("wibble.gleam")]);
+    assert_eq!(
+        next_message(&mut buffer),
+        Some("change wibble.gleam".into())
+    );
+    assert_eq!(next_message(&mut buffer), Some("compile".into()));
+
+    // While compiling, more messages arrive.
+    send_while_busy(
+        &mut buffer,
+        &client,
+        &server,
+        vec![
+            hover(1, "wobble.gleam"),
+            change("wibble.gleam"),
+            hover(2, "wibble.gleam"),
+            rename(3, "wobble.gleam"),
+            hover(4, "wubble.gleam"),
+            hover(5, "wabble.gleam"),
+        ],
+    );
+
+    // Only the requests for files without pending changes are taken, if the
+    // server can answer them.
+    let requests = buffer.take_analysis_requests(|uri| !uri.as_str().ends_with("wabble.gleam"));
+    let ids: Vec<_> = requests.into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec![1.into(), 4.into()]);
+
+    // The other messages are handled once the server is done.
+    receive_messages(&mut buffer, &client, &server, vec![]);
+    let messages: Vec<_> = std::iter::from_fn(|| next_message(&mut buffer)).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "change wibble.gleam",
+            "compile",
+            "hover 2",
+            "compile",
+            "rename 3",
+            "hover 5",
+            "compile"
+        ]
+    );
+}
+
+#[test]
+fn changes_still_to_be_handled_stop_requests_being_answered_while_busy() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+    receive_messages(
+        &mut buffer,
+        &client,
+        &server,
+        vec![
+            change("wibble.gleam"),
+            rename(1, "wobble.gleam"),
+            change("wubble.gleam"),
+        ],
+    );
+    assert_eq!(
+        next_message(&mut buffer),
+        Some("change wibble.gleam".into())
+    );
+    assert_eq!(next_message(&mut buffer), Some("compile".into()));
+
+    send_while_busy(
+        &mut buffer,
+        &client,
+        &server,
+        vec![hover(2, "wubble.gleam"), hover(3, "wibble.gleam")],
+    );
+
+    // The change to `wubble.gleam` has not been handled yet.
+    let requests = buffer.take_analysis_requests(|_| true);
+    let ids: Vec<_> = requests.into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec![3.into()]);
+}
+
+#[test]
+fn shutdown_request_sent_while_busy_stops_the_buffer_afterwards() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+    send_while_busy(
+        &mut buffer,
+        &client,
+        &server,
+        vec![
+            lsp_server::Message::Request(lsp_server::Request {
+                id: 1.into(),
+                method: "shutdown".into(),
+                params: json!(null),
+            }),
+            lsp_server::Message::Notification(lsp_server::Notification {
+                method: "exit".into(),
+                params: json!(null),
+            }),
+        ],
+    );
+
+    assert!(matches!(buffer.receive(&server), Next::Stop));
+}
@@ -3,9 +3,23 @@
 
 use std::time::SystemTime;
 
-use gleam_core::{Error, io::FileSystemWriter, paths::ProjectPaths};
+use camino::Utf8PathBuf;
+use gleam_core::{
+    Error,
+    build::{Mode, Target},
+    io::{FileSystemReader, FileSystemWriter},
+    paths::ProjectPaths,
+    type_::{self, Type, TypeVar},
+};
+use itertools::Itertools;
+use lsp_types::{HoverParams, Position, TextDocumentIdentifier, TextDocumentPositionParams};
 
-use crate::{files::FileSystemProxy, tests::Action};
+use crate::{
+    feedback::{DiagnosticsReport, DiagnosticsStore, Feedback},
+    files::FileSystemProxy,
+    tests::Action,
+    url_from_path,
+};
 
 use super::LanguageServerTestIO;
 
@@ -120,3 +134,430 @@ fn set_up_minimal_router(paths: &ProjectPaths) -> (LanguageServerTestIO, Router)
     io.write(&paths.root_config(), toml).unwrap();
     (io, router)
 }
+
+#[test]
+fn workspace_member_uses_workspace_build_directory() {
+    let (io, mut router) = set_up_workspace_router();
+
+    let project = router
+        .project_for_path("/ws/packages/app/src/app.gleam".into())
+        .unwrap()
+        .unwrap();
+    assert_eq!(project.engine.paths.root(), "/ws/packages/app");
+    assert_eq!(project.engine.paths.build_directory(), "/ws/build");
+    assert_eq!(project.engine.paths.manifest(), "/ws/manifest.toml");
+
+    assert_eq!(project.engine.compile_please().result, Ok(()));
+    assert!(io.exists("/ws/build".into()));
+    assert!(!io.exists("/ws/packages/app/build".into()));
+}
+
+#[test]
+fn changing_workspace_config_redownloads_dependencies() {
+    let paths = ProjectPaths::new_workspace_member("/ws/packages/app".into(), "/ws".into());
+    let (io, mut router) = set_up_workspace_router();
+
+    _ = compile(&mut router, &paths);
+    io.actions.lock().unwrap().clear();
+
+    let config_path = paths.workspace_config().unwrap();
+    io.write(&config_path, r#"members = ["packages/*", "apps/*"]"#)
+        .unwrap();
+    io.io
+        .try_set_modification_time(&config_path, SystemTime::now())
+        .unwrap();
+
+    assert_eq!(
+        compile(&mut router, &paths),
+        Ok(()),
+        "Compile after changing the workspace config should succeed"
+    );
+
+    {
+        let actions = io.actions.lock().unwrap();
+        assert!(
+            actions.contains(&Action::DownloadDependencies),
+            "Compile after changing the workspace config should re-download dependencies"
+        );
+    }
+}
+
+fn set_up_workspace_router() -> (LanguageServerTestIO, Router) {
+    let io = LanguageServerTestIO::new();
+    let router = Router::new(io.clone(), FileSystemProxy::new(io.clone()));
+
+    io.write(
+        "/ws/gleam-workspace.toml".into(),
+        r#"members = ["packages/*"]"#,
+    )
+    .unwrap();
+    let toml = r#"name = "app"
+    version = "1.0.0""#;
+    io.write("/ws/packages/app/gleam.toml".into(), toml)
+        .unwrap();
+    io.write(
+        "/ws/packages/app/src/app.gleam".into(),
+        "pub fn main() { Nil }",
+    )
+    .unwrap();
+    (io, router)
+}
+
+#[test]
+fn opening_workspace_folder_starts_engine_for_its_project() {
+    let (_, mut router) = set_up_workspace_folders_router();
+
+    assert_eq!(
+        router.add_workspace_folder("/one".into()),
+        Ok(Some("/one".into()))
+    );
+    assert_eq!(project_roots(&mut router), vec!["/one"]);
+}
+
+#[test]
+fn opening_workspace_folder_inside_project_starts_engine_for_the_project() {
+    let (_, mut router) = set_up_workspace_folders_router();
+
+    assert_eq!(
+        router.add_workspace_folder("/one/src".into()),
+        Ok(Some("/one".into()))
+    );
+    assert_eq!(project_roots(&mut router), vec!["/one"]);
+}
+
+#[test]
+fn opening_workspace_folder_without_project_starts_no_engine() {
+    let (_, mut router) = set_up_workspace_folders_router();
+
+    assert_eq!(router.add_workspace_folder("/notes".into()), Ok(None));
+    assert_eq!(project_roots(&mut router), Vec::<String>::new());
+}
+
+#[test]
+fn closing_workspace_folder_shuts_down_its_engines() {
+    let (_, mut router) = set_up_workspace_folders_router();
+    _ = router.add_workspace_folder("/one".into()).unwrap();
+    _ = router.add_workspace_folder("/two".into()).unwrap();
+
+    let removed = router.remove_workspace_folder("/one".into());
+
+    assert_eq!(
+        removed
+            .iter()
+            .map(|project| project.engine.paths.root().to_string())
+            .collect_vec(),
+        vec!["/one"]
+    );
+    assert_eq!(project_roots(&mut router), vec!["/two"]);
+}
+
+#[test]
+fn closing_workspace_folder_keeps_engine_of_project_in_another_open_folder() {
+    let (_, mut router) = set_up_workspace_folders_router();
+    _ = router.add_workspace_folder("/one".into()).unwrap();
+    _ = router.add_workspace_folder("/one/src".into()).unwrap();
+
+    assert!(router.remove_workspace_folder("/one/src".into()).is_empty());
+    assert_eq!(project_roots(&mut router), vec!["/one"]);
+}
+
+#[test]
+fn closing_workspace_folder_shuts_down_engines_of_files_opened_in_it() {
+    let (_, mut router) = set_up_workspace_folders_router();
+    _ = router.add_workspace_folder("/".into()).unwrap();
+    _ = router
+        .project_for_path("/two/src/two.gleam".into())
+        .unwrap()
+        .unwrap();
+
+    let removed = router.remove_workspace_folder("/".into());
+
+    assert_eq!(removed.len(), 1);
+    assert_eq!(project_roots(&mut router), Vec::<String>::new());
+}
+
+#[test]
+fn projects_share_modules_of_the_same_hex_packages() {
+    let (io, mut router) = set_up_workspace_folders_router();
+    let one = ProjectPaths::new("/one".into());
+    let two = ProjectPaths::new("/two".into());
+
+    assert_eq!(compile(&mut router, &one), Ok(()));
+    assert_eq!(compile(&mut router, &two), Ok(()));
+
+    // The package has only been loaded, and so compiled, by the first project.
+    let package_build_directory =
+        |paths: &ProjectPaths| paths.build_directory_for_package(Mode::Lsp, Target::Erlang, "hex");
+    assert!(io.exists(&package_build_directory(&one)));
+    assert!(!io.exists(&package_build_directory(&two)));
+
+    // The second project still knows about the modules of the package, which
+    // are found in its own copy of the package.
+    let project = router.project_for_path("/two".into()).unwrap().unwrap();
+    let module = project
+        .engine
+        .compiler
+        .get_module_interface("hex_module")
+        .expect("module of the shared package");
+    assert_eq!(
+        module.src_path,
+        "/two/build/packages/hex/src/hex_module.gleam"
+    );
+}
+
+#[test]
+fn projects_sharing_a_generic_hex_package_can_use_its_types() {
+    let (io, mut router) = set_up_workspace_folders_router();
+    let one = ProjectPaths::new("/one".into());
+    let two = ProjectPaths::new("/two".into());
+    for paths in [&one, &two] {
+        io.write(
+            &paths
+                .build_packages_package("hex")
+                .join("src/hex_module.gleam"),
+            "pub type Box(a) { Box(a) }
+pub fn unbox(box: Box(a)) -> a { let Box(value) = box value }",
+        )
+        .unwrap();
+    }
+    let path = Utf8PathBuf::from("/two/src/two.gleam");
+    io.write(
+        &path,
+        "import hex_module
+pub fn main() {
+  let box = hex_module.Box(1)
+  hex_module.unbox(box)
+}",
+    )
+    .unwrap();
+
+    assert_eq!(compile(&mut router, &one), Ok(()));
+    assert_eq!(compile(&mut router, &two), Ok(()));
+
+    // The second project uses the first's modules, but their type variables
+    // are given ids from its own compiler so they can't collide with those of
+    // the type variables it creates.
+    let one_id = unbox_type_variable_id(&mut router, "/one");
+    let two_id = unbox_type_variable_id(&mut router, "/two");
+    assert!(one_id.is_some());
+    assert!(two_id.is_some());
+    assert_ne!(one_id, two_id);
+
+    let project = router.project_for_path("/two".into()).unwrap().unwrap();
+    let params = HoverParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(url_from_path(path.as_str()).unwrap()),
+            Position::new(2, 7),
+        ),
+        work_done_progress_params: Default::default(),
+    };
+    let hover = project.engine.hover(params).result.unwrap().unwrap();
+    let contents = format!("{:?}", hover.contents);
+    assert!(contents.contains("hex_module.Box(Int)"), "{contents}");
+
+    io.write(
+        &path,
+        "import hex_module
+pub fn main() -> String {
+  hex_module.unbox(hex_module.Box(1))
+}",
+    )
+    .unwrap();
+    io.io
+        .try_set_modification_time(&path, SystemTime::now())
+        .unwrap();
+    let Err(Error::Type { failed_modules, .. }) = compile(&mut router, &two) else {
+        panic!("The second project should have a type error");
+    };
+    let errors = failed_modules
+        .values()
+        .flat_map(|module| module.errors.iter())
+        .collect_vec();
+    assert!(matches!(
+        errors.as_slice(),
+        [type_::Error::CouldNotUnify { expected, given, .. }]
+            if expected.is_string() && given.is_int()
+    ));
+}
+
+/// The id of the type variable returned by `hex_module.unbox`, as known by
+/// the compiler of the project.
+fn unbox_type_variable_id(router: &mut Router, root: &str) -> Option<u64> {
+    let project = router.project_for_path(root.into()).unwrap()?;
+    let module = project.engine.compiler.get_module_interface("hex_module")?;
+    let Type::Fn { return_, .. } = module.values.get("unbox")?.type_.as_ref() else {
+        return None;
+    };
+    let Type::Var { type_ } = return_.as_ref() else {
+        return None;
+    };
+    match *type_.borrow() {
+        TypeVar::Generic { id } => Some(id),
+        TypeVar::Unbound { .. } | TypeVar::Link { .. } => None,
+    }
+}
+
+fn project_roots(router: &mut Router) -> Vec<String> {
+    router
+        .projects()
+        .map(|project| project.engine.paths.root().to_string())
+        .sorted()
+        .collect()
+}
+
+/// Two independent projects, `/one` and `/two`, which both depend on the same
+/// version of the `hex` package.
+fn set_up_workspace_folders_router() -> (LanguageServerTestIO, Router) {
+    let mut io = LanguageServerTestIO::new();
+    io.add_hex_package("hex");
+    let router = Router::new(io.clone(), FileSystemProxy::new(io.clone()));
+
+    for name in ["one", "two"] {
+        let paths = ProjectPaths::new(Utf8PathBuf::from("/").join(name));
+        let toml = format!("name = \"{name}\"\nversion = \"1.0.0\"");
+        io.write(&paths.root_config(), &toml).unwrap();
+        io.write(
+            &paths.src_directory().join(name).with_extension("gleam"),
+            "pub fn main() { Nil }",
+        )
+        .unwrap();
+
+        let package = paths.build_packages_package("hex");
+        io.write(
+            &package.join("gleam.toml"),
+            "name = \"hex\"\nversion = \"1.0.0\"",
+        )
+        .unwrap();
+        io.write(
+            &package.join("src/hex_module.gleam"),
+            "pub fn wibble() { Nil }",
+        )
+        .unwrap();
+    }
+
+    (io, router)
+}
+
+#[test]
+fn project_without_engine_is_unloaded() {
+    let (_, mut router) = set_up_workspace_folders_router();
+
+    assert_eq!(
+        router.unloaded_project_path("/one/src/one.gleam".into()),
+        Some("/one".into())
+    );
+
+    _ = router.project_for_path("/one".into()).unwrap().unwrap();
+    assert_eq!(
+        router.unloaded_project_path("/one/src/one.gleam".into()),
+        None
+    );
+    assert_eq!(router.unloaded_project_path("/notes/todo.txt".into()), None);
+}
+
+#[test]
+fn workspace_folder_project_without_engine_is_unloaded() {
+    let (_, mut router) = set_up_workspace_folders_router();
+    _ = router.add_workspace_folder("/one".into()).unwrap();
+    _ = router.add_workspace_folder("/two/src".into()).unwrap();
+    _ = router.add_workspace_folder("/notes".into()).unwrap();
+    assert_eq!(
+        router.unloaded_workspace_projects(),
+        Vec::<Utf8PathBuf>::new()
+    );
+
+    router.delete_engine_for_path("/two/src/two.gleam".into());
+    assert_eq!(router.unloaded_workspace_projects(), vec!["/two"]);
+}
+
+#[test]
+fn first_diagnostics_pull_compiles_unloaded_project() {
+    let (io, mut router) = set_up_workspace_folders_router();
+    let path = Utf8PathBuf::from("/one/src/one.gleam");
+    io.write(&path, "pub fn main() { wibble }").unwrap();
+    let mut store = DiagnosticsStore::default();
+
+    let DiagnosticsReport::Full {
+        result_id,
+        diagnostics,
+    } = pull_diagnostics(&io, &mut router, &mut store, &path, None)
+    else {
+        panic!("The first pull should get a full report");
+    };
+    assert_eq!(diagnostics.len(), 1);
+
+    // The project is now loaded, and nothing has changed since.
+    assert_eq!(
+        pull_diagnostics(&io, &mut router, &mut store, &path, Some(&result_id)),
+        DiagnosticsReport::Unchanged {
+            result_id: result_id.clone()
+        }
+    );
+
+    // A pull from a client with outdated diagnostics gets the latest ones.
+    assert!(matches!(
+        pull_diagnostics(&io, &mut router, &mut store, &path, Some("outdated")),
+        DiagnosticsReport::Full { result_id: id, .. } if id == result_id
+    ));
+}
+
+#[test]
+fn diagnostics_pull_after_fix_reports_no_diagnostics() {
+    let (io, mut router) = set_up_workspace_folders_router();
+    let path = Utf8PathBuf::from("/one/src/one.gleam");
+    io.write(&path, "pub fn main() { wibble }").unwrap();
+    let mut store = DiagnosticsStore::default();
+
+    let DiagnosticsReport::Full { result_id, .. } =
+        pull_diagnostics(&io, &mut router, &mut store, &path, None)
+    else {
+        panic!("The first pull should get a full report");
+    };
+
+    io.write(&path, "pub fn main() { Nil }").unwrap();
+    let feedback = compile_with_feedback(&mut router, "/one".into());
+    _ = store.update(&feedback);
+
+    let DiagnosticsReport::Full {
+        result_id: new_result_id,
+        diagnostics,
+    } = pull_diagnostics(&io, &mut router, &mut store, &path, Some(&result_id))
+    else {
+        panic!("The fixed file should get a full report");
+    };
+    assert_ne!(new_result_id, result_id);
+    assert_eq!(diagnostics, vec![]);
+}
+
+/// Does what the language server does when the client pulls the diagnostics
+/// of a file: its project is compiled if it has never been loaded, and the
+/// diagnostics are reported.
+fn pull_diagnostics(
+    io: &LanguageServerTestIO,
+    router: &mut Router,
+    store: &mut DiagnosticsStore,
+    path: &Utf8PathBuf,
+    previous_result_id: Option<&str>,
+) -> DiagnosticsReport {
+    if let Some(root) = router.unloaded_project_path(path) {
+        let feedback = compile_with_feedback(router, root);
+        _ = store.update(&feedback);
+    }
+    let source = io.read(path).unwrap_or_default();
+    store.report(path, &source, previous_result_id)
+}
+
+fn compile_with_feedback(router: &mut Router, root: Utf8PathBuf) -> Feedback {
+    let project = router.project_for_path(root).unwrap().unwrap();
+    let response = project.engine.compile_please();
+    match response.result {
+        Ok(()) => project
+            .feedback
+            .response(response.compilation, response.warnings),
+        Err(error) => {
+            project
+                .feedback
+                .build_with_error(error, response.compilation, response.warnings)
+        }
+    }
+}
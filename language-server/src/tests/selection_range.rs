@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{SelectionRange, SelectionRangeParams};
+
+use super::*;
+
+fn selection_ranges(tester: TestProject<'_>, position: PositionFinder) -> String {
+    let position = position.find_position(tester.src);
+    let selection_range = tester.at(position, |engine, params, _| {
+        let params = SelectionRangeParams {
+            text_document: params.text_document,
+            positions: vec![params.position],
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .selection_range(params)
+            .result
+            .expect("selection range should not fail")
+            .into_iter()
+            .next()
+            .expect("a selection range for each position")
+    });
+
+    show_selection_ranges(tester.src, selection_range)
+}
+
+/// Shows the code selected by each of the ranges, from the innermost one to
+/// the outermost one.
+///
+fn show_selection_ranges(src: &str, selection_range: SelectionRange) -> String {
+    let line_numbers = LineNumbers::new(src);
+    let mut output = String::new();
+    let mut selection_range = Some(selection_range);
+    let mut level = 1;
+
+    while let Some(SelectionRange { range, parent }) = selection_range {
+        let start = line_numbers.byte_index(range.start) as usize;
+        let end = line_numbers.byte_index(range.end) as usize;
+        output.push_str(&format!("{level}: {}\n", &src[start..end]));
+        selection_range = parent.map(|parent| *parent);
+        level += 1;
+    }
+
+    output
+}
+
+#[test]
+fn selection_range_in_pipeline() {
+    let src = "
+pub fn main(items) {
+  let result =
+    items
+    |> wibble(10)
+    |> wobble
+  result
+}
+
+fn wibble(items, amount) {
+  amount
+}
+
+fn wobble(amount) {
+  amount
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("10")
+    ));
+}
+
+#[test]
+fn selection_range_of_call_argument() {
+    let src = "
+pub fn main() {
+  wibble(1, wobble: 2)
+}
+
+fn wibble(first, wobble second) {
+  first + second
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("2)")
+    ));
+}
+
+#[test]
+fn selection_range_in_case_clause() {
+    let src = "
+pub fn main(x) {
+  case x {
+    Ok(value) -> value + 1
+    Error(_) -> 0
+  }
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("value").nth_occurrence(2)
+    ));
+}
+
+#[test]
+fn selection_range_in_case_pattern() {
+    let src = "
+pub fn main(x) {
+  case x {
+    Ok(value) -> value + 1
+    Error(_) -> 0
+  }
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("value")
+    ));
+}
+
+#[test]
+fn selection_range_in_anonymous_function() {
+    let src = "
+pub fn main() {
+  let add = fn(a, b) {
+    let sum = a + b
+    sum
+  }
+  add(1, 2)
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("b\n")
+    ));
+}
+
+#[test]
+fn selection_range_in_custom_type() {
+    let src = "
+pub type Wibble {
+  Wibble(name: String, age: Int)
+  Wobble
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("Int")
+    ));
+}
+
+#[test]
+fn selection_range_outside_of_any_definition() {
+    let src = "
+pub fn main() {
+  Nil
+}
+
+pub fn wibble() {
+  Nil
+}
+";
+
+    assert_snapshot!(selection_ranges(
+        TestProject::for_source(src),
+        find_position_of("}\n\n").with_char_offset(2)
+    ));
+}
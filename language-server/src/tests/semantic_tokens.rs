@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{Range, SemanticTokens, SemanticTokensParams, SemanticTokensRangeParams};
+
+use crate::semantic_tokens::token_names;
+
+use super::*;
+
+fn semantic_tokens(tester: TestProject<'_>) -> String {
+    tester.at(Position::default(), |engine, param, src| {
+        let params = SemanticTokensParams {
+            text_document: param.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let tokens = engine
+            .semantic_tokens_full(params)
+            .result
+            .expect("semantic tokens should not fail")
+            .expect("semantic tokens for the module");
+        pretty_tokens(&src, &tokens)
+    })
+}
+
+fn semantic_tokens_in_range(tester: TestProject<'_>, range: Range) -> String {
+    tester.at(Position::default(), |engine, param, src| {
+        let params = SemanticTokensRangeParams {
+            text_document: param.text_document,
+            range,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let tokens = engine
+            .semantic_tokens_range(params)
+            .result
+            .expect("semantic tokens should not fail")
+            .expect("semantic tokens for the module");
+        pretty_tokens(&src, &tokens)
+    })
+}
+
+/// Decodes the relative positions of the tokens and prints each one next to
+/// the piece of code it highlights.
+///
+fn pretty_tokens(code: &str, tokens: &SemanticTokens) -> String {
+    let lines: Vec<Vec<u16>> = code
+        .lines()
+        .map(|line| line.encode_utf16().collect())
+        .collect();
+
+    let mut output = format!("----- Code -----\n{code}\n\n----- Tokens -----\n");
+    let mut line = 0;
+    let mut column = 0;
+    for token in &tokens.data {
+        if token.delta_line == 0 {
+            column += token.delta_start;
+        } else {
+            line += token.delta_line;
+            column = token.delta_start;
+        }
+
+        let start = column as usize;
+        let end = start + token.length as usize;
+        let text = lines
+            .get(line as usize)
+            .and_then(|line| line.get(start..end))
+            .map(String::from_utf16_lossy)
+            .unwrap_or_default();
+
+        let (type_, modifiers) = token_names(token);
+        let modifiers = if modifiers.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", modifiers.join(", "))
+        };
+        output.push_str(&format!("{line}:{column} `{text}` {type_}{modifiers}\n"));
+    }
+
+    output
+}
+
+#[test]
+fn semantic_tokens_for_definitions() {
+    let code = r#"
+pub const answer = 42
+
+const greeting = "Hello"
+
+pub type Wibble(a) {
+  Wibble(value: a, count: Int)
+  Wobble
+}
+
+type Alias =
+  Wibble(Int)
+
+pub fn wibble(first: Int, with second: String) -> Alias {
+  todo
+}
+
+fn wobble(_discarded, label _ignored) {
+  Nil
+}
+"#;
+
+    assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_for_expressions() {
+    let code = r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+const default_age = 10
+
+fn new(name name: String) -> Person {
+  let person = Person(name:, age: default_age)
+  let older = Person(..person, age: person.age + 1)
+  let add = fn(a, b) { a + b }
+  older
+}
+"#;
+
+    assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_for_patterns() {
+    let code = r#"
+pub type Wibble {
+  Wibble(first: Int, second: Int)
+  Wobble
+}
+
+pub fn main(wibble: Wibble, name: String) {
+  case wibble, name {
+    Wibble(first:, second: other) as matched, "prefix" as prefix <> rest
+      if first > other
+    -> #(matched, prefix, rest)
+    Wobble as matched, _ -> #(matched, "", "")
+    _, _ -> todo
+  }
+}
+"#;
+
+    assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_for_imports_and_module_selects() {
+    let code = r#"
+import gleam/wibble as wobble
+import gleam/wubble.{type Wubble as MyWubble, Wubble, wubble}
+
+pub fn main() -> MyWubble {
+  wobble.wibble()
+  wubble(wobble.answer)
+  Wubble
+}
+"#;
+
+    assert_snapshot!(semantic_tokens(
+        TestProject::for_source(code)
+            .add_hex_module(
+                "gleam/wibble",
+                "pub fn wibble() { Nil } pub const answer = 42"
+            )
+            .add_hex_module(
+                "gleam/wubble",
+                "pub type Wubble { Wubble } pub fn wubble(a) { a }"
+            )
+    ));
+}
+
+#[test]
+fn semantic_tokens_modifiers() {
+    let code = r#"
+import wibble
+
+@deprecated("Use wobble instead")
+pub fn deprecated() { Nil }
+
+@external(erlang, "wibble", "external")
+pub fn external() -> Nil
+
+pub fn main() {
+  deprecated()
+  external()
+  wibble.deprecated_dep()
+  wibble.external_dep()
+}
+"#;
+
+    assert_snapshot!(semantic_tokens(TestProject::for_source(code).add_module(
+        "wibble",
+        r#"
+@deprecated("Don't use this")
+pub fn deprecated_dep() { Nil }
+
+@external(javascript, "./wibble.mjs", "external")
+pub fn external_dep() -> Nil { Nil }
+"#
+    )));
+}
+
+#[test]
+fn semantic_tokens_ignore_generated_variables() {
+    let code = r#"
+pub fn main() {
+  use #(a, b) <- apply(#(1, 2))
+  use c <- apply(a)
+  1
+  |> add(b, _)
+  |> add(c)
+}
+
+fn apply(value, f) { f(value) }
+
+fn add(a, b) { a + b }
+"#;
+
+    assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_with_unicode() {
+    let code = r#"
+pub fn main() {
+  let emoji = "🌸🌸"
+  #(emoji, "ø", emoji)
+}
+"#;
+
+    assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_only_in_requested_range() {
+    let code = r#"
+pub fn wibble() {
+  Nil
+}
+
+pub fn wobble(value) {
+  value
+}
+
+pub fn wubble() {
+  Nil
+}
+"#;
+
+    assert_snapshot!(semantic_tokens_in_range(
+        TestProject::for_source(code),
+        Range::new(Position::new(5, 0), Position::new(7, 1))
+    ));
+}
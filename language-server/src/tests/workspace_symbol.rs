@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use insta::assert_snapshot;
+use lsp_types::{SymbolTag, WorkspaceSymbolLocation, WorkspaceSymbolParams};
+
+use crate::workspace_symbol::{fuzzy_match, sort_symbols};
+
+use super::*;
+
+fn workspace_symbols(tester: TestProject<'_>, query: &str) -> String {
+    tester.run(|engine| {
+        let params = WorkspaceSymbolParams {
+            query: query.into(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let mut symbols = engine
+            .workspace_symbol(&params)
+            .result
+            .expect("workspace symbols should not fail");
+        sort_symbols(&mut symbols);
+
+        symbols
+            .into_iter()
+            .map(|scored| {
+                let info = scored.symbol.base_symbol_information;
+                let WorkspaceSymbolLocation::Location(location) = scored.symbol.location else {
+                    panic!("Workspace symbol without a range");
+                };
+                let deprecated = match info.tags {
+                    Some(tags) if tags.contains(&SymbolTag::Deprecated) => " (deprecated)",
+                    Some(_) | None => "",
+                };
+                format!(
+                    "{} {}.{} {:?} {}:{}{deprecated}",
+                    scored.score,
+                    info.container_name.unwrap_or_default(),
+                    info.name,
+                    info.kind,
+                    location.range.start.line,
+                    location.range.start.character,
+                )
+            })
+            .join("\n")
+    })
+}
+
+#[test]
+fn workspace_symbols_of_every_kind() {
+    let code = "
+pub fn wibble() { Nil }
+
+const wobble = 1
+
+pub type Wubble {
+  Wubble(value: Int)
+  NoWubble
+}
+
+pub type Alias = Wubble
+";
+
+    assert_snapshot!(workspace_symbols(TestProject::for_source(code), ""));
+}
+
+#[test]
+fn workspace_symbols_fuzzy_match() {
+    let code = "
+pub fn parse_expression() { Nil }
+pub fn parse() { Nil }
+pub fn reparse() { Nil }
+pub fn print_all_expressions() { Nil }
+pub fn wibble() { Nil }
+";
+
+    assert_snapshot!(workspace_symbols(TestProject::for_source(code), "parse"));
+}
+
+#[test]
+fn workspace_symbols_are_case_insensitive() {
+    let code = "
+pub type HttpRequest
+
+pub fn http_request() { Nil }
+";
+
+    assert_snapshot!(workspace_symbols(TestProject::for_source(code), "httpreq"));
+}
+
+#[test]
+fn workspace_symbols_include_deprecated_tag() {
+    let code = r#"
+@deprecated("Use wobble instead")
+pub fn wibble() { Nil }
+
+pub type Wobble {
+  @deprecated("Use Wubble instead")
+  Wobble
+  Wubble
+}
+"#;
+
+    assert_snapshot!(workspace_symbols(TestProject::for_source(code), "w"));
+}
+
+#[test]
+fn workspace_symbols_include_other_root_package_modules() {
+    let code = "
+pub fn wibble() { Nil }
+";
+
+    assert_snapshot!(workspace_symbols(
+        TestProject::for_source(code).add_module("wobble", "fn wibble_private() { Nil }"),
+        "wibble",
+    ));
+}
+
+#[test]
+fn workspace_symbols_include_only_public_dependency_symbols() {
+    let code = "
+pub fn wibble() { Nil }
+";
+
+    assert_snapshot!(workspace_symbols(
+        TestProject::for_source(code)
+            .add_dep_module(
+                "dep_module",
+                "pub fn wibble_dep() { Nil } fn wibble_private() { Nil }"
+            )
+            .add_hex_module(
+                "hex_module",
+                "pub const wibble_hex = 1 const wibble_private = 2"
+            ),
+        "wibble",
+    ));
+}
+
+#[test]
+fn fuzzy_match_scores() {
+    assert_eq!(fuzzy_match("", "wibble"), Some(0));
+    assert_eq!(fuzzy_match("wibble", "wibble"), Some(0));
+    assert_eq!(fuzzy_match("WIBBLE", "wibble"), Some(0));
+    assert_eq!(fuzzy_match("wib", "wibble"), Some(1));
+    assert_eq!(fuzzy_match("ble", "wibble"), Some(2));
+    assert_eq!(fuzzy_match("wbl", "wibble"), Some(5));
+    assert_eq!(fuzzy_match("wobble", "wibble"), None);
+}
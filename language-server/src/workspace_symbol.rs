@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2026 The Gleam contributors
+
+use ecow::EcoString;
+use gleam_core::{
+    ast::Publicity,
+    type_::{Deprecation, ModuleInterface, ValueConstructorVariant},
+};
+use lsp_types::{
+    BaseSymbolInformation, Location, SymbolKind, WorkspaceSymbol, WorkspaceSymbolLocation,
+};
+
+use crate::{
+    compiler::ModuleSourceInformation, engine::make_deprecated_symbol_tag, src_span_to_lsp_range,
+    url_from_path,
+};
+
+/// A symbol matching a workspace symbol query, along with the score it got
+/// when matched against the query. Lower scores are better matches.
+///
+#[derive(Debug)]
+pub struct ScoredSymbol {
+    pub score: u32,
+    pub symbol: WorkspaceSymbol,
+}
+
+/// Finds all the symbols defined in a module that match the given query.
+///
+/// Private definitions are only included if `include_private` is true, which
+/// is the case for modules belonging to the root package: a programmer can
+/// find anything in the package they're working in, but only the public API
+/// of their dependencies.
+///
+pub fn module_symbols(
+    module: &ModuleInterface,
+    source: &ModuleSourceInformation,
+    include_private: bool,
+    query: &str,
+) -> Vec<ScoredSymbol> {
+    let Some(uri) = url_from_path(source.path.as_str()) else {
+        return vec![];
+    };
+
+    let mut symbols = vec![];
+    let mut push = |name: &EcoString, kind, location, deprecation: &Deprecation| {
+        let Some(score) = fuzzy_match(query, name) else {
+            return;
+        };
+        symbols.push(ScoredSymbol {
+            score,
+            symbol: WorkspaceSymbol {
+                location: Location {
+                    uri: uri.clone(),
+                    range: src_span_to_lsp_range(location, &source.line_numbers),
+                }
+                .into(),
+                data: None,
+                base_symbol_information: BaseSymbolInformation {
+                    name: name.to_string(),
+                    kind,
+                    tags: make_deprecated_symbol_tag(deprecation),
+                    container_name: Some(module.name.to_string()),
+                },
+            },
+        });
+    };
+    let is_visible = |publicity: &Publicity| include_private || publicity.is_importable();
+
+    for (name, value) in &module.values {
+        if !is_visible(&value.publicity) {
+            continue;
+        }
+
+        let (kind, location, defining_module) = match &value.variant {
+            ValueConstructorVariant::ModuleFn {
+                location, module, ..
+            } => (SymbolKind::Function, location, module),
+            ValueConstructorVariant::ModuleConstant {
+                location, module, ..
+            } => (SymbolKind::Constant, location, module),
+            // Just like document symbols, constructors with no fields are
+            // shown as enum members.
+            ValueConstructorVariant::Record {
+                location,
+                module,
+                arity,
+                ..
+            } => {
+                let kind = if *arity == 0 {
+                    SymbolKind::EnumMember
+                } else {
+                    SymbolKind::Constructor
+                };
+                (kind, location, module)
+            }
+            ValueConstructorVariant::LocalVariable { .. } => continue,
+        };
+
+        // Values imported in the module are not defined there, so we skip
+        // them to avoid listing the same symbol more than once.
+        if *defining_module != module.name {
+            continue;
+        }
+
+        push(name, kind, *location, &value.deprecation);
+    }
+
+    // Type aliases are also included in the module's types.
+    for (name, type_) in &module.types {
+        if !is_visible(&type_.publicity) || type_.module != module.name {
+            continue;
+        }
+        push(name, SymbolKind::Class, type_.origin, &type_.deprecation);
+    }
+
+    symbols
+}
+
+/// Sorts the matched symbols so that the best matches come first. Symbols with
+/// the same score are sorted so that shorter names come first, and then by
+/// name, module and position so that the results are stable.
+///
+pub fn sort_symbols(symbols: &mut [ScoredSymbol]) {
+    symbols.sort_by(|one, other| {
+        let one_info = &one.symbol.base_symbol_information;
+        let other_info = &other.symbol.base_symbol_information;
+        one.score
+            .cmp(&other.score)
+            .then_with(|| one_info.name.len().cmp(&other_info.name.len()))
+            .then_with(|| one_info.name.cmp(&other_info.name))
+            .then_with(|| one_info.container_name.cmp(&other_info.container_name))
+            .then_with(|| start_position(&one.symbol).cmp(&start_position(&other.symbol)))
+    });
+}
+
+fn start_position(symbol: &WorkspaceSymbol) -> Option<(u32, u32)> {
+    match &symbol.location {
+        WorkspaceSymbolLocation::Location(location) => {
+            Some((location.range.start.line, location.range.start.character))
+        }
+        WorkspaceSymbolLocation::LocationUriOnly(_) => None,
+    }
+}
+
+/// Fuzzy matches a query against a symbol name, returning a score if the name
+/// matches. The lower the score, the better the match:
+///
+/// - An exact match scores `0`.
+/// - A match on a prefix of the name scores `1`.
+/// - A match anywhere else in the name scores `2`.
+/// - Otherwise, all the query's characters must appear in order in the name
+///   and the score grows with the number of gaps between them.
+///
+/// As suggested by the LSP specification, matching is case-insensitive: the
+/// client is going to apply its own scoring and highlighting to the results.
+///
+pub fn fuzzy_match(query: &str, name: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if query.is_empty() || name == query {
+        return Some(0);
+    } else if name.starts_with(&query) {
+        return Some(1);
+    } else if name.contains(&query) {
+        return Some(2);
+    }
+
+    let mut gaps = 0;
+    let mut previous_matched = true;
+    let mut query_chars = query.chars().peekable();
+    for char in name.chars() {
+        match query_chars.peek() {
+            None => break,
+            Some(query_char) if *query_char == char => {
+                _ = query_chars.next();
+                previous_matched = true;
+            }
+            Some(_) => {
+                if previous_matched {
+                    gaps += 1;
+                }
+                previous_matched = false;
+            }
+        }
+    }
+
+    match query_chars.peek() {
+        Some(_) => None,
+        None => Some(3 + gaps),
+    }
+}
@@ -12,7 +12,7 @@ use gleam_core::{
 
 use gleam_cli::{
     fs,
-    run::{self, Which},
+    run::{self, Offline, Which},
 };
 
 fn run_and_produce_pretty_snapshot(
@@ -73,6 +73,7 @@ fn run_and_capture_output(
         Some(main_module.into()),
         Which::Src,
         true,
+        Offline::No,
     )
     .expect("run setup");
 